@@ -21,11 +21,28 @@ async fn main() -> Result<()> {
             hysteresis: 3,
             daa_score_range: vec![],
             tick_reserved: vec![],
+            tick_reserved_max: default_tick_reserved_max(),
+            tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+            balance_dust_threshold: default_balance_dust_threshold(),
+            enabled_op_types: default_enabled_op_types(),
+            allow_unknown_ops: default_allow_unknown_ops(),
+            checkpoint_hash: default_checkpoint_hash(),
+            batch_max_retries: default_batch_max_retries(),
+            batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+            consistency_mode: default_consistency_mode(),
+            tick_ignored: default_tick_ignored(),
             kaspa_node_url: "http://127.0.0.1:16210".to_string(),
             is_testnet: true,
+            scan_interval_ms: default_scan_interval_ms(),
+            vspc_check_window: default_vspc_check_window(),
+            tx_fetch_concurrency: default_tx_fetch_concurrency(),
+            node_reset_pause_ms: default_node_reset_pause_ms(),
+            op_score_multiplier: default_op_score_multiplier(),
+            runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
         },
         rocksdb: RocksConfig {
             path: "./testnet_data".to_string(),
+            ..Default::default()
         },
         distributed: DistributedConfig {
             node: DistributedNodeConfig {
@@ -53,13 +70,19 @@ async fn main() -> Result<()> {
                 retry_interval: 1,
                 enable_compression: false,
                 compression_level: 1,
+                compression_codec: default_compression_codec(),
             },
             performance: PerformanceConfig::default(),
             monitoring: MonitoringConfig::default(),
             security: SecurityConfig::default(),
         },
         http: HttpConfig::default(),
+        script_limits: ScriptLimitsConfig::default(),
+        standby: StandbyConfig::default(),
+        alerts: AlertsConfig::default(),
+        shutdown_timeout_ms: default_shutdown_timeout_ms(),
         rest: RestConfig::default(),
+        mint_rate_limit: MintRateLimitConfig::default(),
         debug: 0,
         testnet: true,
         is_testnet: true,
@@ -106,7 +129,7 @@ async fn main() -> Result<()> {
     let storage: Arc<StorageManager> = Arc::new(storage_manager);
 
     // Create operation manager
-    let operation_manager = OperationManager::new(storage.clone());
+    let operation_manager = OperationManager::new(&storage);
     println!("✅ Operation manager initialization completed");
 
     // Get supported operation types
@@ -182,6 +205,7 @@ async fn test_deploy_operation(
         ca: None,
         price: None,
         utxo: None,
+        desc: None,
     };
 
     // Validate deployment script
@@ -251,6 +275,7 @@ async fn test_mint_operation(
         ca: None,
         price: None,
         utxo: None,
+        desc: None,
     };
 
     // Validate minting script
@@ -321,6 +346,7 @@ async fn test_transfer_operation(
         ca: None,
         price: None,
         utxo: None,
+        desc: None,
     };
 
     // Validate transfer script
@@ -392,6 +418,7 @@ async fn test_issue_operation(
         ca: None,
         price: None,
         utxo: None,
+        desc: None,
     };
 
     // Validate issuance script
@@ -462,6 +489,7 @@ async fn test_chown_operation(
         ca: None,
         price: None,
         utxo: None,
+        desc: None,
     };
 
     // Validate ownership transfer script
@@ -530,6 +558,7 @@ async fn test_burn_operation(
         ca: None,
         price: None,
         utxo: None,
+        desc: None,
     };
 
     // Validate burning script
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use kaspa_indexer_rust::config::types::{
     Config, DistributedConfig, DistributedNodeConfig, HashRingConfig, ReplicationConfig,
-    ShardConfig,
+    ShardConfig, default_compression_codec,
 };
 use kaspa_indexer_rust::storage::StorageManager;
 use tracing::{info, warn};
@@ -57,6 +57,7 @@ async fn main() -> Result<()> {
                 retry_interval: 5,
                 enable_compression: true,
                 compression_level: 6,
+                compression_codec: default_compression_codec(),
             },
             performance: Default::default(),
             monitoring: Default::default(),
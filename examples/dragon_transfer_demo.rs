@@ -20,15 +20,37 @@ async fn main() -> Result<()> {
             hysteresis: 3,
             daa_score_range: vec![],
             tick_reserved: vec![],
+            tick_reserved_max: default_tick_reserved_max(),
+            tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+            balance_dust_threshold: default_balance_dust_threshold(),
+            enabled_op_types: default_enabled_op_types(),
+            allow_unknown_ops: default_allow_unknown_ops(),
+            checkpoint_hash: default_checkpoint_hash(),
+            batch_max_retries: default_batch_max_retries(),
+            batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+            consistency_mode: default_consistency_mode(),
+            tick_ignored: default_tick_ignored(),
             kaspa_node_url: "https://testnet.kaspa.org:16210".to_string(),
             is_testnet: true,
+            scan_interval_ms: default_scan_interval_ms(),
+            vspc_check_window: default_vspc_check_window(),
+            tx_fetch_concurrency: default_tx_fetch_concurrency(),
+            node_reset_pause_ms: default_node_reset_pause_ms(),
+            op_score_multiplier: default_op_score_multiplier(),
+            runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
         },
         rocksdb: RocksConfig {
             path: "./testnet_data".to_string(),
+            ..Default::default()
         },
         distributed: DistributedConfig::default(),
         http: HttpConfig::default(),
+        script_limits: ScriptLimitsConfig::default(),
+        standby: StandbyConfig::default(),
+        alerts: AlertsConfig::default(),
+        shutdown_timeout_ms: default_shutdown_timeout_ms(),
         rest: RestConfig::default(),
+        mint_rate_limit: MintRateLimitConfig::default(),
         debug: 2,
         testnet: true,
         is_testnet: true,
@@ -71,6 +93,7 @@ async fn main() -> Result<()> {
         max: None,
         dec: None,
         name: None,
+        desc: None,
     };
 
     // Validate transfer script
@@ -89,6 +112,7 @@ async fn main() -> Result<()> {
         state_balance_map: std::collections::HashMap::new(),
         state_market_map: std::collections::HashMap::new(),
         state_blacklist_map: std::collections::HashMap::new(),
+        state_mint_rate_map: std::collections::HashMap::new(),
     };
 
     // Simulate sender already has DRAGON token balance
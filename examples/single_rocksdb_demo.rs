@@ -13,6 +13,7 @@ async fn main() -> Result<()> {
     // Configure single RocksDB
     let rocks_config = RocksConfig {
         path: "./data/single_rocksdb".to_string(),
+        ..Default::default()
     };
 
     info!("📊 Single RocksDB configuration:");
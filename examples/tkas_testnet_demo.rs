@@ -22,11 +22,28 @@ async fn main() -> Result<()> {
             hysteresis: 3,
             daa_score_range: vec![],
             tick_reserved: vec![],
+            tick_reserved_max: default_tick_reserved_max(),
+            tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+            balance_dust_threshold: default_balance_dust_threshold(),
+            enabled_op_types: default_enabled_op_types(),
+            allow_unknown_ops: default_allow_unknown_ops(),
+            checkpoint_hash: default_checkpoint_hash(),
+            batch_max_retries: default_batch_max_retries(),
+            batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+            consistency_mode: default_consistency_mode(),
+            tick_ignored: default_tick_ignored(),
             kaspa_node_url: "http://127.0.0.1:16210".to_string(),
             is_testnet: true,
+            scan_interval_ms: default_scan_interval_ms(),
+            vspc_check_window: default_vspc_check_window(),
+            tx_fetch_concurrency: default_tx_fetch_concurrency(),
+            node_reset_pause_ms: default_node_reset_pause_ms(),
+            op_score_multiplier: default_op_score_multiplier(),
+            runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
         },
         rocksdb: RocksConfig {
             path: "./testnet_data".to_string(),
+            ..Default::default()
         },
         distributed: DistributedConfig {
             node: DistributedNodeConfig {
@@ -54,13 +71,19 @@ async fn main() -> Result<()> {
                 retry_interval: 5,
                 enable_compression: true,
                 compression_level: 6,
+                compression_codec: default_compression_codec(),
             },
             performance: PerformanceConfig::default(),
             monitoring: MonitoringConfig::default(),
             security: SecurityConfig::default(),
         },
         http: HttpConfig::default(),
+        script_limits: ScriptLimitsConfig::default(),
+        standby: StandbyConfig::default(),
+        alerts: AlertsConfig::default(),
+        shutdown_timeout_ms: default_shutdown_timeout_ms(),
         rest: RestConfig::default(),
+        mint_rate_limit: MintRateLimitConfig::default(),
         debug: 2,
         testnet: true,
         is_testnet: true,
@@ -99,7 +122,7 @@ async fn main() -> Result<()> {
     let storage: Arc<StorageManager> = Arc::new(storage_manager);
 
     // Create operation manager
-    let operation_manager = OperationManager::new(Arc::clone(&storage));
+    let operation_manager = OperationManager::new(&storage);
     println!("✅ Operation manager initialization completed");
 
     // Display supported operations
@@ -161,6 +184,7 @@ async fn test_pepek_deploy(
         utxo: None,
         price: None,
         ca: None,
+        desc: None,
     };
 
     // Validate deployment script
@@ -281,6 +305,7 @@ async fn test_pepek_mint(
         utxo: Some("".to_string()),
         price: Some("".to_string()),
         ca: Some("".to_string()),
+        desc: None,
     };
 
     // Validate minting script
@@ -426,6 +451,7 @@ async fn test_pepek_transfer(
         mod_type: "".to_string(),
         name: Some("".to_string()),
         ca: Some("".to_string()),
+        desc: None,
     };
 
     // Validate transfer script
@@ -560,6 +586,7 @@ async fn test_pepek_burn(
         mod_type: "".to_string(),
         name: Some("".to_string()),
         ca: Some("".to_string()),
+        desc: None,
     };
 
     // Validate burn script
@@ -1,10 +1,12 @@
 pub mod types;
 
+use crate::utils::daa_range::DaaScoreRanges;
 use anyhow::Result;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
-use tracing::info;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use tracing::{info, warn};
 
 // Version constant corresponding to Go version
 pub const VERSION: &str = "0.1";
@@ -13,6 +15,47 @@ pub const VERSION: &str = "0.1";
 static TICK_RESERVED: Lazy<Mutex<HashMap<String, String>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+// Global DAA score ending the reservations, 0 until applied from configuration keeps them forever
+static TICK_RESERVED_EXPIRY_DAA: AtomicU64 = AtomicU64::new(0);
+
+// Global ignored token set, holds the default list until applied from configuration
+static TICK_IGNORED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| {
+    Mutex::new(
+        crate::config::types::default_tick_ignored()
+            .into_iter()
+            .collect(),
+    )
+});
+
+// Global mint rate limit, disabled until applied from configuration
+static MINT_RATE_LIMIT: Lazy<Mutex<crate::config::types::MintRateLimitConfig>> =
+    Lazy::new(|| Mutex::new(crate::config::types::MintRateLimitConfig::default()));
+
+// Global script field size limits, defaults until applied from configuration
+static SCRIPT_LIMITS: Lazy<Mutex<crate::config::types::ScriptLimitsConfig>> =
+    Lazy::new(|| Mutex::new(crate::config::types::ScriptLimitsConfig::default()));
+
+// Global op_score slots per DAA score, the Go version value until applied from configuration
+static OP_SCORE_MULTIPLIER: AtomicU64 =
+    AtomicU64::new(crate::storage::types::OP_SCORE_MULTIPLIER_DEFAULT);
+
+// Global checkpoint hash algorithm, blake3 until applied from configuration
+static CHECKPOINT_HASH: Lazy<Mutex<crate::storage::checkpoint::CheckpointHash>> =
+    Lazy::new(|| Mutex::new(crate::storage::checkpoint::CheckpointHash::default()));
+
+// Global set of executed operation types, None executes every type
+static ENABLED_OP_TYPES: Lazy<Mutex<Option<HashSet<String>>>> = Lazy::new(|| Mutex::new(None));
+
+// Global write batch retry limit and base backoff, defaults until applied from configuration
+static BATCH_MAX_RETRIES: AtomicU32 = AtomicU32::new(5);
+static BATCH_RETRY_BACKOFF_MS: AtomicU64 = AtomicU64::new(10);
+
+// Global leniency for unregistered operation types, off rejects them
+static ALLOW_UNKNOWN_OPS: AtomicBool = AtomicBool::new(false);
+
+// Global balance dust threshold, 0 until applied from configuration keeps every balance
+static BALANCE_DUST_THRESHOLD: AtomicU64 = AtomicU64::new(0);
+
 /// Load configuration file, corresponding to Go version's config.Load
 pub fn load_config(config: &mut crate::config::types::Config) -> Result<()> {
     // Try to load configuration file, only use TOML format
@@ -36,10 +79,51 @@ pub fn load_config(config: &mut crate::config::types::Config) -> Result<()> {
             *config = toml::from_str(&content)?;
             info!("Configuration loaded successfully from: {}", config_path);
 
-            // Apply reserved token list
+            // Apply the durability preset first, the settings it bundles are read below
+            apply_consistency_mode(config);
+
+            // Apply reserved token list, malformed entries are rejected by validate_config
             if !config.startup.tick_reserved.is_empty() {
                 apply_tick_reserved(&config.startup.tick_reserved);
             }
+            apply_tick_reserved_expiry(config.startup.tick_reserved_expiry_daa);
+
+            // Apply ignored token list
+            apply_tick_ignored(&config.startup.tick_ignored);
+
+            // Apply mint rate limit
+            apply_mint_rate_limit(&config.mint_rate_limit);
+
+            // Apply script field size limits
+            apply_script_limits(&config.script_limits);
+
+            // Apply op_score multiplier
+            apply_op_score_multiplier(config.startup.op_score_multiplier);
+
+            // Apply checkpoint hash algorithm, unknown names are rejected by validate_config
+            if let Some(hash) =
+                crate::storage::checkpoint::CheckpointHash::from_name(&config.startup.checkpoint_hash)
+            {
+                apply_checkpoint_hash(hash);
+            }
+
+            // Apply executed operation types
+            apply_enabled_op_types(&config.startup.enabled_op_types);
+            apply_allow_unknown_ops(config.startup.allow_unknown_ops);
+
+            // Apply write batch retries
+            apply_batch_retry(
+                config.startup.batch_max_retries,
+                config.startup.batch_retry_backoff_ms,
+            );
+
+            // Apply balance dust threshold
+            apply_balance_dust_threshold(config.startup.balance_dust_threshold);
+
+            // Apply operation execution time metrics
+            crate::operations::metrics::apply_op_metrics_enabled(
+                config.distributed.monitoring.enable_metrics,
+            );
 
             return Ok(());
         } else {
@@ -51,6 +135,17 @@ pub fn load_config(config: &mut crate::config::types::Config) -> Result<()> {
     Err(anyhow::anyhow!("No TOML configuration file found"))
 }
 
+/// Override the settings bundled by `startup.consistencyMode`, unknown modes are rejected by
+/// validate_config
+pub fn apply_consistency_mode(config: &mut crate::config::types::Config) {
+    if let Some(mode) =
+        crate::config::types::ConsistencyMode::from_name(&config.startup.consistency_mode)
+    {
+        mode.apply(config);
+        info!("Consistency mode: {}", mode.name());
+    }
+}
+
 /// Apply reserved token list, corresponding to Go version's ApplyTickReserved
 pub fn apply_tick_reserved(reserved_list: &[String]) {
     let mut tick_reserved = TICK_RESERVED.lock().unwrap();
@@ -98,18 +193,179 @@ fn apply_default_reserved_tokens(tick_reserved: &mut std::collections::HashMap<S
     }
 }
 
-/// Check if token is reserved, corresponding to Go version's TickReserved check
-pub fn is_tick_reserved(tick: &str) -> bool {
-    let tick_reserved = TICK_RESERVED.lock().unwrap();
-    tick_reserved.contains_key(&tick.to_uppercase())
+/// Apply the DAA score from which reserved ticks not yet deployed are released, 0 never releases
+pub fn apply_tick_reserved_expiry(expiry_daa: u64) {
+    TICK_RESERVED_EXPIRY_DAA.store(expiry_daa, Ordering::Relaxed);
+}
+
+/// Reservations only hold for operations accepted before the configured expiry
+fn is_reservation_active(daa_score: u64) -> bool {
+    let expiry_daa = TICK_RESERVED_EXPIRY_DAA.load(Ordering::Relaxed);
+    expiry_daa == 0 || daa_score < expiry_daa
+}
+
+/// Check if token is reserved at a DAA score, corresponding to Go version's TickReserved check
+pub fn is_tick_reserved(tick: &str, daa_score: u64) -> bool {
+    get_reserved_tick_address(tick, daa_score).is_some()
 }
 
-/// Get reserved token address, corresponding to Go version's TickReserved[tick]
-pub fn get_reserved_tick_address(tick: &str) -> Option<String> {
+/// Get reserved token address at a DAA score, corresponding to Go version's TickReserved[tick]
+pub fn get_reserved_tick_address(tick: &str, daa_score: u64) -> Option<String> {
+    if !is_reservation_active(daa_score) {
+        return None;
+    }
     let tick_reserved = TICK_RESERVED.lock().unwrap();
     tick_reserved.get(&tick.to_uppercase()).cloned()
 }
 
+/// Apply ignored token list, replacing the current one
+pub fn apply_tick_ignored(ignored_list: &[String]) {
+    let mut tick_ignored = TICK_IGNORED.lock().unwrap();
+    *tick_ignored = ignored_list
+        .iter()
+        .map(|tick| tick.to_uppercase())
+        .collect();
+    info!("Applied {} ignored tokens", tick_ignored.len());
+}
+
+/// Check if token is ignored and can never be deployed
+pub fn is_tick_ignored(tick: &str) -> bool {
+    let tick_ignored = TICK_IGNORED.lock().unwrap();
+    tick_ignored.contains(&tick.to_uppercase())
+}
+
+/// Apply mint rate limit configuration
+pub fn apply_mint_rate_limit(limit: &crate::config::types::MintRateLimitConfig) {
+    let mut mint_rate_limit = MINT_RATE_LIMIT.lock().unwrap();
+    *mint_rate_limit = limit.clone();
+    info!(
+        "Applied mint rate limit: window={}, maxMints={}, tickLimits={}",
+        limit.window,
+        limit.max_mints,
+        limit.tick_limits.len()
+    );
+}
+
+/// Apply script field size limits
+pub fn apply_script_limits(limits: &crate::config::types::ScriptLimitsConfig) {
+    let mut script_limits = SCRIPT_LIMITS.lock().unwrap();
+    *script_limits = limits.clone();
+    info!(
+        "Applied script limits: maxNameLen={}, maxAddressLen={}, maxFieldLen={}, maxDescLen={}",
+        limits.max_name_len, limits.max_address_len, limits.max_field_len, limits.max_desc_len
+    );
+}
+
+/// Get script field size limits
+pub fn get_script_limits() -> crate::config::types::ScriptLimitsConfig {
+    SCRIPT_LIMITS.lock().unwrap().clone()
+}
+
+/// Apply the op_score multiplier
+pub fn apply_op_score_multiplier(multiplier: u64) {
+    OP_SCORE_MULTIPLIER.store(multiplier, Ordering::Relaxed);
+    info!("Applied op_score multiplier: {}", multiplier);
+}
+
+/// Get the op_score multiplier, the maximum number of operations per DAA score
+pub fn get_op_score_multiplier() -> u64 {
+    OP_SCORE_MULTIPLIER.load(Ordering::Relaxed)
+}
+
+/// Apply the hash algorithm of newly sealed checkpoints
+pub fn apply_checkpoint_hash(hash: crate::storage::checkpoint::CheckpointHash) {
+    *CHECKPOINT_HASH.lock().unwrap() = hash;
+    info!("Applied checkpoint hash: {}", hash.name());
+}
+
+/// Get the hash algorithm of newly sealed checkpoints
+pub fn get_checkpoint_hash() -> crate::storage::checkpoint::CheckpointHash {
+    *CHECKPOINT_HASH.lock().unwrap()
+}
+
+/// Apply executed operation types, an empty list executes every type
+pub fn apply_enabled_op_types(op_types: &[String]) {
+    let mut enabled_op_types = ENABLED_OP_TYPES.lock().unwrap();
+    if op_types.is_empty() {
+        *enabled_op_types = None;
+        info!("Applied enabled operation types: all");
+        return;
+    }
+    *enabled_op_types = Some(op_types.iter().map(|op| op.to_lowercase()).collect());
+    warn!(
+        "Only executing operation types {:?}, state of other operations is not tracked",
+        op_types
+    );
+}
+
+/// Check if operations of a type are executed
+pub fn is_op_type_enabled(op: &str) -> bool {
+    let enabled_op_types = ENABLED_OP_TYPES.lock().unwrap();
+    enabled_op_types
+        .as_ref()
+        .is_none_or(|op_types| op_types.contains(&op.to_lowercase()))
+}
+
+/// Apply whether scripts of unregistered operation types are accepted
+pub fn apply_allow_unknown_ops(allow: bool) {
+    ALLOW_UNKNOWN_OPS.store(allow, Ordering::Relaxed);
+    if allow {
+        warn!("Accepting unknown operation types, they are stored unexecuted");
+    }
+}
+
+/// Check if scripts of unregistered operation types are accepted
+pub fn is_unknown_op_allowed() -> bool {
+    ALLOW_UNKNOWN_OPS.load(Ordering::Relaxed)
+}
+
+/// Apply the retry limit and base backoff of failed write batch chunks
+pub fn apply_batch_retry(max_retries: u32, backoff_ms: u64) {
+    BATCH_MAX_RETRIES.store(max_retries, Ordering::Relaxed);
+    BATCH_RETRY_BACKOFF_MS.store(backoff_ms, Ordering::Relaxed);
+    info!(
+        "Applied batch retries: max {}, backoff {}ms",
+        max_retries, backoff_ms
+    );
+}
+
+/// Get the retries of a failed write batch chunk
+pub fn get_batch_max_retries() -> u32 {
+    BATCH_MAX_RETRIES.load(Ordering::Relaxed)
+}
+
+/// Get the backoff (milliseconds) before the first retry of a write batch chunk
+pub fn get_batch_retry_backoff_ms() -> u64 {
+    BATCH_RETRY_BACKOFF_MS.load(Ordering::Relaxed)
+}
+
+/// Apply the amount below which unlocked balances are swept as dust, 0 disables the sweep
+pub fn apply_balance_dust_threshold(threshold: u64) {
+    BALANCE_DUST_THRESHOLD.store(threshold, Ordering::Relaxed);
+}
+
+/// Get the balance dust threshold, 0 when disabled
+pub fn get_balance_dust_threshold() -> u64 {
+    BALANCE_DUST_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Get mint rate limit of a tick as (window, max mints), None if unlimited
+pub fn get_mint_rate_limit(tick: &str) -> Option<(u64, u64)> {
+    let mint_rate_limit = MINT_RATE_LIMIT.lock().unwrap();
+    let tick = tick.to_uppercase();
+    let max_mints = mint_rate_limit
+        .tick_limits
+        .iter()
+        .filter_map(|item| item.rsplit_once('_'))
+        .find(|(t, _)| t.to_uppercase() == tick)
+        .and_then(|(_, max)| max.parse::<u64>().ok())
+        .unwrap_or(mint_rate_limit.max_mints);
+    if max_mints == 0 || mint_rate_limit.window == 0 {
+        return None;
+    }
+    Some((mint_rate_limit.window, max_mints))
+}
+
 /// Validate configuration, corresponding to Go version's configuration validation
 pub fn validate_config(config: &crate::config::types::Config) -> Result<()> {
     // Validate startup configuration
@@ -123,10 +379,33 @@ pub fn validate_config(config: &crate::config::types::Config) -> Result<()> {
     
     // Validate REST configuration
     validate_rest_config(&config.rest)?;
+
+    // Validate mint rate limit configuration
+    validate_mint_rate_limit_config(&config.mint_rate_limit)?;
+
+    // Validate script limits configuration
+    validate_script_limits_config(&config.script_limits)?;
+
+    // Validate standby configuration
+    validate_standby_config(&config.standby)?;
+
+    // Validate alerts configuration
+    validate_alerts_config(&config.alerts)?;
+
+    if config.shutdown_timeout_ms == 0 {
+        return Err(anyhow::anyhow!("shutdownTimeoutMs must be greater than 0"));
+    }
     
     // Validate distributed configuration if enabled
     if config.distributed.node.enabled {
         validate_distributed_config(&config.distributed)?;
+        // Both defaults are 8080, only a problem once distributed nodes actually listen
+        if config.distributed.node.port == config.http.port {
+            warn!(
+                "distributed.node.port ({}) is the same as http.port",
+                config.distributed.node.port
+            );
+        }
     }
 
     info!("Configuration validation passed");
@@ -148,28 +427,130 @@ fn validate_startup_config(startup: &crate::config::types::StartupConfig) -> Res
         return Err(anyhow::anyhow!("hysteresis must be greater than 0"));
     }
     
-    // Validate DAA score range
-    for range in &startup.daa_score_range {
-        if range.len() != 2 {
-            return Err(anyhow::anyhow!("Invalid daa_score_range format"));
-        }
-        if range[0] >= range[1] {
+    // The check window is rescanned in every batch and must stay within the runtime VSPC list
+    if startup.vspc_check_window == 0 {
+        return Err(anyhow::anyhow!("vspcCheckWindow must be greater than 0"));
+    }
+    if startup.vspc_check_window >= crate::storage::types::LEN_VSPC_LIST_MAX {
+        return Err(anyhow::anyhow!(
+            "vspcCheckWindow ({}) must be less than the VSPC batch size ({})",
+            startup.vspc_check_window,
+            crate::storage::types::LEN_VSPC_LIST_MAX
+        ));
+    }
+    if startup.vspc_check_window > crate::storage::types::LEN_VSPC_LIST_RUNTIME_MAX {
+        return Err(anyhow::anyhow!(
+            "vspcCheckWindow ({}) cannot be greater than the runtime VSPC list size ({})",
+            startup.vspc_check_window,
+            crate::storage::types::LEN_VSPC_LIST_RUNTIME_MAX
+        ));
+    }
+
+    if startup.tx_fetch_concurrency == 0 {
+        return Err(anyhow::anyhow!("txFetchConcurrency must be greater than 0"));
+    }
+
+    // Below the Go version value a busy DAA score would no longer fit
+    if startup.op_score_multiplier < crate::storage::types::OP_SCORE_MULTIPLIER_DEFAULT
+        || startup.op_score_multiplier > crate::storage::types::OP_SCORE_MULTIPLIER_MAX
+    {
+        return Err(anyhow::anyhow!(
+            "opScoreMultiplier must be between {} and {}",
+            crate::storage::types::OP_SCORE_MULTIPLIER_DEFAULT,
+            crate::storage::types::OP_SCORE_MULTIPLIER_MAX
+        ));
+    }
+
+    // oplist ranges must cover whole DAA scores, or a DAA score whole ranges
+    let op_range_by = crate::storage::types::OP_RANGE_BY;
+    if !op_range_by.is_multiple_of(startup.op_score_multiplier)
+        && !startup.op_score_multiplier.is_multiple_of(op_range_by)
+    {
+        return Err(anyhow::anyhow!(
+            "opScoreMultiplier must divide or be a multiple of {}",
+            op_range_by
+        ));
+    }
+
+    if startup.runtime_flush_interval_ms == 0 {
+        return Err(anyhow::anyhow!(
+            "runtimeFlushIntervalMs must be greater than 0"
+        ));
+    }
+
+    if startup.batch_retry_backoff_ms == 0 {
+        return Err(anyhow::anyhow!(
+            "batchRetryBackoffMs must be greater than 0"
+        ));
+    }
+
+    if crate::storage::checkpoint::CheckpointHash::from_name(&startup.checkpoint_hash).is_none() {
+        return Err(anyhow::anyhow!(
+            "checkpointHash must be one of: blake3, sha256"
+        ));
+    }
+
+    if !startup.consistency_mode.is_empty()
+        && crate::config::types::ConsistencyMode::from_name(&startup.consistency_mode).is_none()
+    {
+        return Err(anyhow::anyhow!(
+            "consistencyMode must be one of: fast, safe"
+        ));
+    }
+
+    for op_type in &startup.enabled_op_types {
+        if !crate::operations::validate_op(&mut op_type.clone()) {
             return Err(anyhow::anyhow!(
-                "Invalid daa_score_range: start must be less than end"
+                "enabledOpTypes contains unknown operation type '{}'",
+                op_type
             ));
         }
     }
+
+    // Validate DAA score range
+    DaaScoreRanges::new(startup.daa_score_range.clone()).validate()?;
     
-    // Validate reserved tokens format
+    validate_tick_reserved(startup)?;
+    
+    Ok(())
+}
+
+/// Validate the reserved token list, every entry must be `TICK_ADDRESS` with a valid tick and a
+/// Kaspa address of the configured network
+pub fn validate_tick_reserved(startup: &crate::config::types::StartupConfig) -> Result<()> {
+    if startup.tick_reserved_max == 0 {
+        return Err(anyhow::anyhow!("tickReservedMax must be greater than 0"));
+    }
+    if startup.tick_reserved.len() > startup.tick_reserved_max {
+        return Err(anyhow::anyhow!(
+            "tickReserved has {} entries, more than tickReservedMax ({})",
+            startup.tick_reserved.len(),
+            startup.tick_reserved_max
+        ));
+    }
+
     for reserved_token in &startup.tick_reserved {
-        if !reserved_token.contains('_') {
+        let Some((tick, address)) = reserved_token.split_once('_') else {
             return Err(anyhow::anyhow!(
-                "Invalid reserved token format '{}': must be in format 'TICK_ADDRESS'", 
+                "Invalid reserved token format '{}': must be in format 'TICK_ADDRESS'",
                 reserved_token
             ));
+        };
+        if !crate::operations::validate_tick(&mut tick.to_string()) {
+            return Err(anyhow::anyhow!(
+                "Invalid reserved token '{}': '{}' is not a valid tick",
+                reserved_token,
+                tick
+            ));
+        }
+        if !crate::utils::address::verify_address(address, startup.is_testnet) {
+            return Err(anyhow::anyhow!(
+                "Invalid reserved token '{}': '{}' is not a valid Kaspa address",
+                reserved_token,
+                address
+            ));
         }
     }
-    
     Ok(())
 }
 
@@ -188,6 +569,38 @@ fn validate_rocksdb_config(rocksdb: &crate::config::types::RocksConfig) -> Resul
             ));
         }
     }
+
+    if rocksdb.critical_free_space_mb > rocksdb.min_free_space_mb {
+        return Err(anyhow::anyhow!(
+            "rocksdb.criticalFreeSpaceMB ({}) cannot be greater than minFreeSpaceMB ({})",
+            rocksdb.critical_free_space_mb,
+            rocksdb.min_free_space_mb
+        ));
+    }
+
+    if rocksdb.critical_free_inodes > rocksdb.min_free_inodes {
+        return Err(anyhow::anyhow!(
+            "rocksdb.criticalFreeInodes ({}) cannot be greater than minFreeInodes ({})",
+            rocksdb.critical_free_inodes,
+            rocksdb.min_free_inodes
+        ));
+    }
+
+    if rocksdb.disk_check_interval == 0 {
+        return Err(anyhow::anyhow!("rocksdb.diskCheckInterval must be greater than 0"));
+    }
+
+    if rocksdb.stall_backoff_ms == 0 {
+        return Err(anyhow::anyhow!("rocksdb.stallBackoffMs must be greater than 0"));
+    }
+
+    if rocksdb.stall_resume_pending_compaction_mb > rocksdb.stall_pending_compaction_mb {
+        return Err(anyhow::anyhow!(
+            "rocksdb.stallResumePendingCompactionMB ({}) cannot be greater than stallPendingCompactionMB ({})",
+            rocksdb.stall_resume_pending_compaction_mb,
+            rocksdb.stall_pending_compaction_mb
+        ));
+    }
     
     Ok(())
 }
@@ -205,6 +618,48 @@ fn validate_http_config(http: &crate::config::types::HttpConfig) -> Result<()> {
     if http.bind.parse::<std::net::IpAddr>().is_err() {
         return Err(anyhow::anyhow!("Invalid HTTP bind address: {}", http.bind));
     }
+
+    if !http.admin_bind.is_empty() {
+        let Ok(admin_addr) = http.admin_bind.parse::<std::net::SocketAddr>() else {
+            return Err(anyhow::anyhow!(
+                "Invalid http.admin_bind address: {}",
+                http.admin_bind
+            ));
+        };
+        // The same port is only free on another interface than the public listener's
+        let public_ip: std::net::IpAddr = http.bind.parse()?;
+        if admin_addr.port() == http.port
+            && (admin_addr.ip() == public_ip
+                || admin_addr.ip().is_unspecified()
+                || public_ip.is_unspecified())
+        {
+            return Err(anyhow::anyhow!(
+                "http.admin_bind ({}) overlaps the public listener {}:{}",
+                http.admin_bind,
+                http.bind,
+                http.port
+            ));
+        }
+    }
+
+    if http.max_page_size == 0 {
+        return Err(anyhow::anyhow!("http.max_page_size must be greater than 0"));
+    }
+
+    if http.max_concurrent_reads == 0
+        || http.max_concurrent_reads > tokio::sync::Semaphore::MAX_PERMITS
+    {
+        return Err(anyhow::anyhow!(
+            "http.max_concurrent_reads must be between 1 and {}",
+            tokio::sync::Semaphore::MAX_PERMITS
+        ));
+    }
+
+    if http.cache_control.finality_depth == 0 {
+        return Err(anyhow::anyhow!(
+            "http.cache_control.finality_depth must be greater than 0"
+        ));
+    }
     
     Ok(())
 }
@@ -222,6 +677,81 @@ fn validate_rest_config(rest: &crate::config::types::RestConfig) -> Result<()> {
     Ok(())
 }
 
+fn validate_mint_rate_limit_config(
+    limit: &crate::config::types::MintRateLimitConfig,
+) -> Result<()> {
+    if limit.window == 0 {
+        return Err(anyhow::anyhow!("mintRateLimit window must be greater than 0"));
+    }
+
+    for item in &limit.tick_limits {
+        let valid = item
+            .rsplit_once('_')
+            .map(|(tick, max)| !tick.is_empty() && max.parse::<u64>().is_ok())
+            .unwrap_or(false);
+        if !valid {
+            return Err(anyhow::anyhow!("Invalid mintRateLimit tickLimits entry: {}", item));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_script_limits_config(
+    limits: &crate::config::types::ScriptLimitsConfig,
+) -> Result<()> {
+    if limits.max_name_len == 0
+        || limits.max_address_len == 0
+        || limits.max_field_len == 0
+        || limits.max_desc_len == 0
+    {
+        return Err(anyhow::anyhow!("scriptLimits lengths must be greater than 0"));
+    }
+
+    Ok(())
+}
+
+fn validate_alerts_config(alerts: &crate::config::types::AlertsConfig) -> Result<()> {
+    if alerts.webhook_url.is_empty() {
+        return Ok(());
+    }
+    if !alerts.webhook_url.starts_with("http://") && !alerts.webhook_url.starts_with("https://") {
+        return Err(anyhow::anyhow!(
+            "alerts.webhookUrl must start with http:// or https://"
+        ));
+    }
+    if !(0.0..=1.0).contains(&alerts.rejected_rate) {
+        return Err(anyhow::anyhow!(
+            "alerts.rejectedRate must be between 0 and 1"
+        ));
+    }
+    Ok(())
+}
+
+fn validate_standby_config(standby: &crate::config::types::StandbyConfig) -> Result<()> {
+    if !standby.enabled {
+        return Ok(());
+    }
+    if !standby.leader_url.starts_with("http://") && !standby.leader_url.starts_with("https://") {
+        return Err(anyhow::anyhow!(
+            "standby.leaderUrl must start with http:// or https://"
+        ));
+    }
+    if standby.poll_interval_ms == 0 {
+        return Err(anyhow::anyhow!(
+            "standby.pollIntervalMs must be greater than 0"
+        ));
+    }
+    // A single slow poll must not promote the follower
+    if standby.failover_timeout_ms <= standby.poll_interval_ms {
+        return Err(anyhow::anyhow!(
+            "standby.failoverTimeoutMs must be greater than standby.pollIntervalMs"
+        ));
+    }
+
+    Ok(())
+}
+
 fn validate_distributed_config(distributed: &crate::config::types::DistributedConfig) -> Result<()> {
     // Validate node configuration
     if distributed.node.node_id.is_empty() {
@@ -248,6 +778,8 @@ fn validate_distributed_config(distributed: &crate::config::types::DistributedCo
         ));
     }
     
+    validate_distributed_peers(&distributed.node)?;
+
     // Validate hash ring configuration
     if distributed.hash_ring.virtual_nodes == 0 {
         return Err(anyhow::anyhow!("distributed.hash_ring.virtual_nodes must be greater than 0"));
@@ -266,6 +798,12 @@ fn validate_distributed_config(distributed: &crate::config::types::DistributedCo
         ));
     }
     
+    if !["zstd", "lz4", "snappy"].contains(&distributed.replication.compression_codec.as_str()) {
+        return Err(anyhow::anyhow!(
+            "distributed.replication.compression_codec must be one of: zstd, lz4, snappy"
+        ));
+    }
+    
     if distributed.replication.timeout == 0 {
         return Err(anyhow::anyhow!("distributed.replication.timeout must be greater than 0"));
     }
@@ -288,6 +826,85 @@ fn validate_distributed_config(distributed: &crate::config::types::DistributedCo
     Ok(())
 }
 
+/// Split a peer address `host:port` into host and port, IPv6 hosts are written as `[addr]:port`
+fn parse_node_address(address: &str) -> Option<(String, u16)> {
+    let (host, port) = address.rsplit_once(':')?;
+    let port = port.parse::<u16>().ok().filter(|port| *port > 0)?;
+    if let Some(ipv6) = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+    {
+        return ipv6
+            .parse::<std::net::Ipv6Addr>()
+            .ok()
+            .map(|ip| (ip.to_string(), port));
+    }
+    let valid_host = !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    valid_host.then(|| (host.to_string(), port))
+}
+
+/// Check if a peer host names this machine
+fn is_local_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .map(|ip| ip.is_loopback() || ip.is_unspecified())
+            .unwrap_or(false)
+}
+
+/// Validate peer entries of `distributed.node.nodes`, written as `host:port` or `nodeId@host:port`
+fn validate_distributed_peers(node: &crate::config::types::DistributedNodeConfig) -> Result<()> {
+    let mut addresses = HashSet::new();
+    let mut node_ids = HashSet::from([node.node_id.as_str()]);
+    for (index, peer) in node.nodes.iter().enumerate() {
+        let (node_id, address) = match peer.split_once('@') {
+            Some((node_id, address)) => (Some(node_id), address),
+            None => (None, peer.as_str()),
+        };
+        let (host, port) = parse_node_address(address).ok_or_else(|| {
+            anyhow::anyhow!(
+                "distributed.node.nodes[{}] '{}' is not a valid host:port address",
+                index,
+                peer
+            )
+        })?;
+        if port == node.port && is_local_host(&host) {
+            return Err(anyhow::anyhow!(
+                "distributed.node.nodes[{}] '{}' is this node's own address (port {})",
+                index,
+                peer,
+                node.port
+            ));
+        }
+        if !addresses.insert((host.to_ascii_lowercase(), port)) {
+            return Err(anyhow::anyhow!(
+                "distributed.node.nodes[{}] '{}' duplicates an earlier peer address",
+                index,
+                peer
+            ));
+        }
+        if let Some(node_id) = node_id {
+            if node_id.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "distributed.node.nodes[{}] '{}' has an empty node id",
+                    index,
+                    peer
+                ));
+            }
+            if !node_ids.insert(node_id) {
+                warn!(
+                    "distributed.node.nodes[{}] '{}' reuses node id '{}'",
+                    index, peer, node_id
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Get current configuration's testnet status
 pub fn is_testnet(config: &crate::config::types::Config) -> bool {
     config.testnet || config.is_testnet
@@ -7,15 +7,357 @@ pub struct StartupConfig {
     pub daa_score_range: Vec<[u64; 2]>,
     #[serde(rename = "tickReserved")]
     pub tick_reserved: Vec<String>,
+    /// Largest number of `tickReserved` entries accepted, bounding the reserved tick map
+    #[serde(
+        rename = "tickReservedMax",
+        alias = "tick_reserved_max",
+        default = "default_tick_reserved_max"
+    )]
+    pub tick_reserved_max: usize,
+    /// DAA score from which reserved ticks that are still not deployed can be deployed by anyone,
+    /// 0 keeps reservations forever
+    #[serde(
+        rename = "tickReservedExpiryDaa",
+        alias = "tick_reserved_expiry_daa",
+        default = "default_tick_reserved_expiry_daa"
+    )]
+    pub tick_reserved_expiry_daa: u64,
+    /// Unlocked balances below this amount, in the token's smallest unit, are burned and their
+    /// entry deleted after an operation leaves them, 0 keeps every balance
+    #[serde(
+        rename = "balanceDustThreshold",
+        alias = "balance_dust_threshold",
+        default = "default_balance_dust_threshold"
+    )]
+    pub balance_dust_threshold: u64,
+    /// Ticks that can never be deployed, defaults to well-known stablecoin/wrapped tickers
+    #[serde(
+        rename = "tickIgnored",
+        alias = "tick_ignored",
+        default = "default_tick_ignored"
+    )]
+    pub tick_ignored: Vec<String>,
     #[serde(rename = "kaspaNodeURL")]
     pub kaspa_node_url: String,
     #[serde(rename = "isTestnet")]
     pub is_testnet: bool,
+    /// Target duration (milliseconds) of one scan loop once synced
+    #[serde(rename = "scanIntervalMs", default = "default_scan_interval_ms")]
+    pub scan_interval_ms: u64,
+    /// Number of DAA scores rescanned behind the last block to detect VSPC reorganizations
+    #[serde(
+        rename = "vspcCheckWindow",
+        alias = "vspc_check_window",
+        default = "default_vspc_check_window"
+    )]
+    pub vspc_check_window: usize,
+    /// Maximum number of node block requests in flight while fetching a batch
+    #[serde(
+        rename = "txFetchConcurrency",
+        alias = "tx_fetch_concurrency",
+        default = "default_tx_fetch_concurrency"
+    )]
+    pub tx_fetch_concurrency: usize,
+    /// Pause (milliseconds) before re-checking a node whose tip fell behind the indexed chain
+    #[serde(
+        rename = "nodeResetPauseMs",
+        alias = "node_reset_pause_ms",
+        default = "default_node_reset_pause_ms"
+    )]
+    pub node_reset_pause_ms: u64,
+    /// op_score slots per DAA score, bounds the operations a single DAA score can hold.
+    /// Keys of indexed operations depend on it, so it cannot change once data exists.
+    #[serde(
+        rename = "opScoreMultiplier",
+        alias = "op_score_multiplier",
+        default = "default_op_score_multiplier"
+    )]
+    pub op_score_multiplier: u64,
+    /// Interval (milliseconds) at which runtime counters are persisted, also while no batch is written
+    #[serde(
+        rename = "runtimeFlushIntervalMs",
+        alias = "runtime_flush_interval_ms",
+        default = "default_runtime_flush_interval_ms"
+    )]
+    pub runtime_flush_interval_ms: u64,
+    /// Operation types executed, empty executes every type. Operations of other types keep their
+    /// op_score but are stored unexecuted, so state of ticks they touch diverges from a full indexer.
+    #[serde(
+        rename = "enabledOpTypes",
+        alias = "enabled_op_types",
+        default = "default_enabled_op_types"
+    )]
+    pub enabled_op_types: Vec<String>,
+    /// Accept scripts of unregistered operation types and store them unexecuted instead of
+    /// rejecting them. Only meant for forward-compatibility testing against newer protocols.
+    #[serde(
+        rename = "allowUnknownOps",
+        alias = "allow_unknown_ops",
+        default = "default_allow_unknown_ops"
+    )]
+    pub allow_unknown_ops: bool,
+    /// Hash algorithm of sealed checkpoints (blake3, sha256), set to match the implementation
+    /// checkpoints are compared with. Each checkpoint stores the algorithm it was computed with.
+    #[serde(
+        rename = "checkpointHash",
+        alias = "checkpoint_hash",
+        default = "default_checkpoint_hash"
+    )]
+    pub checkpoint_hash: String,
+    /// Retries of a failed state or operation write batch before the batch is given up
+    #[serde(
+        rename = "batchMaxRetries",
+        alias = "batch_max_retries",
+        default = "default_batch_max_retries"
+    )]
+    pub batch_max_retries: u32,
+    /// Backoff (milliseconds) before the first retry of a write batch, doubled on each
+    /// further retry and jittered
+    #[serde(
+        rename = "batchRetryBackoffMs",
+        alias = "batch_retry_backoff_ms",
+        default = "default_batch_retry_backoff_ms"
+    )]
+    pub batch_retry_backoff_ms: u64,
+    /// Durability preset (fast, safe) overriding the individual RocksDB and flush settings it
+    /// bundles, empty keeps each setting as configured. See `ConsistencyMode::apply`.
+    #[serde(
+        rename = "consistencyMode",
+        alias = "consistency_mode",
+        default = "default_consistency_mode"
+    )]
+    pub consistency_mode: String,
+}
+
+pub fn default_tick_reserved_max() -> usize {
+    1000
+}
+
+pub fn default_tick_reserved_expiry_daa() -> u64 {
+    0
+}
+
+pub fn default_balance_dust_threshold() -> u64 {
+    0
+}
+
+pub fn default_enabled_op_types() -> Vec<String> {
+    Vec::new()
+}
+
+pub fn default_allow_unknown_ops() -> bool {
+    false
+}
+
+pub fn default_checkpoint_hash() -> String {
+    "blake3".to_string()
+}
+
+pub fn default_consistency_mode() -> String {
+    String::new()
+}
+
+/// Bundled durability settings, trading crash consistency for sync throughput
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsistencyMode {
+    /// Initial sync throughput: unsynced writes, deferred compaction, rare runtime flushes
+    Fast,
+    /// Steady-state crash consistency: synced writes and fsync, frequent runtime flushes
+    Safe,
+}
+
+impl ConsistencyMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "fast" => Some(ConsistencyMode::Fast),
+            "safe" => Some(ConsistencyMode::Safe),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConsistencyMode::Fast => "fast",
+            ConsistencyMode::Safe => "safe",
+        }
+    }
+
+    /// Override the settings the mode bundles:
+    /// `rocksdb.syncWrites`, `rocksdb.useFsync`, `rocksdb.bulkLoad`,
+    /// `rocksdb.stallPendingCompactionMB`, `rocksdb.stallResumePendingCompactionMB` and
+    /// `startup.runtimeFlushIntervalMs`
+    pub fn apply(&self, config: &mut Config) {
+        let rocksdb = &mut config.rocksdb;
+        match self {
+            ConsistencyMode::Fast => {
+                rocksdb.sync_writes = false;
+                rocksdb.use_fsync = false;
+                rocksdb.bulk_load = true;
+                // Let compaction fall further behind before the scanner pauses
+                rocksdb.stall_pending_compaction_mb = 256 * 1024;
+                rocksdb.stall_resume_pending_compaction_mb = 128 * 1024;
+                config.startup.runtime_flush_interval_ms = 30000;
+            }
+            ConsistencyMode::Safe => {
+                rocksdb.sync_writes = true;
+                rocksdb.use_fsync = true;
+                rocksdb.bulk_load = false;
+                rocksdb.stall_pending_compaction_mb = default_stall_pending_compaction_mb();
+                rocksdb.stall_resume_pending_compaction_mb =
+                    default_stall_resume_pending_compaction_mb();
+                config.startup.runtime_flush_interval_ms = 1000;
+            }
+        }
+    }
+}
+
+pub fn default_batch_max_retries() -> u32 {
+    5
+}
+
+pub fn default_batch_retry_backoff_ms() -> u64 {
+    10
+}
+
+/// Caps the synced scan loop at roughly 1.2 iterations per second
+pub fn default_scan_interval_ms() -> u64 {
+    850
+}
+
+pub fn default_vspc_check_window() -> usize {
+    crate::storage::types::LEN_VSPC_CHECK
+}
+
+pub fn default_tx_fetch_concurrency() -> usize {
+    16
+}
+
+pub fn default_node_reset_pause_ms() -> u64 {
+    30000
+}
+
+pub fn default_op_score_multiplier() -> u64 {
+    crate::storage::types::OP_SCORE_MULTIPLIER_DEFAULT
+}
+
+pub fn default_runtime_flush_interval_ms() -> u64 {
+    10000
+}
+
+pub fn default_shutdown_timeout_ms() -> u64 {
+    30000
+}
+
+pub fn default_tick_ignored() -> Vec<String> {
+    [
+        "KASPA", "KASPLX", "KASP", "WKAS", "GIGA", "WBTC", "WETH", "USDT", "USDC", "FDUSD", "USDD",
+        "TUSD", "USDP", "PYUSD", "EURC", "BUSD", "GUSD", "EURT", "XAUT", "TETHER",
+    ]
+    .iter()
+    .map(|tick| tick.to_string())
+    .collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RocksConfig {
     pub path: String,
+    /// Free space (MB) below which storage is reported unhealthy
+    #[serde(rename = "minFreeSpaceMB", default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+    /// Free space (MB) below which new batches are refused
+    #[serde(
+        rename = "criticalFreeSpaceMB",
+        default = "default_critical_free_space_mb"
+    )]
+    pub critical_free_space_mb: u64,
+    /// Free inodes below which storage is reported unhealthy
+    #[serde(rename = "minFreeInodes", default = "default_min_free_inodes")]
+    pub min_free_inodes: u64,
+    /// Free inodes below which new batches are refused
+    #[serde(rename = "criticalFreeInodes", default = "default_critical_free_inodes")]
+    pub critical_free_inodes: u64,
+    /// Disk space check interval (seconds)
+    #[serde(rename = "diskCheckInterval", default = "default_disk_check_interval")]
+    pub disk_check_interval: u64,
+    /// Holder count reconciliation interval (seconds), 0 disables it
+    #[serde(
+        rename = "holderReconcileInterval",
+        default = "default_holder_reconcile_interval"
+    )]
+    pub holder_reconcile_interval: u64,
+    /// Pending compaction (MB) at or above which the scanner backs off
+    #[serde(
+        rename = "stallPendingCompactionMB",
+        default = "default_stall_pending_compaction_mb"
+    )]
+    pub stall_pending_compaction_mb: u64,
+    /// Pending compaction (MB) below which a scanner paused on a write stall resumes
+    #[serde(
+        rename = "stallResumePendingCompactionMB",
+        default = "default_stall_resume_pending_compaction_mb"
+    )]
+    pub stall_resume_pending_compaction_mb: u64,
+    /// Immutable memtables waiting for flush at or above which the scanner backs off
+    #[serde(
+        rename = "stallImmutableMemtables",
+        default = "default_stall_immutable_memtables"
+    )]
+    pub stall_immutable_memtables: u64,
+    /// Scanner backoff (milliseconds) while writes are stalled
+    #[serde(rename = "stallBackoffMs", default = "default_stall_backoff_ms")]
+    pub stall_backoff_ms: u64,
+    /// Open with auto compaction disabled for initial sync, compacted once the scanner is synced.
+    /// The WAL stays on so a crash mid-sync recovers like normal mode, only compaction is deferred.
+    #[serde(rename = "bulkLoad", alias = "bulk_load", default)]
+    pub bulk_load: bool,
+    /// Sync the WAL on every batch write, so a machine crash loses no written batch
+    #[serde(rename = "syncWrites", alias = "sync_writes", default)]
+    pub sync_writes: bool,
+    /// Sync files with fsync rather than fdatasync
+    #[serde(
+        rename = "useFsync",
+        alias = "use_fsync",
+        default = "default_use_fsync"
+    )]
+    pub use_fsync: bool,
+}
+
+fn default_min_free_space_mb() -> u64 {
+    2048
+}
+fn default_critical_free_space_mb() -> u64 {
+    512
+}
+fn default_min_free_inodes() -> u64 {
+    100000
+}
+fn default_critical_free_inodes() -> u64 {
+    10000
+}
+fn default_disk_check_interval() -> u64 {
+    30
+}
+fn default_holder_reconcile_interval() -> u64 {
+    // Every balance is scanned, drift is rare enough for a pass every 6 hours
+    6 * 3600
+}
+fn default_stall_pending_compaction_mb() -> u64 {
+    // RocksDB default soft_pending_compaction_bytes_limit
+    64 * 1024
+}
+fn default_stall_resume_pending_compaction_mb() -> u64 {
+    // Half the stall threshold, so a pause lasts until compaction made real progress
+    32 * 1024
+}
+fn default_stall_immutable_memtables() -> u64 {
+    // One less than max_write_buffer_number, the next full memtable would stop writes
+    3
+}
+fn default_stall_backoff_ms() -> u64 {
+    1000
+}
+fn default_use_fsync() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -30,6 +372,156 @@ pub struct RestConfig {
 
 fn default_kaspa_rest_base_url() -> String { "https://api-tn10.kaspa.org".to_string() }
 
+/// Per-tick mint rate limit, counted per DAA score window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintRateLimitConfig {
+    /// Window size in DAA score
+    #[serde(default = "default_mint_rate_window")]
+    pub window: u64,
+    /// Max mints per tick per window for all ticks, 0 means unlimited
+    #[serde(rename = "maxMints", default)]
+    pub max_mints: u64,
+    /// Per-tick overrides in "TICK_maxMints" format
+    #[serde(rename = "tickLimits", default)]
+    pub tick_limits: Vec<String>,
+}
+
+fn default_mint_rate_window() -> u64 {
+    600
+}
+
+/// Maximum byte lengths of string fields in parsed scripts, bounding the state a script can add
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptLimitsConfig {
+    /// Token name of issue mode deploys
+    #[serde(rename = "maxNameLen", default = "default_max_name_len")]
+    pub max_name_len: usize,
+    /// Sender and receiver addresses
+    #[serde(rename = "maxAddressLen", default = "default_max_address_len")]
+    pub max_address_len: usize,
+    /// Any other string field, ticks and amounts keep their own stricter validation
+    #[serde(rename = "maxFieldLen", default = "default_max_field_len")]
+    pub max_field_len: usize,
+    /// Token description set by the update operation
+    #[serde(rename = "maxDescLen", default = "default_max_desc_len")]
+    pub max_desc_len: usize,
+}
+
+fn default_max_name_len() -> usize {
+    32
+}
+
+fn default_max_address_len() -> usize {
+    128
+}
+
+fn default_max_field_len() -> usize {
+    256
+}
+
+fn default_max_desc_len() -> usize {
+    256
+}
+
+/// Warm standby: a follower applies the leader's event log instead of scanning the node,
+/// and starts scanning itself once the leader stops answering
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandbyConfig {
+    /// Run as a follower of `leader_url`
+    #[serde(default)]
+    pub enabled: bool,
+    /// HTTP base URL of the leader indexer
+    #[serde(rename = "leaderUrl", alias = "leader_url", default)]
+    pub leader_url: String,
+    #[serde(
+        rename = "pollIntervalMs",
+        alias = "poll_interval_ms",
+        default = "default_standby_poll_interval_ms"
+    )]
+    pub poll_interval_ms: u64,
+    /// Promote to scanning once the leader has been unreachable this long
+    #[serde(
+        rename = "failoverTimeoutMs",
+        alias = "failover_timeout_ms",
+        default = "default_standby_failover_timeout_ms"
+    )]
+    pub failover_timeout_ms: u64,
+    /// Events the leader keeps for its followers, 0 disables the event log
+    #[serde(rename = "eventRetention", alias = "event_retention", default)]
+    pub event_retention: u64,
+}
+
+fn default_standby_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_standby_failover_timeout_ms() -> u64 {
+    30000
+}
+
+/// Webhook notified of deep reorgs and rejected operation spikes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    /// URL alerts are POSTed to, alerting is disabled if empty
+    #[serde(rename = "webhookUrl", alias = "webhook_url", default)]
+    pub webhook_url: String,
+    /// Alert on reorgs replacing more VSPC blocks than this
+    #[serde(
+        rename = "reorgDepth",
+        alias = "reorg_depth",
+        default = "default_alert_reorg_depth"
+    )]
+    pub reorg_depth: u64,
+    /// Alert on batches rejecting more than this fraction of their operations
+    #[serde(
+        rename = "rejectedRate",
+        alias = "rejected_rate",
+        default = "default_alert_rejected_rate"
+    )]
+    pub rejected_rate: f64,
+    /// Batches with fewer operations never raise a rejected operation alert
+    #[serde(
+        rename = "rejectedMinOps",
+        alias = "rejected_min_ops",
+        default = "default_alert_rejected_min_ops"
+    )]
+    pub rejected_min_ops: usize,
+    /// Retries of a failed POST
+    #[serde(
+        rename = "maxRetries",
+        alias = "max_retries",
+        default = "default_alert_max_retries"
+    )]
+    pub max_retries: u32,
+    /// Pause (milliseconds) before retrying a failed POST
+    #[serde(
+        rename = "retryBackoffMs",
+        alias = "retry_backoff_ms",
+        default = "default_alert_retry_backoff_ms"
+    )]
+    pub retry_backoff_ms: u64,
+}
+
+fn default_alert_reorg_depth() -> u64 {
+    10
+}
+
+fn default_alert_rejected_rate() -> f64 {
+    0.5
+}
+
+fn default_alert_rejected_min_ops() -> usize {
+    100
+}
+
+fn default_alert_max_retries() -> u32 {
+    3
+}
+
+fn default_alert_retry_backoff_ms() -> u64 {
+    1000
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpConfig {
     /// Listen address
@@ -38,6 +530,42 @@ pub struct HttpConfig {
     /// Listen port
     #[serde(default = "default_http_port")]
     pub port: u16,
+    /// Admin token required by admin endpoints, admin endpoints are disabled if empty
+    #[serde(default)]
+    pub admin_token: String,
+    /// Upper bound of `?limit=` on list endpoints, larger limits are clamped to it
+    #[serde(default = "default_max_page_size")]
+    pub max_page_size: usize,
+    /// `Cache-Control` durations of the read endpoints
+    #[serde(default)]
+    pub cache_control: CacheControlConfig,
+    /// Serve `/v1/debug` developer endpoints, keep disabled in production
+    #[serde(default)]
+    pub debug_endpoints: bool,
+    /// Seconds listed rollback candidates are cached, listing scans all operations
+    #[serde(default = "default_rollback_candidates_cache_secs")]
+    pub rollback_candidates_cache_secs: u64,
+    /// Read requests served concurrently, further reads are rejected with 503
+    #[serde(default = "default_max_concurrent_reads")]
+    pub max_concurrent_reads: usize,
+    /// `ip:port` of a separate listener serving `/v1/metrics` and `/v1/admin`, which are then
+    /// no longer served on the public listener. Empty serves all routes on the public listener.
+    #[serde(default)]
+    pub admin_bind: String,
+}
+
+/// `Cache-Control` max-ages, in seconds, per endpoint freshness class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheControlConfig {
+    /// Max-age of `/v1/info`
+    #[serde(default = "default_info_max_age")]
+    pub info_max_age: u64,
+    /// Max-age of operations that are final and can no longer change
+    #[serde(default = "default_final_op_max_age")]
+    pub final_op_max_age: u64,
+    /// DAA scores an operation must lie behind the last processed DAA score to be final
+    #[serde(default = "default_finality_depth")]
+    pub finality_depth: u64,
 }
 
 fn default_bind_addr() -> String {
@@ -46,6 +574,24 @@ fn default_bind_addr() -> String {
 fn default_http_port() -> u16 {
     8080
 }
+fn default_max_page_size() -> usize {
+    500
+}
+fn default_max_concurrent_reads() -> usize {
+    64
+}
+fn default_rollback_candidates_cache_secs() -> u64 {
+    10
+}
+fn default_info_max_age() -> u64 {
+    5
+}
+fn default_final_op_max_age() -> u64 {
+    86400
+}
+fn default_finality_depth() -> u64 {
+    86400
+}
 
 /// Distributed storage node configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -115,6 +661,17 @@ pub struct ReplicationConfig {
     /// Compression level (1-9)
     #[serde(rename = "compressionLevel")]
     pub compression_level: u8,
+    /// Codec used when compression is enabled (zstd, lz4, snappy)
+    #[serde(
+        rename = "compressionCodec",
+        alias = "compression_codec",
+        default = "default_compression_codec"
+    )]
+    pub compression_codec: String,
+}
+
+pub fn default_compression_codec() -> String {
+    "zstd".to_string()
 }
 
 /// Consistent hashing configuration
@@ -245,6 +802,21 @@ pub struct Config {
     pub http: HttpConfig,
     #[serde(default)]
     pub rest: RestConfig,
+    #[serde(rename = "mintRateLimit", default)]
+    pub mint_rate_limit: MintRateLimitConfig,
+    #[serde(rename = "scriptLimits", default)]
+    pub script_limits: ScriptLimitsConfig,
+    #[serde(default)]
+    pub standby: StandbyConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    /// Time (milliseconds) storage gets to shut down before the process force-exits
+    #[serde(
+        rename = "shutdownTimeoutMs",
+        alias = "shutdown_timeout_ms",
+        default = "default_shutdown_timeout_ms"
+    )]
+    pub shutdown_timeout_ms: u64,
     pub debug: u8,
     pub testnet: bool,
     #[serde(rename = "isTestnet")]
@@ -257,8 +829,24 @@ impl Default for StartupConfig {
             hysteresis: 3,
             daa_score_range: vec![],
             tick_reserved: vec![],
+            tick_reserved_max: default_tick_reserved_max(),
+            tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+            balance_dust_threshold: default_balance_dust_threshold(),
+            tick_ignored: default_tick_ignored(),
             kaspa_node_url: "http://localhost:16110".to_string(),
             is_testnet: false,
+            scan_interval_ms: default_scan_interval_ms(),
+            vspc_check_window: default_vspc_check_window(),
+            tx_fetch_concurrency: default_tx_fetch_concurrency(),
+            node_reset_pause_ms: default_node_reset_pause_ms(),
+            op_score_multiplier: default_op_score_multiplier(),
+            runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
+            enabled_op_types: default_enabled_op_types(),
+            allow_unknown_ops: default_allow_unknown_ops(),
+            checkpoint_hash: default_checkpoint_hash(),
+            batch_max_retries: default_batch_max_retries(),
+            batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+            consistency_mode: default_consistency_mode(),
         }
     }
 }
@@ -267,6 +855,19 @@ impl Default for RocksConfig {
     fn default() -> Self {
         Self {
             path: "./data".to_string(),
+            min_free_space_mb: default_min_free_space_mb(),
+            critical_free_space_mb: default_critical_free_space_mb(),
+            min_free_inodes: default_min_free_inodes(),
+            critical_free_inodes: default_critical_free_inodes(),
+            disk_check_interval: default_disk_check_interval(),
+            holder_reconcile_interval: default_holder_reconcile_interval(),
+            stall_pending_compaction_mb: default_stall_pending_compaction_mb(),
+            stall_resume_pending_compaction_mb: default_stall_resume_pending_compaction_mb(),
+            stall_immutable_memtables: default_stall_immutable_memtables(),
+            stall_backoff_ms: default_stall_backoff_ms(),
+            bulk_load: false,
+            sync_writes: false,
+            use_fsync: default_use_fsync(),
         }
     }
 }
@@ -276,6 +877,69 @@ impl Default for HttpConfig {
         Self {
             bind: default_bind_addr(),
             port: default_http_port(),
+            admin_token: String::new(),
+            max_page_size: default_max_page_size(),
+            cache_control: CacheControlConfig::default(),
+            debug_endpoints: false,
+            rollback_candidates_cache_secs: default_rollback_candidates_cache_secs(),
+            max_concurrent_reads: default_max_concurrent_reads(),
+            admin_bind: String::new(),
+        }
+    }
+}
+
+impl Default for CacheControlConfig {
+    fn default() -> Self {
+        Self {
+            info_max_age: default_info_max_age(),
+            final_op_max_age: default_final_op_max_age(),
+            finality_depth: default_finality_depth(),
+        }
+    }
+}
+
+impl Default for MintRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            window: default_mint_rate_window(),
+            max_mints: 0,
+            tick_limits: vec![],
+        }
+    }
+}
+
+impl Default for StandbyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            leader_url: String::new(),
+            poll_interval_ms: default_standby_poll_interval_ms(),
+            failover_timeout_ms: default_standby_failover_timeout_ms(),
+            event_retention: 0,
+        }
+    }
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: String::new(),
+            reorg_depth: default_alert_reorg_depth(),
+            rejected_rate: default_alert_rejected_rate(),
+            rejected_min_ops: default_alert_rejected_min_ops(),
+            max_retries: default_alert_max_retries(),
+            retry_backoff_ms: default_alert_retry_backoff_ms(),
+        }
+    }
+}
+
+impl Default for ScriptLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_name_len: default_max_name_len(),
+            max_address_len: default_max_address_len(),
+            max_field_len: default_max_field_len(),
+            max_desc_len: default_max_desc_len(),
         }
     }
 }
@@ -318,6 +982,7 @@ impl Default for ReplicationConfig {
             retry_interval: 5,
             enable_compression: true,
             compression_level: 6,
+            compression_codec: default_compression_codec(),
         }
     }
 }
@@ -399,6 +1064,11 @@ impl Default for Config {
             distributed: DistributedConfig::default(),
             http: HttpConfig::default(),
             rest: RestConfig::default(),
+            mint_rate_limit: MintRateLimitConfig::default(),
+            script_limits: ScriptLimitsConfig::default(),
+            standby: StandbyConfig::default(),
+            alerts: AlertsConfig::default(),
+            shutdown_timeout_ms: default_shutdown_timeout_ms(),
             debug: 2,
             testnet: false,
             is_testnet: false,
@@ -0,0 +1,152 @@
+use crate::config::types::AlertsConfig;
+use crate::storage::types::{DataOperationType, DataVspcType};
+use reqwest::Client;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Event reported to the alert webhook, tagged by `event`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(
+    tag = "event",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AlertEvent {
+    /// A reorg replaced `depth` VSPC blocks from `daa_score` on, forking from `fork_hash` when
+    /// the fork is still in the runtime list
+    Reorg {
+        daa_score: u64,
+        depth: u64,
+        fork_hash: Option<String>,
+        fork_daa_score: Option<u64>,
+    },
+    /// A batch rejected `rejected` of its `total` operations
+    RejectedSpike {
+        daa_score_start: u64,
+        daa_score_end: u64,
+        rejected: usize,
+        total: usize,
+        rate: f64,
+    },
+}
+
+/// Body of an alert POST
+#[derive(Debug, Serialize)]
+struct AlertPayload<'a> {
+    /// Milliseconds since the epoch the alert was raised at
+    timestamp: u64,
+    #[serde(flatten)]
+    event: &'a AlertEvent,
+}
+
+/// Posts alerts to the configured webhook. Every POST runs in its own task, so a slow or
+/// failing webhook never holds up the scanner.
+#[derive(Debug, Clone, Default)]
+pub struct AlertSender {
+    config: AlertsConfig,
+    client: Client,
+}
+
+impl AlertSender {
+    pub fn new(config: AlertsConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+
+    /// Alerting is enabled once a webhook URL is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.config.webhook_url.is_empty()
+    }
+
+    /// Alert on a reorg replacing more than `reorgDepth` VSPC blocks
+    pub fn check_reorg(
+        &self,
+        daa_score: u64,
+        depth: u64,
+        fork: Option<&DataVspcType>,
+    ) -> Option<JoinHandle<()>> {
+        if depth <= self.config.reorg_depth {
+            return None;
+        }
+        self.send(AlertEvent::Reorg {
+            daa_score,
+            depth,
+            fork_hash: fork.map(|fork| fork.hash.clone()),
+            fork_daa_score: fork.map(|fork| fork.daa_score),
+        })
+    }
+
+    /// Alert on a batch of at least `rejectedMinOps` operations rejecting more than
+    /// `rejectedRate` of them
+    pub fn check_rejected(
+        &self,
+        op_data_list: &[DataOperationType],
+        daa_score_start: u64,
+        daa_score_end: u64,
+    ) -> Option<JoinHandle<()>> {
+        let total = op_data_list.len();
+        if total == 0 || total < self.config.rejected_min_ops {
+            return None;
+        }
+        let rejected = op_data_list
+            .iter()
+            .filter(|op_data| op_data.op_accept < 0)
+            .count();
+        let rate = rejected as f64 / total as f64;
+        if rate <= self.config.rejected_rate {
+            return None;
+        }
+        self.send(AlertEvent::RejectedSpike {
+            daa_score_start,
+            daa_score_end,
+            rejected,
+            total,
+            rate,
+        })
+    }
+
+    /// POST `event` in the background, retrying failures up to `maxRetries` times.
+    /// Returns None when alerting is disabled.
+    pub fn send(&self, event: AlertEvent) -> Option<JoinHandle<()>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let client = self.client.clone();
+        let config = self.config.clone();
+        Some(tokio::spawn(async move {
+            let payload = AlertPayload {
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+                event: &event,
+            };
+            for attempt in 0..=config.max_retries {
+                if attempt > 0 {
+                    tokio::time::sleep(Duration::from_millis(config.retry_backoff_ms)).await;
+                }
+                match client.post(&config.webhook_url).json(&payload).send().await {
+                    Ok(response) if response.status().is_success() => {
+                        info!("Alert sent: {:?}", event);
+                        return;
+                    }
+                    Ok(response) => warn!(
+                        "Alert webhook answered {}, attempt {}",
+                        response.status(),
+                        attempt + 1
+                    ),
+                    Err(e) => warn!("Alert webhook failed, attempt {}: {}", attempt + 1, e),
+                }
+            }
+            warn!(
+                "Alert dropped after {} attempts: {:?}",
+                config.max_retries + 1,
+                event
+            );
+        }))
+    }
+}
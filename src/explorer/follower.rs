@@ -0,0 +1,124 @@
+use crate::config::types::StandbyConfig;
+use crate::storage::StorageManager;
+use crate::storage::events::EventEntry;
+use anyhow::Result;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+#[derive(Debug, Deserialize)]
+struct EventsResponse {
+    data: Vec<EventEntry>,
+}
+
+/// Warm standby follower, applies the leader's event log instead of scanning the node
+pub struct Follower {
+    storage: Arc<StorageManager>,
+    config: StandbyConfig,
+    client: reqwest::Client,
+    op_score: u64,
+}
+
+impl Follower {
+    /// Resume from the last leader event applied to `storage`
+    pub async fn new(storage: Arc<StorageManager>, config: StandbyConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.failover_timeout_ms))
+            .build()?;
+        let (_, op_score) = storage.runtime.get_runtime_follower_cursor().await?;
+        Ok(Self {
+            storage,
+            config,
+            client,
+            op_score,
+        })
+    }
+
+    /// op_score reached by the applied events
+    pub fn op_score(&self) -> u64 {
+        self.op_score
+    }
+
+    /// Events following the cursor, None while the leader is unreachable
+    async fn fetch_events(&self) -> Result<Option<Vec<EventEntry>>> {
+        let (seq, _) = self.storage.runtime.get_runtime_follower_cursor().await?;
+        let url = format!(
+            "{}/v1/events?after={}",
+            self.config.leader_url.trim_end_matches('/'),
+            seq
+        );
+        let response = match self.client.get(&url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("standby.Poll leader unreachable: {}", e);
+                return Ok(None);
+            }
+        };
+        let status = response.status();
+        if status.is_server_error() {
+            warn!("standby.Poll leader status: {}", status);
+            return Ok(None);
+        }
+        if !status.is_success() {
+            // E.g. the follower fell behind the leader's event retention, it needs a fresh copy
+            return Err(anyhow::anyhow!(
+                "Leader rejected event request with {}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        Ok(Some(response.json::<EventsResponse>().await?.data))
+    }
+
+    /// Fetch and apply the next leader events, returns how many were applied,
+    /// None while the leader is unreachable
+    pub async fn poll_once(&mut self) -> Result<Option<usize>> {
+        let Some(entries) = self.fetch_events().await? else {
+            return Ok(None);
+        };
+        self.op_score = self
+            .storage
+            .apply_follower_events(&entries, self.op_score)
+            .await?;
+        if !entries.is_empty() {
+            debug!(
+                "standby.Poll applied: {} opScore: {}",
+                entries.len(),
+                self.op_score
+            );
+        }
+        Ok(Some(entries.len()))
+    }
+
+    /// Follow the leader until it has been unreachable for failoverTimeoutMs,
+    /// then return the op_score the promoted scanner continues from
+    pub async fn run(&mut self) -> Result<u64> {
+        info!(
+            "standby.Run leader: {} opScore: {}",
+            self.config.leader_url, self.op_score
+        );
+        let failover_timeout = Duration::from_millis(self.config.failover_timeout_ms);
+        let mut last_contact = Instant::now();
+        loop {
+            match self.poll_once().await? {
+                // More events may be waiting, poll again right away
+                Some(applied) if applied > 0 => {
+                    last_contact = Instant::now();
+                    continue;
+                }
+                Some(_) => last_contact = Instant::now(),
+                None if last_contact.elapsed() >= failover_timeout => {
+                    info!(
+                        "standby.Run leader unreachable for {}ms, promoting at opScore: {}",
+                        self.config.failover_timeout_ms, self.op_score
+                    );
+                    return Ok(self.op_score);
+                }
+                None => {}
+            }
+            sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+        }
+    }
+}
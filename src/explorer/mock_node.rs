@@ -0,0 +1,131 @@
+use crate::explorer::node_client::NodeClient;
+use crate::storage::types::*;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Scripted VSPC block, transactions carry the node's verbose transaction JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockBlock {
+    pub daa_score: u64,
+    pub hash: String,
+    #[serde(default)]
+    pub transactions: Vec<MockTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockTransaction {
+    pub tx_id: String,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+struct MockChain {
+    blocks: Vec<MockBlock>,
+    // Reported tip, the last block's DAA score when unset
+    tip_daa_score: Option<u64>,
+    // Hashes of blocks reorganized away, until taken by the scanner
+    removed_hashes: Vec<String>,
+}
+
+/// Node client serving a scripted selected parent chain, for deterministic scanner tests.
+/// Clones share the chain so a test can reorganize it while the scanner owns a handle.
+#[derive(Debug, Clone, Default)]
+pub struct MockNodeClient {
+    chain: Arc<Mutex<MockChain>>,
+}
+
+impl MockNodeClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a block to the selected parent chain
+    pub fn push_block(&self, block: MockBlock) {
+        self.chain.lock().unwrap().blocks.push(block);
+    }
+
+    /// Replace the chain from `daa_score` on with `blocks`, as a VSPC reorganization does
+    pub fn reorg(&self, daa_score: u64, blocks: Vec<MockBlock>) {
+        {
+            let mut chain = self.chain.lock().unwrap();
+            let (removed, kept): (Vec<MockBlock>, Vec<MockBlock>) =
+                std::mem::take(&mut chain.blocks)
+                    .into_iter()
+                    .partition(|block| block.daa_score >= daa_score);
+            chain.blocks = kept;
+            // Removed chain blocks are reported highest first, as kaspad does
+            chain
+                .removed_hashes
+                .extend(removed.into_iter().rev().map(|block| block.hash));
+        }
+        for block in blocks {
+            self.push_block(block);
+        }
+    }
+
+    /// Override the reported tip, e.g. to simulate a node re-synced from scratch
+    pub fn set_tip_daa_score(&self, tip_daa_score: Option<u64>) {
+        self.chain.lock().unwrap().tip_daa_score = tip_daa_score;
+    }
+}
+
+impl NodeClient for MockNodeClient {
+    async fn get_vspc_list(&self, daa_score_start: u64, limit: usize) -> Result<Vec<DataVspcType>> {
+        let chain = self.chain.lock().unwrap();
+        // Each block's selected parent is the block before it on the chain
+        let selected_parents = std::iter::once(String::new())
+            .chain(chain.blocks.iter().map(|block| block.hash.clone()));
+        Ok(chain
+            .blocks
+            .iter()
+            .zip(selected_parents)
+            .filter(|(block, _)| block.daa_score >= daa_score_start)
+            .take(limit)
+            .map(|(block, selected_parent)| DataVspcType {
+                daa_score: block.daa_score,
+                hash: block.hash.clone(),
+                tx_id_list: block
+                    .transactions
+                    .iter()
+                    .map(|tx| tx.tx_id.clone())
+                    .collect(),
+                selected_parent,
+            })
+            .collect())
+    }
+
+    async fn get_transaction_data_list(
+        &self,
+        tx_data_list: &[DataTransactionType],
+    ) -> Result<Vec<DataTransactionType>> {
+        // Like kaspad, which has no transaction index, transactions are only found through
+        // the chain block accepting them
+        let chain = self.chain.lock().unwrap();
+        Ok(tx_data_list
+            .iter()
+            .map(|tx_data| DataTransactionType {
+                data: chain
+                    .blocks
+                    .iter()
+                    .filter(|block| block.hash == tx_data.block_accept)
+                    .flat_map(|block| &block.transactions)
+                    .find(|tx| tx.tx_id == tx_data.tx_id)
+                    .map(|tx| tx.data.clone()),
+                ..tx_data.clone()
+            })
+            .collect())
+    }
+
+    async fn get_virtual_daa_score(&self) -> Result<u64> {
+        let chain = self.chain.lock().unwrap();
+        Ok(chain
+            .tip_daa_score
+            .or_else(|| chain.blocks.last().map(|block| block.daa_score))
+            .unwrap_or(0))
+    }
+
+    fn take_removed_chain_block_hashes(&self) -> Vec<String> {
+        std::mem::take(&mut self.chain.lock().unwrap().removed_hashes)
+    }
+}
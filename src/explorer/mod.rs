@@ -1,16 +1,24 @@
+pub mod alert;
+pub mod follower;
+#[cfg(feature = "mock-node")]
+pub mod mock_node;
+pub mod node_client;
 pub mod rollback;
 pub mod scanner;
 pub mod sync;
 pub mod vspc_client;
 
-use crate::config::types::StartupConfig;
+use crate::config::types::{AlertsConfig, StartupConfig};
 use crate::storage::StorageManager;
 use anyhow::Result;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use tokio::sync::mpsc;
 use tracing::{debug, info};
 
 pub use crate::storage::types::OperationData;
+pub use follower::Follower;
+pub use node_client::NodeClient;
 pub use rollback::RollbackManager;
 pub use scanner::VSPCScanner;
 pub use sync::SyncManager;
@@ -70,6 +78,18 @@ impl Explorer {
         })
     }
 
+    /// Post deep reorgs and rejected operation spikes to the `[alerts]` webhook
+    pub fn with_alerts(mut self, config: AlertsConfig) -> Self {
+        self.scanner = self.scanner.with_alerts(config);
+        self
+    }
+
+    /// Keep an event log of the last `retention` scan steps for standby followers
+    pub fn with_event_retention(mut self, retention: u64) -> Self {
+        self.scanner = self.scanner.with_event_retention(retention);
+        self
+    }
+
     /// Get storage manager reference
     pub fn get_storage(&self) -> &Arc<StorageManager> {
         &self.storage
@@ -90,6 +110,12 @@ impl Explorer {
         &self.rollback_manager
     }
 
+    /// Flag ending `run` once the scanner completed its batch in progress, so storage is never
+    /// shut down under a batch write. `shutdown` still has to be called afterwards.
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        self.scanner.stop_handle()
+    }
+
     /// Check if running
     pub fn is_running(&self) -> bool {
         self.is_running
@@ -108,6 +134,9 @@ impl ExplorerInterface for Explorer {
     async fn init(&mut self) -> Result<()> {
         info!("Initializing Explorer...");
 
+        // Finish a rollback interrupted by a crash before the scanner reads the runtime state
+        self.rollback_manager.resume_interrupted_rollback().await?;
+
         // Initialize scanner
         self.scanner.init().await?;
 
@@ -135,8 +164,8 @@ impl ExplorerInterface for Explorer {
         // Start scanning loop
         self.scanner.start_scanning().await?;
 
-        // Process operations
-        while let Some(operation) = self.operation_rx.recv().await {
+        // Process operations queued before scanning stopped
+        while let Ok(operation) = self.operation_rx.try_recv() {
             self.process_operation(operation).await?;
         }
 
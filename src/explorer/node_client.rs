@@ -0,0 +1,57 @@
+use crate::explorer::vspc_client::VSPCClient;
+use crate::storage::types::*;
+use anyhow::Result;
+use std::future::Future;
+
+/// Source of VSPC blocks and transaction data for the scanner
+pub trait NodeClient: Send + Sync {
+    /// Get VSPC data list, corresponding to Go version's GetNodeVspcList
+    fn get_vspc_list(
+        &self,
+        daa_score_start: u64,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<DataVspcType>>> + Send;
+
+    /// Fill in transaction data, corresponding to Go version's GetNodeTransactionDataList.
+    /// Transactions are found through their `block_accept`, without it they get no data.
+    fn get_transaction_data_list(
+        &self,
+        tx_data_list: &[DataTransactionType],
+    ) -> impl Future<Output = Result<Vec<DataTransactionType>>> + Send;
+
+    /// Current tip (virtual DAA score) reported by the node's GetBlockDagInfo
+    fn get_virtual_daa_score(&self) -> impl Future<Output = Result<u64>> + Send;
+
+    /// Remember chain blocks persisted by an earlier run as start points of the next read
+    fn seed_chain(&self, _vspc_list: &[DataVspcType]) {}
+
+    /// Chain blocks the node removed from the selected parent chain since the last call
+    fn take_removed_chain_block_hashes(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl NodeClient for VSPCClient {
+    async fn get_vspc_list(&self, daa_score_start: u64, limit: usize) -> Result<Vec<DataVspcType>> {
+        VSPCClient::get_vspc_list(self, daa_score_start, limit).await
+    }
+
+    async fn get_transaction_data_list(
+        &self,
+        tx_data_list: &[DataTransactionType],
+    ) -> Result<Vec<DataTransactionType>> {
+        VSPCClient::get_transaction_data_list(self, tx_data_list).await
+    }
+
+    async fn get_virtual_daa_score(&self) -> Result<u64> {
+        VSPCClient::get_virtual_daa_score(self).await
+    }
+
+    fn seed_chain(&self, vspc_list: &[DataVspcType]) {
+        VSPCClient::seed_chain(self, vspc_list)
+    }
+
+    fn take_removed_chain_block_hashes(&self) -> Vec<String> {
+        VSPCClient::take_removed_chain_block_hashes(self)
+    }
+}
@@ -1,9 +1,23 @@
+use crate::operations::{FreezeOperation, UpdateOperation};
 use crate::storage::StorageManager;
+use crate::storage::backend::StorageBackend;
+use crate::storage::checkpoint;
+use crate::storage::holders;
+use crate::storage::opscore;
+use crate::storage::state;
+use crate::storage::state_key::{StateKey, StateKeyKind};
+use crate::storage::tickops;
+use crate::storage::tickstats;
+use crate::storage::tokenindex;
 use crate::storage::types::*;
+use crate::storage::versioned;
+use crate::storage::vspcarchive;
 use anyhow::Result;
+use once_cell::sync::Lazy;
 use rocksdb::WriteBatch;
-use std::collections::HashMap;
-use std::sync::Arc;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
 use tracing::{debug, info, warn};
 
 /// Rollback history entry
@@ -18,12 +32,19 @@ pub struct RollbackHistoryEntry {
 }
 
 /// Rollback candidate point
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RollbackCandidate {
     pub block_hash: String,
     pub daa_score: u64,
+    #[serde(skip)]
     pub timestamp: u64,
+    /// Operations accepted by this block
     pub operation_count: usize,
+    /// Operations accepted after this block, which a rollback to it deletes
+    pub rollback_operation_count: usize,
+    /// Stored opdata and oplist bytes of the deleted operations
+    pub estimated_bytes: u64,
+    #[serde(rename = "estimated_duration_ms", serialize_with = "serialize_millis")]
     pub estimated_duration: std::time::Duration,
 }
 
@@ -34,10 +55,27 @@ impl RollbackCandidate {
             daa_score,
             timestamp,
             operation_count,
-            estimated_duration: std::time::Duration::from_millis(0), // Placeholder, actual estimation needed
+            rollback_operation_count: 0,
+            estimated_bytes: 0,
+            estimated_duration: std::time::Duration::ZERO,
         }
     }
 
+    /// Set the cost of rolling back to this block from the operations it deletes and the
+    /// observed rollback time per operation
+    pub fn with_rollback_cost(
+        mut self,
+        operations: usize,
+        bytes: u64,
+        per_operation: std::time::Duration,
+    ) -> Self {
+        self.rollback_operation_count = operations;
+        self.estimated_bytes = bytes;
+        self.estimated_duration =
+            per_operation.saturating_mul(u32::try_from(operations).unwrap_or(u32::MAX));
+        self
+    }
+
     pub fn summary(&self) -> String {
         format!(
             "Block: {} (DAA: {}, Ops: {})",
@@ -46,8 +84,66 @@ impl RollbackCandidate {
     }
 }
 
+fn serialize_millis<S: serde::Serializer>(
+    duration: &std::time::Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
+/// Rollback time per operation assumed until a rollback has been timed
+pub const ROLLBACK_OPERATION_DURATION_DEFAULT: std::time::Duration =
+    std::time::Duration::from_micros(100);
+
+// Completed rollbacks of this process, estimates of new rollbacks are based on them
+static ROLLBACK_TIMINGS: Lazy<Mutex<RollbackTimings>> =
+    Lazy::new(|| Mutex::new(RollbackTimings::default()));
+
+/// Totals of the rollbacks completed since startup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RollbackTimings {
+    pub rollbacks: u64,
+    pub operations: u64,
+    pub duration: std::time::Duration,
+}
+
+impl RollbackTimings {
+    /// Average time of a completed rollback, zero before the first one
+    pub fn average_duration(&self) -> std::time::Duration {
+        if self.rollbacks == 0 {
+            return std::time::Duration::ZERO;
+        }
+        average(self.duration, self.rollbacks)
+    }
+
+    /// Average rollback time of one operation, the default before an operation was rolled back
+    pub fn average_operation_duration(&self) -> std::time::Duration {
+        if self.operations == 0 {
+            return ROLLBACK_OPERATION_DURATION_DEFAULT;
+        }
+        average(self.duration, self.operations)
+    }
+}
+
+fn average(total: std::time::Duration, count: u64) -> std::time::Duration {
+    std::time::Duration::from_nanos((total.as_nanos() / count as u128) as u64)
+}
+
+/// Record a completed rollback of `operations` operations
+pub fn record_rollback_duration(operations: usize, duration: std::time::Duration) {
+    let mut timings = ROLLBACK_TIMINGS.lock().unwrap();
+    timings.rollbacks += 1;
+    timings.operations += operations as u64;
+    timings.duration += duration;
+}
+
+/// Snapshot of the rollback timings recorded since startup
+pub fn get_rollback_timings() -> RollbackTimings {
+    *ROLLBACK_TIMINGS.lock().unwrap()
+}
+
 /// Rollback statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RollbackStatistics {
     pub total_operations: usize,
     pub completed_operations: usize,
@@ -107,13 +203,6 @@ impl RollbackManager {
         Ok(Self { storage })
     }
 
-    // Temporary constructor to avoid circular references
-    pub fn new_dummy() -> Self {
-        Self {
-            storage: Arc::new(StorageManager::new_dummy()),
-        }
-    }
-
     pub fn init(&self) -> Result<()> {
         info!("Rollback manager initialized");
         Ok(())
@@ -124,11 +213,7 @@ impl RollbackManager {
     pub async fn rollback_op_state_batch(&self, rollback: &DataRollbackType) -> Result<i64> {
         let start_time = std::time::Instant::now();
 
-        // Validate rollback data
-        if rollback.op_score_list.is_empty() {
-            return Err(anyhow::anyhow!("Empty rollback operation list"));
-        }
-
+        // Validate rollback data, a batch without operations still restores its state
         if rollback.op_score_list.len() != rollback.tx_id_list.len() {
             return Err(anyhow::anyhow!(
                 "Mismatched operation score and tx ID lists"
@@ -136,11 +221,15 @@ impl RollbackManager {
         }
 
         // Save pre-rollback state to RocksDB (using batch write)
-        let _rocks_duration = self.save_state_batch_rocks_begin(&rollback.state_map_before)?;
+        let _rocks_duration = self
+            .save_state_batch_rocks_begin(&rollback.state_map_before)
+            .await?;
 
         // Delete operation data (using batch write)
-        let _delete_duration =
-            self.delete_op_data_batch_rocks(&rollback.op_score_list, &rollback.tx_id_list)?;
+        if !rollback.op_score_list.is_empty() {
+            self.delete_op_data_batch_rocks(&rollback.op_score_list, &rollback.tx_id_list)
+                .await?;
+        }
 
         let duration = start_time.elapsed().as_millis() as i64;
         info!("Rollback completed in {}ms", duration);
@@ -149,31 +238,37 @@ impl RollbackManager {
 
     /// Delete operation data batch (RocksDB version, improved)
     /// Improvement: Add batch processing and retry mechanism
-    fn delete_op_data_batch_rocks(
+    async fn delete_op_data_batch_rocks(
         &self,
         op_score_list: &[u64],
         tx_id_list: &[String],
     ) -> Result<i64> {
         let start_time = std::time::Instant::now();
 
-        let mut batch = WriteBatch::default();
-
-        // Batch delete operation list
-        for &op_score in op_score_list {
-            let op_range = op_score / OP_RANGE_BY;
-            let key = format!("oplist:{}:{}", op_range, op_score);
-            batch.delete(key.as_bytes());
-        }
-
-        // Batch delete operation data
-        for tx_id in tx_id_list {
-            let key = format!("opdata:{}", tx_id);
-            batch.delete(key.as_bytes());
+        write_batch_with_retry(&self.storage.rocksdb, |batch| {
+            // Batch delete operation data, their derived records are reverted in the same batch
+            let mut op_data_list = Vec::with_capacity(tx_id_list.len());
+            for tx_id in tx_id_list {
+                let key = format!("opdata:{}", tx_id);
+                if let Some(value) = self.storage.rocksdb.get_raw(&key)? {
+                    match versioned::decode::<DataOperationType>(&value) {
+                        Ok(op_data) => op_data_list.push(op_data),
+                        Err(e) => warn!("Tick counters not reverted for {}: {}", tx_id, e),
+                    }
+                }
+                batch.delete(key.as_bytes());
+            }
+            stage_op_records_reverted(&self.storage.rocksdb, batch, op_score_list, &op_data_list)?;
+            opscore::stage_reverted(&self.storage.rocksdb, batch, op_score_list)
+        })
+        .await?;
+        self.storage
+            .runtime
+            .decrement_operations_processed(tx_id_list.len() as u64)?;
+        if let Some(&op_score_start) = op_score_list.iter().min() {
+            checkpoint::unseal_from_op_score(&self.storage.rocksdb, op_score_start)?;
         }
 
-        // Execute batch delete
-        self.storage.rocksdb.write_batch(batch)?;
-
         let duration = start_time.elapsed().as_millis() as i64;
         Ok(duration)
     }
@@ -186,60 +281,71 @@ impl RollbackManager {
     ) -> Result<i64> {
         let start_time = std::time::Instant::now();
 
-        let mut batch = WriteBatch::default();
-
-        // Prepare state and script JSON mapping (corresponding to Go version's stateJsonMap and scriptJsonMap)
-        let mut state_json_map = HashMap::new();
-        let mut script_json_map = HashMap::new();
-
-        for op_data in op_data_list {
-            // Create operation state (corresponding to Go version's DataOpStateType)
-            let state = DataOpStateType {
-                block_accept: Some(op_data.block_accept.clone()),
-                fee: Some(op_data.fee),
-                fee_least: Some(op_data.fee_least),
-                mts_add: Some(op_data.mts_add),
-                op_score: Some(op_data.op_score),
-                op_accept: Some(op_data.op_accept),
-                op_error: Some(op_data.op_error.clone()),
-                checkpoint: Some(op_data.checkpoint.clone()),
-            };
-
-            let state_json = serde_json::to_string(&state)?;
-            let script_json = if !op_data.op_script.is_empty() {
-                serde_json::to_string(&op_data.op_script[0])?
-            } else {
-                "{}".to_string()
-            };
-
-            state_json_map.insert(op_data.tx_id.clone(), state_json.clone());
-            script_json_map.insert(op_data.tx_id.clone(), script_json.clone());
+        write_batch_with_retry(&self.storage.rocksdb, |batch| {
+            // Prepare state and script JSON mapping (corresponding to Go version's stateJsonMap and scriptJsonMap)
+            let mut state_json_map = HashMap::new();
+            let mut script_json_map = HashMap::new();
+
+            for op_data in op_data_list {
+                // Create operation state (corresponding to Go version's DataOpStateType)
+                let state = DataOpStateType {
+                    block_accept: Some(op_data.block_accept.clone()),
+                    fee: Some(op_data.fee),
+                    fee_least: Some(op_data.fee_least),
+                    mts_add: Some(op_data.mts_add),
+                    op_score: Some(op_data.op_score),
+                    op_accept: Some(op_data.op_accept),
+                    op_error: Some(op_data.op_error.clone()),
+                    checkpoint: Some(op_data.checkpoint.clone()),
+                };
+
+                let state_json = serde_json::to_string(&state)?;
+                let script_json = if !op_data.op_script.is_empty() {
+                    serde_json::to_string(&op_data.op_script[0])?
+                } else {
+                    "{}".to_string()
+                };
 
-            // Save to opdata table
-            let opdata_key = format!("opdata:{}", op_data.tx_id);
-            let opdata_value = serde_json::to_string(&op_data)?;
-            batch.put(opdata_key.as_bytes(), opdata_value.as_bytes());
-        }
+                state_json_map.insert(op_data.tx_id.clone(), state_json.clone());
+                script_json_map.insert(op_data.tx_id.clone(), script_json.clone());
 
-        // Save to oplist table (corresponding to Go version's second batch operation)
-        for op_data in op_data_list {
-            let op_range = op_data.op_score / OP_RANGE_BY;
-            let oplist_key = format!("oplist:{}:{}", op_range, op_data.op_score);
-
-            // Build oplist value (corresponding to Go version's cqlnSaveOpList)
-            let oplist_value = serde_json::json!({
-                "tx_id": op_data.tx_id,
-                "state_json": state_json_map.get(&op_data.tx_id).unwrap_or(&"{}".to_string()),
-                "script_json": script_json_map.get(&op_data.tx_id).unwrap_or(&"{}".to_string()),
-                "tick_affc": op_data.ss_info.as_ref().map(|s| &s.tick_affc).unwrap_or(&Vec::new()),
-                "address_affc": op_data.ss_info.as_ref().map(|s| &s.address_affc).unwrap_or(&Vec::new()),
-            });
-
-            batch.put(oplist_key.as_bytes(), oplist_value.to_string().as_bytes());
-        }
+                // Save to opdata table
+                let opdata_key = format!("opdata:{}", op_data.tx_id);
+                let opdata_value = versioned::encode(op_data)?;
+                batch.put(opdata_key.as_bytes(), opdata_value);
+            }
 
-        // Execute batch write
-        self.storage.rocksdb.write_batch(batch)?;
+            // Save to oplist table (corresponding to Go version's second batch operation)
+            for op_data in op_data_list {
+                let op_range = op_data.op_score / OP_RANGE_BY;
+                let oplist_key = format!("oplist:{}:{}", op_range, op_data.op_score);
+
+                // Build oplist value (corresponding to Go version's cqlnSaveOpList)
+                let oplist_value = serde_json::json!({
+                    "tx_id": op_data.tx_id,
+                    "state_json": state_json_map.get(&op_data.tx_id).unwrap_or(&"{}".to_string()),
+                    "script_json": script_json_map.get(&op_data.tx_id).unwrap_or(&"{}".to_string()),
+                    "tick_affc": op_data.ss_info.as_ref().map(|s| &s.tick_affc).unwrap_or(&Vec::new()),
+                    "address_affc": op_data.ss_info.as_ref().map(|s| &s.address_affc).unwrap_or(&Vec::new()),
+                });
+
+                batch.put(oplist_key.as_bytes(), oplist_value.to_string().as_bytes());
+            }
+            tickstats::stage_applied(&self.storage.rocksdb, batch, op_data_list)?;
+            tickops::stage_applied(batch, op_data_list);
+            opscore::stage_applied(&self.storage.rocksdb, batch, op_data_list)
+        })
+        .await?;
+        self.storage
+            .runtime
+            .increment_operations_processed(op_data_list.len() as u64)?;
+
+        // Seal checkpoints of the ranges this batch moved past
+        let op_score_list: Vec<u64> = op_data_list
+            .iter()
+            .map(|op_data| op_data.op_score)
+            .collect();
+        checkpoint::seal_completed_ranges(&self.storage.rocksdb, &op_score_list)?;
 
         let duration = start_time.elapsed().as_millis() as i64;
         Ok(duration)
@@ -257,7 +363,7 @@ impl RollbackManager {
 
         // 1. Save state to RocksDB
         mts_batch_list[0] = start_time.elapsed().as_millis() as i64;
-        let _rocks_duration = self.save_state_batch_rocks_begin(state_map)?;
+        let _rocks_duration = self.save_state_batch_rocks_begin(state_map).await?;
         mts_batch_list[1] = start_time.elapsed().as_millis() as i64;
 
         // 2. Save operation data to RocksDB
@@ -274,66 +380,6 @@ impl RollbackManager {
         Ok(mts_batch_list)
     }
 
-    /// Batch operation processing (corresponding to Go version's startExecuteBatchCassa)
-    /// Improvement: Add retry mechanism and batch processing
-    async fn execute_batch_rocks<F>(
-        &self,
-        items: &[String],
-        batch_size: usize,
-        mut operation: F,
-    ) -> Result<i64>
-    where
-        F: FnMut(&str, &mut WriteBatch) -> Result<()>,
-    {
-        let start_time = std::time::Instant::now();
-        let mut retry_count = 0;
-        let max_retries = 5;
-
-        for chunk in items.chunks(batch_size) {
-            let mut success = false;
-            let mut current_retry = 0;
-
-            while !success && current_retry < max_retries {
-                let mut batch = WriteBatch::default();
-                let mut chunk_success = true;
-
-                for item in chunk {
-                    if let Err(e) = operation(item, &mut batch) {
-                        warn!("Batch operation failed for item {}: {}", item, e);
-                        chunk_success = false;
-                        break;
-                    }
-                }
-
-                if chunk_success {
-                    self.storage.rocksdb.write_batch(batch)?;
-                    success = true;
-                } else {
-                    current_retry += 1;
-                    if current_retry < max_retries {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(
-                            10 * current_retry as u64,
-                        ))
-                        .await;
-                    }
-                }
-            }
-
-            if !success {
-                retry_count += 1;
-                if retry_count > max_retries {
-                    return Err(anyhow::anyhow!(
-                        "Failed to execute batch after {} retries",
-                        max_retries
-                    ));
-                }
-            }
-        }
-
-        let duration = start_time.elapsed().as_millis() as i64;
-        Ok(duration)
-    }
-
     /// Improved rollback to block functionality
     /// Improvement: Add better state management and error recovery
     pub async fn rollback_to_block(
@@ -361,18 +407,66 @@ impl RollbackManager {
         self.storage.runtime.stop_sync()?;
 
         // Execute rollback
-        self.perform_rollback(target_block_hash, target_daa_score)
+        self.rollback_data_to_block(target_block_hash, target_daa_score)
             .await?;
 
         // Update runtime state
-        let mut new_state = current_state;
+        self.complete_rollback(target_block_hash, target_daa_score)
+            .await?;
+
+        info!("Rollback completed to block: {}", target_block_hash);
+        Ok(())
+    }
+
+    /// Roll back the operation data, leaving the pending marker set until the runtime state
+    /// has been updated by complete_rollback
+    pub async fn rollback_data_to_block(
+        &self,
+        target_block_hash: &str,
+        target_daa_score: u64,
+    ) -> Result<()> {
+        let pending = PendingRollback {
+            block_hash: target_block_hash.to_string(),
+            daa_score: target_daa_score,
+        };
+        self.storage
+            .runtime
+            .set_runtime_rollback_pending(&pending)
+            .await?;
+        self.perform_rollback(target_block_hash, target_daa_score)
+            .await
+    }
+
+    /// Point the runtime state at the rollback target and clear the pending marker
+    async fn complete_rollback(
+        &self,
+        target_block_hash: &str,
+        target_daa_score: u64,
+    ) -> Result<()> {
+        let mut new_state = self.storage.runtime.get_runtime_state()?;
         new_state.last_processed_block = target_block_hash.to_string();
         new_state.last_processed_daa_score = target_daa_score;
         new_state.is_syncing = false;
         self.storage.runtime.update_runtime_state(new_state)?;
+        self.storage.runtime.clear_runtime_rollback_pending().await
+    }
 
-        info!("Rollback completed to block: {}", target_block_hash);
-        Ok(())
+    /// Finish a rollback interrupted by a crash, returns false if none was pending.
+    /// The data rollback is re-applied first, it is a no-op if the batch had already landed.
+    pub async fn resume_interrupted_rollback(&self) -> Result<bool> {
+        let Some(pending) = self.storage.runtime.get_runtime_rollback_pending().await? else {
+            return Ok(false);
+        };
+        warn!(
+            "Resuming interrupted rollback to block: {} (DAA: {})",
+            pending.block_hash, pending.daa_score
+        );
+        self.perform_rollback(&pending.block_hash, pending.daa_score)
+            .await?;
+        self.complete_rollback(&pending.block_hash, pending.daa_score)
+            .await?;
+        info!("Rollback resumed to block: {}", pending.block_hash);
+        Ok(true)
     }
 
     /// Improved rollback execution logic
@@ -382,26 +476,67 @@ impl RollbackManager {
         _target_block_hash: &str,
         target_daa_score: u64,
     ) -> Result<()> {
+        let start_time = std::time::Instant::now();
+
         // Get operations to rollback
         let operations_to_rollback = self.get_operations_to_rollback(target_daa_score).await?;
 
         info!("Rolling back {} operations", operations_to_rollback.len());
 
         let mut batch = WriteBatch::default();
+        // Tokens and balances restored so far, later operations on the same state build on them
+        let mut states = DataStateMapType::new();
 
         // Rollback operations in descending order by DAA score
-        for operation in operations_to_rollback.iter().rev() {
-            self.rollback_operation_in_batch(operation, &mut batch)
-                .await?;
+        let mut op_data_list = Vec::with_capacity(operations_to_rollback.len());
+        for operation in &operations_to_rollback {
+            if let Some(op_data) = self
+                .rollback_operation_in_batch(operation, &mut batch, &mut states)
+                .await?
+            {
+                op_data_list.push(op_data);
+            }
         }
+        let op_score_list: Vec<u64> = op_data_list
+            .iter()
+            .map(|op_data| op_data.op_score)
+            .collect();
+        stage_op_records_reverted(
+            &self.storage.rocksdb,
+            &mut batch,
+            &op_score_list,
+            &op_data_list,
+        )?;
+
+        // Restored state is written the way a batch's state is, holder counts and the token
+        // index follow the stored balances and tokens it replaces
+        holders::stage_balances(&self.storage.rocksdb, &mut batch, &states)?;
+        for (tick, token) in &states.state_token_map {
+            tokenindex::stage_token(&self.storage.rocksdb, &mut batch, tick, token.as_ref())?;
+        }
+        state::stage_state_writes(&mut batch, state::collect_state_writes(&states)?);
 
         // Delete VSPC data beyond target DAA score
         self.remove_vspc_data_beyond_in_batch(target_daa_score, &mut batch)
             .await?;
 
+        let op_score_start = target_daa_score
+            .saturating_add(1)
+            .saturating_mul(crate::config::get_op_score_multiplier());
+        if !operations_to_rollback.is_empty() {
+            opscore::stage_reverted(&self.storage.rocksdb, &mut batch, &[op_score_start])?;
+        }
+
         // Execute batch write
         self.storage.rocksdb.write_batch(batch)?;
+        if !operations_to_rollback.is_empty() {
+            self.storage
+                .runtime
+                .decrement_operations_processed(operations_to_rollback.len() as u64)?;
+            checkpoint::unseal_from_op_score(&self.storage.rocksdb, op_score_start)?;
+        }
 
+        record_rollback_duration(operations_to_rollback.len(), start_time.elapsed());
         Ok(())
     }
 
@@ -423,10 +558,16 @@ impl RollbackManager {
         let op_data_iter = self.storage.rocksdb.scan_prefix(prefix)?;
 
         for (_key, value) in op_data_iter.iter() {
-            if let Ok(op_data) = serde_json::from_slice::<OperationData>(value) {
-                if op_data.block_daa_score > target_daa_score {
-                    operations.push(op_data);
-                }
+            // Operations saved by the scanner are versioned DataOperationType records
+            let op_data = match serde_json::from_slice::<OperationData>(value) {
+                Ok(op_data) => op_data,
+                Err(_) => match versioned::decode::<DataOperationType>(value) {
+                    Ok(op_data) => OperationData::from(&op_data),
+                    Err(_) => continue,
+                },
+            };
+            if op_data.block_daa_score > target_daa_score {
+                operations.push(op_data);
             }
         }
 
@@ -437,38 +578,55 @@ impl RollbackManager {
         Ok(operations)
     }
 
-    /// Rollback operation in batch
-    /// Improvement: Add batch support and better error handling
+    /// Rollback operation in batch, returning its saved record whose derived records the caller
+    /// reverts together with those of the other operations
     async fn rollback_operation_in_batch(
         &self,
         operation: &OperationData,
         batch: &mut WriteBatch,
-    ) -> Result<()> {
+        states: &mut DataStateMapType,
+    ) -> Result<Option<DataOperationType>> {
         debug!(
             "Rolling back operation: {} ({})",
             operation.operation_type, operation.tick
         );
 
+        let opdata_key = format!("opdata:{}", operation.tx_id);
+        let op_data = match self.storage.rocksdb.get_raw(&opdata_key)? {
+            Some(value) => versioned::decode::<DataOperationType>(&value).ok(),
+            None => None,
+        };
+        let accepted = op_data
+            .as_ref()
+            .is_none_or(|op_data| op_data.op_accept == 1);
+
         // Execute corresponding rollback operation based on operation type
         match operation.operation_type.as_str() {
+            // Rejected operations changed no state, only their records are deleted
+            _ if !accepted => {
+                debug!(
+                    "Skipping state rollback of rejected operation: {}",
+                    operation.tx_id
+                );
+            }
             "send" => {
                 info!("Rolling back send operation: {}", operation.tx_id);
-                self.rollback_send_operation_in_batch(operation, batch)
+                self.rollback_send_operation_in_batch(operation, states)
                     .await?;
             }
             "transfer" => {
                 info!("Rolling back transfer operation: {}", operation.tx_id);
-                self.rollback_transfer_operation_in_batch(operation, batch)
+                self.rollback_transfer_operation_in_batch(operation, states)
                     .await?;
             }
             "issue" => {
                 info!("Rolling back issue operation: {}", operation.tx_id);
-                self.rollback_issue_operation_in_batch(operation, batch)
+                self.rollback_issue_operation_in_batch(operation, states)
                     .await?;
             }
             "burn" => {
                 info!("Rolling back burn operation: {}", operation.tx_id);
-                self.rollback_burn_operation_in_batch(operation, batch)
+                self.rollback_burn_operation_in_batch(operation, states)
                     .await?;
             }
             "list" => {
@@ -486,45 +644,75 @@ impl RollbackManager {
                 self.rollback_blacklist_operation_in_batch(operation, batch)
                     .await?;
             }
+            "update" => {
+                info!("Rolling back update operation: {}", operation.tx_id);
+                self.rollback_token_state_in_batch(
+                    operation,
+                    op_data.as_ref(),
+                    states,
+                    UpdateOperation::rollback,
+                )
+                .await?;
+            }
+            "freeze" => {
+                info!("Rolling back freeze operation: {}", operation.tx_id);
+                self.rollback_token_state_in_batch(
+                    operation,
+                    op_data.as_ref(),
+                    states,
+                    FreezeOperation::rollback,
+                )
+                .await?;
+            }
             _ => {
                 warn!("Unknown operation type: {}", operation.operation_type);
             }
         }
 
-        // Delete operation records from RocksDB
-        let op_range = operation.block_daa_score / OP_RANGE_BY;
-        let oplist_key = format!("oplist:{}:{}", op_range, operation.block_daa_score);
-        let opdata_key = format!("opdata:{}", operation.tx_id);
-
-        batch.delete(oplist_key.as_bytes());
         batch.delete(opdata_key.as_bytes());
 
         info!("Successfully rolled back operation: {}", operation.tx_id);
-        Ok(())
+        Ok(op_data)
+    }
+
+    /// Balance of `address` restored so far, read from RocksDB on first use. None when the
+    /// address holds no readable balance of the tick.
+    fn restored_balance<'a>(
+        &self,
+        states: &'a mut DataStateMapType,
+        address: &str,
+        tick: &str,
+    ) -> Result<Option<&'a mut StateBalanceType>> {
+        let key = StateKey::balance(address, tick);
+        let map_key = key.map_key();
+        if !states.state_balance_map.contains_key(&map_key) {
+            let Some(balance_data) = self.storage.rocksdb.get_raw(&key.encode())? else {
+                return Ok(None);
+            };
+            let Ok(balance) = versioned::decode::<StateBalanceType>(&balance_data) else {
+                return Ok(None);
+            };
+            states
+                .state_balance_map
+                .insert(map_key.clone(), Some(balance));
+        }
+        Ok(states
+            .state_balance_map
+            .get_mut(&map_key)
+            .and_then(Option::as_mut))
     }
 
     // Improved specific rollback operation implementation (in batch)
     async fn rollback_send_operation_in_batch(
         &self,
         operation: &OperationData,
-        batch: &mut WriteBatch,
+        states: &mut DataStateMapType,
     ) -> Result<()> {
         // Restore sender's balance
-        if let (Some(from), Some(amount)) = (&operation.from_address, &operation.amount) {
-            let parts: Vec<&str> = from.split('_').collect();
-            if parts.len() >= 2 {
-                let balance_key = format!("{}{}_{}", KEY_PREFIX_STATE_BALANCE, parts[0], parts[1]);
-                if let Some(balance_data) = self.storage.rocksdb.get_raw(&balance_key)? {
-                    if let Ok(mut balance) =
-                        serde_json::from_slice::<StateBalanceType>(&balance_data)
-                    {
-                        balance.balance =
-                            (balance.balance.parse::<u64>().unwrap_or(0) + amount).to_string();
-                        let new_balance_json = serde_json::to_string(&balance)?;
-                        batch.put(balance_key.as_bytes(), new_balance_json.as_bytes());
-                    }
-                }
-            }
+        if let (Some(from), Some(amount)) = (&operation.from_address, &operation.amount)
+            && let Some(balance) = self.restored_balance(states, from, &operation.tick)?
+        {
+            balance.balance = (balance.balance.parse::<u64>().unwrap_or(0) + amount).to_string();
         }
         Ok(())
     }
@@ -532,7 +720,7 @@ impl RollbackManager {
     async fn rollback_transfer_operation_in_batch(
         &self,
         operation: &OperationData,
-        batch: &mut WriteBatch,
+        states: &mut DataStateMapType,
     ) -> Result<()> {
         // Restore transfer operation
         if let (Some(from), Some(to), Some(amount)) = (
@@ -541,42 +729,16 @@ impl RollbackManager {
             &operation.amount,
         ) {
             // Restore sender balance
-            let from_parts: Vec<&str> = from.split('_').collect();
-            if from_parts.len() >= 2 {
-                let from_balance_key = format!(
-                    "{}{}_{}",
-                    KEY_PREFIX_STATE_BALANCE, from_parts[0], from_parts[1]
-                );
-                if let Some(balance_data) = self.storage.rocksdb.get_raw(&from_balance_key)? {
-                    if let Ok(mut balance) =
-                        serde_json::from_slice::<StateBalanceType>(&balance_data)
-                    {
-                        balance.balance =
-                            (balance.balance.parse::<u64>().unwrap_or(0) + amount).to_string();
-                        let new_balance_json = serde_json::to_string(&balance)?;
-                        batch.put(from_balance_key.as_bytes(), new_balance_json.as_bytes());
-                    }
-                }
+            if let Some(balance) = self.restored_balance(states, from, &operation.tick)? {
+                balance.balance =
+                    (balance.balance.parse::<u64>().unwrap_or(0) + amount).to_string();
             }
 
             // Reduce receiver balance
-            let to_parts: Vec<&str> = to.split('_').collect();
-            if to_parts.len() >= 2 {
-                let to_balance_key = format!(
-                    "{}{}_{}",
-                    KEY_PREFIX_STATE_BALANCE, to_parts[0], to_parts[1]
-                );
-                if let Some(balance_data) = self.storage.rocksdb.get_raw(&to_balance_key)? {
-                    if let Ok(mut balance) =
-                        serde_json::from_slice::<StateBalanceType>(&balance_data)
-                    {
-                        let current_balance = balance.balance.parse::<u64>().unwrap_or(0);
-                        if current_balance >= *amount {
-                            balance.balance = (current_balance - amount).to_string();
-                            let new_balance_json = serde_json::to_string(&balance)?;
-                            batch.put(to_balance_key.as_bytes(), new_balance_json.as_bytes());
-                        }
-                    }
+            if let Some(balance) = self.restored_balance(states, to, &operation.tick)? {
+                let current_balance = balance.balance.parse::<u64>().unwrap_or(0);
+                if current_balance >= *amount {
+                    balance.balance = (current_balance - amount).to_string();
                 }
             }
         }
@@ -586,27 +748,15 @@ impl RollbackManager {
     async fn rollback_issue_operation_in_batch(
         &self,
         operation: &OperationData,
-        batch: &mut WriteBatch,
+        states: &mut DataStateMapType,
     ) -> Result<()> {
         // Restore issue operation
-        if let Some(to) = &operation.to_address {
-            let parts: Vec<&str> = to.split('_').collect();
-            if parts.len() >= 2 {
-                let balance_key = format!("{}{}_{}", KEY_PREFIX_STATE_BALANCE, parts[0], parts[1]);
-                if let Some(balance_data) = self.storage.rocksdb.get_raw(&balance_key)? {
-                    if let Ok(mut balance) =
-                        serde_json::from_slice::<StateBalanceType>(&balance_data)
-                    {
-                        if let Some(amount) = &operation.amount {
-                            let current_balance = balance.balance.parse::<u64>().unwrap_or(0);
-                            if current_balance >= *amount {
-                                balance.balance = (current_balance - amount).to_string();
-                                let new_balance_json = serde_json::to_string(&balance)?;
-                                batch.put(balance_key.as_bytes(), new_balance_json.as_bytes());
-                            }
-                        }
-                    }
-                }
+        if let (Some(to), Some(amount)) = (&operation.to_address, &operation.amount)
+            && let Some(balance) = self.restored_balance(states, to, &operation.tick)?
+        {
+            let current_balance = balance.balance.parse::<u64>().unwrap_or(0);
+            if current_balance >= *amount {
+                balance.balance = (current_balance - amount).to_string();
             }
         }
         Ok(())
@@ -615,26 +765,13 @@ impl RollbackManager {
     async fn rollback_burn_operation_in_batch(
         &self,
         operation: &OperationData,
-        batch: &mut WriteBatch,
+        states: &mut DataStateMapType,
     ) -> Result<()> {
         // Restore burn operation
-        if let Some(from) = &operation.from_address {
-            let parts: Vec<&str> = from.split('_').collect();
-            if parts.len() >= 2 {
-                let balance_key = format!("{}{}_{}", KEY_PREFIX_STATE_BALANCE, parts[0], parts[1]);
-                if let Some(balance_data) = self.storage.rocksdb.get_raw(&balance_key)? {
-                    if let Ok(mut balance) =
-                        serde_json::from_slice::<StateBalanceType>(&balance_data)
-                    {
-                        if let Some(amount) = &operation.amount {
-                            balance.balance =
-                                (balance.balance.parse::<u64>().unwrap_or(0) + amount).to_string();
-                            let new_balance_json = serde_json::to_string(&balance)?;
-                            batch.put(balance_key.as_bytes(), new_balance_json.as_bytes());
-                        }
-                    }
-                }
-            }
+        if let (Some(from), Some(amount)) = (&operation.from_address, &operation.amount)
+            && let Some(balance) = self.restored_balance(states, from, &operation.tick)?
+        {
+            balance.balance = (balance.balance.parse::<u64>().unwrap_or(0) + amount).to_string();
         }
         Ok(())
     }
@@ -655,12 +792,12 @@ impl RollbackManager {
         batch: &mut WriteBatch,
     ) -> Result<()> {
         // Restore ownership change operation
-        let token_key = format!("{}{}", KEY_PREFIX_STATE_TOKEN, operation.tick);
+        let token_key = StateKey::token(&operation.tick).encode();
         if let Some(token_data) = self.storage.rocksdb.get_raw(&token_key)? {
-            if let Ok(token) = serde_json::from_slice::<StateTokenType>(&token_data) {
+            if let Ok(token) = versioned::decode::<StateTokenType>(&token_data) {
                 // TODO: Restore original owner
-                let new_token_json = serde_json::to_string(&token)?;
-                batch.put(token_key.as_bytes(), new_token_json.as_bytes());
+                let new_token_json = versioned::encode(&token)?;
+                batch.put(token_key.as_bytes(), new_token_json);
             }
         }
         Ok(())
@@ -673,18 +810,37 @@ impl RollbackManager {
     ) -> Result<()> {
         // Restore blacklist operation
         if let Some(address) = &operation.from_address {
-            let parts: Vec<&str> = address.split('_').collect();
-            if parts.len() >= 2 {
-                let blacklist_key = format!(
-                    "{}{}_{}",
-                    KEY_PREFIX_STATE_BLACKLIST, parts[0], operation.tick
-                );
-                batch.delete(blacklist_key.as_bytes());
-            }
+            let blacklist_key = StateKey::blacklist(&operation.tick, address).encode();
+            batch.delete(blacklist_key.as_bytes());
         }
         Ok(())
     }
 
+    /// Restore the token fields recorded in the st_before of an update or freeze, the token is
+    /// written with the other restored state
+    async fn rollback_token_state_in_batch(
+        &self,
+        operation: &OperationData,
+        op_data: Option<&DataOperationType>,
+        states: &mut DataStateMapType,
+        rollback: fn(&DataOperationType, &mut DataStateMapType) -> Result<()>,
+    ) -> Result<()> {
+        let Some(op_data) = op_data else {
+            warn!("No state recorded for operation: {}", operation.tx_id);
+            return Ok(());
+        };
+
+        if !states.state_token_map.contains_key(&operation.tick) {
+            let token_key = StateKey::token(&operation.tick).encode();
+            let token = match self.storage.rocksdb.get_raw(&token_key)? {
+                Some(token_data) => Some(versioned::decode::<StateTokenType>(&token_data)?),
+                None => None,
+            };
+            states.state_token_map.insert(operation.tick.clone(), token);
+        }
+        rollback(op_data, states)
+    }
+
     /// Delete VSPC data in batch
     async fn remove_vspc_data_beyond_in_batch(
         &self,
@@ -698,10 +854,10 @@ impl RollbackManager {
         let vspc_iter = self.storage.rocksdb.scan_prefix(prefix)?;
 
         for (key, value) in vspc_iter.iter() {
-            if let Ok(vspc_data) = serde_json::from_slice::<VSPCData>(value) {
-                if vspc_data.daa_score > target_daa_score {
-                    batch.delete(key.as_bytes());
-                }
+            if vspcarchive::vspc_daa_score(value)
+                .is_some_and(|daa_score| daa_score > target_daa_score)
+            {
+                batch.delete(key.as_bytes());
             }
         }
 
@@ -711,57 +867,18 @@ impl RollbackManager {
 
     /// Save state batch to RocksDB (improved version)
     /// Improvement: Add batch support and better error handling
-    fn save_state_batch_rocks_begin(&self, state_map: &DataStateMapType) -> Result<i64> {
+    async fn save_state_batch_rocks_begin(&self, state_map: &DataStateMapType) -> Result<i64> {
         let start_time = std::time::Instant::now();
 
-        let mut batch = WriteBatch::default();
-
-        // Save Token state
-        for (key, token) in &state_map.state_token_map {
-            let full_key = format!("{}{}", KEY_PREFIX_STATE_TOKEN, key);
-            if let Some(token_data) = token {
-                let value_json = serde_json::to_string(token_data)?;
-                batch.put(full_key.as_bytes(), value_json.as_bytes());
-            } else {
-                batch.delete(full_key.as_bytes());
-            }
-        }
-
-        // Save Balance state
-        for (key, balance) in &state_map.state_balance_map {
-            let full_key = format!("{}{}", KEY_PREFIX_STATE_BALANCE, key);
-            if let Some(balance_data) = balance {
-                let value_json = serde_json::to_string(balance_data)?;
-                batch.put(full_key.as_bytes(), value_json.as_bytes());
-            } else {
-                batch.delete(full_key.as_bytes());
-            }
-        }
-
-        // Save Market state
-        for (key, market) in &state_map.state_market_map {
-            let full_key = format!("{}{}", KEY_PREFIX_STATE_MARKET, key);
-            if let Some(market_data) = market {
-                let value_json = serde_json::to_string(market_data)?;
-                batch.put(full_key.as_bytes(), value_json.as_bytes());
-            } else {
-                batch.delete(full_key.as_bytes());
+        write_batch_with_retry(&self.storage.rocksdb, |batch| {
+            for (key, token) in &state_map.state_token_map {
+                tokenindex::stage_token(&self.storage.rocksdb, batch, key, token.as_ref())?;
             }
-        }
-
-        // Save Blacklist state
-        for (key, blacklist) in &state_map.state_blacklist_map {
-            let full_key = format!("{}{}", KEY_PREFIX_STATE_BLACKLIST, key);
-            if let Some(blacklist_data) = blacklist {
-                let value_json = serde_json::to_string(blacklist_data)?;
-                batch.put(full_key.as_bytes(), value_json.as_bytes());
-            } else {
-                batch.delete(full_key.as_bytes());
-            }
-        }
-
-        // Execute batch write
-        self.storage.rocksdb.write_batch(batch)?;
+            holders::stage_balances(&self.storage.rocksdb, batch, state_map)?;
+            state::stage_state_writes(batch, state::collect_state_writes(state_map)?);
+            Ok(())
+        })
+        .await?;
 
         let duration = start_time.elapsed().as_millis() as i64;
         Ok(duration)
@@ -950,9 +1067,8 @@ impl RollbackManager {
 
         for (balance_key, balance) in balance_map {
             if let Some(_balance_data) = balance {
-                let parts: Vec<&str> = balance_key.split('_').collect();
-                if parts.len() >= 2 {
-                    let tick = parts[1];
+                if let Some(key) = StateKey::from_map_key(StateKeyKind::Balance, &balance_key) {
+                    let tick = key.tick();
                     if !token_map.contains_key(tick) {
                         warn!("Balance exists for non-existent token: {}", tick);
                     }
@@ -1092,8 +1208,7 @@ impl RollbackManager {
             rollback_candidates: rollback_candidates.len(),
             rollback_history_count: rollback_history.len(),
             last_rollback_timestamp: self.get_last_rollback_timestamp().await?,
-            average_rollback_duration: self
-                .calculate_average_rollback_duration(&rollback_history)?,
+            average_rollback_duration: self.calculate_average_rollback_duration(),
             duration,
         };
 
@@ -1148,15 +1263,9 @@ impl RollbackManager {
         }
     }
 
-    /// Calculate average rollback duration
-    fn calculate_average_rollback_duration(&self, history: &[RollbackHistoryEntry]) -> Result<u64> {
-        if history.is_empty() {
-            return Ok(0);
-        }
-
-        let total_duration: u64 = history.iter().map(|entry| entry.timestamp).sum();
-
-        Ok(total_duration / history.len() as u64)
+    /// Average duration in milliseconds of the rollbacks completed since startup
+    fn calculate_average_rollback_duration(&self) -> u64 {
+        get_rollback_timings().average_duration().as_millis() as u64
     }
 
     /// Clean up expired rollback data, corresponding to Go version's cleanup functionality
@@ -1204,86 +1313,258 @@ impl RollbackManager {
         Ok(())
     }
 
-    /// Get rollback candidates, corresponding to Go version's rollback point selection
-    /// Improvement: Add intelligent candidate selection
+    /// Get rollback candidates, the last `max_blocks` VSPC blocks below the last processed DAA
+    /// score, newest first
     pub async fn get_rollback_candidates(&self, max_blocks: u64) -> Result<Vec<RollbackCandidate>> {
-        let mut candidates = Vec::new();
-
-        // Get VSPC list
         let vspc_list = self.storage.runtime.get_runtime_vspc_last().await?;
-
-        // Select candidates
-        for (i, vspc) in vspc_list.iter().enumerate() {
-            if i >= max_blocks as usize {
-                break;
-            }
-
-            // Calculate operation count
-            let operation_count = self.get_operation_count_for_block(&vspc.hash)?;
-
-            // Check if it is a valid rollback point
-            if self
-                .is_valid_rollback_point(&vspc.hash, vspc.daa_score)
-                .await?
-            {
-                let candidate = RollbackCandidate::new(
+        let last_daa_score = self
+            .storage
+            .runtime
+            .get_checkpoint()
+            .map(|(_, daa_score)| daa_score)
+            .unwrap_or(u64::MAX);
+        let operation_counts = self.get_operation_counts_by_block()?;
+        let cost_by_daa_score = self.get_rollback_cost_by_daa_score()?;
+        let per_operation = get_rollback_timings().average_operation_duration();
+
+        let mut candidates: Vec<RollbackCandidate> = vspc_list
+            .iter()
+            .filter(|vspc| vspc.daa_score < last_daa_score)
+            .map(|vspc| {
+                RollbackCandidate::new(
                     vspc.hash.clone(),
                     vspc.daa_score,
                     0, // DataVspcType has no timestamp field, use 0 as placeholder
-                    operation_count.try_into().unwrap_or(0),
-                );
-                candidates.push(candidate);
-            }
-        }
-
-        // Sort in descending order by DAA score
+                    operation_counts.get(&vspc.hash).copied().unwrap_or(0),
+                )
+            })
+            .collect();
         candidates.sort_by(|a, b| b.daa_score.cmp(&a.daa_score));
+        candidates.truncate(max_blocks.try_into().unwrap_or(usize::MAX));
+
+        // Newest first, each candidate deletes the operations above it
+        let mut newer = cost_by_daa_score.iter().rev().peekable();
+        let (mut operations, mut bytes) = (0, 0);
+        let candidates = candidates
+            .into_iter()
+            .map(|candidate| {
+                while let Some((_, &(count, size))) =
+                    newer.next_if(|(daa_score, _)| **daa_score > candidate.daa_score)
+                {
+                    operations += count;
+                    bytes += size;
+                }
+                candidate.with_rollback_cost(operations, bytes, per_operation)
+            })
+            .collect::<Vec<_>>();
 
         info!("Found {} rollback candidates", candidates.len());
         Ok(candidates)
     }
 
-    /// Check if it is a valid rollback point
-    async fn is_valid_rollback_point(&self, block_hash: &str, daa_score: u64) -> Result<bool> {
-        // Check if block exists
-        if !self.block_exists_in_vspc(block_hash, daa_score).await? {
-            return Ok(false);
+    /// Stored operations and their opdata and oplist bytes by accepting DAA score
+    fn get_rollback_cost_by_daa_score(&self) -> Result<BTreeMap<u64, (usize, u64)>> {
+        let mut costs: BTreeMap<u64, (usize, u64)> = BTreeMap::new();
+        for (key, value) in self.storage.rocksdb.scan_prefix("opdata:")? {
+            if let Ok(op_data) = versioned::decode::<DataOperationType>(&value) {
+                let cost = costs.entry(op_data.daa_score).or_default();
+                cost.0 += 1;
+                cost.1 += (key.len() + value.len()) as u64;
+            }
         }
+        let multiplier = crate::config::get_op_score_multiplier();
+        for (key, value) in self.storage.rocksdb.scan_prefix("oplist:")? {
+            let op_score = key
+                .rsplit(':')
+                .next()
+                .and_then(|op_score| op_score.parse::<u64>().ok());
+            if let Some(cost) =
+                op_score.and_then(|op_score| costs.get_mut(&(op_score / multiplier)))
+            {
+                cost.1 += (key.len() + value.len()) as u64;
+            }
+        }
+        Ok(costs)
+    }
 
-        // Check if there is sufficient operation data
-        let operation_count = self.get_operation_count_for_block(block_hash)?;
-        if operation_count == 0 {
-            return Ok(false);
+    /// Count stored operations by the block that accepted them
+    fn get_operation_counts_by_block(&self) -> Result<HashMap<String, usize>> {
+        let mut counts = HashMap::new();
+        for (_, value) in self.storage.rocksdb.scan_prefix("opdata:")? {
+            if let Ok(op_data) = versioned::decode::<DataOperationType>(&value) {
+                *counts.entry(op_data.block_accept).or_insert(0) += 1;
+            }
         }
+        Ok(counts)
+    }
+}
 
-        // Check state consistency
-        if !self
-            .validate_state_consistency(block_hash, daa_score)
-            .await
-            .is_ok()
-        {
-            return Ok(false);
+/// Stage the removal of what saving rolled back operations derived from them: their oplist
+/// entries at `op_score_list`, tick counters and tick operation index entries. Both the per-batch
+/// and the rollback-to-block paths revert through it, so neither misses a record.
+fn stage_op_records_reverted(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    op_score_list: &[u64],
+    op_data_list: &[DataOperationType],
+) -> Result<()> {
+    for &op_score in op_score_list {
+        let op_range = op_score / OP_RANGE_BY;
+        let key = format!("oplist:{}:{}", op_range, op_score);
+        batch.delete(key.as_bytes());
+    }
+    tickstats::stage_reverted(backend, batch, op_data_list)?;
+    tickops::stage_reverted(batch, op_data_list);
+    Ok(())
+}
+
+/// Longest backoff between two retries of a write batch chunk
+const BATCH_RETRY_BACKOFF_MAX_MS: u64 = 5000;
+
+/// Backoff before retry `retry` (from 1) of a write batch: the configured base doubled per
+/// earlier retry and capped, up to half of it is jittered away so writers do not retry in lockstep
+fn batch_retry_backoff(retry: u32) -> std::time::Duration {
+    let doublings = retry.saturating_sub(1).min(32);
+    let backoff_ms = crate::config::get_batch_retry_backoff_ms()
+        .saturating_mul(1 << doublings)
+        .min(BATCH_RETRY_BACKOFF_MAX_MS);
+    let jitter_ms = rand::random::<u64>() % (backoff_ms / 2 + 1);
+    std::time::Duration::from_millis(backoff_ms - jitter_ms)
+}
+
+/// Stage a batch with `stage` and write it. A failed staging or write is staged afresh and
+/// retried up to batchMaxRetries times with jittered exponential backoff, after which the error
+/// is returned.
+pub async fn write_batch_with_retry<F>(backend: &dyn StorageBackend, mut stage: F) -> Result<()>
+where
+    F: FnMut(&mut WriteBatch) -> Result<()>,
+{
+    let max_retries = crate::config::get_batch_max_retries();
+    let mut retry = 0;
+    loop {
+        let mut batch = WriteBatch::default();
+        let Err(e) = stage(&mut batch).and_then(|()| backend.write_batch(batch)) else {
+            return Ok(());
+        };
+        if retry >= max_retries {
+            return Err(anyhow::anyhow!(
+                "Failed to execute batch after {} retries: {}",
+                max_retries,
+                e
+            ));
         }
+        retry += 1;
+        warn!("{}, retry {}/{}", e, retry, max_retries);
+        tokio::time::sleep(batch_retry_backoff(retry)).await;
+    }
+}
 
-        Ok(true)
+/// Apply `operation` to `items` in chunks of `batch_size`, each chunk written as one batch with
+/// write_batch_with_retry. Once a chunk runs out of retries later chunks are not written.
+pub async fn execute_batch_with_retry<F>(
+    backend: &dyn StorageBackend,
+    items: &[String],
+    batch_size: usize,
+    mut operation: F,
+) -> Result<()>
+where
+    F: FnMut(&str, &mut WriteBatch) -> Result<()>,
+{
+    for chunk in items.chunks(batch_size.max(1)) {
+        write_batch_with_retry(backend, |batch| {
+            for item in chunk {
+                operation(item, batch).map_err(|e| {
+                    anyhow::anyhow!("Batch operation failed for item {}: {}", item, e)
+                })?;
+            }
+            Ok(())
+        })
+        .await?;
     }
+    Ok(())
+}
 
-    /// Get operation count for block
-    fn get_operation_count_for_block(&self, block_hash: &str) -> Result<u64> {
-        let mut count = 0;
+/// Serves rollback candidates, each listing scans the VSPC list and all operations so the
+/// candidates are cached for a short while
+pub struct RollbackCandidateCache {
+    ttl: std::time::Duration,
+    cached: std::sync::Mutex<Option<(Vec<RollbackCandidate>, std::time::Instant)>>,
+}
 
-        // Scan operation data
-        let prefix = "opdata:";
-        let op_data_iter = self.storage.rocksdb.scan_prefix(prefix)?;
+impl RollbackCandidateCache {
+    pub fn new(ttl: std::time::Duration) -> Self {
+        Self {
+            ttl,
+            cached: std::sync::Mutex::new(None),
+        }
+    }
 
-        for (_key, value) in op_data_iter.iter() {
-            if let Ok(op_data) = serde_json::from_slice::<OperationData>(value) {
-                if op_data.tx_id.contains(block_hash) {
-                    count += 1;
-                }
+    /// The newest `max_blocks` candidates
+    pub async fn get(
+        &self,
+        storage: &Arc<StorageManager>,
+        max_blocks: u64,
+    ) -> Result<Vec<RollbackCandidate>> {
+        let cached = match self.cached.lock().unwrap().as_ref() {
+            Some((candidates, listed_at)) if listed_at.elapsed() < self.ttl => {
+                Some(candidates.clone())
+            }
+            _ => None,
+        };
+        let mut candidates = match cached {
+            Some(candidates) => candidates,
+            None => {
+                let candidates = RollbackManager::new(Arc::clone(storage))?
+                    .get_rollback_candidates(u64::MAX)
+                    .await?;
+                *self.cached.lock().unwrap() =
+                    Some((candidates.clone(), std::time::Instant::now()));
+                candidates
             }
+        };
+        candidates.truncate(max_blocks.try_into().unwrap_or(usize::MAX));
+        Ok(candidates)
+    }
+
+    /// Drop the cached candidates, they are stale once a rollback completed
+    pub fn invalidate(&self) {
+        *self.cached.lock().unwrap() = None;
+    }
+}
+
+/// How long rollback statistics are served before they are computed again
+pub const ROLLBACK_STATISTICS_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Serves rollback statistics, computing them scans all operations so they are cached for a
+/// short while
+pub struct RollbackStatisticsCache {
+    ttl: std::time::Duration,
+    cached: std::sync::Mutex<Option<(RollbackStatistics, std::time::Instant)>>,
+}
+
+impl RollbackStatisticsCache {
+    pub fn new(ttl: std::time::Duration) -> Self {
+        Self {
+            ttl,
+            cached: std::sync::Mutex::new(None),
         }
+    }
 
-        Ok(count)
+    pub async fn get(&self, storage: &Arc<StorageManager>) -> Result<RollbackStatistics> {
+        if let Some((stats, computed_at)) = self.cached.lock().unwrap().as_ref()
+            && computed_at.elapsed() < self.ttl
+        {
+            return Ok(stats.clone());
+        }
+        let stats = RollbackManager::new(Arc::clone(storage))?
+            .get_rollback_statistics()
+            .await?;
+        *self.cached.lock().unwrap() = Some((stats.clone(), std::time::Instant::now()));
+        Ok(stats)
+    }
+
+    /// Drop the cached statistics, they are stale once a rollback completed
+    pub fn invalidate(&self) {
+        *self.cached.lock().unwrap() = None;
     }
 }
@@ -1,28 +1,31 @@
-use crate::config::types::StartupConfig;
+use crate::config::types::{AlertsConfig, StartupConfig};
 use crate::explorer::RollbackManager;
 use crate::explorer::ScanStats;
+use crate::explorer::alert::AlertSender;
+use crate::explorer::node_client::NodeClient;
+use crate::explorer::vspc_client::VSPCClient;
 use crate::operations::handler::OperationManager;
 use crate::storage::StorageManager;
+use crate::storage::deadletter;
+use crate::storage::events::{self, ScanEvent};
+use crate::storage::runtime::{ScanCounters, append_runtime_lists};
 use crate::storage::types::*;
+use crate::utils::daa_range::DaaScoreRanges;
+use crate::utils::script_parser::ScriptParser;
+use crate::utils::utxo::UtxoIndex;
 use anyhow::Result;
-use reqwest::Client;
-use serde_json::Value;
-use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::time::sleep;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 // Constant definitions, consistent with Go version
 const LEN_VSPC_LIST_MAX: usize = 1200;
-const LEN_VSPC_LIST_RUNTIME_MAX: usize = 3600;
-const LEN_VSPC_CHECK: usize = 200;
-const LEN_ROLLBACK_LIST_RUNTIME_MAX: usize = 3600;
+/// Transactions whose outputs are kept for fee input resolution across batches
+const UTXO_CACHE_MAX: usize = 100000;
 
-// DAA Score range, consistent with Go version
-const DAA_SCORE_RANGE: [[u64; 2]; 2] = [[83441551, 83525600], [90090600, 18446744073709551615]];
-
-pub struct VSPCScanner {
+pub struct VSPCScanner<C: NodeClient = VSPCClient> {
     last_scan_time: u64,
     total_vspc_processed: u64,
     total_operations_found: u64,
@@ -30,22 +33,141 @@ pub struct VSPCScanner {
     config: StartupConfig,
     is_scanning: bool,
     scan_start_time: u64,
-    http_client: Client,
     // Runtime state from Go version
     vspc_list: Vec<DataVspcType>,
     rollback_list: Vec<DataRollbackType>,
     synced: bool,
     op_score_last: u64,
     testnet: bool,
+    // Indexed DAA score ranges
+    daa_score_ranges: DaaScoreRanges,
     // Add operation manager
     operation_manager: Option<OperationManager>,
     // Add rollback manager
     rollback_manager: RollbackManager,
+    // Paused due to critically low disk space
+    paused: bool,
+    // Paused because the node tip is behind the last indexed VSPC
+    node_regressed: bool,
+    // Source of VSPC blocks and transactions
+    node_client: C,
+    // Events kept for standby followers, 0 disables the event log
+    event_retention: u64,
+    // Last time the in-memory scan counters were persisted
+    last_runtime_flush: Instant,
+    // Outputs of recent batches and fetched inputs, resolving fee inputs without the node
+    utxo_cache: UtxoIndex,
+    // Set on shutdown, the scanning loop exits once the batch in progress completed
+    stop_requested: Arc<AtomicBool>,
+    // Webhook notified of deep reorgs and rejected operation spikes
+    alerts: AlertSender,
 }
 
 impl VSPCScanner {
     pub fn new(storage: Arc<StorageManager>, config: StartupConfig, testnet: bool) -> Result<Self> {
+        let node_client = VSPCClient::new(config.kaspa_node_url.clone())
+            .with_tx_fetch_concurrency(config.tx_fetch_concurrency);
+        Self::with_node_client(storage, config, testnet, node_client)
+    }
+
+    /// Delay padding a synced scan loop up to `target_ms`, None once the scan took that long.
+    /// Keeps the steady-state loop at about 1.2/s with the default 850ms target.
+    pub fn steady_state_delay(target_ms: u64, scan_duration_ms: u64) -> Option<Duration> {
+        match target_ms.checked_sub(scan_duration_ms) {
+            Some(delay) if delay > 0 => Some(Duration::from_millis(delay)),
+            _ => None,
+        }
+    }
+
+    /// Check rollback, corresponding to Go version's checkRollbackNext
+    pub fn check_rollback_next(
+        vspc_list_prev: &[DataVspcType],
+        vspc_list_next: &[DataVspcType],
+        daa_score_start: u64,
+    ) -> (u64, Vec<DataVspcType>) {
+        if vspc_list_prev.is_empty() {
+            return (0, vspc_list_next.to_vec());
+        }
+
+        let mut vspc_list1 = Vec::new();
+        let vspc_list2;
+
+        for vspc in vspc_list_prev {
+            if vspc.daa_score < daa_score_start {
+                continue;
+            }
+            vspc_list1.push(vspc.clone());
+        }
+
+        let len_check = vspc_list1.len();
+        if len_check > 0 {
+            if vspc_list_next.len() < len_check {
+                return (0, Vec::new());
+            } else {
+                vspc_list2 = vspc_list_next[..len_check].to_vec();
+            }
+        } else {
+            vspc_list2 = vspc_list_next.to_vec();
+        }
+
+        // Check rollback
+        let mut rollback_daa_score = 0;
+        for (i, vspc1) in vspc_list1.iter().enumerate() {
+            if i >= vspc_list2.len() {
+                break;
+            }
+            let vspc2 = &vspc_list2[i];
+            if vspc1.hash != vspc2.hash {
+                rollback_daa_score = vspc1.daa_score;
+                break;
+            }
+        }
+        if rollback_daa_score > 0 || len_check == 0 {
+            return (rollback_daa_score, vspc_list2);
+        }
+
+        // Only the blocks after the checked ones are new
+        (0, vspc_list_next[len_check..].to_vec())
+    }
+
+    /// Rollback DAA score from the `removedChainBlockHashes` of a GetVirtualChainFromBlock
+    /// response: the oldest runtime block the node removed, 0 when it removed none of them
+    pub fn check_rollback_removed(
+        vspc_list_prev: &[DataVspcType],
+        removed_chain_block_hashes: &[String],
+    ) -> u64 {
+        vspc_list_prev
+            .iter()
+            .find(|vspc| removed_chain_block_hashes.contains(&vspc.hash))
+            .map_or(0, |vspc| vspc.daa_score)
+    }
+
+    /// Last block of `vspc_list_prev` the next chain still builds on, found by following the
+    /// selected parent of the first next block missing from the previous chain.
+    pub fn find_vspc_fork(
+        vspc_list_prev: &[DataVspcType],
+        vspc_list_next: &[DataVspcType],
+    ) -> Option<DataVspcType> {
+        let diverged = vspc_list_next
+            .iter()
+            .find(|next| !vspc_list_prev.iter().any(|prev| prev.hash == next.hash))?;
+        vspc_list_prev
+            .iter()
+            .find(|prev| prev.hash == diverged.selected_parent)
+            .cloned()
+    }
+}
+
+impl<C: NodeClient> VSPCScanner<C> {
+    /// Create a scanner reading blocks and transactions from `node_client`
+    pub fn with_node_client(
+        storage: Arc<StorageManager>,
+        config: StartupConfig,
+        testnet: bool,
+        node_client: C,
+    ) -> Result<Self> {
         let rollback_manager = RollbackManager::new(storage.clone())?;
+        let daa_score_ranges = DaaScoreRanges::for_network(&config.daa_score_range, testnet);
 
         Ok(Self {
             storage,
@@ -55,17 +177,56 @@ impl VSPCScanner {
             total_vspc_processed: 0,
             total_operations_found: 0,
             scan_start_time: 0,
-            http_client: Client::new(),
             vspc_list: Vec::new(),
             rollback_list: Vec::new(),
             synced: false,
             op_score_last: 0,
             testnet,
+            daa_score_ranges,
             operation_manager: None,
             rollback_manager,
+            paused: false,
+            node_regressed: false,
+            node_client,
+            event_retention: 0,
+            last_runtime_flush: Instant::now(),
+            utxo_cache: UtxoIndex::default(),
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            alerts: AlertSender::default(),
         })
     }
 
+    /// Post deep reorgs and rejected operation spikes to the `[alerts]` webhook
+    pub fn with_alerts(mut self, config: AlertsConfig) -> Self {
+        self.alerts = AlertSender::new(config);
+        self
+    }
+
+    /// Flag stopping the scanning loop after the batch in progress, settable from another task
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop_requested)
+    }
+
+    /// Publish an event log of the last `retention` scan steps for standby followers
+    pub fn with_event_retention(mut self, retention: u64) -> Self {
+        self.event_retention = retention;
+        self
+    }
+
+    /// Append a scan step to the event log when it is enabled
+    fn publish_event(&self, event: ScanEvent, op_score_before: u64) -> Result<()> {
+        if self.event_retention > 0 {
+            events::append_event(
+                &self.storage.rocksdb,
+                event,
+                op_score_before,
+                self.op_score_last,
+                self.event_retention,
+            )?;
+        }
+        Ok(())
+    }
+
     pub async fn init(&mut self) -> Result<()> {
         info!("explorer.Init start.");
 
@@ -79,11 +240,6 @@ impl VSPCScanner {
             // Need to modify config structure to support hysteresis field
         }
 
-        // Handle DAA Score range, consistent with Go version
-        if !self.testnet || self.config.daa_score_range.is_empty() {
-            // Use default DAA_SCORE_RANGE
-        }
-
         // Handle reserved ticks, consistent with Go version
         if self.testnet && !self.config.tick_reserved.is_empty() {
             // Apply reserved ticks
@@ -95,6 +251,28 @@ impl VSPCScanner {
         // Get VSPC list, consistent with Go version
         self.vspc_list = self.storage.runtime.get_runtime_vspc_last().await?;
 
+        // Read the virtual chain on from the persisted chain blocks, or the checkpoint block.
+        // Without a runtime state nothing was processed yet and there is nothing to seed.
+        if !self.vspc_list.is_empty() {
+            self.node_client.seed_chain(&self.vspc_list);
+        } else if let Ok((checkpoint_hash, checkpoint_daa_score)) =
+            self.storage.runtime.get_checkpoint()
+            && !checkpoint_hash.is_empty()
+        {
+            self.node_client.seed_chain(&[DataVspcType {
+                daa_score: checkpoint_daa_score,
+                hash: checkpoint_hash,
+                tx_id_list: Vec::new(),
+                selected_parent: String::new(),
+            }]);
+        }
+
+        // Restore the scan counters of the last runtime flush
+        let counters = self.storage.runtime.get_runtime_scan_counters().await?;
+        self.total_vspc_processed = counters.total_vspc_processed;
+        self.total_operations_found = counters.total_operations_found;
+        self.last_scan_time = counters.last_scan_time;
+
         // Set op_score_last, consistent with Go version
         if let Some(last_rollback) = self.rollback_list.last() {
             self.op_score_last = last_rollback.op_score_last;
@@ -114,16 +292,16 @@ impl VSPCScanner {
         } else {
             info!(
                 "explorer.Init lastVspcDaaScore={} lastVspcBlockHash=",
-                DAA_SCORE_RANGE[0][0]
+                self.daa_score_start()
             );
             self.storage
                 .runtime
-                .set_runtime_synced(false, self.op_score_last, DAA_SCORE_RANGE[0][0])
+                .set_runtime_synced(false, self.op_score_last, self.daa_score_start())
                 .await?;
         }
 
         // Initialize operation manager
-        self.operation_manager = Some(OperationManager::new(self.storage.clone()));
+        self.operation_manager = Some(OperationManager::new(&self.storage));
 
         // Initialize rollback manager
         self.rollback_manager.init()?;
@@ -142,8 +320,17 @@ impl VSPCScanner {
 
         info!("Starting VSPC scanning...");
 
-        while self.is_scanning {
-            if let Err(e) = self.scan_vspc_batch().await {
+        while self.is_scanning && !self.stop_requested.load(Ordering::SeqCst) {
+            // Hold the scan lock for the whole batch so admin operations can pause scanning
+            let scan_guard = self.storage.runtime.lock_scanning().await;
+            let result = self.scan_vspc_batch().await;
+            // Flushed under the scan lock, so it never interleaves with a batch's writes
+            if let Err(e) = self.flush_runtime_state_if_due().await {
+                warn!("explorer.flushRuntimeState failed: {}", e);
+            }
+            drop(scan_guard);
+
+            if let Err(e) = result {
                 error!("Error scanning VSPC batch: {}", e);
                 sleep(Duration::from_secs(3)).await;
                 continue;
@@ -156,13 +343,132 @@ impl VSPCScanner {
         Ok(())
     }
 
+    /// Persist the in-memory scan counters, which batches do not write
+    pub async fn flush_runtime_state(&mut self) -> Result<()> {
+        let counters = ScanCounters {
+            total_vspc_processed: self.total_vspc_processed,
+            total_operations_found: self.total_operations_found,
+            last_scan_time: self.last_scan_time,
+        };
+        self.storage
+            .runtime
+            .set_runtime_scan_counters(&counters)
+            .await?;
+        self.last_runtime_flush = Instant::now();
+        Ok(())
+    }
+
+    /// Flush once runtimeFlushIntervalMs passed since the last flush, whether or not batches
+    /// were written meanwhile. Returns true if it flushed.
+    pub async fn flush_runtime_state_if_due(&mut self) -> Result<bool> {
+        let interval = Duration::from_millis(self.config.runtime_flush_interval_ms);
+        if self.last_runtime_flush.elapsed() < interval {
+            return Ok(false);
+        }
+        self.flush_runtime_state().await?;
+        Ok(true)
+    }
+
     pub async fn stop_scanning(&mut self) -> Result<()> {
         self.is_scanning = false;
         info!("VSPC scanning stopped");
         Ok(())
     }
 
-    async fn scan_vspc_batch(&mut self) -> Result<()> {
+    /// Check disk pressure before a batch, pausing the scanner while space is critically low
+    pub fn check_disk_pressure(&mut self) -> bool {
+        let critical = if self.paused {
+            // Re-check directly while paused so scanning resumes once space is freed
+            self.storage
+                .rocksdb
+                .check_disk_space()
+                .map(|s| s.pressure == DiskPressure::Critical)
+                .unwrap_or(false)
+        } else {
+            self.storage.rocksdb.is_disk_space_critical()
+        };
+        if critical && !self.paused {
+            warn!("explorer.scan paused: RocksDB disk space critically low");
+        } else if !critical && self.paused {
+            info!("explorer.scan resumed: RocksDB disk space recovered");
+        }
+        self.paused = critical;
+        self.paused
+    }
+
+    /// Check if scanner is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Check the node tip before a batch, pausing the scanner while it is below the last
+    /// indexed VSPC, which happens when the node was re-synced from scratch or replaced
+    pub async fn check_node_tip(&mut self) -> bool {
+        let daa_score_last = match self.vspc_list.last() {
+            Some(vspc_last) => vspc_last.daa_score,
+            None => return false,
+        };
+        // A failed tip query is left to the regular scan error handling
+        let regressed = match self.node_client.get_virtual_daa_score().await {
+            Ok(daa_score_tip) if daa_score_tip < daa_score_last => {
+                if !self.node_regressed {
+                    warn!(
+                        "explorer.scan paused: node tip DAA score {} is below last processed {}, node was re-synced or replaced",
+                        daa_score_tip, daa_score_last
+                    );
+                }
+                true
+            }
+            _ => false,
+        };
+        if !regressed && self.node_regressed {
+            info!("explorer.scan resumed: node tip caught up with last processed VSPC");
+        }
+        self.node_regressed = regressed;
+        self.node_regressed
+    }
+
+    /// Check if scanner is paused on a regressed node tip
+    pub fn is_node_regressed(&self) -> bool {
+        self.node_regressed
+    }
+
+    /// Check RocksDB write stalls before a batch, returns the backoff to apply while ingestion
+    /// is paused. A stall keeps the scanner paused until compaction caught up.
+    pub fn check_write_backpressure(&self) -> Option<Duration> {
+        if self.storage.rocksdb.check_write_pause() {
+            Some(Duration::from_millis(
+                self.storage.rocksdb.get_config().stall_backoff_ms,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Scan one VSPC batch: fetch, parse, execute and persist, or roll back on reorg
+    pub async fn scan_vspc_batch(&mut self) -> Result<()> {
+        // Refuse new batches when disk space is critically low to avoid half-written state
+        if self.check_disk_pressure() {
+            sleep(Duration::from_millis(1550)).await;
+            return Ok(());
+        }
+
+        // Slow down ingestion while RocksDB is stalling writes instead of piling up batches
+        if let Some(backoff) = self.check_write_backpressure() {
+            debug!(
+                "explorer.scan backing off {:?}: RocksDB write stall",
+                backoff
+            );
+            sleep(backoff).await;
+            return Ok(());
+        }
+
+        // Never scan a chain the node has not reached yet, it would look like a deep reorg
+        if self.check_node_tip().await {
+            sleep(Duration::from_millis(self.config.node_reset_pause_ms)).await;
+            return Ok(());
+        }
+
         let start_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -170,9 +476,10 @@ impl VSPCScanner {
 
         // Get next VSPC data list, consistent with Go version
         let mut vspc_last = DataVspcType {
-            daa_score: DAA_SCORE_RANGE[0][0],
+            daa_score: self.daa_score_start(),
             hash: String::new(),
             tx_id_list: Vec::new(),
+            selected_parent: String::new(),
         };
         let mut daa_score_start = vspc_last.daa_score;
 
@@ -180,18 +487,22 @@ impl VSPCScanner {
         let len_vspc_runtime = self.vspc_list.len();
         if len_vspc_runtime > 0 {
             vspc_last = self.vspc_list[len_vspc_runtime - 1].clone();
-            daa_score_start = vspc_last.daa_score - LEN_VSPC_CHECK as u64;
+            daa_score_start = vspc_last
+                .daa_score
+                .saturating_sub(self.config.vspc_check_window as u64);
             if daa_score_start < self.vspc_list[0].daa_score {
                 daa_score_start = self.vspc_list[0].daa_score;
             }
         }
 
-        // Get next VSPC data list from cluster database - call StateManager method
+        // Get next VSPC data list from the node
         let vspc_list_next = self
-            .storage
-            .state
             .get_node_vspc_list(daa_score_start, LEN_VSPC_LIST_MAX + 5)
             .await?;
+        self.last_scan_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
 
         if vspc_list_next.is_empty() {
             debug!(
@@ -206,10 +517,10 @@ impl VSPCScanner {
 
         // If synced, ignore last reserved VSPC data to reduce VSPC reorganization probability
         if self.synced {
-            len_vspc_next -= self.config.hysteresis as usize;
+            len_vspc_next = len_vspc_next.saturating_sub(self.config.hysteresis as usize);
         }
 
-        if len_vspc_next <= 0 {
+        if len_vspc_next == 0 {
             debug!(
                 "storage.GetNodeVspcList empty. daaScore: {}",
                 daa_score_start
@@ -226,17 +537,50 @@ impl VSPCScanner {
         );
 
         // Check rollback, corresponding to Go version's checkRollbackNext
-        let (rollback_daa_score, vspc_list_filtered) =
-            self.check_rollback_next(&self.vspc_list, &vspc_list_next, daa_score_start);
+        let (mut rollback_daa_score, mut vspc_list_filtered) =
+            VSPCScanner::check_rollback_next(&self.vspc_list, &vspc_list_next, daa_score_start);
+
+        // Chain blocks the node reports removed may lie before the checked window
+        let removed_chain_block_hashes = self.node_client.take_removed_chain_block_hashes();
+        let rollback_removed =
+            VSPCScanner::check_rollback_removed(&self.vspc_list, &removed_chain_block_hashes);
+        if rollback_removed > 0
+            && (rollback_daa_score == 0 || rollback_removed < rollback_daa_score)
+        {
+            if rollback_daa_score == 0 {
+                vspc_list_filtered = vspc_list_next.clone();
+            }
+            rollback_daa_score = rollback_removed;
+        }
 
         if rollback_daa_score > 0 {
+            let fork = VSPCScanner::find_vspc_fork(&self.vspc_list, &vspc_list_next);
+            match &fork {
+                Some(fork) => warn!(
+                    "VSPC reorg at daaScore {}, selected parent chain forks from {} at daaScore {}",
+                    rollback_daa_score, fork.hash, fork.daa_score
+                ),
+                None => warn!(
+                    "VSPC reorg at daaScore {}, selected parent chain fork not in runtime list",
+                    rollback_daa_score
+                ),
+            }
+            let depth = self
+                .vspc_list
+                .iter()
+                .filter(|vspc| vspc.daa_score >= rollback_daa_score)
+                .count();
+            self.alerts
+                .check_reorg(rollback_daa_score, depth as u64, fork.as_ref());
+
             // Need rollback, corresponding to Go version's rollback logic
             let mut daa_score_last = 0u64;
             let mut mts_rollback = 0i64;
+            let mut undone = false;
 
             // Rollback to last state data batch
             let len_rollback = self.rollback_list.len().saturating_sub(1);
-            if len_rollback > 0
+            if !self.rollback_list.is_empty()
                 && self.rollback_list[len_rollback].daa_score_end >= rollback_daa_score
             {
                 daa_score_last = self.rollback_list[len_rollback].daa_score_start;
@@ -267,6 +611,7 @@ impl VSPCScanner {
 
                 // Remove last rollback data
                 self.rollback_list.truncate(len_rollback);
+                undone = true;
                 self.storage
                     .runtime
                     .set_runtime_rollback_last(&self.rollback_list)
@@ -279,6 +624,13 @@ impl VSPCScanner {
                 .runtime
                 .set_runtime_vspc_last(&self.vspc_list)
                 .await?;
+            self.publish_event(
+                ScanEvent::Rollback {
+                    undone,
+                    vspc_list: self.vspc_list.clone(),
+                },
+                self.op_score_last,
+            )?;
             info!(
                 "explorer.checkRollbackNext start/rollback/last: {}/{}/{} mSecond: {}",
                 daa_score_start, rollback_daa_score, daa_score_last, mts_rollback
@@ -286,20 +638,23 @@ impl VSPCScanner {
             return Ok(());
         }
 
-        // Get transaction data list - call StateManager method
-        let tx_data_list = self
-            .storage
-            .state
-            .get_node_transaction_data_list(&vspc_list_filtered)
-            .await?;
-        let len_tx_data = tx_data_list.len();
-
-        if len_tx_data == 0 {
-            debug!("storage.GetNodeTransactionDataList empty");
+        // Nothing beyond the blocks already processed
+        if vspc_list_filtered.is_empty() {
             sleep(Duration::from_millis(1550)).await;
             return Ok(());
         }
 
+        // Get transaction data list from the node
+        let tx_data_list: Vec<DataTransactionType> = vspc_list_filtered
+            .iter()
+            .flat_map(DataVspcType::transaction_list)
+            .collect();
+        let tx_data_list = self
+            .node_client
+            .get_transaction_data_list(&tx_data_list)
+            .await?;
+        let len_tx_data = tx_data_list.len();
+
         info!(
             "storage.GetNodeTransactionDataList lenTransaction: {}",
             len_tx_data
@@ -312,7 +667,7 @@ impl VSPCScanner {
         info!("explorer.ParseOpDataList lenOperation: {}", len_op_data);
 
         // Prepare state batch - call OperationManager method
-        let (state_map, _) = self.prepare_state_batch(&op_data_list).await?;
+        let (mut state_map, _) = self.prepare_state_batch(&op_data_list).await?;
 
         debug!(
             "operation.PrepareStateBatch lenToken: {}, lenBalance: {}",
@@ -327,25 +682,76 @@ impl VSPCScanner {
             String::new()
         };
 
-        let (rollback, _) = self
-            .execute_batch(&mut op_data_list, state_map, &checkpoint_last)
+        let (mut rollback, _) = self
+            .execute_batch(&mut op_data_list, &mut state_map, &checkpoint_last)
             .await?;
+        rollback.daa_score_start = vspc_list_filtered[0].daa_score;
+        rollback.daa_score_end = vspc_list_filtered[vspc_list_filtered.len() - 1].daa_score;
+
+        // Save state and operation data, batches failing to persist go to the dead-letter store
+        if let Err(e) = self
+            .rollback_manager
+            .save_op_state_batch(&op_data_list, &state_map)
+            .await
+        {
+            match deadletter::record_failed_batch(
+                &self.storage.rocksdb,
+                &op_data_list,
+                rollback.daa_score_start,
+                rollback.daa_score_end,
+                &e,
+            ) {
+                Ok(entry) => error!(
+                    "explorer.saveOpStateBatch failed opScore {}-{} attempts: {}: {}",
+                    entry.op_score_start, entry.op_score_end, entry.attempts, e
+                ),
+                Err(record_err) => error!(
+                    "explorer.saveOpStateBatch failed: {}, dead-letter record failed: {}",
+                    e, record_err
+                ),
+            }
+            return Err(e);
+        }
+
+        self.alerts.check_rejected(
+            &op_data_list,
+            rollback.daa_score_start,
+            rollback.daa_score_end,
+        );
 
         // Update runtime state
+        let op_score_before = self.op_score_last;
         self.update_runtime_state(&vspc_list_filtered, &rollback)
             .await?;
+        self.publish_event(
+            ScanEvent::Batch {
+                op_data_list,
+                state_map: Box::new(state_map),
+                vspc_list: vspc_list_filtered.clone(),
+                rollback: Box::new(rollback),
+                synced: self.synced,
+            },
+            op_score_before,
+        )?;
+
+        // Initial sync caught up, switch RocksDB back to normal compaction
+        if self.synced && self.storage.rocksdb.is_bulk_load() {
+            self.storage.rocksdb.finish_bulk_load()?;
+        }
 
         // Update progress, call update_progress method
-        if let Some(vspc_data) = self.fetch_vspc_data(daa_score_start, 1).await?.first() {
-            self.update_progress(vspc_data).await?;
+        if let Some(vspc_last) = vspc_list_filtered.last() {
+            self.update_progress(vspc_last).await?;
         }
+        self.total_vspc_processed += vspc_list_filtered.len() as u64;
+        self.total_operations_found += len_op_data as u64;
 
         // Record scan statistics
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_millis() as u64;
-        let scan_duration = current_time - start_time;
+        let scan_duration = current_time.saturating_sub(start_time);
         info!(
             "explorer.scan lenRuntimeVspc: {}, lenRuntimeRollback: {}, lenOperation: {}, mSecondLoop: {}",
             self.vspc_list.len(),
@@ -354,65 +760,17 @@ impl VSPCScanner {
             scan_duration
         );
 
-        // If synced, add extra delay
-        if self.synced {
-            let delay = 850 - scan_duration as i64;
-            if delay > 0 {
-                sleep(Duration::from_millis(delay as u64)).await;
-            }
+        // If synced, pad the loop up to the target interval
+        if self.synced
+            && let Some(delay) =
+                VSPCScanner::steady_state_delay(self.config.scan_interval_ms, scan_duration)
+        {
+            sleep(delay).await;
         }
 
         Ok(())
     }
 
-    /// Check rollback, corresponding to Go version's checkRollbackNext
-    fn check_rollback_next(
-        &self,
-        vspc_list_prev: &[DataVspcType],
-        vspc_list_next: &[DataVspcType],
-        daa_score_start: u64,
-    ) -> (u64, Vec<DataVspcType>) {
-        if vspc_list_prev.is_empty() {
-            return (0, vspc_list_next.to_vec());
-        }
-
-        let mut vspc_list1 = Vec::new();
-        let mut vspc_list2 = Vec::new();
-
-        for vspc in vspc_list_prev {
-            if vspc.daa_score < daa_score_start {
-                continue;
-            }
-            vspc_list1.push(vspc.clone());
-        }
-
-        let len_check = vspc_list1.len();
-        if len_check > 0 {
-            if vspc_list_next.len() <= len_check {
-                return (0, Vec::new());
-            } else {
-                vspc_list2 = vspc_list_next[..len_check].to_vec();
-            }
-        } else {
-            vspc_list2 = vspc_list_next.to_vec();
-        }
-
-        // Check rollback
-        let mut rollback_daa_score = 0;
-        for (i, vspc1) in vspc_list1.iter().enumerate() {
-            if i >= vspc_list2.len() {
-                break;
-            }
-            let vspc2 = &vspc_list2[i];
-            if vspc1.hash != vspc2.hash {
-                rollback_daa_score = vspc1.daa_score;
-                break;
-            }
-        }
-
-        (rollback_daa_score, vspc_list2)
-    }
-
     /// Update runtime state
     async fn update_runtime_state(
         &mut self,
@@ -422,522 +780,49 @@ impl VSPCScanner {
         // Update sync state
         self.synced = vspc_list.len() < 99;
 
-        // Update VSPC list
-        self.vspc_list.extend(vspc_list.to_vec());
-
-        // Limit VSPC list size
-        if self.vspc_list.len() > LEN_VSPC_LIST_RUNTIME_MAX {
-            let len_start = self.vspc_list.len() - LEN_VSPC_LIST_RUNTIME_MAX;
-            self.vspc_list = self.vspc_list[len_start..].to_vec();
-        }
-
-        // Update rollback list
-        self.rollback_list.push(rollback.clone());
-
-        // Limit rollback list size
-        let len_rollback = self.rollback_list.len();
-        let mut len_start = 0;
-        for i in (0..len_rollback).rev() {
-            if self.rollback_list[len_rollback - 1].daa_score_end
-                - self.rollback_list[i].daa_score_start
-                >= LEN_ROLLBACK_LIST_RUNTIME_MAX as u64
-            {
-                len_start = i;
-                break;
-            }
-        }
-
-        if len_start > 0 {
-            self.rollback_list = self.rollback_list[len_start..].to_vec();
-        }
+        // Update VSPC and rollback lists
+        append_runtime_lists(
+            &mut self.vspc_list,
+            &mut self.rollback_list,
+            vspc_list,
+            rollback,
+        );
 
         // Update operation score
         if rollback.op_score_last > 0 {
             self.op_score_last = rollback.op_score_last;
         }
 
-        Ok(())
-    }
-
-    /// Get VSPC data, corresponding to Go version functionality
-    async fn fetch_vspc_data(&self, from_daa_score: u64, limit: usize) -> Result<Vec<VSPCData>> {
-        debug!(
-            "Fetching VSPC data from DAA score: {} with limit: {}",
-            from_daa_score, limit
-        );
-
-        // Implement fetching VSPC data from Kaspa node
-        let kaspa_url = &self.config.kaspa_node_url;
-
-        // Build RPC request
-        let request_body = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getVirtualSelectedParentChainFromBlock",
-            "params": {
-                "startHash": "0000000000000000000000000000000000000000000000000000000000000000",
-                "includeAcceptedTransactionIds": true
-            }
-        });
-
-        let mut vspc_data_list = Vec::new();
-
-        // Send HTTP request to Kaspa node
-        let response = self
-            .http_client
-            .post(kaspa_url)
-            .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
+        // Persist runtime lists so a restart resumes where this batch ended
+        self.storage
+            .runtime
+            .set_runtime_rollback_last(&self.rollback_list)
             .await?;
-
-        if response.status().is_success() {
-            let response_json: Value = response.json().await?;
-
-            if let Some(result) = response_json.get("result") {
-                if let Some(blocks) = result.get("blocks") {
-                    if let Some(blocks_array) = blocks.as_array() {
-                        for block in blocks_array {
-                            if let Some(daa_score) = block.get("daaScore").and_then(|v| v.as_u64())
-                            {
-                                if daa_score > from_daa_score && vspc_data_list.len() < limit {
-                                    // Parse VSPC data
-                                    let vspc_data = self.parse_vspc_block(block).await?;
-                                    vspc_data_list.push(vspc_data);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        info!("Fetched {} VSPC data records", vspc_data_list.len());
-        Ok(vspc_data_list)
-    }
-
-    /// Parse VSPC block, corresponding to Go version functionality
-    async fn parse_vspc_block(&self, block: &Value) -> Result<VSPCData> {
-        let block_hash = block
-            .get("hash")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing block hash"))?;
-
-        let daa_score = block
-            .get("daaScore")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| anyhow::anyhow!("Missing DAA score"))?;
-
-        let timestamp = block
-            .get("timestamp")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| anyhow::anyhow!("Missing timestamp"))?;
-
-        let blue_score = block.get("blueScore").and_then(|v| v.as_u64()).unwrap_or(0);
-
-        let parent_hashes = block
-            .get("parents")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let _accepted_tx_ids: Vec<String> = block
-            .get("acceptedTransactionIds")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        Ok(VSPCData {
-            block_hash: block_hash.to_string(),
-            parent_hashes,
-            daa_score,
-            timestamp,
-            blue_score,
-            blue_work: "0".to_string(),
-            pruning_point: "0".to_string(),
-            difficulty: 0.0,
-            is_header_only: false,
-            block_level: 0,
-            block_status: 0,
-            merge_set_blues: Vec::new(),
-            merge_set_reds: Vec::new(),
-            selected_parent: "0".to_string(),
-            selected_tip: "0".to_string(),
-            block_ghostdag_data: GhostDagData::default(),
-            block_relations: BlockRelations::default(),
-            block_acceptance_data: BlockAcceptanceData::default(),
-        })
-    }
-
-    /// Process VSPC data, corresponding to Go version's process_vspc_data function
-    async fn process_vspc_data(&mut self, vspc_data: &VSPCData) -> Result<()> {
-        // Convert VSPCData to DataTransactionType
-        let tx_data = DataTransactionType {
-            tx_id: vspc_data.block_hash.clone(), // Use block_hash as tx_id
-            daa_score: vspc_data.daa_score,
-            block_accept: vspc_data.block_hash.clone(),
-            data: None, // Temporarily set to None
-        };
-
-        // Get transaction data list
-        let tx_data_list = self.get_node_transaction_data_list(vec![tx_data]).await?;
-        let len_tx_data = tx_data_list.len();
-
-        if len_tx_data == 0 {
-            debug!("storage.GetNodeTransactionDataList empty");
-            return Ok(());
-        }
-
-        info!(
-            "storage.GetNodeTransactionDataList lenTransaction: {}",
-            len_tx_data
-        );
-
-        // Parse operation data list - corresponding to Go version's ParseOpDataList
-        let op_data_list = self.parse_op_data_list(tx_data_list).await?;
-        let len_op_data = op_data_list.len();
-
-        info!("explorer.ParseOpDataList lenOperation: {}", len_op_data);
-
-        if len_op_data == 0 {
-            return Ok(());
-        }
-
-        // Prepare state batch - corresponding to Go version's PrepareStateBatch
-        let state_map = DataStateMapType::new();
-        // TODO: Implement batch state preparation
-
-        debug!(
-            "operation.PrepareStateBatch lenToken: {}, lenBalance: {}",
-            state_map.state_token_map.len(),
-            state_map.state_balance_map.len()
-        );
-
-        // Execute batch - corresponding to Go version's ExecuteBatch
-        let _checkpoint_last = if !self.rollback_list.is_empty() {
-            self.rollback_list.last().unwrap().checkpoint_after.clone()
-        } else {
-            String::new()
-        };
-
-        // TODO: Implement batch execution
-        let rollback = DataRollbackType::new(
-            DataStateMapType::new(),
-            DataStateMapType::new(),
-            Vec::new(),
-            Vec::new(),
-            0,
-            0,
-            "".to_string(),
-            "".to_string(),
-            0,
-        );
-
-        // Save operation state batch
-        self.save_op_state_batch(&op_data_list).await?;
-
-        // Update rollback list
-        self.rollback_list.push(rollback);
-
-        info!(
-            "Successfully processed {} operations from VSPC",
-            len_op_data
-        );
-        Ok(())
-    }
-
-    /// Extract operations, corresponding to Go version's functionality
-    async fn extract_operations(&self, vspc_data: &VSPCData) -> Result<Vec<OperationData>> {
-        debug!("Extracting operations from VSPC data");
-
-        // Implement operation extraction logic
-        let mut operations = Vec::new();
-
-        // Extract transaction ID list from VSPC data
-        let tx_ids = self.get_transaction_ids_from_vspc(vspc_data).await?;
-
-        for tx_id in tx_ids {
-            // Get transaction details
-            if let Some(tx_data) = self.fetch_transaction_data(&tx_id).await? {
-                // Parse operations in transaction
-                let tx_operations = self
-                    .parse_transaction_operations(&tx_data, vspc_data)
-                    .await?;
-                operations.extend(tx_operations);
-            }
-        }
-
-        info!("Extracted {} operations from VSPC data", operations.len());
-        Ok(operations)
-    }
-
-    /// Get transaction ID list from VSPC data, corresponding to Go version's functionality
-    async fn get_transaction_ids_from_vspc(&self, _vspc_data: &VSPCData) -> Result<Vec<String>> {
-        // TODO: Implement extracting transaction IDs from VSPC data
-        Ok(Vec::new())
-    }
-
-    /// Get transaction data, corresponding to Go version's functionality
-    async fn fetch_transaction_data(&self, tx_id: &str) -> Result<Option<Value>> {
-        debug!("Fetching transaction data for tx_id: {}", tx_id);
-
-        // Get transaction details from Kaspa node
-        let kaspa_url = &self.config.kaspa_node_url;
-
-        let request_body = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getTransaction",
-            "params": {
-                "transactionId": tx_id,
-                "includeTransactionVerboseData": true
-            }
-        });
-
-        let response = self
-            .http_client
-            .post(kaspa_url)
-            .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
+        self.storage
+            .runtime
+            .set_runtime_vspc_last(&self.vspc_list)
             .await?;
-
-        if response.status().is_success() {
-            let response_json: Value = response.json().await?;
-            if let Some(result) = response_json.get("result") {
-                return Ok(Some(result.clone()));
-            }
-        }
-
-        Ok(None)
-    }
-
-    /// Parse transaction operations, corresponding to Go version's functionality
-    async fn parse_transaction_operations(
-        &self,
-        tx_data: &Value,
-        vspc_data: &VSPCData,
-    ) -> Result<Vec<OperationData>> {
-        debug!("Parsing transaction operations from tx_data");
-
-        let mut operations = Vec::new();
-
-        // Parse operation scripts in transaction outputs
-        if let Some(outputs) = tx_data.get("outputs").and_then(|v| v.as_array()) {
-            for (index, output) in outputs.iter().enumerate() {
-                if let Some(script_public_key) = output.get("scriptPublicKey") {
-                    if let Some(script) = script_public_key.as_str() {
-                        // Parse operations in script
-                        if let Some(operation) = self
-                            .parse_operation_script(script, tx_data, index, vspc_data)
-                            .await?
-                        {
-                            operations.push(operation);
-                        }
-                    }
-                }
-            }
-        }
-
-        info!("Parsed {} operations from transaction", operations.len());
-        Ok(operations)
-    }
-
-    async fn parse_operation_script(
-        &self,
-        script: &str,
-        tx_data: &Value,
-        output_index: usize,
-        vspc_data: &VSPCData,
-    ) -> Result<Option<OperationData>> {
-        // Parse KRC-20 operation script
-        // Check if it is a KRC-20 operation
-        if !script.contains("KRC-20") {
-            return Ok(None);
-        }
-
-        // Parse operation type and parameters
-        let operation_type = self.extract_operation_type(script)?;
-        let script_data = self.extract_script_data(script, tx_data, output_index)?;
-
-        let operation = OperationData {
-            ca: None,
-            operation_type: operation_type.clone(),
-            tick: script_data.tick.clone().unwrap_or_default(),
-            from_address: script_data.from.clone(),
-            to_address: script_data.to.clone(),
-            amount: script_data
-                .amount
-                .as_ref()
-                .and_then(|s| s.parse::<u64>().ok()),
-            tx_hash: tx_data
-                .get("transactionId")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            block_hash: vspc_data.block_hash.clone(),
-            timestamp: vspc_data.timestamp,
-            block_daa_score: vspc_data.daa_score,
-            script: Some(script_data.clone()),
-            is_testnet: self.config.is_testnet,
-            daa_score: vspc_data.daa_score,
-            tx_id: script_data.tx_hash.clone().unwrap_or_default(),
-        };
-
-        Ok(Some(operation))
-    }
-
-    fn extract_operation_type(&self, script: &str) -> Result<String> {
-        // Extract operation type from script
-        if script.contains("send") {
-            Ok("send".to_string())
-        } else if script.contains("transfer") {
-            Ok("transfer".to_string())
-        } else if script.contains("issue") {
-            Ok("issue".to_string())
-        } else if script.contains("burn") {
-            Ok("burn".to_string())
-        } else if script.contains("list") {
-            Ok("list".to_string())
-        } else if script.contains("chown") {
-            Ok("chown".to_string())
-        } else if script.contains("blacklist") {
-            Ok("blacklist".to_string())
-        } else {
-            Ok("unknown".to_string())
-        }
-    }
-
-    fn extract_script_data(
-        &self,
-        script: &str,
-        tx_data: &Value,
-        output_index: usize,
-    ) -> Result<crate::storage::types::ScriptData> {
-        // Extract data from script
-        Ok(crate::storage::types::ScriptData {
-            p: "KRC-20".to_string(),
-            operation: self.extract_operation_type(script)?,
-            from: self.extract_from_address(tx_data, output_index)?,
-            to: self.extract_to_address(tx_data, output_index)?,
-            tick: self.extract_tick(script)?,
-            amount: self.extract_amount(script)?,
-            utxo: self.extract_utxo(tx_data, output_index)?,
-            ca: self.extract_ca(script)?,
-            mode: self.extract_mode(script)?,
-            tx_hash: None, // Get from transaction data
-        })
-    }
-
-    fn extract_from_address(
-        &self,
-        tx_data: &Value,
-        _output_index: usize,
-    ) -> Result<Option<String>> {
-        // Extract sender address from transaction data
-        if let Some(inputs) = tx_data.get("inputs").and_then(|v| v.as_array()) {
-            if let Some(input) = inputs.get(0) {
-                if let Some(address) = input.get("address").and_then(|v| v.as_str()) {
-                    return Ok(Some(address.to_string()));
-                }
-            }
-        }
-        Ok(None)
-    }
-
-    fn extract_to_address(&self, tx_data: &Value, output_index: usize) -> Result<Option<String>> {
-        // Extract receiver address from transaction data
-        if let Some(outputs) = tx_data.get("outputs").and_then(|v| v.as_array()) {
-            if let Some(output) = outputs.get(output_index) {
-                if let Some(address) = output.get("address").and_then(|v| v.as_str()) {
-                    return Ok(Some(address.to_string()));
-                }
-            }
-        }
-        Ok(None)
-    }
-
-    fn extract_tick(&self, _script: &str) -> Result<Option<String>> {
-        // Extract tick from script
-        // TODO: Implement specific parsing logic here
-        Ok(None)
-    }
-
-    fn extract_amount(&self, _script: &str) -> Result<Option<String>> {
-        // Extract amount from script
-        // TODO: Implement specific parsing logic here
-        Ok(None)
-    }
-
-    fn extract_utxo(&self, tx_data: &Value, output_index: usize) -> Result<Option<String>> {
-        // Extract UTXO from transaction data
-        if let Some(outputs) = tx_data.get("outputs").and_then(|v| v.as_array()) {
-            if let Some(output) = outputs.get(output_index) {
-                if let Some(amount) = output.get("amount").and_then(|v| v.as_u64()) {
-                    let tx_id = tx_data
-                        .get("transactionId")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("");
-                    return Ok(Some(format!("{}_{}", tx_id, amount)));
-                }
-            }
-        }
-        Ok(None)
-    }
-
-    fn extract_ca(&self, _script: &str) -> Result<Option<String>> {
-        // Extract CA from script
-        // TODO: Implement specific parsing logic here
-        Ok(None)
-    }
-
-    fn extract_mode(&self, _script: &str) -> Result<Option<String>> {
-        // Extract mode from script
-        // TODO: Implement specific parsing logic here
-        Ok(None)
-    }
-
-    /// Process operation, corresponding to Go version's process_operation function
-    async fn process_operation(&self, operation: OperationData) -> Result<()> {
-        debug!(
-            "Processing operation: {} for tick: {}",
-            operation.operation_type, operation.tick
-        );
-
-        // TODO: Use operation manager to process operations
-        // let operation_manager = self.storage.get_operation_manager()?;
-        // Should call specific operation methods here, not generic validate_operation and execute_operation
-
-        // Save operation to distributed storage
-        if let Some(_distributed) = &self.storage.distributed {
-            // TODO: Implement operation insertion in distributed storage
-            debug!("Operation would be saved to distributed storage");
+        if let Some(vspc_last) = vspc_list.last() {
+            self.storage
+                .runtime
+                .set_runtime_synced(self.synced, self.op_score_last, vspc_last.daa_score)
+                .await?;
         }
 
-        info!("Successfully processed operation: {}", operation.tx_id);
         Ok(())
     }
 
     /// Update progress, corresponding to Go version's functionality
-    async fn update_progress(&self, vspc_data: &VSPCData) -> Result<()> {
+    async fn update_progress(&self, vspc_data: &DataVspcType) -> Result<()> {
         // Update runtime state with progress
         self.storage
             .runtime
-            .update_progress(&vspc_data.block_hash, vspc_data.daa_score)?;
+            .update_progress(&vspc_data.hash, vspc_data.daa_score)?;
 
         // Save checkpoint
         self.storage
             .runtime
-            .save_checkpoint(&vspc_data.block_hash, vspc_data.daa_score)?;
+            .save_checkpoint(&vspc_data.hash, vspc_data.daa_score)?;
 
         Ok(())
     }
@@ -989,143 +874,89 @@ impl VSPCScanner {
 
     /// Check if DAA score is valid, corresponding to Go version's functionality
     pub fn is_daa_score_valid(&self, daa_score: u64) -> bool {
-        // Check if DAA score is within valid range
-        for range in &self.config.daa_score_range {
-            if daa_score >= range[0] && daa_score <= range[1] {
-                return true;
-            }
-        }
-        false
+        self.daa_score_ranges.contains(daa_score)
     }
 
-    /// Check if it is a reserved token, corresponding to Go version's functionality
-    fn is_tick_reserved(&self, tick: &str) -> bool {
-        crate::operations::is_tick_reserved(tick)
-    }
-
-    /// Check DAA score range, corresponding to Go version's checkDaaScoreRange
-    fn check_daa_score_range(&self, daa_score: u64) -> (bool, u64) {
-        for range in &self.config.daa_score_range {
-            if daa_score < range[0] {
-                return (false, range[0]);
-            } else if daa_score <= range[1] {
-                return (true, daa_score);
-            }
-        }
-        (false, daa_score)
+    /// DAA score scanning starts from when no VSPC was indexed yet
+    fn daa_score_start(&self) -> u64 {
+        self.daa_score_ranges.first_start().unwrap_or_default()
     }
 
     /// Get node VSPC list, corresponding to Go version's GetNodeVspcList
     async fn get_node_vspc_list(
         &self,
-        _daa_score_start: u64,
-        _limit: usize,
+        daa_score_start: u64,
+        limit: usize,
     ) -> Result<Vec<DataVspcType>> {
-        // TODO: Implement getting VSPC list from Kaspa node
-        // Should call Kaspa RPC API here
-        Ok(Vec::new())
-    }
-
-    /// Get node transaction data list, corresponding to Go version's GetNodeTransactionDataList
-    async fn get_node_transaction_data_list(
-        &self,
-        tx_data_list: Vec<DataTransactionType>,
-    ) -> Result<Vec<DataTransactionType>> {
-        // TODO: Implement getting transaction data from Kaspa node
-        Ok(tx_data_list)
+        self.storage
+            .state
+            .get_node_vspc_list(&self.node_client, daa_score_start, limit)
+            .await
     }
 
     /// Parse operation data list, corresponding to Go version's ParseOpDataList
     async fn parse_op_data_list(
-        &self,
+        &mut self,
         tx_data_list: Vec<DataTransactionType>,
     ) -> Result<Vec<DataOperationType>> {
-        // TODO: Implement transaction parsing logic
-        let mut op_data_list = Vec::new();
-
-        for _tx_data in tx_data_list {
-            // Parse operations in transaction
-            // TODO: Need to provide VSPC data parameter
-            let operations = self
-                .parse_transaction_operations(&serde_json::Value::Null, &VSPCData::default())
+        let mut op_data_list = ScriptParser::parse_op_data_list(&tx_data_list, self.testnet)?;
+        crate::operations::metrics::log_script_rejection_summary();
+
+        // Fee inputs created neither in this batch nor in recent ones are asked from the node,
+        // which has no transaction index. Inputs it can not resolve leave the fee unknown.
+        if self.utxo_cache.len() > UTXO_CACHE_MAX {
+            self.utxo_cache.clear();
+        }
+        self.utxo_cache.insert_transactions(&tx_data_list);
+        let input_tx_list: Vec<DataTransactionType> =
+            ScriptParser::fee_input_tx_ids(&op_data_list, &tx_data_list, &self.utxo_cache)
+                .into_iter()
+                .map(|tx_id| DataTransactionType {
+                    tx_id,
+                    daa_score: 0,
+                    block_accept: String::new(),
+                    data: None,
+                })
+                .collect();
+        if !input_tx_list.is_empty() {
+            let input_tx_list = self
+                .node_client
+                .get_transaction_data_list(&input_tx_list)
                 .await?;
-            // Convert OperationData to DataOperationType
-            for op in operations {
-                let data_op = DataOperationType {
-                    tx_id: op.tx_id,
-                    daa_score: op.daa_score,
-                    block_accept: op.block_hash,
-                    fee: 0,
-                    fee_least: 0,
-                    mts_add: op.timestamp as i64,
-                    op_score: op.block_daa_score,
-                    op_accept: 0,
-                    op_error: String::new(),
-                    op_script: Vec::new(),
-                    script_sig: String::new(),
-                    st_before: Vec::new(),
-                    st_after: Vec::new(),
-                    checkpoint: String::new(),
-                    ss_info: None,
-                };
-                op_data_list.push(data_op);
-            }
+            self.utxo_cache.insert_transactions(&input_tx_list);
         }
-
+        // Inputs cached from earlier batches resolve fees without any node request
+        ScriptParser::calculate_fees(&mut op_data_list, &tx_data_list, &self.utxo_cache);
         Ok(op_data_list)
     }
 
+    fn get_operation_manager(&self) -> Result<&OperationManager> {
+        self.operation_manager
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("explorer not initialized"))
+    }
+
     /// Prepare state batch, corresponding to Go version's PrepareStateBatch
     async fn prepare_state_batch(
         &self,
-        _op_data_list: &[DataOperationType],
+        op_data_list: &[DataOperationType],
     ) -> Result<(DataStateMapType, i64)> {
-        // TODO: Implement state preparation logic
-        let state_map = DataStateMapType {
-            state_token_map: HashMap::new(),
-            state_balance_map: HashMap::new(),
-            state_market_map: HashMap::new(),
-            state_blacklist_map: HashMap::new(),
-        };
-        Ok((state_map, 0))
+        self.get_operation_manager()?
+            .prepare_state_batch(op_data_list)
     }
 
     /// Execute batch, corresponding to Go version's ExecuteBatch
     async fn execute_batch(
         &self,
-        _op_data_list: &mut [DataOperationType],
-        _state_map: DataStateMapType,
+        op_data_list: &mut [DataOperationType],
+        state_map: &mut DataStateMapType,
         checkpoint_last: &str,
     ) -> Result<(DataRollbackType, i64)> {
-        // TODO: Implement batch execution logic
-        let rollback = DataRollbackType {
-            checkpoint_before: checkpoint_last.to_string(),
-            checkpoint_after: checkpoint_last.to_string(),
-            daa_score_start: 0,
-            daa_score_end: 0,
-            state_map_before: _state_map,
-            state_map_after: DataStateMapType {
-                state_token_map: HashMap::new(),
-                state_balance_map: HashMap::new(),
-                state_market_map: HashMap::new(),
-                state_blacklist_map: HashMap::new(),
-            },
-            op_score_list: Vec::new(),
-            tx_id_list: Vec::new(),
-            op_score_last: 0,
-        };
-        Ok((rollback, 0))
-    }
-
-    /// Save operation state batch, corresponding to Go version's SaveOpStateBatch
-    async fn save_op_state_batch(&self, _op_data_list: &[DataOperationType]) -> Result<Vec<i64>> {
-        // TODO: Implement state saving logic
-        Ok(Vec::new())
-    }
-
-    /// Rollback operation state batch, corresponding to Go version's RollbackOpStateBatch
-    async fn rollback_op_state_batch(&self, _rollback: &DataRollbackType) -> Result<i64> {
-        // TODO: Implement rollback logic
-        Ok(0)
+        self.get_operation_manager()?.execute_batch(
+            op_data_list,
+            state_map,
+            checkpoint_last,
+            self.testnet,
+        )
     }
 }
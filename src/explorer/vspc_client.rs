@@ -1,27 +1,56 @@
+use crate::protobuf::ProtobufHandler;
+use crate::protobuf::convert;
+use crate::protobuf::protowire::*;
 use crate::storage::types::*;
 use anyhow::Result;
-use reqwest::Client;
-use serde_json::{Value, json};
-use std::collections::HashMap;
-use tracing::{error, info};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use tokio::sync::OnceCell;
+use tracing::info;
+
+/// Chain blocks remembered as start points of GetVirtualChainFromBlock
+const LEN_CHAIN_HASHES_MAX: usize = 4096;
+/// Chain block headers fetched by one get_vspc_list call
+const LEN_CHAIN_HEADERS_MAX: usize = 4096;
 
 /// VSPC client for fetching data from Kaspa node
 pub struct VSPCClient {
-    client: Client,
     node_url: String,
-    timeout: std::time::Duration,
+    // Maximum number of GetBlock requests in flight
+    tx_fetch_concurrency: usize,
+    // gRPC handler of the node, connected on first use
+    rpc: OnceCell<ProtobufHandler>,
+    // Hashes of returned chain blocks by DAA score
+    chain_hashes: Mutex<BTreeMap<u64, String>>,
+    // Chain blocks the node reported removed, until taken by the scanner
+    removed_hashes: Mutex<Vec<String>>,
 }
 
 impl VSPCClient {
     pub fn new(node_url: String) -> Self {
         Self {
-            client: Client::new(),
             node_url,
-            timeout: std::time::Duration::from_secs(30),
+            tx_fetch_concurrency: crate::config::types::default_tx_fetch_concurrency(),
+            rpc: OnceCell::new(),
+            chain_hashes: Mutex::new(BTreeMap::new()),
+            removed_hashes: Mutex::new(Vec::new()),
         }
     }
 
-    /// Get VSPC data list, corresponding to Go version's GetNodeVspcList
+    /// Set how many transactions are fetched concurrently, at least one
+    pub fn with_tx_fetch_concurrency(mut self, tx_fetch_concurrency: usize) -> Self {
+        self.tx_fetch_concurrency = tx_fetch_concurrency.max(1);
+        self
+    }
+
+    /// Get VSPC data list, corresponding to Go version's GetNodeVspcList. The chain is read with
+    /// GetVirtualChainFromBlock from the last known chain block before `daa_score_start`, or
+    /// from the pruning point, and holds at most `limit` blocks from `daa_score_start` on.
+    /// Block headers are fetched concurrently, in chain order, until the limit is reached or
+    /// LEN_CHAIN_HEADERS_MAX were fetched. The last block fetched before `daa_score_start` is
+    /// remembered too, so a read far below it resumes where the previous one stopped.
     pub async fn get_vspc_list(
         &self,
         daa_score_start: u64,
@@ -32,124 +61,304 @@ impl VSPCClient {
             daa_score_start, limit
         );
 
+        let mut handler = self.rpc_handler().await?;
+        let start_hash = match self.chain_start_hash(daa_score_start) {
+            Some(hash) => hash,
+            None => Self::get_pruning_point_hash(&mut handler).await?,
+        };
+        let mut chain = Self::get_virtual_chain(&mut handler, start_hash).await?;
+
+        // Chain blocks come in ascending DAA score, skip the ones before daa_score_start
+        let added = std::mem::take(&mut chain.added_chain_block_hashes);
+        let mut headers = stream::iter(added)
+            .take(LEN_CHAIN_HEADERS_MAX)
+            .map(|hash| {
+                let mut handler = handler.clone();
+                async move { Self::get_chain_block(&mut handler, &hash).await }
+            })
+            .buffered(self.tx_fetch_concurrency);
+        let mut skipped = None;
         let mut vspc_list = Vec::new();
-
-        // Build RPC request to get block information
-        let request_body = json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getVirtualSelectedParentChainFromBlock",
-            "params": {
-                "startHash": "0000000000000000000000000000000000000000000000000000000000000000",
-                "includeAcceptedTransactionIds": true
+        while vspc_list.len() < limit
+            && let Some(vspc) = headers.try_next().await?
+        {
+            if vspc.daa_score < daa_score_start {
+                skipped = Some(vspc);
+            } else {
+                vspc_list.push(vspc);
             }
-        });
-
-        let response = self
-            .client
-            .post(&self.node_url)
-            .header("Content-Type", "application/json")
-            .timeout(self.timeout)
-            .json(&request_body)
-            .send()
-            .await?;
+        }
 
-        if response.status().is_success() {
-            let response_json: Value = response.json().await?;
-
-            if let Some(result) = response_json.get("result") {
-                if let Some(blocks) = result.get("blocks") {
-                    if let Some(blocks_array) = blocks.as_array() {
-                        for block in blocks_array {
-                            if let Some(daa_score) = block.get("daaScore").and_then(|v| v.as_u64())
-                            {
-                                if daa_score >= daa_score_start && vspc_list.len() < limit {
-                                    // Parse VSPC data
-                                    if let Ok(vspc_data) = self.parse_vspc_block(block).await {
-                                        vspc_list.push(vspc_data);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        } else {
-            error!("Failed to fetch VSPC data: {}", response.status());
+        let mut accepted: HashMap<String, Vec<String>> = chain
+            .accepted_transaction_ids
+            .into_iter()
+            .map(|ids| (ids.accepting_block_hash, ids.accepted_transaction_ids))
+            .collect();
+        for vspc in &mut vspc_list {
+            vspc.tx_id_list = accepted.remove(&vspc.hash).unwrap_or_default();
+        }
+        self.remember_chain(&chain.removed_chain_block_hashes, &vspc_list);
+        if let Some(skipped) = skipped {
+            self.remember_chain(&[], &[skipped]);
         }
+        self.removed_hashes
+            .lock()
+            .unwrap()
+            .extend(chain.removed_chain_block_hashes);
 
         info!("Fetched {} VSPC data records", vspc_list.len());
         Ok(vspc_list)
     }
 
-    /// Get transaction data map, corresponding to Go version's GetNodeTransactionDataMap
-    pub async fn get_transaction_data_map(
-        &self,
-        tx_data_list: &[DataTransactionType],
-    ) -> Result<HashMap<String, Value>> {
-        info!(
-            "Fetching transaction data for {} transactions",
-            tx_data_list.len()
-        );
+    /// gRPC handler of the node, connecting on first use
+    async fn rpc_handler(&self) -> Result<ProtobufHandler> {
+        let handler = self
+            .rpc
+            .get_or_try_init(|| async {
+                let mut handler = ProtobufHandler::new();
+                handler.connect(self.node_url.clone()).await?;
+                Ok::<_, anyhow::Error>(handler)
+            })
+            .await?;
+        Ok(handler.clone())
+    }
 
-        let mut tx_data_map = HashMap::new();
+    /// Remember chain blocks persisted by an earlier run, e.g. the runtime VSPC list
+    pub fn seed_chain(&self, vspc_list: &[DataVspcType]) {
+        self.remember_chain(&[], vspc_list);
+    }
 
-        // Batch fetch transaction data
-        for tx_data in tx_data_list {
-            let request_body = json!({
-                "jsonrpc": "2.0",
-                "id": 1,
-                "method": "getTransaction",
-                "params": {
-                    "transactionId": tx_data.tx_id,
-                    "includeTransactionVerboseData": true
-                }
-            });
-
-            let response = self
-                .client
-                .post(&self.node_url)
-                .header("Content-Type", "application/json")
-                .timeout(self.timeout)
-                .json(&request_body)
-                .send()
-                .await?;
+    /// Chain blocks the node removed since the last call, as reported by GetVirtualChainFromBlock
+    pub fn take_removed_chain_block_hashes(&self) -> Vec<String> {
+        std::mem::take(&mut *self.removed_hashes.lock().unwrap())
+    }
 
-            if response.status().is_success() {
-                let response_json: Value = response.json().await?;
+    /// Last remembered chain block before `daa_score_start`
+    fn chain_start_hash(&self, daa_score_start: u64) -> Option<String> {
+        let chain_hashes = self.chain_hashes.lock().unwrap();
+        chain_hashes
+            .range(..daa_score_start)
+            .next_back()
+            .map(|(_, hash)| hash.clone())
+    }
 
-                if let Some(result) = response_json.get("result") {
-                    tx_data_map.insert(tx_data.tx_id.clone(), result.clone());
-                }
-            }
+    /// Forget the chain blocks the node removed and remember the ones returned
+    fn remember_chain(&self, removed_chain_block_hashes: &[String], vspc_list: &[DataVspcType]) {
+        let mut chain_hashes = self.chain_hashes.lock().unwrap();
+        chain_hashes.retain(|_, hash| !removed_chain_block_hashes.contains(hash));
+        for vspc in vspc_list {
+            chain_hashes.insert(vspc.daa_score, vspc.hash.clone());
+        }
+        while chain_hashes.len() > LEN_CHAIN_HASHES_MAX {
+            chain_hashes.pop_first();
+        }
+    }
+
+    async fn get_pruning_point_hash(handler: &mut ProtobufHandler) -> Result<String> {
+        Ok(Self::get_block_dag_info(handler).await?.pruning_point_hash)
+    }
+
+    async fn get_block_dag_info(
+        handler: &mut ProtobufHandler,
+    ) -> Result<GetBlockDagInfoResponseMessage> {
+        let request = KaspadRequest {
+            id: 1,
+            payload: Some(kaspad_request::Payload::GetBlockDagInfoRequest(
+                GetBlockDagInfoRequestMessage {},
+            )),
+        };
+        match handler.send_request(request).await?.payload {
+            Some(kaspad_response::Payload::GetBlockDagInfoResponse(resp)) => Ok(resp),
+            _ => Err(anyhow::anyhow!("Unexpected response type")),
         }
+    }
+
+    async fn get_virtual_chain(
+        handler: &mut ProtobufHandler,
+        start_hash: String,
+    ) -> Result<GetVirtualChainFromBlockResponseMessage> {
+        let request = KaspadRequest {
+            id: 1,
+            payload: Some(kaspad_request::Payload::GetVirtualChainFromBlockRequest(
+                GetVirtualChainFromBlockRequestMessage {
+                    start_hash,
+                    include_accepted_transaction_ids: true,
+                },
+            )),
+        };
+        match handler.send_request(request).await?.payload {
+            Some(kaspad_response::Payload::GetVirtualChainFromBlockResponse(resp)) => Ok(resp),
+            _ => Err(anyhow::anyhow!("Unexpected response type")),
+        }
+    }
+
+    /// Chain block `hash` without its accepted transactions
+    async fn get_chain_block(handler: &mut ProtobufHandler, hash: &str) -> Result<DataVspcType> {
+        let block = Self::get_block(handler, hash, false).await?;
+        let daa_score = block
+            .header
+            .map(|header| header.daa_score)
+            .ok_or_else(|| anyhow::anyhow!("Missing header of block {}", hash))?;
+        let selected_parent = block
+            .verbose_data
+            .map(|verbose_data| verbose_data.selected_parent_hash)
+            .unwrap_or_default();
+
+        Ok(DataVspcType {
+            daa_score,
+            hash: hash.to_string(),
+            tx_id_list: Vec::new(),
+            selected_parent,
+        })
+    }
+
+    async fn get_block(
+        handler: &mut ProtobufHandler,
+        hash: &str,
+        include_transactions: bool,
+    ) -> Result<RpcBlock> {
+        let request = KaspadRequest {
+            id: 1,
+            payload: Some(kaspad_request::Payload::GetBlockRequest(
+                GetBlockRequestMessage {
+                    hash: hash.to_string(),
+                    include_transactions,
+                },
+            )),
+        };
+        let block = match handler.send_request(request).await?.payload {
+            Some(kaspad_response::Payload::GetBlockResponse(resp)) => resp.block,
+            _ => return Err(anyhow::anyhow!("Unexpected response type")),
+        };
+        block.ok_or_else(|| anyhow::anyhow!("Block {} not found", hash))
+    }
+
+    /// Get transaction data map, corresponding to Go version's GetNodeTransactionDataMap
+    pub async fn get_transaction_data_map(
+        &self,
+        tx_data_list: &[DataTransactionType],
+    ) -> Result<HashMap<String, Value>> {
+        let tx_data_map: HashMap<String, Value> = self
+            .get_transaction_data_list(tx_data_list)
+            .await?
+            .into_iter()
+            .filter_map(|tx_data| tx_data.data.map(|data| (tx_data.tx_id, data)))
+            .collect();
 
-        info!("Fetched {} transaction data records", tx_data_map.len());
         Ok(tx_data_map)
     }
 
-    /// Get transaction data list, corresponding to Go version's GetNodeTransactionDataList
+    /// Get transaction data list, corresponding to Go version's GetNodeTransactionDataList.
+    /// kaspad has no transaction index, so transactions are read from the blocks merged by
+    /// their accepting chain block, fetched with bounded concurrency. The list keeps the input
+    /// order, transactions without an accepting block get no data.
     pub async fn get_transaction_data_list(
         &self,
         tx_data_list: &[DataTransactionType],
     ) -> Result<Vec<DataTransactionType>> {
-        let tx_data_map = self.get_transaction_data_map(tx_data_list).await?;
+        info!(
+            "Fetching transaction data for {} transactions",
+            tx_data_list.len()
+        );
 
-        let mut updated_tx_list = Vec::new();
+        let mut block_accept_list: Vec<String> = tx_data_list
+            .iter()
+            .map(|tx_data| tx_data.block_accept.clone())
+            .filter(|block_accept| !block_accept.is_empty())
+            .collect();
+        block_accept_list.sort_unstable();
+        block_accept_list.dedup();
+
+        let mut fetched = HashMap::new();
+        if !block_accept_list.is_empty() {
+            let handler = self.rpc_handler().await?;
+            let merged_hashes = self.get_merged_hashes(&handler, block_accept_list).await?;
+            let tx_id_set: HashSet<&str> = tx_data_list
+                .iter()
+                .map(|tx_data| tx_data.tx_id.as_str())
+                .collect();
+            fetched = self
+                .get_merged_transactions(&handler, merged_hashes, &tx_id_set)
+                .await?;
+        }
 
+        let mut updated_tx_list = Vec::with_capacity(tx_data_list.len());
+        let mut len_fetched = 0;
         for tx_data in tx_data_list {
             let mut updated_tx = tx_data.clone();
-            if let Some(tx_info) = tx_data_map.get(&tx_data.tx_id) {
-                updated_tx.data = Some(tx_info.clone());
+            if let Some(data) = fetched.get(&tx_data.tx_id) {
+                len_fetched += 1;
+                updated_tx.data = Some(data.clone());
             }
             updated_tx_list.push(updated_tx);
         }
 
+        info!("Fetched {} transaction data records", len_fetched);
         Ok(updated_tx_list)
     }
 
-    /// Parse VSPC block, corresponding to Go version functionality
-    async fn parse_vspc_block(&self, block: &Value) -> Result<DataVspcType> {
+    /// Hashes of the blocks merged by the accepting chain blocks, blues and reds alike
+    async fn get_merged_hashes(
+        &self,
+        handler: &ProtobufHandler,
+        block_accept_list: Vec<String>,
+    ) -> Result<Vec<String>> {
+        let mut merged_hashes: Vec<String> = stream::iter(block_accept_list)
+            .map(|hash| {
+                let mut handler = handler.clone();
+                async move { Self::get_block(&mut handler, &hash, false).await }
+            })
+            .buffer_unordered(self.tx_fetch_concurrency)
+            .map_ok(|block| {
+                block
+                    .verbose_data
+                    .map(|verbose_data| {
+                        let mut hashes = verbose_data.merge_set_blues_hashes;
+                        hashes.extend(verbose_data.merge_set_reds_hashes);
+                        hashes
+                    })
+                    .unwrap_or_default()
+            })
+            .try_concat()
+            .await?;
+        merged_hashes.sort_unstable();
+        merged_hashes.dedup();
+        Ok(merged_hashes)
+    }
+
+    /// Transactions of the merged blocks in `tx_id_set`, as JSON by transaction id
+    async fn get_merged_transactions(
+        &self,
+        handler: &ProtobufHandler,
+        merged_hashes: Vec<String>,
+        tx_id_set: &HashSet<&str>,
+    ) -> Result<HashMap<String, Value>> {
+        stream::iter(merged_hashes)
+            .map(|hash| {
+                let mut handler = handler.clone();
+                async move { Self::get_block(&mut handler, &hash, true).await }
+            })
+            .buffer_unordered(self.tx_fetch_concurrency)
+            .map_ok(|block| {
+                block
+                    .transactions
+                    .iter()
+                    .filter_map(|tx| {
+                        let tx_id = &tx.verbose_data.as_ref()?.transaction_id;
+                        tx_id_set
+                            .contains(tx_id.as_str())
+                            .then(|| (tx_id.clone(), convert::rpc_transaction_to_json(tx)))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .try_concat()
+            .await
+            .map(|fetched| fetched.into_iter().collect())
+    }
+
+    /// Parse VSPC block, corresponding to Go version functionality.
+    /// The accepted transaction ids become the block's tx_id_list.
+    pub fn parse_vspc_block(block: &Value) -> Result<DataVspcType> {
         let block_hash = block
             .get("hash")
             .and_then(|v| v.as_str())
@@ -171,102 +380,24 @@ impl VSPCClient {
             })
             .unwrap_or_default();
 
+        let selected_parent = block
+            .pointer("/verboseData/selectedParentHash")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
         Ok(DataVspcType {
             daa_score,
             hash: block_hash.to_string(),
             tx_id_list: accepted_tx_ids,
+            selected_parent: selected_parent.to_string(),
         })
     }
 
-    /// Get block information
-    pub async fn get_block_info(&self, block_hash: &str) -> Result<Value> {
-        let request_body = json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getBlock",
-            "params": {
-                "blockHash": block_hash,
-                "includeTransactionVerboseData": true
-            }
-        });
-
-        let response = self
-            .client
-            .post(&self.node_url)
-            .header("Content-Type", "application/json")
-            .timeout(self.timeout)
-            .json(&request_body)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            let response_json: Value = response.json().await?;
-            if let Some(result) = response_json.get("result") {
-                Ok(result.clone())
-            } else {
-                Err(anyhow::anyhow!("No result in response"))
-            }
-        } else {
-            Err(anyhow::anyhow!(
-                "Failed to get block info: {}",
-                response.status()
-            ))
-        }
-    }
-
-    /// Get transaction information
-    pub async fn get_transaction_info(&self, tx_id: &str) -> Result<Value> {
-        let request_body = json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getTransaction",
-            "params": {
-                "transactionId": tx_id,
-                "includeTransactionVerboseData": true
-            }
-        });
-
-        let response = self
-            .client
-            .post(&self.node_url)
-            .header("Content-Type", "application/json")
-            .timeout(self.timeout)
-            .json(&request_body)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            let response_json: Value = response.json().await?;
-            if let Some(result) = response_json.get("result") {
-                Ok(result.clone())
-            } else {
-                Err(anyhow::anyhow!("No result in response"))
-            }
-        } else {
-            Err(anyhow::anyhow!(
-                "Failed to get transaction info: {}",
-                response.status()
-            ))
-        }
-    }
-
-    /// Health check
-    pub async fn health_check(&self) -> Result<bool> {
-        let request_body = json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getInfo"
-        });
-
-        let response = self
-            .client
-            .post(&self.node_url)
-            .header("Content-Type", "application/json")
-            .timeout(self.timeout)
-            .json(&request_body)
-            .send()
-            .await?;
-
-        Ok(response.status().is_success())
+    /// Get the node's virtual DAA score, i.e. the current chain tip
+    pub async fn get_virtual_daa_score(&self) -> Result<u64> {
+        let mut handler = self.rpc_handler().await?;
+        Ok(Self::get_block_dag_info(&mut handler)
+            .await?
+            .virtual_daa_score)
     }
 }
@@ -0,0 +1,39 @@
+use crate::config::types::CacheControlConfig;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+
+/// Live data such as balances, never served from a cache
+pub const NO_STORE: &str = "no-store";
+/// Data that may still change, caches must revalidate before reuse
+pub const NO_CACHE: &str = "no-cache";
+
+/// `Cache-Control` of `/v1/info`, cached briefly
+pub fn info(config: &CacheControlConfig) -> String {
+    format!("public, max-age={}", config.info_max_age)
+}
+
+/// `Cache-Control` of an operation accepted at `daa_score`. Operations only become immutable once
+/// they lie `finality_depth` DAA scores behind the last processed DAA score, a reorg may still
+/// roll back more recent ones.
+pub fn operation(
+    config: &CacheControlConfig,
+    daa_score: u64,
+    last_processed_daa_score: Option<u64>,
+) -> String {
+    let is_final = last_processed_daa_score
+        .is_some_and(|processed| processed.saturating_sub(daa_score) >= config.finality_depth);
+    if is_final {
+        format!("public, max-age={}, immutable", config.final_op_max_age)
+    } else {
+        NO_CACHE.to_string()
+    }
+}
+
+/// Attach a `Cache-Control` header to a response
+pub fn with_cache_control(cache_control: &str, response: impl IntoResponse) -> Response {
+    (
+        [(header::CACHE_CONTROL, cache_control.to_string())],
+        response,
+    )
+        .into_response()
+}
@@ -1,20 +1,122 @@
-use axum::{Router, routing::get};
+pub mod cache;
+pub mod node_tip;
+pub mod pagination;
+
+use crate::config::types::CacheControlConfig;
+use crate::explorer::RollbackManager;
+use crate::explorer::rollback::{RollbackCandidateCache, RollbackStatisticsCache};
+use crate::http::node_tip::NodeTipCache;
+use crate::http::pagination::Pagination;
+use crate::operations::{validate_tick, validate_tick_tx_id, validate_tx_id};
+use crate::storage::StorageManager;
+use crate::storage::checkpoint;
+use crate::storage::deadletter;
+use crate::storage::events;
+use crate::storage::opscore;
+use crate::storage::state::compare_listings;
+use crate::storage::stats::StatsCache;
+use crate::storage::tickops;
+use crate::storage::tickstats::{self, TickOpStats};
+use crate::storage::tokenindex;
+use crate::storage::types::{
+    DataOperationType, DataScriptType, DiskPressure, FeeBreakdown, StateMarketType, StateTokenType,
+    TokenDeployInfo,
+};
+use crate::storage::versioned;
+use crate::utils::crypto::constant_time_eq;
+use crate::utils::script_parser::ScriptParser;
+use axum::{
+    Router,
+    extract::{Request, rejection::PathRejection},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
 
 #[derive(Clone)]
 pub struct HttpState {
     pub kaspa_rest_base_url: String,
+    pub storage: Arc<StorageManager>,
+    pub admin_token: String,
+    pub node_tip: Arc<NodeTipCache>,
+    pub stats: Arc<StatsCache>,
+    /// Upper bound of `?limit=` on list endpoints
+    pub max_page_size: usize,
+    /// `Cache-Control` durations of the read endpoints
+    pub cache_control: CacheControlConfig,
+    /// Serve the `/v1/debug` developer endpoints
+    pub debug_endpoints: bool,
+    pub rollback_candidates: Arc<RollbackCandidateCache>,
+    pub rollback_stats: Arc<RollbackStatisticsCache>,
+    /// Permits of concurrently served RocksDB reads, reads beyond them are shed with 503
+    pub read_permits: Arc<Semaphore>,
 }
 
 pub fn build_router_with_state(state: HttpState) -> Router {
-    Router::new().nest("/v1", v1_router()).with_state(state)
+    Router::new()
+        .nest(
+            "/v1",
+            public_router(state.clone()).merge(internal_router(state.clone())),
+        )
+        .with_state(state)
 }
 
-fn v1_router() -> Router<HttpState> {
+/// Public API routes only, for a listener separate from the metrics and admin routes
+pub fn build_public_router(state: HttpState) -> Router {
     Router::new()
+        .nest("/v1", public_router(state.clone()))
+        .with_state(state)
+}
+
+/// Metrics and admin routes only, served on the listener bound to `http.admin_bind`
+pub fn build_internal_router(state: HttpState) -> Router {
+    Router::new()
+        .nest("/v1", internal_router(state.clone()))
+        .with_state(state)
+}
+
+fn public_router(state: HttpState) -> Router<HttpState> {
+    let router = if state.debug_endpoints {
+        Router::new().nest("/debug", debug_router())
+    } else {
+        Router::new()
+    };
+    router
         .route("/info", get(handler_info))
+        .route("/health", get(handler_health))
+        .merge(read_router(state))
+}
+
+/// Operational routes, which may be kept off the public interface
+fn internal_router(state: HttpState) -> Router<HttpState> {
+    Router::new()
+        .route("/metrics/operations", get(handler_metrics_operations))
+        .route(
+            "/metrics/script-rejections",
+            get(handler_metrics_script_rejections),
+        )
+        .route("/metrics/write-stall", get(handler_metrics_write_stall))
+        .nest("/admin", admin_router(state))
+}
+
+/// Read routes scanning RocksDB, bounded by the read permits so API load cannot starve the scanner
+fn read_router(state: HttpState) -> Router<HttpState> {
+    Router::new()
+        .route("/stats", get(handler_stats))
+        .route("/checkpoint/{range}", get(handler_checkpoint))
+        .route("/events", get(handler_events))
         .route("/krc20/tokenlist", get(handler_krc20_tokenlist))
         .route("/krc20/token/{tick}", get(handler_krc20_token))
+        .route("/krc20/token/{tick}/stats", get(handler_krc20_token_stats))
+        .route(
+            "/krc20/token/{tick}/oplist",
+            get(handler_krc20_token_oplist),
+        )
         .route(
             "/krc20/address/{address}/tokenlist",
             get(handler_krc20_address_tokenlist),
@@ -29,6 +131,32 @@ fn v1_router() -> Router<HttpState> {
         .route("/archive/oplist/{oprange}", get(handler_archive_oplist))
         .route("/krc20/market/{tick}", get(handler_krc20_market))
         .route("/krc20/blacklist/{ca}", get(handler_krc20_blacklist))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state,
+            limit_concurrent_reads,
+        ))
+}
+
+/// Developer routes, only served when enabled in the HTTP configuration
+fn debug_router() -> Router<HttpState> {
+    Router::new().route("/parse-script", post(handler_debug_parse_script))
+}
+
+/// Admin routes, all protected by the admin bearer token
+fn admin_router(state: HttpState) -> Router<HttpState> {
+    Router::new()
+        .route("/rollback", post(handler_admin_rollback))
+        .route(
+            "/rollback/candidates",
+            get(handler_admin_rollback_candidates),
+        )
+        .route("/rollback/stats", get(handler_admin_rollback_stats))
+        .route("/prune/dry-run", post(handler_admin_prune_dry_run))
+        .route("/deadletter", get(handler_admin_deadletter))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state,
+            require_admin_token,
+        ))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,88 +164,905 @@ struct ApiOk<T> {
     data: T,
 }
 
-async fn handler_info(axum::extract::State(state): axum::extract::State<HttpState>) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({
-            "version": crate::config::VERSION,
-            "status": "ok",
-            "kaspa_rest_base_url": state.kaspa_rest_base_url,
+#[derive(Debug, Serialize, Deserialize)]
+struct ApiError {
+    error: String,
+}
+
+fn api_error(
+    status_code: StatusCode,
+    message: &str,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    (
+        status_code,
+        axum::Json(serde_json::json!(ApiError {
+            error: message.to_string(),
+        })),
+    )
+}
+
+/// Shed a read with 503 while all read permits are in use, rather than queuing it
+async fn limit_concurrent_reads(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Ok(_permit) = state.read_permits.try_acquire() else {
+        return api_error(StatusCode::SERVICE_UNAVAILABLE, "too many concurrent reads")
+            .into_response();
+    };
+    next.run(request).await
+}
+
+/// Require the admin bearer token, admin endpoints are disabled without a configured token
+async fn require_admin_token(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = !state.admin_token.is_empty()
+        && request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|token| constant_time_eq(token.as_bytes(), state.admin_token.as_bytes()))
+            .unwrap_or(false);
+
+    if !authorized {
+        return api_error(StatusCode::UNAUTHORIZED, "unauthorized").into_response();
+    }
+    next.run(request).await
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminRollbackRequest {
+    target_daa_score: u64,
+    target_block_hash: String,
+}
+
+/// Default number of rollback candidates listed
+const ROLLBACK_CANDIDATES_DEFAULT: u64 = 100;
+
+#[derive(Debug, Deserialize)]
+struct RollbackCandidatesQuery {
+    max_blocks: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdminPruneRequest {
+    horizon_daa_score: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DebugParseScriptRequest {
+    script_hex: String,
+    #[serde(default)]
+    testnet: bool,
+}
+
+async fn handler_info(axum::extract::State(state): axum::extract::State<HttpState>) -> Response {
+    let node_daa_score = state.node_tip.get_daa_score().await;
+    let last_processed_daa_score = state
+        .storage
+        .runtime
+        .get_runtime_state()
+        .map(|runtime| runtime.last_processed_daa_score)
+        .ok();
+    let lag = match (node_daa_score, last_processed_daa_score) {
+        (Some(node), Some(processed)) => Some(node.saturating_sub(processed)),
+        _ => None,
+    };
+
+    cache::with_cache_control(
+        &cache::info(&state.cache_control),
+        axum::Json(ApiOk {
+            data: serde_json::json!({
+                "version": crate::config::VERSION,
+                "status": "ok",
+                "kaspa_rest_base_url": state.kaspa_rest_base_url,
+                "node_daa_score": node_daa_score,
+                "last_processed_daa_score": last_processed_daa_score,
+                "lag": lag,
+                "checkpoint_hash": crate::config::get_checkpoint_hash().name(),
+            }),
         }),
-    })
+    )
 }
 
-async fn handler_krc20_tokenlist() -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({ "items": [], "hasMore": false }),
+async fn handler_health(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+) -> (StatusCode, axum::Json<ApiOk<serde_json::Value>>) {
+    let disk = state.storage.rocksdb.get_disk_status();
+    let is_healthy = disk
+        .as_ref()
+        .map(|d| d.pressure == DiskPressure::Normal)
+        .unwrap_or(true);
+    let status_code = if is_healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status_code,
+        axum::Json(ApiOk {
+            data: serde_json::json!({
+                "status": if is_healthy { "healthy" } else { "unhealthy" },
+                "disk": disk,
+                "write_stall": {
+                    "count": state.storage.rocksdb.get_write_stall_count(),
+                    "last": state.storage.rocksdb.get_write_stall_status(),
+                    "paused": state.storage.rocksdb.is_write_paused(),
+                    "pause_count": state.storage.rocksdb.get_write_pause_count(),
+                },
+            }),
+        }),
+    )
+}
+
+async fn handler_stats(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    match state.stats.get(&state.storage) {
+        Ok(stats) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: stats })),
+        ),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+async fn handler_metrics_operations() -> (StatusCode, axum::Json<serde_json::Value>) {
+    let histogram = crate::operations::metrics::get_op_duration_histogram();
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!(ApiOk { data: histogram })),
+    )
+}
+
+/// Write stall checks and ingestion pauses since startup
+async fn handler_metrics_write_stall(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let rocksdb = &state.storage.rocksdb;
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!(ApiOk {
+            data: serde_json::json!({
+                "stall_count": rocksdb.get_write_stall_count(),
+                "paused": rocksdb.is_write_paused(),
+                "pause_count": rocksdb.get_write_pause_count(),
+            }),
+        })),
+    )
+}
+
+/// Scripts skipped by the parser since startup, by rejection reason
+async fn handler_metrics_script_rejections() -> (StatusCode, axum::Json<serde_json::Value>) {
+    let rejections = crate::operations::metrics::get_script_rejections();
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!(ApiOk { data: rejections })),
+    )
+}
+
+async fn handler_checkpoint(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((range,)): axum::extract::Path<(String,)>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let range = match range.parse::<u64>() {
+        Ok(range) => range,
+        Err(_) => return api_error(StatusCode::BAD_REQUEST, "invalid range"),
+    };
+    match checkpoint::get_checkpoint(&state.storage.rocksdb, range) {
+        Ok(Some(checkpoint)) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: checkpoint })),
+        ),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "checkpoint not found"),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    /// Seq of the last event the follower applied
+    #[serde(default)]
+    after: u64,
+}
+
+/// Scan step event log tailed by standby followers
+async fn handler_events(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Query(query): axum::extract::Query<EventsQuery>,
+    pagination: Pagination,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    match events::list_events(&state.storage.rocksdb, query.after, pagination.limit) {
+        Ok(entries) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: entries })),
+        ),
+        Err(e) => api_error(StatusCode::GONE, &e.to_string()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenListQuery {
+    /// Deploy DAA score window, inclusive on both edges
+    from_daa: Option<u64>,
+    to_daa: Option<u64>,
+    /// `asc` lists the oldest deploys first, `desc` the newest
+    order: Option<String>,
+}
+
+fn token_json(token: &StateTokenType) -> serde_json::Value {
+    serde_json::json!({
+        "tick": token.tick,
+        "minted": token.minted,
+        "burned": token.burned,
+        "owner": token.to,
+        "op_mod": token.op_mod,
+        "mts_mod": token.mts_mod,
+        "deploy": TokenDeployInfo::from(token),
     })
 }
 
+/// Tokens in deploy order, optionally only those deployed within a DAA score window
+async fn handler_krc20_tokenlist(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Query(query): axum::extract::Query<TokenListQuery>,
+    pagination: Pagination,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let from_daa = query.from_daa.unwrap_or(0);
+    let to_daa = query.to_daa.unwrap_or(u64::MAX);
+    if from_daa > to_daa {
+        return api_error(StatusCode::BAD_REQUEST, "from_daa must not exceed to_daa");
+    }
+    // Newest deploys first unless asked otherwise
+    let descending = match query.order.as_deref() {
+        Some("asc") => false,
+        None | Some("desc") => true,
+        Some(_) => return api_error(StatusCode::BAD_REQUEST, "order must be asc or desc"),
+    };
+    match tokenindex::list_tokens_by_daa(
+        &state.storage.rocksdb,
+        from_daa,
+        to_daa,
+        pagination.cursor.as_deref(),
+        pagination.limit,
+        descending,
+    ) {
+        Ok((tokens, next)) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk {
+                data: serde_json::json!({
+                    "items": tokens.iter().map(token_json).collect::<Vec<_>>(),
+                    "hasMore": next.is_some(),
+                    "nextCursor": next.as_deref().map(Pagination::encode_cursor),
+                }),
+            })),
+        ),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
 async fn handler_krc20_token(
-    axum::extract::Path((_tick,)): axum::extract::Path<(String,)>,
-) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({}),
-    })
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((tick,)): axum::extract::Path<(String,)>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let tick = tick.to_uppercase();
+    match state.storage.state.get_state_token(&tick) {
+        Ok(Some(token)) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk {
+                data: token_json(&token),
+            })),
+        ),
+        Ok(None) => reserved_tick_error(&state, &tick),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
 }
 
+/// 404 of a tick not deployed yet, naming the address it is reserved for at the last processed
+/// DAA score when it is reserved
+fn reserved_tick_error(
+    state: &HttpState,
+    tick: &str,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let daa_score = match state.storage.runtime.get_checkpoint() {
+        Ok((_, daa_score)) => daa_score,
+        Err(e) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+    match crate::config::get_reserved_tick_address(tick, daa_score) {
+        Some(address) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "error": "tick not found",
+                "reserved": true,
+                "reservedAddress": address,
+            })),
+        ),
+        None => api_error(StatusCode::NOT_FOUND, "tick not found"),
+    }
+}
+
+/// Accepted and rejected operation counts of a tick, rejections broken down by op_error
+async fn handler_krc20_token_stats(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((tick,)): axum::extract::Path<(String,)>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let tick = tick.to_uppercase();
+    let stats = match tickstats::get_tick_stats(&state.storage.rocksdb, &tick) {
+        Ok(Some(stats)) => stats,
+        // Deployed tokens without executed operations report zero counts
+        Ok(None) => match state.storage.state.get_state_token(&tick) {
+            Ok(Some(_)) => TickOpStats {
+                tick,
+                ..Default::default()
+            },
+            Ok(None) => return api_error(StatusCode::NOT_FOUND, "tick not found"),
+            Err(e) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+        },
+        Err(e) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!(ApiOk { data: stats })),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenOpListQuery {
+    /// Only operations of this type, e.g. `transfer`
+    op_type: Option<String>,
+}
+
+/// Operations affecting a tick newest first, the cursor is the op_score of the previous page's
+/// last operation
+async fn handler_krc20_token_oplist(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((tick,)): axum::extract::Path<(String,)>,
+    axum::extract::Query(query): axum::extract::Query<TokenOpListQuery>,
+    pagination: Pagination,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let before = match pagination.cursor.as_deref() {
+        Some(cursor) => match cursor.parse::<u64>() {
+            Ok(op_score) => Some(op_score),
+            Err(_) => return api_error(StatusCode::BAD_REQUEST, "invalid cursor"),
+        },
+        None => None,
+    };
+    match tickops::list_tick_ops(
+        &state.storage.rocksdb,
+        &tick,
+        query.op_type.as_deref(),
+        before,
+        pagination.limit,
+    ) {
+        Ok((operations, next)) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk {
+                data: serde_json::json!({
+                    "items": operations,
+                    "hasMore": next.is_some(),
+                    "nextCursor": next.map(|op_score| Pagination::encode_cursor(&op_score.to_string())),
+                }),
+            })),
+        ),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AddressTokenListQuery {
+    /// Also list ticks the address no longer holds any of
+    #[serde(default, rename = "includeZero")]
+    include_zero: bool,
+}
+
+/// Balances held by an address in tick order, the cursor is the previous page's last tick
 async fn handler_krc20_address_tokenlist(
-    axum::extract::Path((_address,)): axum::extract::Path<(String,)>,
-) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({ "items": [], "hasMore": false }),
-    })
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((address,)): axum::extract::Path<(String,)>,
+    axum::extract::Query(query): axum::extract::Query<AddressTokenListQuery>,
+    pagination: Pagination,
+) -> Response {
+    let balances = match state.storage.state.list_balances_for_address(&address) {
+        Ok(balances) => balances,
+        Err(e) => {
+            return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response();
+        }
+    };
+    let is_zero = |amount: &str| amount.parse::<u128>().unwrap_or(0) == 0;
+    let mut balances = balances
+        .into_iter()
+        .filter(|balance| {
+            query.include_zero || !is_zero(&balance.balance) || !is_zero(&balance.locked)
+        })
+        .filter(|balance| {
+            pagination
+                .cursor
+                .as_deref()
+                .is_none_or(|after| balance.tick.as_str() > after)
+        })
+        .peekable();
+    let items: Vec<serde_json::Value> = balances
+        .by_ref()
+        .take(pagination.limit)
+        .map(|balance| {
+            serde_json::json!({
+                "tick": balance.tick,
+                "balance": balance.balance,
+                "locked": balance.locked,
+                "dec": balance.dec,
+            })
+        })
+        .collect();
+    let has_more = balances.peek().is_some();
+    let next_cursor = match has_more {
+        true => items
+            .last()
+            .and_then(|item| item["tick"].as_str())
+            .map(Pagination::encode_cursor),
+        false => None,
+    };
+    cache::with_cache_control(
+        cache::NO_STORE,
+        axum::Json(ApiOk {
+            data: serde_json::json!({
+                "items": items,
+                "hasMore": has_more,
+                "nextCursor": next_cursor,
+            }),
+        }),
+    )
 }
 
+/// Balance of an address for one tick
 async fn handler_krc20_address_token(
-    axum::extract::Path((_address, _tick)): axum::extract::Path<(String, String)>,
-) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({}),
-    })
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((address, mut tick)): axum::extract::Path<(String, String)>,
+) -> Response {
+    if !validate_tick(&mut tick) {
+        return cache::with_cache_control(
+            cache::NO_STORE,
+            api_error(StatusCode::BAD_REQUEST, "invalid tick"),
+        );
+    }
+    let response = match state.storage.state.get_state_balance(&address, &tick) {
+        Ok(Some(balance)) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk {
+                data: serde_json::json!({
+                    "address": balance.address,
+                    "tick": balance.tick,
+                    "balance": balance.balance,
+                    "locked": balance.locked,
+                    "dec": balance.dec,
+                    "op_mod": balance.op_mod,
+                }),
+            })),
+        ),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "balance not found"),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+    cache::with_cache_control(cache::NO_STORE, response)
 }
 
-async fn handler_krc20_oplist() -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({ "items": [], "hasMore": false }),
-    })
+/// An oplist entry with the op_score of its key
+fn oplist_item(op_score: u64, value: &[u8]) -> serde_json::Value {
+    let mut item: serde_json::Value = serde_json::from_slice(value).unwrap_or_default();
+    if let Some(item) = item.as_object_mut() {
+        item.insert("op_score".to_string(), serde_json::json!(op_score));
+    }
+    item
+}
+
+#[derive(Debug, Deserialize)]
+struct OpListQuery {
+    /// Only operations affecting this tick
+    tick: Option<String>,
+    /// Only operations changing a balance of this address
+    address: Option<String>,
 }
 
+/// Committed operations newest first. The first page starts at the highest committed op_score,
+/// the cursor is the op_score of the previous page's last operation.
+async fn handler_krc20_oplist(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Query(query): axum::extract::Query<OpListQuery>,
+    pagination: Pagination,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let before = match pagination.cursor.as_deref() {
+        Some(cursor) => match cursor.parse::<u64>() {
+            Ok(op_score) => Some(op_score),
+            Err(_) => return api_error(StatusCode::BAD_REQUEST, "invalid cursor"),
+        },
+        None => None,
+    };
+    let filter = opscore::OpListFilter {
+        tick: query.tick,
+        address: query.address,
+    };
+    match opscore::list_newest(&state.storage.rocksdb, before, pagination.limit, &filter) {
+        Ok((entries, next)) => {
+            let items: Vec<serde_json::Value> = entries
+                .iter()
+                .map(|(op_score, value)| oplist_item(*op_score, value))
+                .collect();
+            (
+                StatusCode::OK,
+                axum::Json(serde_json::json!(ApiOk {
+                    data: serde_json::json!({
+                        "items": items,
+                        "hasMore": next.is_some(),
+                        "nextCursor": next.map(|op_score| Pagination::encode_cursor(&op_score.to_string())),
+                    }),
+                })),
+            )
+        }
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+/// An operation by transaction id, cached long once it is beyond the finality depth
 async fn handler_krc20_op(
-    axum::extract::Path((_id,)): axum::extract::Path<(String,)>,
-) -> axum::response::Result<axum::Json<ApiOk<serde_json::Value>>> {
-    Ok(axum::Json(ApiOk {
-        data: serde_json::json!({}),
-    }))
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((mut id,)): axum::extract::Path<(String,)>,
+) -> Response {
+    if !validate_tx_id(&mut id) {
+        return api_error(StatusCode::BAD_REQUEST, "invalid transaction id").into_response();
+    }
+    let value = match state.storage.rocksdb.get_raw(&format!("opdata:{}", id)) {
+        Ok(Some(value)) => value,
+        Ok(None) => return api_error(StatusCode::NOT_FOUND, "operation not found").into_response(),
+        Err(e) => {
+            return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response();
+        }
+    };
+    let op_data: DataOperationType = match versioned::decode(&value) {
+        Ok(op_data) => op_data,
+        Err(e) => {
+            return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response();
+        }
+    };
+    let last_processed_daa_score = state
+        .storage
+        .runtime
+        .get_runtime_state()
+        .map(|runtime| runtime.last_processed_daa_score)
+        .ok();
+    let cache_control = cache::operation(
+        &state.cache_control,
+        op_data.daa_score,
+        last_processed_daa_score,
+    );
+    let fee = FeeBreakdown::from(&op_data);
+    cache::with_cache_control(
+        &cache_control,
+        axum::Json(serde_json::json!(ApiOk {
+            data: serde_json::json!({
+                "op": op_data,
+                "fee": fee,
+            }),
+        })),
+    )
 }
 
+/// Archived VSPC entry of a processed block, with the transactions it accepted
 async fn handler_archive_vspc(
-    axum::extract::Path((_daa_score,)): axum::extract::Path<(u64,)>,
-) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({ "vspc": [] }),
-    })
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    path: Result<axum::extract::Path<(u64,)>, PathRejection>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    // Answer malformed scores with the JSON error body instead of axum's plain text
+    let daa_score = match path {
+        Ok(axum::extract::Path((daa_score,))) => daa_score,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, &e.body_text()),
+    };
+    match state.storage.state.get_vspc_by_daa_score(daa_score) {
+        Ok(Some(vspc)) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: vspc })),
+        ),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "no VSPC entry at this DAA score"),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
 }
 
+/// Every operation of an op_score range (op_score / `OP_RANGE_BY`) in op_score order, for
+/// mirroring the history range by range
 async fn handler_archive_oplist(
-    axum::extract::Path((_oprange,)): axum::extract::Path<(String,)>,
-) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({ "items": [] }),
-    })
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((oprange,)): axum::extract::Path<(String,)>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let Ok(range) = oprange.parse::<u64>() else {
+        return api_error(StatusCode::BAD_REQUEST, "oprange must be a number");
+    };
+    match state.storage.state.list_oplist_by_range(range) {
+        Ok(entries) => {
+            let items: Vec<serde_json::Value> = entries
+                .iter()
+                .map(|(op_score, value)| oplist_item(*op_score, value))
+                .collect();
+            (
+                StatusCode::OK,
+                axum::Json(serde_json::json!(ApiOk {
+                    data: serde_json::json!({ "range": range, "items": items }),
+                })),
+            )
+        }
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
 }
 
+/// Active listings of a tick cheapest first. The cursor is `t_amt:u_amt:u_tx_id` of the
+/// previous page's last listing, so pages stay in place when listings are sold.
 async fn handler_krc20_market(
-    axum::extract::Path((_tick,)): axum::extract::Path<(String,)>,
-) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({ "items": [], "hasMore": false }),
-    })
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((mut tick,)): axum::extract::Path<(String,)>,
+    pagination: Pagination,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    if !validate_tick(&mut tick) {
+        return api_error(StatusCode::BAD_REQUEST, "invalid tick");
+    }
+    let after = match pagination.cursor.as_deref() {
+        Some(cursor) => match cursor.splitn(3, ':').collect::<Vec<_>>()[..] {
+            [t_amt, u_amt, u_tx_id] => Some(StateMarketType {
+                t_amt: t_amt.to_string(),
+                u_amt: u_amt.to_string(),
+                u_tx_id: u_tx_id.to_string(),
+                ..Default::default()
+            }),
+            // Positions of other lists hold no listings
+            _ => {
+                return (
+                    StatusCode::OK,
+                    axum::Json(serde_json::json!(ApiOk {
+                        data: serde_json::json!({ "items": [], "hasMore": false }),
+                    })),
+                );
+            }
+        },
+        None => None,
+    };
+    let listings = match state.storage.state.list_market_for_tick(&tick) {
+        Ok(listings) => listings,
+        Err(e) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+    let mut listings = listings
+        .into_iter()
+        .filter(|listing| {
+            after
+                .as_ref()
+                .is_none_or(|after| compare_listings(listing, after).is_gt())
+        })
+        .peekable();
+    let page: Vec<StateMarketType> = listings.by_ref().take(pagination.limit).collect();
+    let next_cursor = match listings.peek() {
+        Some(_) => page.last().map(|listing| {
+            Pagination::encode_cursor(&format!(
+                "{}:{}:{}",
+                listing.t_amt, listing.u_amt, listing.u_tx_id
+            ))
+        }),
+        None => None,
+    };
+    let items: Vec<serde_json::Value> = page
+        .iter()
+        .map(|listing| {
+            serde_json::json!({
+                "u_addr": listing.u_addr,
+                "u_amt": listing.u_amt,
+                "t_amt": listing.t_amt,
+                "t_addr": listing.t_addr,
+                "u_tx_id": listing.u_tx_id,
+                "op_add": listing.op_add,
+            })
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!(ApiOk {
+            data: serde_json::json!({
+                "items": items,
+                "hasMore": next_cursor.is_some(),
+                "nextCursor": next_cursor,
+            }),
+        })),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct BlacklistQuery {
+    /// Address to check when the path holds a bare contract address
+    address: Option<String>,
 }
 
+/// Whether an address is blacklisted for a token. The path is `{address}_{tick}`, or a tick or
+/// contract address together with `?address=`.
 async fn handler_krc20_blacklist(
-    axum::extract::Path((_ca,)): axum::extract::Path<(String,)>,
-) -> axum::Json<ApiOk<serde_json::Value>> {
-    axum::Json(ApiOk {
-        data: serde_json::json!({ "blacklisted": false }),
-    })
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Path((ca,)): axum::extract::Path<(String,)>,
+    axum::extract::Query(query): axum::extract::Query<BlacklistQuery>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    // Ticks never contain '_', addresses may
+    let (address, mut tick) = match (ca.rsplit_once('_'), query.address) {
+        (Some((address, tick)), None) => (address.to_string(), tick.to_string()),
+        (None, Some(address)) => (address, ca.clone()),
+        _ => {
+            return api_error(
+                StatusCode::BAD_REQUEST,
+                "expected {address}_{tick}, or a tick or contract address with ?address=",
+            );
+        }
+    };
+    if address.is_empty() || !validate_tick_tx_id(&mut tick) {
+        return api_error(
+            StatusCode::BAD_REQUEST,
+            &format!("invalid address or tick in '{}'", ca),
+        );
+    }
+    match state.storage.state.get_state_blacklist(&address, &tick) {
+        Ok(entry) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk {
+                data: serde_json::json!({
+                    "address": address,
+                    "tick": tick,
+                    "blacklisted": entry.is_some(),
+                    "op_add": entry.map(|entry| entry.op_add),
+                }),
+            })),
+        ),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+/// Parse a signature script the way the indexer does, for debugging integrations
+async fn handler_debug_parse_script(
+    axum::Json(request): axum::Json<DebugParseScriptRequest>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let (is_op, script_info) =
+        match ScriptParser::parse_script_input(&request.script_hex, request.testnet) {
+            Ok(parsed) => parsed,
+            Err(e) => return api_error(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+    let field = |index: usize| script_info.get(index).cloned().unwrap_or_default();
+    let (fields, fields_error) = match script_info.get(1) {
+        Some(json) if is_op => match serde_json::from_str::<DataScriptType>(json) {
+            Ok(script) => (Some(script), None),
+            Err(e) => (None, Some(e.to_string())),
+        },
+        _ => (None, None),
+    };
+    (
+        StatusCode::OK,
+        axum::Json(serde_json::json!(ApiOk {
+            data: serde_json::json!({
+                "is_op": is_op,
+                "from": field(0),
+                "p0": field(1),
+                "p1": field(2),
+                "p2": field(3),
+                "script_sig": field(4),
+                "fields": fields,
+                "fields_error": fields_error,
+            }),
+        })),
+    )
+}
+
+async fn handler_admin_rollback(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::Json(request): axum::Json<AdminRollbackRequest>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    if !state.storage.runtime.try_begin_rollback() {
+        return api_error(StatusCode::CONFLICT, "rollback already in progress");
+    }
+
+    info!(
+        "admin.rollback start daaScore={} blockHash={}",
+        request.target_daa_score, request.target_block_hash
+    );
+
+    // Pause the scanner for the duration of the rollback
+    let scan_guard = state.storage.runtime.lock_scanning().await;
+    let result = async {
+        let rollback_manager = RollbackManager::new(Arc::clone(&state.storage))?;
+        rollback_manager
+            .rollback_to_block(&request.target_block_hash, request.target_daa_score)
+            .await?;
+        rollback_manager.get_rollback_statistics().await
+    }
+    .await;
+    drop(scan_guard);
+    state.storage.runtime.end_rollback();
+    state.rollback_candidates.invalidate();
+    state.rollback_stats.invalidate();
+
+    match result {
+        Ok(stats) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: stats })),
+        ),
+        Err(e) => {
+            warn!("admin.rollback failed: {}", e);
+            api_error(StatusCode::BAD_REQUEST, &e.to_string())
+        }
+    }
+}
+
+/// Blocks an admin rollback can target, newest first, to pick a `target_block_hash` from
+async fn handler_admin_rollback_candidates(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::extract::Query(query): axum::extract::Query<RollbackCandidatesQuery>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    let max_blocks = query.max_blocks.unwrap_or(ROLLBACK_CANDIDATES_DEFAULT);
+    match state
+        .rollback_candidates
+        .get(&state.storage, max_blocks)
+        .await
+    {
+        Ok(candidates) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: candidates })),
+        ),
+        Err(e) => {
+            warn!("admin.rollback candidates failed: {}", e);
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+        }
+    }
+}
+
+/// Rollback statistics, cached for a short while
+async fn handler_admin_rollback_stats(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    match state.rollback_stats.get(&state.storage).await {
+        Ok(stats) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: stats })),
+        ),
+        Err(e) => {
+            warn!("admin.rollback stats failed: {}", e);
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+        }
+    }
+}
+
+async fn handler_admin_prune_dry_run(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    axum::Json(request): axum::Json<AdminPruneRequest>,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    match state.storage.prune_dry_run(request.horizon_daa_score) {
+        Ok(report) => (
+            StatusCode::OK,
+            axum::Json(serde_json::json!(ApiOk { data: report })),
+        ),
+        Err(e) => {
+            warn!("admin.prune dry-run failed: {}", e);
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+        }
+    }
+}
+
+async fn handler_admin_deadletter(
+    axum::extract::State(state): axum::extract::State<HttpState>,
+    pagination: Pagination,
+) -> (StatusCode, axum::Json<serde_json::Value>) {
+    match deadletter::list_dead_letters(&state.storage.rocksdb) {
+        Ok(mut entries) => {
+            // Oldest failed batches first, they are the ones to retry
+            entries.truncate(pagination.limit);
+            (
+                StatusCode::OK,
+                axum::Json(serde_json::json!(ApiOk { data: entries })),
+            )
+        }
+        Err(e) => {
+            warn!("admin.deadletter failed: {}", e);
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+        }
+    }
 }
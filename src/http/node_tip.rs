@@ -0,0 +1,57 @@
+use crate::explorer::vspc_client::VSPCClient;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// How long a fetched node tip is served before asking the node again
+pub const NODE_TIP_CACHE_TTL: Duration = Duration::from_secs(2);
+
+/// Node tip DAA score cache, keeps info requests from hitting the node on every call
+pub struct NodeTipCache {
+    client: Option<VSPCClient>,
+    ttl: Duration,
+    cached: Mutex<Option<(u64, Instant)>>,
+}
+
+impl NodeTipCache {
+    pub fn new(node_url: String, ttl: Duration) -> Self {
+        Self {
+            client: Some(VSPCClient::new(node_url)),
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Cache without a node, the tip is always unknown
+    pub fn disabled() -> Self {
+        Self {
+            client: None,
+            ttl: Duration::ZERO,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Current node tip DAA score, None if the node is unreachable
+    pub async fn get_daa_score(&self) -> Option<u64> {
+        let client = self.client.as_ref()?;
+
+        // Hold the lock across the fetch so concurrent requests share one node call
+        let mut cached = self.cached.lock().await;
+        if let Some((daa_score, fetched_at)) = *cached
+            && fetched_at.elapsed() < self.ttl
+        {
+            return Some(daa_score);
+        }
+
+        match client.get_virtual_daa_score().await {
+            Ok(daa_score) => {
+                *cached = Some((daa_score, Instant::now()));
+                Some(daa_score)
+            }
+            Err(e) => {
+                warn!("Failed to get node tip DAA score: {}", e);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::http::{HttpState, api_error};
+use axum::extract::{FromRequestParts, Query};
+use axum::http::StatusCode;
+use axum::http::request::Parts;
+use serde::Deserialize;
+
+/// Page size used when a request does not ask for one
+pub const PAGE_LIMIT_DEFAULT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct PaginationQuery {
+    limit: Option<String>,
+    cursor: Option<String>,
+}
+
+/// `?limit=&cursor=` of a list request. The limit is clamped to `[1, http.max_page_size]`,
+/// the cursor is the opaque position returned with the previous page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    pub limit: usize,
+    /// Decoded cursor, None for the first page
+    pub cursor: Option<String>,
+}
+
+impl Pagination {
+    /// Opaque cursor of a position, handed to clients to request the following page
+    pub fn encode_cursor(position: &str) -> String {
+        hex::encode(position)
+    }
+
+    fn decode_cursor(cursor: &str) -> Option<String> {
+        if cursor.is_empty() {
+            return None;
+        }
+        String::from_utf8(hex::decode(cursor).ok()?).ok()
+    }
+
+    /// Parse and clamp a list request's query against the configured maximum page size
+    pub fn parse(
+        limit: Option<&str>,
+        cursor: Option<&str>,
+        max_page_size: usize,
+    ) -> Result<Self, String> {
+        let limit = match limit {
+            Some(limit) => limit
+                .parse::<usize>()
+                .map_err(|_| format!("invalid limit: {}", limit))?,
+            None => PAGE_LIMIT_DEFAULT,
+        };
+        let cursor = match cursor {
+            Some(cursor) => Some(
+                Self::decode_cursor(cursor).ok_or_else(|| format!("invalid cursor: {}", cursor))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            limit: limit.clamp(1, max_page_size.max(1)),
+            cursor,
+        })
+    }
+}
+
+impl FromRequestParts<HttpState> for Pagination {
+    type Rejection = (StatusCode, axum::Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &HttpState,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<PaginationQuery>::try_from_uri(&parts.uri)
+            .map_err(|e| api_error(StatusCode::BAD_REQUEST, &e.body_text()))?;
+        Self::parse(
+            query.limit.as_deref(),
+            query.cursor.as_deref(),
+            state.max_page_size,
+        )
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, &e))
+    }
+}
@@ -7,24 +7,58 @@ mod storage;
 mod utils;
 // remove tonic Server import; use axum's server instead
 use crate::config::VERSION;
-use crate::explorer::{Explorer, ExplorerInterface};
+use crate::explorer::{Explorer, ExplorerInterface, Follower};
 use crate::operations::handler::OperationManager;
 use crate::storage::StorageManager;
+use crate::utils::shutdown::stop_task;
 use anyhow::Result;
 use axum::Router;
+use clap::{Parser, Subcommand};
 use std::fs;
 use std::net::SocketAddr;
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::AsRawFd;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::signal;
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+#[derive(Parser)]
+#[command(version = VERSION, about = "KASPlex Executor")]
+struct Cli {
+    /// Disable RocksDB auto compaction during initial sync, compact once caught up
+    #[arg(long)]
+    bulk_load: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Remove opdata/oplist/vspc history older than a DAA score, then exit
+    Prune {
+        /// Keys below this DAA score are removed
+        #[arg(long)]
+        horizon: u64,
+        /// Only report what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Repair a corrupted RocksDB, then report what was lost and exit. Any reported supply or
+    /// VSPC damage cannot be repaired in place and needs a full resync into an empty database.
+    RepairDb,
+    /// Check that the persisted oplist is strictly ordered by op_score, then report the first
+    /// anomaly and exit
+    VerifyOpScores,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     // Print startup banner (corresponding to Go version version info)
     println!("KASPlex Executor v{}", VERSION);
 
@@ -44,6 +78,8 @@ async fn main() -> Result<()> {
     let mut config = crate::config::types::Config::default();
     crate::config::load_config(&mut config)?;
     crate::config::validate_config(&config)?;
+    config.rocksdb.bulk_load |= cli.bulk_load;
+    let shutdown_timeout = Duration::from_millis(config.shutdown_timeout_ms);
 
     // Set the log level based on config (corresponding to Go version debug level setup)
     set_log_level_from_config(&config)?;
@@ -57,26 +93,108 @@ async fn main() -> Result<()> {
         info!("main stopping ..");
         shutdown_flag_clone.store(true, Ordering::SeqCst);
     };
+    tokio::pin!(shutdown_signal);
+
+    // Repair before opening, a corrupted database may not open at all
+    if let Some(Command::RepairDb) = cli.command {
+        crate::storage::repair::repair_db(&config.rocksdb)?;
+    }
 
     // Initialize storage driver (corresponding to Go version storage.Init)
     // Note: Go version passes cfg.Cassandra and cfg.Rocksdb, but actually only uses Rocksdb
     let mut storage = StorageManager::new(config.rocksdb, Some(config.distributed)).await?;
     storage.init().await?;
-
-    // Initialize operation manager (Rust version specific, for operation handling)
-    let operation_manager = OperationManager::new(Arc::new(storage.clone()));
-    storage.set_operation_manager(operation_manager);
+    storage
+        .runtime
+        .check_op_score_multiplier(config.startup.op_score_multiplier)?;
+
+    // Run one-shot maintenance commands instead of the indexer
+    if let Some(Command::Prune { horizon, dry_run }) = cli.command {
+        let report = if dry_run {
+            storage.prune_dry_run(horizon)?
+        } else {
+            storage.prune(horizon)?
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        storage.shutdown().await?;
+        release_file_lock(lock_file)?;
+        return Ok(());
+    }
+    if let Some(Command::VerifyOpScores) = cli.command {
+        let report = storage.verify_op_score_ordering()?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        storage.shutdown().await?;
+        release_file_lock(lock_file)?;
+        return Ok(());
+    }
+    if let Some(Command::RepairDb) = cli.command {
+        let report = crate::storage::repair::check_repaired(&storage).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        storage.shutdown().await?;
+        release_file_lock(lock_file)?;
+        return Ok(());
+    }
 
     // Convert to Arc for explorer
     let storage = Arc::new(storage);
 
+    // Initialize operation manager (Rust version specific, for operation handling)
+    let operation_manager = OperationManager::new(&storage);
+    storage.set_operation_manager_arc(Arc::new(operation_manager));
+
+    // Start RocksDB disk space monitor
+    let disk_monitor_handle = storage.start_disk_monitor();
+    let holder_reconciler_handle = storage.start_holder_reconciler();
+
     // Initialize explorer if not shutting down (corresponding to Go version explorer.Init)
     if !shutdown_flag.load(Ordering::SeqCst) {
         // Start HTTP server
         let http_state = crate::http::HttpState {
             kaspa_rest_base_url: config.rest.kaspa_rest_base_url.clone(),
+            storage: Arc::clone(&storage),
+            admin_token: config.http.admin_token.clone(),
+            node_tip: Arc::new(crate::http::node_tip::NodeTipCache::new(
+                config.startup.kaspa_node_url.clone(),
+                crate::http::node_tip::NODE_TIP_CACHE_TTL,
+            )),
+            stats: Arc::new(crate::storage::stats::StatsCache::new(
+                crate::storage::stats::STATS_CACHE_TTL,
+            )),
+            max_page_size: config.http.max_page_size,
+            cache_control: config.http.cache_control.clone(),
+            debug_endpoints: config.http.debug_endpoints,
+            rollback_candidates: Arc::new(crate::explorer::rollback::RollbackCandidateCache::new(
+                std::time::Duration::from_secs(config.http.rollback_candidates_cache_secs),
+            )),
+            rollback_stats: Arc::new(crate::explorer::rollback::RollbackStatisticsCache::new(
+                crate::explorer::rollback::ROLLBACK_STATISTICS_CACHE_TTL,
+            )),
+            read_permits: Arc::new(tokio::sync::Semaphore::new(
+                config.http.max_concurrent_reads,
+            )),
+        };
+        // Metrics and admin routes move to their own listener when an admin bind is configured
+        let public_router = if config.http.admin_bind.is_empty() {
+            crate::http::build_router_with_state(http_state)
+        } else {
+            let admin_addr: SocketAddr = config.http.admin_bind.parse()?;
+            let admin_router = crate::http::build_internal_router(http_state.clone());
+            info!("HTTP admin server listening on {}", admin_addr);
+            tokio::spawn(async move {
+                match tokio::net::TcpListener::bind(admin_addr).await {
+                    Ok(listener) => {
+                        if let Err(e) =
+                            axum::serve(listener, admin_router.into_make_service()).await
+                        {
+                            error!("HTTP admin server error: {}", e);
+                        }
+                    }
+                    Err(e) => error!("Failed to bind {}: {}", admin_addr, e),
+                }
+            });
+            crate::http::build_public_router(http_state)
         };
-        let http_router = crate::http::build_router_with_state(http_state).layer(
+        let http_router = public_router.layer(
             CorsLayer::new()
                 .allow_origin(Any)
                 .allow_methods(Any)
@@ -90,16 +208,6 @@ async fn main() -> Result<()> {
         let http_addr: SocketAddr = (bind_ip, config.http.port).into();
         info!("HTTP server listening on {}", http_addr);
 
-        let mut explorer = Explorer::new(Arc::clone(&storage), config.startup, config.testnet)?;
-        explorer.init().await?;
-
-        // Start explorer in background (corresponding to Go version explorer.Run)
-        let explorer_handle = tokio::spawn(async move {
-            if let Err(e) = explorer.run().await {
-                error!("Explorer error: {}", e);
-            }
-        });
-
         let http_handle = tokio::spawn(async move {
             match tokio::net::TcpListener::bind(http_addr).await {
                 Ok(listener) => {
@@ -111,22 +219,81 @@ async fn main() -> Result<()> {
             }
         });
 
-        // Wait for shutdown signal
-        tokio::select! {
-            _ = shutdown_signal => {
-                info!("Shutting down...");
-            }
-            _ = explorer_handle => {
-                info!("Explorer completed");
+        // Warm standby: apply the leader's events until it stops answering, then scan from there
+        let mut scanning = true;
+        if config.standby.enabled {
+            let mut follower = Follower::new(Arc::clone(&storage), config.standby.clone()).await?;
+            tokio::select! {
+                result = follower.run() => {
+                    info!("Standby promoted to scanning at opScore {}", result?);
+                }
+                _ = &mut shutdown_signal => {
+                    info!("Shutting down...");
+                    scanning = false;
+                }
             }
-            _ = http_handle => {
-                info!("HTTP server completed");
+        }
+
+        if scanning {
+            let mut explorer = Explorer::new(Arc::clone(&storage), config.startup, config.testnet)?
+                .with_event_retention(config.standby.event_retention)
+                .with_alerts(config.alerts.clone());
+            explorer.init().await?;
+
+            // Start explorer in background (corresponding to Go version explorer.Run)
+            let explorer_stop = explorer.stop_handle();
+            let mut explorer_handle = tokio::spawn(async move {
+                if let Err(e) = explorer.run().await {
+                    error!("Explorer error: {}", e);
+                }
+                if let Err(e) = explorer.shutdown().await {
+                    error!("Explorer shutdown error: {}", e);
+                }
+            });
+
+            // Wait for shutdown signal
+            let explorer_completed = tokio::select! {
+                _ = &mut shutdown_signal => {
+                    info!("Shutting down...");
+                    false
+                }
+                _ = &mut explorer_handle => {
+                    info!("Explorer completed");
+                    true
+                }
+                _ = http_handle => {
+                    info!("HTTP server completed");
+                    false
+                }
+            };
+
+            // The scanner finishes its batch and stops before storage shuts down
+            if !explorer_completed
+                && stop_task(&explorer_stop, explorer_handle, shutdown_timeout)
+                    .await
+                    .is_none()
+            {
+                warn!(
+                    "Explorer did not stop within {}ms, aborted",
+                    shutdown_timeout.as_millis()
+                );
             }
         }
     }
 
     // Graceful shutdown (corresponding to Go version graceful shutdown)
-    shutdown_gracefully(storage).await?;
+    disk_monitor_handle.abort();
+    if let Some(handle) = holder_reconciler_handle {
+        handle.abort();
+    }
+    if !shutdown_gracefully(storage, shutdown_timeout).await? {
+        warn!(
+            "Storage did not shut down within {}ms, forcing exit",
+            shutdown_timeout.as_millis()
+        );
+        release_file_lock(lock_file)?;
+        std::process::exit(1);
+    }
 
     // Release file lock
     release_file_lock(lock_file)?;
@@ -238,12 +405,15 @@ fn release_file_lock(lock_file: fs::File) -> Result<()> {
     Ok(())
 }
 
-async fn shutdown_gracefully(storage: Arc<StorageManager>) -> Result<()> {
+/// Returns false when storage did not shut down within `grace`
+async fn shutdown_gracefully(storage: Arc<StorageManager>, grace: Duration) -> Result<bool> {
     info!("Performing graceful shutdown...");
 
     // Shutdown storage
-    storage.shutdown().await?;
+    if !storage.shutdown_within(grace).await? {
+        return Ok(false);
+    }
 
     info!("Graceful shutdown completed");
-    Ok(())
+    Ok(true)
 }
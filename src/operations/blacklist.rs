@@ -1,3 +1,4 @@
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::script_builder::ScriptBuilder;
 use anyhow::Result;
@@ -58,6 +59,7 @@ impl BlacklistOperation {
         script.utxo = None;
         script.price = None;
         script.ca = None;
+        script.desc = None;
 
         true
     }
@@ -74,7 +76,7 @@ impl BlacklistOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing from address"))?;
 
         // Check if token exists
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if !state_map.state_token_map.contains_key(&token_key) {
             return Err(anyhow::anyhow!("Token {} does not exist", tick));
         }
@@ -103,7 +105,7 @@ impl BlacklistOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing to address"))?;
 
         // Create or update blacklist record
-        let blacklist_key = format!("blacklist:{}:{}", to, tick);
+        let blacklist_key = StateKey::blacklist(tick, to).map_key();
         let blacklist_data = StateBlacklistType {
             tick: tick.clone(),
             address: to.clone(),
@@ -138,7 +140,7 @@ impl BlacklistOperation {
         }
         if let Some(to) = &script.to {
             if let Some(tick) = &script.tick {
-                let key = format!("blacklist:{}:{}", to, tick);
+                let key = StateKey::blacklist(tick, to).map_key();
                 state_map.state_blacklist_map.insert(key, None);
             }
         }
@@ -152,7 +154,17 @@ impl BlacklistOperation {
         _testnet: bool,
     ) -> Result<()> {
         let script = op_data.op_script[index].clone();
-        Self::execute(&script, state_map)
+        match Self::execute(&script, state_map) {
+            Ok(_) => {
+                op_data.op_accept = 1;
+                op_data.op_error = "".to_string();
+            }
+            Err(e) => {
+                op_data.op_accept = -1;
+                op_data.op_error = e.to_string();
+            }
+        }
+        Ok(())
     }
 
     /// Validate token name
@@ -1,3 +1,4 @@
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::script_builder::ScriptBuilder;
 use anyhow::Result;
@@ -61,6 +62,7 @@ impl BurnOperation {
         script.utxo = None;
         script.price = None;
         script.ca = None;
+        script.desc = None;
 
         true
     }
@@ -81,13 +83,13 @@ impl BurnOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing amount"))?;
 
         // Check if token exists
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if !state_map.state_token_map.contains_key(&token_key) {
             return Err(anyhow::anyhow!("Token {} does not exist", tick));
         }
 
         // Check if balance is sufficient
-        let balance_key = format!("balance:{}:{}", from, tick);
+        let balance_key = StateKey::balance(from, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get(&balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -129,7 +131,7 @@ impl BurnOperation {
         }
 
         // Update token total supply
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if let Some(token) = state_map.state_token_map.get_mut(&token_key) {
             if let Some(token_data) = token {
                 let current_minted = token_data.minted.parse::<u64>().unwrap_or(0);
@@ -142,7 +144,7 @@ impl BurnOperation {
         }
 
         // Update user balance
-        let balance_key = format!("balance:{}:{}", from, tick);
+        let balance_key = StateKey::balance(from, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get_mut(&balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -182,7 +184,7 @@ impl BurnOperation {
         }
         if let Some(from) = &script.from {
             if let Some(tick) = &script.tick {
-                let key = format!("balance:{}:{}", from, tick);
+                let key = StateKey::balance(from, tick).map_key();
                 state_map.state_balance_map.insert(key, None);
             }
         }
@@ -197,6 +199,12 @@ impl BurnOperation {
     ) -> Result<()> {
         let script = &op_data.op_script[index];
 
+        if let Some(error) = super::amount_error("burn", script.amt.as_deref()) {
+            op_data.op_accept = -1;
+            op_data.op_error = error;
+            return Ok(());
+        }
+
         // Check if token exists
         if let Some(tick) = &script.tick {
             let token_result = state_map.state_token_map.get(tick);
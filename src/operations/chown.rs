@@ -1,3 +1,4 @@
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::script_builder::ScriptBuilder;
 use anyhow::Result;
@@ -58,6 +59,7 @@ impl ChownOperation {
         script.utxo = None;
         script.price = None;
         script.ca = None;
+        script.desc = None;
 
         true
     }
@@ -74,7 +76,7 @@ impl ChownOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing from address"))?;
 
         // Check if token exists
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if !state_map.state_token_map.contains_key(&token_key) {
             return Err(anyhow::anyhow!("Token {} does not exist", tick));
         }
@@ -103,7 +105,7 @@ impl ChownOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing to address"))?;
 
         // Update token owner
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if let Some(token) = state_map.state_token_map.get_mut(&token_key) {
             if let Some(token_data) = token {
                 token_data.from = to.clone();
@@ -143,7 +145,17 @@ impl ChownOperation {
         _testnet: bool,
     ) -> Result<()> {
         let script = op_data.op_script[index].clone();
-        Self::execute(&script, state_map)
+        match Self::execute(&script, state_map) {
+            Ok(_) => {
+                op_data.op_accept = 1;
+                op_data.op_error = "".to_string();
+            }
+            Err(e) => {
+                op_data.op_accept = -1;
+                op_data.op_error = e.to_string();
+            }
+        }
+        Ok(())
     }
 
     /// Validate token name
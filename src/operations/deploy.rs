@@ -1,3 +1,4 @@
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::address::verify_address;
 use crate::utils::script_builder::ScriptBuilder;
@@ -75,6 +76,7 @@ impl DeployOperation {
         script.utxo = None;
         script.price = None;
         script.ca = None;
+        script.desc = None;
 
         true
     }
@@ -90,7 +92,7 @@ impl DeployOperation {
             if script.pre.as_ref().map(|s| s != "0").unwrap_or(false) {
                 if let Some(to) = &script.to {
                     // Keep consistent with Go version: use "to_tick" format
-                    let balance_key = format!("{}_{}", to, tick);
+                    let balance_key = StateKey::balance(to, tick).map_key();
                     if !state_map.state_balance_map.contains_key(&balance_key) {
                         state_map.state_balance_map.insert(balance_key, None);
                     }
@@ -115,7 +117,7 @@ impl DeployOperation {
                 .unwrap_or(false)
             {
                 op_data.op_accept = -1;
-                op_data.op_error = "tick existed".to_string();
+                op_data.op_error = "tick already deployed".to_string();
                 return Ok(());
             }
 
@@ -126,18 +128,14 @@ impl DeployOperation {
                 return Ok(());
             }
 
-            // Check if it is a reserved token
-            if let Some(reserved_addr) = Self::get_tick_reserved(tick) {
-                if script
-                    .from
-                    .as_ref()
-                    .map(|s| s != &reserved_addr)
-                    .unwrap_or(true)
-                {
-                    op_data.op_accept = -1;
-                    op_data.op_error = "tick reserved".to_string();
-                    return Ok(());
-                }
+            // Check if it is a reserved token, only its reserving address may deploy it
+            if let Some(reserved_addr) =
+                crate::operations::get_reserved_tick_address(tick, op_data.daa_score)
+                && script.from.as_ref() != Some(&reserved_addr)
+            {
+                op_data.op_accept = -1;
+                op_data.op_error = "tick reserved".to_string();
+                return Ok(());
             }
         }
 
@@ -167,7 +165,7 @@ impl DeployOperation {
 
         // Create token state
         if let (Some(tick), Some(from), Some(to)) = (&script.tick, &script.from, &script.to) {
-            let balance_key = format!("{}_{}", to, tick);
+            let balance_key = StateKey::balance(to, tick).map_key();
 
             let dec = script
                 .dec
@@ -194,6 +192,8 @@ impl DeployOperation {
                 minted: "0".to_string(),
                 burned: "0".to_string(),
                 name: name.unwrap_or_default(),
+                desc: String::new(),
+                frozen: false,
                 tx_id: op_data.tx_id.clone(),
                 op_add: op_data.op_score,
                 op_mod: op_data.op_score,
@@ -275,16 +275,8 @@ impl DeployOperation {
     }
 
     fn is_tick_ignored(tick: &str) -> bool {
-        let ignored_ticks = [
-            "KASPA", "KASPLX", "KASP", "WKAS", "GIGA", "WBTC", "WETH", "USDT", "USDC", "FDUSD",
-            "USDD", "TUSD", "USDP", "PYUSD", "EURC", "BUSD", "GUSD", "EURT", "XAUT", "TETHER",
-        ];
-        ignored_ticks.contains(&tick)
-    }
-
-    fn get_tick_reserved(tick: &str) -> Option<String> {
-        // Use the centralized reserved token check from config module
-        crate::config::get_reserved_tick_address(tick)
+        // Use the configurable ignored token list from config module
+        crate::config::is_tick_ignored(tick)
     }
 
     fn verify_address(address: &str, testnet: bool) -> bool {
@@ -0,0 +1,54 @@
+use crate::storage::types::*;
+
+/// Delete the balances of `keys` an accepted script left with a positive unlocked amount below
+/// the configured dust threshold. The swept amount is added to the token's burned supply, so the
+/// circulating supply still matches what holders own. Balances with a locked part are kept, as
+/// their listing still refers to them. The deleted balance and the token are re-emitted into the
+/// st lines of `op_data`, which were built before the sweep. Returns the number of swept balances.
+pub fn sweep_dust(
+    keys: &DataStateMapType,
+    state_map: &mut DataStateMapType,
+    op_data: &mut DataOperationType,
+) -> usize {
+    let threshold = crate::config::get_balance_dust_threshold() as u128;
+    if threshold == 0 {
+        return 0;
+    }
+    let mut swept = 0;
+    for key in keys.state_balance_map.keys() {
+        let Some(Some(balance)) = state_map.state_balance_map.get(key) else {
+            continue;
+        };
+        let amount = balance.balance.parse::<u128>().unwrap_or(0);
+        let locked = balance.locked.parse::<u128>().unwrap_or(0);
+        if amount == 0 || amount >= threshold || locked > 0 {
+            continue;
+        }
+        // Without the token loaded the burn can not be accounted, keep the balance
+        let Some(Some(token)) = state_map.state_token_map.get_mut(&balance.tick) else {
+            continue;
+        };
+        let tick = balance.tick.clone();
+        op_data.st_before = crate::operations::append_st_line_token(
+            &mut op_data.st_before,
+            &tick,
+            Some(token),
+            false,
+            false,
+        );
+        let burned = token.burned.parse::<u128>().unwrap_or(0);
+        token.burned = (burned + amount).to_string();
+        op_data.st_after = crate::operations::append_st_line_token(
+            &mut op_data.st_after,
+            &tick,
+            Some(token),
+            false,
+            true,
+        );
+        op_data.st_after =
+            crate::operations::append_st_line_balance(&mut op_data.st_after, key, None, true);
+        state_map.state_balance_map.insert(key.clone(), None);
+        swept += 1;
+    }
+    swept
+}
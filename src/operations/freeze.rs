@@ -0,0 +1,137 @@
+use crate::operations::{
+    apply_ca, make_st_line_token_frozen, parse_st_line_token_frozen, validate_tick_tx_id,
+};
+use crate::storage::types::*;
+use crate::utils::script_builder::ScriptBuilder;
+use anyhow::Result;
+
+/// Token freeze operation, lets the owner pause the token. Each freeze toggles the frozen flag,
+/// so the owner unfreezes a frozen token with another freeze.
+pub struct FreezeOperation;
+
+impl FreezeOperation {
+    /// Build freeze script
+    pub fn build_script(tick: &str) -> Result<String> {
+        ScriptBuilder::build_freeze_script(tick)
+    }
+
+    /// Validate freeze operation
+    pub fn validate(
+        script: &mut DataScriptType,
+        _tx_id: &str,
+        _daa_score: u64,
+        _testnet: bool,
+    ) -> bool {
+        // Validate required fields
+        if !apply_ca(script)
+            || script.from.as_ref().map(|s| s.is_empty()).unwrap_or(true)
+            || script.p != "KRC-20"
+            || script.op != "freeze"
+            || !validate_tick_tx_id(script.tick.get_or_insert_with(String::new))
+        {
+            return false;
+        }
+
+        // Clear unnecessary fields
+        script.to = None;
+        script.max = None;
+        script.lim = None;
+        script.dec = None;
+        script.pre = None;
+        script.mod_type = String::new();
+        script.name = None;
+        script.amt = None;
+        script.utxo = None;
+        script.price = None;
+        script.ca = None;
+        script.desc = None;
+
+        true
+    }
+
+    /// Script collection extension
+    pub fn script_collect_ex(
+        _index: usize,
+        _script: &mut DataScriptType,
+        _tx_data: &DataTransactionType,
+        _testnet: bool,
+    ) {
+        // Temporarily empty implementation
+    }
+
+    /// Get operation fee
+    pub fn fee_least(_daa_score: u64) -> u64 {
+        100000000 // Freeze operation fee
+    }
+
+    /// Prepare state keys
+    pub fn prepare_state_key(script: &DataScriptType, state_map: &mut DataStateMapType) {
+        if let Some(tick) = &script.tick {
+            // Only insert None when key does not exist, avoid overwriting loaded state
+            if !state_map.state_token_map.contains_key(tick) {
+                state_map.state_token_map.insert(tick.clone(), None);
+            }
+        }
+    }
+
+    /// Execute operation, only the current owner may freeze or unfreeze the token
+    pub fn do_operation(
+        index: usize,
+        op_data: &mut DataOperationType,
+        state_map: &mut DataStateMapType,
+        _testnet: bool,
+    ) -> Result<()> {
+        let script = op_data.op_script[index].clone();
+        let tick = script.tick.clone().unwrap_or_default();
+
+        let st_token = match state_map.state_token_map.get(&tick) {
+            Some(Some(token)) => token.clone(),
+            _ => {
+                op_data.op_accept = -1;
+                op_data.op_error = "tick not found".to_string();
+                return Ok(());
+            }
+        };
+        if script.from.as_deref() != Some(st_token.from.as_str()) {
+            op_data.op_accept = -1;
+            op_data.op_error = "no ownership".to_string();
+            return Ok(());
+        }
+
+        // Set pre-operation state
+        op_data.st_before = vec![make_st_line_token_frozen(&tick, &st_token)];
+
+        let mut new_st_token = st_token;
+        new_st_token.frozen = !new_st_token.frozen;
+        new_st_token.op_mod = op_data.op_score;
+        new_st_token.mts_mod = op_data.mts_add;
+
+        // Set post-operation state
+        op_data.st_after = vec![make_st_line_token_frozen(&tick, &new_st_token)];
+
+        state_map.state_token_map.insert(tick, Some(new_st_token));
+
+        op_data.op_accept = 1;
+        Ok(())
+    }
+
+    /// Restore the frozen flag recorded in st_before of an accepted freeze
+    pub fn rollback(op_data: &DataOperationType, state_map: &mut DataStateMapType) -> Result<()> {
+        if op_data.op_accept != 1 {
+            return Ok(());
+        }
+        for st_line in &op_data.st_before {
+            let (tick, frozen, op_mod, mts_mod) = parse_st_line_token_frozen(st_line)
+                .ok_or_else(|| anyhow::anyhow!("Invalid token frozen state line: {}", st_line))?;
+            let token = state_map
+                .state_token_map
+                .get_mut(&tick)
+                .and_then(|token| token.as_mut())
+                .ok_or_else(|| anyhow::anyhow!("Token {} does not exist", tick))?;
+            token.frozen = frozen;
+            token.op_mod = op_mod;
+            token.mts_mod = mts_mod;
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,22 @@
+use crate::operations::dust;
+use crate::operations::invariant::BalanceInvariant;
+use crate::operations::metrics;
 use crate::operations::{
-    BlacklistOperation, BurnOperation, ChownOperation, DeployOperation, IssueOperation,
-    ListOperation, MintOperation, SendOperation, TransferOperation,
+    BlacklistOperation, BurnOperation, ChownOperation, DeployOperation, FreezeOperation,
+    IssueOperation, ListOperation, MintOperation, OP_ERROR_OP_DISABLED, OP_ERROR_UNKNOWN_OP,
+    SendOperation, TransferOperation, UpdateOperation, is_op_enabled,
 };
 use crate::storage::StorageManager;
 use crate::storage::types::*;
 use anyhow::Result;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tracing::info;
+use std::sync::{Arc, Weak};
+use tracing::{info, warn};
 
 /// Operation manager, corresponding to Go version's operation registration and execution mechanism
 pub struct OperationManager {
-    storage: Arc<StorageManager>,
+    // Weak so the manager registered in StorageManager does not keep storage alive
+    storage: Weak<StorageManager>,
     // Registration mapping corresponding to Go version
     p_registered: HashMap<String, bool>,
     op_registered: HashMap<String, bool>,
@@ -47,9 +52,9 @@ pub trait OperationMethod: Send + Sync {
 }
 
 impl OperationManager {
-    pub fn new(storage: Arc<StorageManager>) -> Self {
+    pub fn new(storage: &Arc<StorageManager>) -> Self {
         let mut manager = Self {
-            storage,
+            storage: Arc::downgrade(storage),
             p_registered: HashMap::new(),
             op_registered: HashMap::new(),
             method_registered: HashMap::new(),
@@ -62,6 +67,13 @@ impl OperationManager {
         manager
     }
 
+    /// Storage this manager was created for, an error once it has been dropped
+    fn storage(&self) -> Result<Arc<StorageManager>> {
+        self.storage
+            .upgrade()
+            .ok_or_else(|| anyhow::anyhow!("Storage manager dropped, operation manager unusable"))
+    }
+
     /// Register operations, corresponding to Go version's init function
     fn register_operations(&mut self) {
         // Register protocol
@@ -113,6 +125,16 @@ impl OperationManager {
         self.method_registered
             .insert("chown".to_string(), Box::new(ChownOperationHandler));
 
+        // Register metadata update operation
+        self.op_registered.insert("update".to_string(), true);
+        self.method_registered
+            .insert("update".to_string(), Box::new(UpdateOperationHandler));
+
+        // Register token freeze operation
+        self.op_registered.insert("freeze".to_string(), true);
+        self.method_registered
+            .insert("freeze".to_string(), Box::new(FreezeOperationHandler));
+
         info!(
             "Registered operations: {:?}",
             self.op_registered.keys().collect::<Vec<_>>()
@@ -168,7 +190,8 @@ impl OperationManager {
     ) -> Result<()> {
         if let Some(first_script) = op_data.op_script.first() {
             if let Some(method) = self.method_registered.get(&first_script.op) {
-                method.do_operation(index, op_data, state_map, testnet)
+                let op = first_script.op.clone();
+                Self::run_method(method.as_ref(), &op, index, op_data, state_map, testnet)
             } else {
                 Err(anyhow::anyhow!("Unknown operation: {}", first_script.op))
             }
@@ -176,6 +199,138 @@ impl OperationManager {
             Err(anyhow::anyhow!("No operation script found"))
         }
     }
+
+    /// Run one script and check the balance invariants on the state it declared. A script
+    /// breaking them is rejected and its changes to the declared state are undone.
+    fn run_method(
+        method: &dyn OperationMethod,
+        op: &str,
+        index: usize,
+        op_data: &mut DataOperationType,
+        state_map: &mut DataStateMapType,
+        testnet: bool,
+    ) -> Result<()> {
+        let mut keys = DataStateMapType::new();
+        method.prepare_state_key(&op_data.op_script[index], &mut keys);
+        let invariant = BalanceInvariant::capture(&keys, state_map);
+
+        let timer = metrics::start_timer();
+        let result = method.do_operation(index, op_data, state_map, testnet);
+        metrics::record_op_duration(op, timer);
+        result?;
+        if op_data.op_accept == 1 {
+            dust::sweep_dust(&keys, state_map, op_data);
+        }
+        if let Err(e) = invariant.check(state_map) {
+            warn!("{} operation {}: {}", op, op_data.tx_id, e);
+            invariant.restore(state_map);
+            op_data.op_accept = -1;
+            op_data.op_error = e.to_string();
+        }
+        Ok(())
+    }
+
+    /// Apply all scripts of one operation in op_script order, which is the input order
+    pub fn execute_op_script_list(
+        &self,
+        op_data: &mut DataOperationType,
+        state_map: &mut DataStateMapType,
+        testnet: bool,
+    ) -> Result<()> {
+        for index in 0..op_data.op_script.len() {
+            let op = op_data.op_script[index].op.clone();
+            if let Some(method) = self.method_registered.get(&op) {
+                Self::run_method(method.as_ref(), &op, index, op_data, state_map, testnet)?;
+            } else {
+                if !crate::config::is_unknown_op_allowed() {
+                    op_data.op_accept = -1;
+                }
+                op_data.op_error = OP_ERROR_UNKNOWN_OP.to_string();
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect the state keys of a batch and load their state, corresponding to Go version's PrepareStateBatch
+    pub fn prepare_state_batch(
+        &self,
+        op_data_list: &[DataOperationType],
+    ) -> Result<(DataStateMapType, i64)> {
+        let start_time = std::time::Instant::now();
+        let mut state_map = DataStateMapType::new();
+
+        for op_data in op_data_list.iter().filter(|op_data| is_op_enabled(op_data)) {
+            for script in &op_data.op_script {
+                if let Some(method) = self.method_registered.get(&script.op) {
+                    method.prepare_state_key(script, &mut state_map);
+                }
+            }
+        }
+
+        let storage = self.storage()?;
+        storage
+            .state
+            .get_state_token_map(&mut state_map.state_token_map)?;
+        storage
+            .state
+            .get_state_balance_map(&mut state_map.state_balance_map)?;
+        storage
+            .state
+            .get_state_market_map(&mut state_map.state_market_map)?;
+        storage
+            .state
+            .get_state_blacklist_map(&mut state_map.state_blacklist_map)?;
+        storage
+            .state
+            .get_state_mint_rate_map(&mut state_map.state_mint_rate_map)?;
+
+        let duration = start_time.elapsed().as_millis() as i64;
+        Ok((state_map, duration))
+    }
+
+    /// Execute a batch in op_score order, corresponding to Go version's ExecuteBatch.
+    /// Every operation applies to the running state_map and storage is never re-read, so
+    /// operations touching the same key see the effects of earlier ones in the batch.
+    /// The returned rollback holds the state before the batch so it can be undone on reorg.
+    /// A failing operation is rejected with `op_accept = -1` and the batch carries on, an error
+    /// means the batch itself could not be executed.
+    pub fn execute_batch(
+        &self,
+        op_data_list: &mut [DataOperationType],
+        state_map: &mut DataStateMapType,
+        checkpoint_last: &str,
+        testnet: bool,
+    ) -> Result<(DataRollbackType, i64)> {
+        let start_time = std::time::Instant::now();
+        let mut rollback = DataRollbackType::new(
+            state_map.clone(),
+            DataStateMapType::new(),
+            Vec::new(),
+            Vec::new(),
+            0,
+            0,
+            checkpoint_last.to_string(),
+            checkpoint_last.to_string(),
+            0,
+        );
+
+        for op_data in op_data_list.iter_mut() {
+            // Disabled operations keep their op_score and are stored unexecuted
+            if is_op_enabled(op_data) {
+                self.execute_op_script_list(op_data, state_map, testnet)?;
+            } else {
+                op_data.op_error = OP_ERROR_OP_DISABLED.to_string();
+            }
+            rollback.op_score_list.push(op_data.op_score);
+            rollback.tx_id_list.push(op_data.tx_id.clone());
+            rollback.op_score_last = op_data.op_score;
+        }
+        rollback.state_map_after = state_map.clone();
+
+        let duration = start_time.elapsed().as_millis() as i64;
+        Ok((rollback, duration))
+    }
 }
 
 // Operation handler implementation - using independent operation modules
@@ -524,6 +679,90 @@ impl OperationMethod for ChownOperationHandler {
     }
 }
 
+struct UpdateOperationHandler;
+
+impl OperationMethod for UpdateOperationHandler {
+    fn script_collect_ex(
+        &self,
+        index: usize,
+        script: &mut DataScriptType,
+        tx_data: &DataTransactionType,
+        testnet: bool,
+    ) {
+        UpdateOperation::script_collect_ex(index, script, tx_data, testnet)
+    }
+
+    fn validate(
+        &self,
+        script: &mut DataScriptType,
+        tx_id: &str,
+        daa_score: u64,
+        testnet: bool,
+    ) -> bool {
+        UpdateOperation::validate(script, tx_id, daa_score, testnet)
+    }
+
+    fn fee_least(&self, daa_score: u64) -> u64 {
+        UpdateOperation::fee_least(daa_score)
+    }
+
+    fn prepare_state_key(&self, script: &DataScriptType, state_map: &mut DataStateMapType) {
+        UpdateOperation::prepare_state_key(script, state_map)
+    }
+
+    fn do_operation(
+        &self,
+        index: usize,
+        op_data: &mut DataOperationType,
+        state_map: &mut DataStateMapType,
+        testnet: bool,
+    ) -> Result<()> {
+        UpdateOperation::do_operation(index, op_data, state_map, testnet)
+    }
+}
+
+struct FreezeOperationHandler;
+
+impl OperationMethod for FreezeOperationHandler {
+    fn script_collect_ex(
+        &self,
+        index: usize,
+        script: &mut DataScriptType,
+        tx_data: &DataTransactionType,
+        testnet: bool,
+    ) {
+        FreezeOperation::script_collect_ex(index, script, tx_data, testnet)
+    }
+
+    fn validate(
+        &self,
+        script: &mut DataScriptType,
+        tx_id: &str,
+        daa_score: u64,
+        testnet: bool,
+    ) -> bool {
+        FreezeOperation::validate(script, tx_id, daa_score, testnet)
+    }
+
+    fn fee_least(&self, daa_score: u64) -> u64 {
+        FreezeOperation::fee_least(daa_score)
+    }
+
+    fn prepare_state_key(&self, script: &DataScriptType, state_map: &mut DataStateMapType) {
+        FreezeOperation::prepare_state_key(script, state_map)
+    }
+
+    fn do_operation(
+        &self,
+        index: usize,
+        op_data: &mut DataOperationType,
+        state_map: &mut DataStateMapType,
+        testnet: bool,
+    ) -> Result<()> {
+        FreezeOperation::do_operation(index, op_data, state_map, testnet)
+    }
+}
+
 struct BlacklistOperationHandler;
 
 impl OperationMethod for BlacklistOperationHandler {
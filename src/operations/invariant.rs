@@ -0,0 +1,129 @@
+use crate::storage::types::*;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Balance accounting invariants of one script execution. `balance` is the unlocked part of a
+/// holder's amount and `locked` the part held by listings, so for every token
+/// `sum(balance + locked) + burned == minted` must hold across all holders. Only the holders a
+/// script touches can change, so the sum is checked on the difference of their state.
+pub struct BalanceInvariant {
+    balances: HashMap<String, Option<StateBalanceType>>,
+    tokens: HashMap<String, Option<StateTokenType>>,
+}
+
+/// Amount of a state field, empty fields count as zero as everywhere else in the state
+fn parse_amount(key: &str, field: &str, value: &str) -> Result<i128> {
+    if value.is_empty() {
+        return Ok(0);
+    }
+    value
+        .parse::<u128>()
+        .ok()
+        .and_then(|amount| i128::try_from(amount).ok())
+        .ok_or_else(|| anyhow::anyhow!("Invariant violated: {} of {} is {:?}", field, key, value))
+}
+
+/// Unlocked and locked amount of a balance entry, zero when it does not exist
+fn balance_amounts(key: &str, balance: Option<&StateBalanceType>) -> Result<(i128, i128)> {
+    match balance {
+        Some(balance) => Ok((
+            parse_amount(key, "balance", &balance.balance)?,
+            parse_amount(key, "locked", &balance.locked)?,
+        )),
+        None => Ok((0, 0)),
+    }
+}
+
+/// Circulating supply `minted - burned` of a token entry, zero when it does not exist
+fn token_supply(tick: &str, token: Option<&StateTokenType>) -> Result<i128> {
+    match token {
+        Some(token) => Ok(parse_amount(tick, "minted", &token.minted)?
+            - parse_amount(tick, "burned", &token.burned)?),
+        None => Ok(0),
+    }
+}
+
+impl BalanceInvariant {
+    /// Capture the entries of `keys` in `state_map` before a script runs,
+    /// `keys` holds the state keys the script declared in prepare_state_key
+    pub fn capture(keys: &DataStateMapType, state_map: &DataStateMapType) -> Self {
+        Self {
+            balances: keys
+                .state_balance_map
+                .keys()
+                .map(|key| {
+                    (
+                        key.clone(),
+                        state_map.state_balance_map.get(key).cloned().flatten(),
+                    )
+                })
+                .collect(),
+            tokens: keys
+                .state_token_map
+                .keys()
+                .map(|tick| {
+                    (
+                        tick.clone(),
+                        state_map.state_token_map.get(tick).cloned().flatten(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Put the captured entries back, undoing what the script changed on them
+    pub fn restore(self, state_map: &mut DataStateMapType) {
+        state_map.state_balance_map.extend(self.balances);
+        state_map.state_token_map.extend(self.tokens);
+    }
+
+    /// Check the captured entries after the script ran: a holder can only lock what it held,
+    /// and the amount held per token changes exactly as much as its circulating supply
+    pub fn check(&self, state_map: &DataStateMapType) -> Result<()> {
+        let mut held_delta: HashMap<String, i128> = HashMap::new();
+        for (key, before) in &self.balances {
+            let after = state_map.state_balance_map.get(key).cloned().flatten();
+            let (balance_before, locked_before) = balance_amounts(key, before.as_ref())?;
+            let (balance_after, locked_after) = balance_amounts(key, after.as_ref())?;
+
+            let locked_added = locked_after - locked_before;
+            if locked_added > 0 && balance_before - balance_after < locked_added {
+                return Err(anyhow::anyhow!(
+                    "Invariant violated: {} locked {} more than its balance released",
+                    key,
+                    locked_added
+                ));
+            }
+
+            let Some(tick) = after.as_ref().or(before.as_ref()).map(|b| b.tick.clone()) else {
+                continue;
+            };
+            *held_delta.entry(tick).or_default() +=
+                balance_after + locked_after - balance_before - locked_before;
+        }
+
+        for (tick, before) in &self.tokens {
+            let after = state_map.state_token_map.get(tick).cloned().flatten();
+            let supply_delta =
+                token_supply(tick, after.as_ref())? - token_supply(tick, before.as_ref())?;
+            let held = held_delta.remove(tick).unwrap_or(0);
+            if held != supply_delta {
+                return Err(anyhow::anyhow!(
+                    "Invariant violated: holders of {} changed by {} but supply by {}",
+                    tick,
+                    held,
+                    supply_delta
+                ));
+            }
+        }
+        // Balances of a token the script did not declare can not change in total either
+        if let Some((tick, held)) = held_delta.into_iter().find(|(_, held)| *held != 0) {
+            return Err(anyhow::anyhow!(
+                "Invariant violated: holders of {} changed by {} without a supply change",
+                tick,
+                held
+            ));
+        }
+        Ok(())
+    }
+}
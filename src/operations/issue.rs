@@ -1,3 +1,4 @@
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::script_builder::ScriptBuilder;
 use anyhow::Result;
@@ -64,6 +65,7 @@ impl IssueOperation {
         script.utxo = None;
         script.price = None;
         script.ca = None;
+        script.desc = None;
 
         true
     }
@@ -84,7 +86,7 @@ impl IssueOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing amount"))?;
 
         // Check if token exists
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if !state_map.state_token_map.contains_key(&token_key) {
             return Err(anyhow::anyhow!("Token {} does not exist", tick));
         }
@@ -131,7 +133,7 @@ impl IssueOperation {
         }
 
         // Update token total supply
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if let Some(token) = state_map.state_token_map.get_mut(&token_key) {
             if let Some(token_data) = token {
                 let current_minted = token_data.minted.parse::<u64>().unwrap_or(0);
@@ -140,7 +142,7 @@ impl IssueOperation {
         }
 
         // Increase recipient balance
-        let balance_key = format!("balance:{}:{}", to, tick);
+        let balance_key = StateKey::balance(to, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get_mut(&balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -199,7 +201,7 @@ impl IssueOperation {
         }
         if let Some(to) = &script.to {
             if let Some(tick) = &script.tick {
-                let key = format!("balance:{}:{}", to, tick);
+                let key = StateKey::balance(to, tick).map_key();
                 state_map.state_balance_map.insert(key, None);
             }
         }
@@ -213,7 +215,17 @@ impl IssueOperation {
         _testnet: bool,
     ) -> Result<()> {
         let script = op_data.op_script[index].clone();
-        Self::execute(&script, state_map)
+        match Self::execute(&script, state_map) {
+            Ok(_) => {
+                op_data.op_accept = 1;
+                op_data.op_error = "".to_string();
+            }
+            Err(e) => {
+                op_data.op_accept = -1;
+                op_data.op_error = e.to_string();
+            }
+        }
+        Ok(())
     }
 
     /// Validate token name
@@ -1,3 +1,4 @@
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::script_builder::ScriptBuilder;
 use anyhow::Result;
@@ -61,6 +62,7 @@ impl ListOperation {
         script.name = None;
         script.utxo = None;
         script.ca = None;
+        script.desc = None;
 
         true
     }
@@ -81,13 +83,13 @@ impl ListOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing amount"))?;
 
         // Check if token exists
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if !state_map.state_token_map.contains_key(&token_key) {
             return Err(anyhow::anyhow!("Token {} does not exist", tick));
         }
 
         // Check if lister balance is sufficient
-        let balance_key = format!("balance:{}:{}", from, tick);
+        let balance_key = StateKey::balance(from, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get(&balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -109,7 +111,11 @@ impl ListOperation {
     }
 
     /// Execute list operation, corresponding to Go version Execute method
-    pub fn execute(script: &DataScriptType, state_map: &mut DataStateMapType) -> Result<()> {
+    pub fn execute(
+        script: &DataScriptType,
+        tx_id: &str,
+        state_map: &mut DataStateMapType,
+    ) -> Result<()> {
         let tick = script
             .tick
             .as_ref()
@@ -135,7 +141,7 @@ impl ListOperation {
         }
 
         // Reduce lister balance
-        let balance_key = format!("balance:{}:{}", from, tick);
+        let balance_key = StateKey::balance(from, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get_mut(&balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -150,11 +156,11 @@ impl ListOperation {
         }
 
         // Create or update market listing
-        let market_key = format!("market:{}:{}", from, tick);
+        let market_key = StateKey::market(tick, from, tx_id).map_key();
         let market_data = StateMarketType {
             tick: tick.clone(),
             t_addr: from.clone(),
-            u_tx_id: "".to_string(),
+            u_tx_id: tx_id.to_string(),
             u_addr: "".to_string(),
             u_amt: list_amount.to_string(),
             u_script: "".to_string(),
@@ -190,16 +196,10 @@ impl ListOperation {
         }
         if let Some(from) = &script.from {
             if let Some(tick) = &script.tick {
-                let key = format!("balance:{}:{}", from, tick);
+                let key = StateKey::balance(from, tick).map_key();
                 state_map.state_balance_map.insert(key, None);
             }
         }
-        if let Some(from) = &script.from {
-            if let Some(tick) = &script.tick {
-                let key = format!("market:{}:{}", from, tick);
-                state_map.state_market_map.insert(key, None);
-            }
-        }
     }
 
     /// Execute operation
@@ -210,7 +210,17 @@ impl ListOperation {
         _testnet: bool,
     ) -> Result<()> {
         let script = op_data.op_script[index].clone();
-        Self::execute(&script, state_map)
+        match Self::execute(&script, &op_data.tx_id, state_map) {
+            Ok(_) => {
+                op_data.op_accept = 1;
+                op_data.op_error = "".to_string();
+            }
+            Err(e) => {
+                op_data.op_accept = -1;
+                op_data.op_error = e.to_string();
+            }
+        }
+        Ok(())
     }
 
     /// Validate token name
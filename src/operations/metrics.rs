@@ -0,0 +1,187 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Upper bounds (microseconds) of the execution time buckets, slower samples land in the last bucket
+pub const OP_DURATION_BUCKETS_US: [u64; 8] = [10, 50, 100, 500, 1000, 5000, 10000, 50000];
+
+// Off until applied from config, so executing operations only pays for an atomic load
+static OP_METRICS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+static OP_DURATION_HISTOGRAM: Lazy<Mutex<HashMap<String, OpDurationHistogram>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Execution time histogram of one operation type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OpDurationHistogram {
+    /// Sample count per bucket of OP_DURATION_BUCKETS_US, plus one overflow bucket
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum_us: u64,
+    pub max_us: u64,
+}
+
+impl OpDurationHistogram {
+    fn record(&mut self, duration_us: u64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; OP_DURATION_BUCKETS_US.len() + 1];
+        }
+        let bucket = OP_DURATION_BUCKETS_US
+            .iter()
+            .position(|bound| duration_us <= *bound)
+            .unwrap_or(OP_DURATION_BUCKETS_US.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_us = self.sum_us.saturating_add(duration_us);
+        self.max_us = self.max_us.max(duration_us);
+    }
+}
+
+/// Enable or disable the per-operation execution time histogram
+pub fn apply_op_metrics_enabled(enabled: bool) {
+    OP_METRICS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Start timing an operation, None when metrics are disabled
+pub fn start_timer() -> Option<Instant> {
+    if OP_METRICS_ENABLED.load(Ordering::Relaxed) {
+        Some(Instant::now())
+    } else {
+        None
+    }
+}
+
+/// Record the time elapsed since `start_timer` under the operation type
+pub fn record_op_duration(op: &str, start: Option<Instant>) {
+    if let Some(start) = start {
+        record_duration(op, start.elapsed());
+    }
+}
+
+fn record_duration(op: &str, duration: Duration) {
+    let duration_us = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+    let mut histogram = OP_DURATION_HISTOGRAM.lock().unwrap();
+    histogram
+        .entry(op.to_string())
+        .or_default()
+        .record(duration_us);
+}
+
+/// Snapshot of the histograms by operation type
+pub fn get_op_duration_histogram() -> BTreeMap<String, OpDurationHistogram> {
+    let histogram = OP_DURATION_HISTOGRAM.lock().unwrap();
+    histogram
+        .iter()
+        .map(|(op, h)| (op.clone(), h.clone()))
+        .collect()
+}
+
+/// Clear all recorded samples
+pub fn reset_op_duration_histogram() {
+    OP_DURATION_HISTOGRAM.lock().unwrap().clear();
+}
+
+/// Minimum time between two logged summaries of rejected scripts
+pub const SCRIPT_REJECTION_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Reason a revealed KRC-20 script was skipped while parsing transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptRejection {
+    /// A field exceeds the configured script limits
+    Oversized,
+    /// `p` is not a supported protocol
+    BadProtocol,
+    /// `op` is not a known operation
+    BadOp,
+    /// The envelope declares a different operation than `op`
+    OpMismatch,
+    /// `to` contains non-ASCII characters
+    NonAscii,
+    /// The operation failed its own validation
+    InvalidOperation,
+}
+
+// Counted whether or not execution time metrics are enabled, one atomic add per rejected script
+static SCRIPT_REJECTIONS: [AtomicU64; 6] = [const { AtomicU64::new(0) }; 6];
+
+static SCRIPT_REJECTION_LOGGED: Lazy<Mutex<Option<(Instant, u64)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Scripts rejected since startup, by reason
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ScriptRejectionCounts {
+    pub oversized: u64,
+    pub bad_protocol: u64,
+    pub bad_op: u64,
+    pub op_mismatch: u64,
+    pub non_ascii: u64,
+    pub invalid_operation: u64,
+}
+
+impl ScriptRejectionCounts {
+    pub fn total(&self) -> u64 {
+        self.oversized
+            + self.bad_protocol
+            + self.bad_op
+            + self.op_mismatch
+            + self.non_ascii
+            + self.invalid_operation
+    }
+}
+
+/// Count a rejected script
+pub fn record_script_rejection(reason: ScriptRejection) {
+    SCRIPT_REJECTIONS[reason as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Snapshot of the rejected script counters
+pub fn get_script_rejections() -> ScriptRejectionCounts {
+    let count =
+        |reason: ScriptRejection| SCRIPT_REJECTIONS[reason as usize].load(Ordering::Relaxed);
+    ScriptRejectionCounts {
+        oversized: count(ScriptRejection::Oversized),
+        bad_protocol: count(ScriptRejection::BadProtocol),
+        bad_op: count(ScriptRejection::BadOp),
+        op_mismatch: count(ScriptRejection::OpMismatch),
+        non_ascii: count(ScriptRejection::NonAscii),
+        invalid_operation: count(ScriptRejection::InvalidOperation),
+    }
+}
+
+/// Clear the rejected script counters
+pub fn reset_script_rejections() {
+    for counter in &SCRIPT_REJECTIONS {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Log the rejected script counters, at most once per SCRIPT_REJECTION_LOG_INTERVAL and only when
+/// scripts were rejected since the last summary
+pub fn log_script_rejection_summary() {
+    let counts = get_script_rejections();
+    let mut logged = SCRIPT_REJECTION_LOGGED.lock().unwrap();
+    let logged_total = match *logged {
+        Some((logged_at, _)) if logged_at.elapsed() < SCRIPT_REJECTION_LOG_INTERVAL => return,
+        Some((_, logged_total)) => logged_total,
+        None => 0,
+    };
+    if counts.total() == logged_total {
+        return;
+    }
+    info!(
+        "Rejected scripts: {} since last summary, total {} (oversized {}, bad protocol {}, bad op {}, op mismatch {}, non-ASCII {}, invalid operation {})",
+        counts.total() - logged_total,
+        counts.total(),
+        counts.oversized,
+        counts.bad_protocol,
+        counts.bad_op,
+        counts.op_mismatch,
+        counts.non_ascii,
+        counts.invalid_operation
+    );
+    *logged = Some((Instant::now(), counts.total()));
+}
@@ -1,8 +1,11 @@
 use crate::operations::validate_tick;
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::address::verify_address;
 use crate::utils::script_builder::ScriptBuilder;
 use anyhow::Result;
+use num_bigint::BigInt;
+use std::str::FromStr;
 
 /// Mint operation implementation, corresponding to Go version's OpMethodMint
 pub struct MintOperation;
@@ -29,6 +32,29 @@ impl MintOperation {
         // Temporarily empty implementation
     }
 
+    /// Count a mint against the rate limit of its tick, returns false if the limit is exceeded.
+    /// Counts live in the state map, so they are saved and rolled back with the batch.
+    pub fn check_mint_rate(tick: &str, daa_score: u64, state_map: &mut DataStateMapType) -> bool {
+        let Some((window, max_mints)) = crate::config::get_mint_rate_limit(tick) else {
+            return true;
+        };
+        let window_start = daa_score - daa_score % window;
+        let entry = state_map
+            .state_mint_rate_map
+            .entry(tick.to_string())
+            .or_default()
+            .get_or_insert_with(MintRateCount::default);
+        if entry.window_start != window_start {
+            entry.window_start = window_start;
+            entry.count = 0;
+        }
+        if entry.count >= max_mints {
+            return false;
+        }
+        entry.count += 1;
+        true
+    }
+
     /// Validate script, corresponding to Go version Validate
     pub fn validate(
         script: &mut DataScriptType,
@@ -68,10 +94,16 @@ impl MintOperation {
         }
         if let Some(to) = &op_script.to {
             if let Some(tick) = &op_script.tick {
-                let key_balance = format!("{}_{}", to, tick);
+                let key_balance = StateKey::balance(to, tick).map_key();
                 state_map.state_balance_map.insert(key_balance, None);
             }
         }
+        if let Some(tick) = &op_script.tick
+            && crate::config::get_mint_rate_limit(tick).is_some()
+            && !state_map.state_mint_rate_map.contains_key(tick)
+        {
+            state_map.state_mint_rate_map.insert(tick.clone(), None);
+        }
     }
 
     /// Execute minting operation, corresponding to Go version Do
@@ -115,6 +147,12 @@ impl MintOperation {
                 return Ok(());
             }
 
+            if super::is_token_frozen(state_map, tick) {
+                op_data.op_accept = -1;
+                op_data.op_error = super::OP_ERROR_TOKEN_FROZEN.to_string();
+                return Ok(());
+            }
+
             // Check token mode
             if let Some(token) = state_map.state_token_map.get(tick).unwrap_or(&None) {
                 // Allow minting when mod_type is "0" or empty string
@@ -146,11 +184,11 @@ impl MintOperation {
         }
 
         // Get state data
-        let key_balance = format!(
-            "{}_{}",
+        let key_balance = StateKey::balance(
             op_script.to.as_ref().map_or("", |v| v),
-            op_script.tick.as_ref().map_or("", |v| v)
-        );
+            op_script.tick.as_ref().map_or("", |v| v),
+        )
+        .map_key();
         let st_token = state_map
             .state_token_map
             .get(op_script.tick.as_ref().map_or("", |v| v))
@@ -164,26 +202,39 @@ impl MintOperation {
 
         // Calculate minting amount
         let token = st_token.as_ref().unwrap();
-        let amt = token.lim.clone();
-        let max_big: u128 = token.max.parse().unwrap_or(0);
-        let minted_big: u128 = token.minted.parse().unwrap_or(0);
-        let left_big = max_big.saturating_sub(minted_big);
+        // Big integers as in the Go version, supplies may exceed native integer ranges
+        let max_big = parse_big(&token.max);
+        let minted_big = parse_big(&token.minted);
+        let left_big = &max_big - &minted_big;
 
-        if left_big == 0 {
+        if left_big <= BigInt::from(0) {
             op_data.op_accept = -1;
             op_data.op_error = "mint finished".to_string();
             return Ok(());
         }
 
-        let lim_big: u128 = amt.parse().unwrap_or(0);
+        let lim_big = parse_big(&token.lim);
         let final_amt = if lim_big > left_big {
             left_big
         } else {
             lim_big
         };
-        let _final_amt_str = final_amt.to_string();
+        if let Some(error) = super::amount_error("mint", Some(&final_amt.to_string())) {
+            op_data.op_accept = -1;
+            op_data.op_error = error;
+            return Ok(());
+        }
 
-        let new_minted = minted_big + final_amt;
+        // Check mint rate limit
+        let tick = op_script.tick.as_ref().map_or("", |v| v);
+        let st_mint_rate = state_map.state_mint_rate_map.get(tick).cloned().flatten();
+        if !Self::check_mint_rate(tick, op_data.daa_score, state_map) {
+            op_data.op_accept = -1;
+            op_data.op_error = "mint rate limited".to_string();
+            return Ok(());
+        }
+
+        let new_minted = &minted_big + &final_amt;
         let new_minted_str = new_minted.to_string();
 
         // Set pre-operation state
@@ -201,6 +252,14 @@ impl MintOperation {
             st_balance.as_ref(),
             false,
         );
+        if state_map.state_mint_rate_map.contains_key(tick) {
+            op_data
+                .st_before
+                .push(crate::operations::make_st_line_mint_rate(
+                    tick,
+                    st_mint_rate.as_ref(),
+                ));
+        }
 
         // Update Token state
         let mut new_st_token = st_token.unwrap().clone();
@@ -226,8 +285,8 @@ impl MintOperation {
             }
         };
 
-        let balance_big: u128 = new_st_balance.balance.parse().unwrap_or(0);
-        let new_balance = balance_big + final_amt;
+        let balance_big = parse_big(&new_st_balance.balance);
+        let new_balance = &balance_big + &final_amt;
         new_st_balance.balance = new_balance.to_string();
         new_st_balance.op_mod = op_data.op_score;
 
@@ -243,8 +302,8 @@ impl MintOperation {
             n_tick_affc,
         );
 
-        let locked_big: u128 = new_st_balance.locked.parse().unwrap_or(0);
-        let balance_total = new_balance + locked_big;
+        let locked_big = parse_big(&new_st_balance.locked);
+        let balance_total = &new_balance + &locked_big;
         op_data.ss_info.as_mut().unwrap().address_affc =
             crate::operations::append_ss_info_address_affc(
                 &mut op_data.ss_info.as_mut().unwrap().address_affc,
@@ -267,8 +326,21 @@ impl MintOperation {
             Some(&new_st_balance),
             true,
         );
+        if let Some(st_mint_rate) = state_map.state_mint_rate_map.get(tick) {
+            op_data
+                .st_after
+                .push(crate::operations::make_st_line_mint_rate(
+                    tick,
+                    st_mint_rate.as_ref(),
+                ));
+        }
 
         op_data.op_accept = 1;
         Ok(())
     }
 }
+
+/// Parse a decimal amount string, invalid values count as zero
+fn parse_big(value: &str) -> BigInt {
+    BigInt::from_str(value).unwrap_or_else(|_| BigInt::from(0))
+}
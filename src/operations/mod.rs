@@ -1,3 +1,5 @@
+use crate::storage::state_key::StateKeyKind;
+use crate::storage::types::DataScriptType;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -20,6 +22,8 @@ static OP_REGISTERED: Lazy<Mutex<HashMap<String, bool>>> = Lazy::new(|| {
     map.insert("list".to_string(), true);
     map.insert("chown".to_string(), true);
     map.insert("blacklist".to_string(), true);
+    map.insert("update".to_string(), true);
+    map.insert("freeze".to_string(), true);
     Mutex::new(map)
 });
 
@@ -47,40 +51,43 @@ pub mod blacklist;
 pub mod burn;
 pub mod chown;
 pub mod deploy;
+pub mod dust;
+pub mod freeze;
 pub mod handler;
+pub mod invariant;
 pub mod issue;
 pub mod list;
+pub mod metrics;
 pub mod mint;
 pub mod send;
 pub mod transfer;
+pub mod update;
 
 pub use blacklist::BlacklistOperation;
 pub use burn::BurnOperation;
 pub use chown::ChownOperation;
 pub use deploy::DeployOperation;
+pub use freeze::FreezeOperation;
 pub use issue::IssueOperation;
 pub use list::ListOperation;
 pub use mint::MintOperation;
 pub use send::SendOperation;
 pub use transfer::TransferOperation;
+pub use update::UpdateOperation;
 
 /// Check if token is reserved, corresponding to Go version's is_tick_reserved
-pub fn is_tick_reserved(tick: &str) -> bool {
-    crate::config::is_tick_reserved(tick)
+pub fn is_tick_reserved(tick: &str, daa_score: u64) -> bool {
+    get_reserved_tick_address(tick, daa_score).is_some()
 }
 
 /// Check if token is ignored
 pub fn is_tick_ignored(tick: &str) -> bool {
-    let ignored_ticks = [
-        "KASPA", "KASPLX", "KASP", "WKAS", "GIGA", "WBTC", "WETH", "USDT", "USDC", "FDUSD", "USDD",
-        "TUSD", "USDP", "PYUSD", "EURC", "BUSD", "GUSD", "EURT", "XAUT", "TETHER",
-    ];
-    ignored_ticks.contains(&tick)
+    crate::config::is_tick_ignored(tick)
 }
 
 /// Get reserved token address, corresponding to Go version's get_reserved_tick_address
-pub fn get_reserved_tick_address(tick: &str) -> Option<String> {
-    crate::config::get_reserved_tick_address(tick)
+pub fn get_reserved_tick_address(tick: &str, daa_score: u64) -> Option<String> {
+    crate::config::get_reserved_tick_address(tick, daa_score)
 }
 
 /// Apply reserved token list, corresponding to Go version's ApplyTickReserved
@@ -122,6 +129,70 @@ pub fn validate_tick_tx_id(tick: &mut String) -> bool {
     }
 }
 
+/// Apply the `ca` (contract address) of a script. In KRC-20, a token deployed in issue mode has
+/// no ticker and is keyed by the tx_id of its deploy; operations reference such a token by
+/// passing that tx_id as `ca`. A non-empty `ca` is normalized to lowercase and replaces the
+/// tick, a malformed one rejects the script. Whether the token exists is checked when the
+/// operation executes, like for ticks.
+pub fn apply_ca(script: &mut DataScriptType) -> bool {
+    let Some(ca) = script.ca.as_mut().filter(|ca| !ca.is_empty()) else {
+        return true;
+    };
+    if !validate_tx_id(ca) {
+        return false;
+    }
+    script.tick = script.ca.clone();
+    true
+}
+
+/// Error of an operation moving tokens from an address to itself. Self-transfers are rejected
+/// rather than processed as no-ops, so they are recorded without changing any balance.
+pub const OP_ERROR_SELF_TRANSFER: &str = "Cannot transfer to the same address";
+
+/// Error of an operation whose type is not in `enabledOpTypes`, stored unexecuted with op_accept 0
+pub const OP_ERROR_OP_DISABLED: &str = "operation type disabled";
+
+/// Error of an operation whose type is not registered. Rejected with op_accept -1 unless
+/// `allowUnknownOps` is set, which stores it unexecuted with op_accept 0.
+pub const OP_ERROR_UNKNOWN_OP: &str = "unknown operation";
+
+/// Whether the primary script of an operation has an enabled type
+pub fn is_op_enabled(op_data: &crate::storage::types::DataOperationType) -> bool {
+    op_data
+        .op_script
+        .first()
+        .is_none_or(|script| crate::config::is_op_type_enabled(&script.op))
+}
+
+/// Error of a transfer, send or mint of a token its owner has frozen
+pub const OP_ERROR_TOKEN_FROZEN: &str = "token frozen";
+
+/// Whether the token of `tick` in the state map is frozen
+pub fn is_token_frozen(state_map: &crate::storage::types::DataStateMapType, tick: &str) -> bool {
+    matches!(state_map.state_token_map.get(tick), Some(Some(token)) if token.frozen)
+}
+
+/// Error of a mint, transfer, burn or send whose amount is missing, malformed, zero or negative,
+/// each prefixed with the operation so rejections stay distinguishable. None for positive amounts.
+pub fn amount_error(op: &str, amount: Option<&str>) -> Option<String> {
+    use num_bigint::BigInt;
+    use std::str::FromStr;
+
+    let Some(amount) = amount.filter(|amount| !amount.is_empty()) else {
+        return Some(format!("{} amount missing", op));
+    };
+    match BigInt::from_str(amount) {
+        Ok(amount) if amount > BigInt::from(0) => None,
+        Ok(_) => Some(format!("{} amount not positive", op)),
+        Err(_) => Some(format!("{} amount invalid", op)),
+    }
+}
+
+/// Whether a transfer or send script names its sender as the recipient
+pub fn is_self_transfer(script: &DataScriptType) -> bool {
+    script.from.is_some() && script.from == script.to
+}
+
 /// Validate amount, corresponding to Go version's ValidateAmount
 pub fn validate_amount(amount: &mut String) -> bool {
     if amount.is_empty() {
@@ -152,7 +223,7 @@ pub fn validate_amount(amount: &mut String) -> bool {
     // Check if it exceeds the limit (99999999999999999999999999999999)
     let limit_big = BigInt::from_str("99999999999999999999999999999999")
         .expect("Limit should be a valid number");
-    
+
     if amount_big > limit_big {
         return false;
     }
@@ -160,6 +231,43 @@ pub fn validate_amount(amount: &mut String) -> bool {
     true
 }
 
+/// Validate string field sizes against the configured script limits, bounding per-token state
+pub fn validate_field_sizes(script: &DataScriptType) -> anyhow::Result<()> {
+    let limits = crate::config::get_script_limits();
+    let fields = [
+        ("name", script.name.as_deref(), limits.max_name_len),
+        ("from", script.from.as_deref(), limits.max_address_len),
+        ("to", script.to.as_deref(), limits.max_address_len),
+        ("tick", script.tick.as_deref(), limits.max_field_len),
+        ("max", script.max.as_deref(), limits.max_field_len),
+        ("lim", script.lim.as_deref(), limits.max_field_len),
+        ("pre", script.pre.as_deref(), limits.max_field_len),
+        ("dec", script.dec.as_deref(), limits.max_field_len),
+        ("amt", script.amt.as_deref(), limits.max_field_len),
+        ("utxo", script.utxo.as_deref(), limits.max_field_len),
+        ("price", script.price.as_deref(), limits.max_field_len),
+        (
+            "mod_type",
+            Some(script.mod_type.as_str()),
+            limits.max_field_len,
+        ),
+        ("ca", script.ca.as_deref(), limits.max_field_len),
+        ("desc", script.desc.as_deref(), limits.max_desc_len),
+    ];
+    for (field, value, max_len) in fields {
+        if let Some(value) = value
+            && value.len() > max_len
+        {
+            return Err(anyhow::anyhow!(
+                "script field {} exceeds {} bytes",
+                field,
+                max_len
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Validate decimal places, corresponding to Go version's ValidateDec
 pub fn validate_dec(dec: &mut String, default: &str) -> bool {
     if dec.is_empty() {
@@ -195,13 +303,20 @@ pub fn validate_uint(value: &mut String, default: &str) -> bool {
     }
 }
 
+/// Whether a state line belongs to the full key, a bare prefix match would let
+/// "stbalance_addr_TICK" claim the line of "stbalance_addr_TICKS"
+fn is_st_line_for_key(line: &str, key_full: &str) -> bool {
+    line.strip_prefix(key_full)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(','))
+}
+
 /// Generate token state line, corresponding to Go version's MakeStLineToken
 pub fn make_st_line_token(
     key: &str,
     st_token: Option<&crate::storage::types::StateTokenType>,
     is_deploy: bool,
 ) -> String {
-    let mut st_line = format!("sttoken_{}", key);
+    let mut st_line = StateKeyKind::Token.full_key(key);
 
     if let Some(token) = st_token {
         st_line.push(',');
@@ -237,13 +352,13 @@ pub fn append_st_line_token(
     is_deploy: bool,
     is_after: bool,
 ) -> Vec<String> {
-    let key_full = format!("sttoken_{}", key);
+    let key_full = StateKeyKind::Token.full_key(key);
     let mut result = st_line.clone();
 
     // Check if it already exists
     let mut i_exists = None;
     for (i, line) in result.iter().enumerate() {
-        if line.starts_with(&key_full) {
+        if is_st_line_for_key(line, &key_full) {
             i_exists = Some(i);
             break;
         }
@@ -260,12 +375,93 @@ pub fn append_st_line_token(
     result
 }
 
+/// Generate token metadata state line, recording what the update operation can change
+///
+/// Name and description are hex encoded so they never clash with the line separator.
+pub fn make_st_line_token_meta(
+    key: &str,
+    st_token: &crate::storage::types::StateTokenType,
+) -> String {
+    format!(
+        "{},meta,{},{},{},{}",
+        StateKeyKind::Token.full_key(key),
+        st_token.op_mod,
+        st_token.mts_mod,
+        hex::encode(&st_token.name),
+        hex::encode(&st_token.desc)
+    )
+}
+
+/// Parse a token metadata state line back into (tick, op_mod, mts_mod, name, desc)
+pub fn parse_st_line_token_meta(st_line: &str) -> Option<(String, u64, i64, String, String)> {
+    let parts: Vec<&str> = st_line.split(',').collect();
+    if parts.len() != 6 || parts[1] != "meta" {
+        return None;
+    }
+    let tick = parts[0].strip_prefix(StateKeyKind::Token.prefix())?;
+    let name = String::from_utf8(hex::decode(parts[4]).ok()?).ok()?;
+    let desc = String::from_utf8(hex::decode(parts[5]).ok()?).ok()?;
+    Some((
+        tick.to_string(),
+        parts[2].parse().ok()?,
+        parts[3].parse().ok()?,
+        name,
+        desc,
+    ))
+}
+
+/// Generate token frozen state line, recording what the freeze operation can change
+pub fn make_st_line_token_frozen(
+    key: &str,
+    st_token: &crate::storage::types::StateTokenType,
+) -> String {
+    format!(
+        "{},frozen,{},{},{}",
+        StateKeyKind::Token.full_key(key),
+        u8::from(st_token.frozen),
+        st_token.op_mod,
+        st_token.mts_mod
+    )
+}
+
+/// Parse a token frozen state line back into (tick, frozen, op_mod, mts_mod)
+pub fn parse_st_line_token_frozen(st_line: &str) -> Option<(String, bool, u64, i64)> {
+    let parts: Vec<&str> = st_line.split(',').collect();
+    if parts.len() != 5 || parts[1] != "frozen" {
+        return None;
+    }
+    let tick = parts[0].strip_prefix(StateKeyKind::Token.prefix())?;
+    let frozen = match parts[2] {
+        "0" => false,
+        "1" => true,
+        _ => return None,
+    };
+    Some((
+        tick.to_string(),
+        frozen,
+        parts[3].parse().ok()?,
+        parts[4].parse().ok()?,
+    ))
+}
+
+/// Generate mint rate state line, recording the mint rate count a mint changes
+pub fn make_st_line_mint_rate(
+    key: &str,
+    st_mint_rate: Option<&crate::storage::types::MintRateCount>,
+) -> String {
+    let mut st_line = StateKeyKind::MintRate.full_key(key);
+    if let Some(mint_rate) = st_mint_rate {
+        st_line.push_str(&format!(",{},{}", mint_rate.window_start, mint_rate.count));
+    }
+    st_line
+}
+
 /// Generate balance state line, corresponding to Go version's MakeStLineBalance
 pub fn make_st_line_balance(
     key: &str,
     st_balance: Option<&crate::storage::types::StateBalanceType>,
 ) -> String {
-    let mut st_line = format!("stbalance_{}", key);
+    let mut st_line = StateKeyKind::Balance.full_key(key);
 
     if let Some(balance) = st_balance {
         st_line.push(',');
@@ -287,13 +483,13 @@ pub fn append_st_line_balance(
     st_balance: Option<&crate::storage::types::StateBalanceType>,
     is_after: bool,
 ) -> Vec<String> {
-    let key_full = format!("stbalance_{}", key);
+    let key_full = StateKeyKind::Balance.full_key(key);
     let mut result = st_line.clone();
 
     // Check if it already exists
     let mut i_exists = None;
     for (i, line) in result.iter().enumerate() {
-        if line.starts_with(&key_full) {
+        if is_st_line_for_key(line, &key_full) {
             i_exists = Some(i);
             break;
         }
@@ -315,7 +511,7 @@ pub fn make_st_line_market(
     key: &str,
     st_market: Option<&crate::storage::types::StateMarketType>,
 ) -> String {
-    let mut st_line = format!("stmarket_{}", key);
+    let mut st_line = StateKeyKind::Market.full_key(key);
 
     if let Some(market) = st_market {
         st_line.push(',');
@@ -336,13 +532,13 @@ pub fn append_st_line_market(
     st_market: Option<&crate::storage::types::StateMarketType>,
     is_after: bool,
 ) -> Vec<String> {
-    let key_full = format!("stmarket_{}", key);
+    let key_full = StateKeyKind::Market.full_key(key);
     let mut result = st_line.clone();
 
     // Check if it already exists
     let mut i_exists = None;
     for (i, line) in result.iter().enumerate() {
-        if line.starts_with(&key_full) {
+        if is_st_line_for_key(line, &key_full) {
             i_exists = Some(i);
             break;
         }
@@ -364,7 +560,7 @@ pub fn make_st_line_blacklist(
     key: &str,
     st_blacklist: Option<&crate::storage::types::StateBlacklistType>,
 ) -> String {
-    let mut st_line = format!("stblacklist_{}", key);
+    let mut st_line = StateKeyKind::Blacklist.full_key(key);
 
     if let Some(blacklist) = st_blacklist {
         st_line.push(',');
@@ -382,13 +578,13 @@ pub fn append_st_line_blacklist(
     st_blacklist: Option<&crate::storage::types::StateBlacklistType>,
     is_after: bool,
 ) -> Vec<String> {
-    let key_full = format!("stblacklist_{}", key);
+    let key_full = StateKeyKind::Blacklist.full_key(key);
     let mut result = st_line.clone();
 
     // Check if it already exists
     let mut i_exists = None;
     for (i, line) in result.iter().enumerate() {
-        if line.starts_with(&key_full) {
+        if is_st_line_for_key(line, &key_full) {
             i_exists = Some(i);
             break;
         }
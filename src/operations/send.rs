@@ -1,3 +1,4 @@
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::script_builder::ScriptBuilder;
 use anyhow::Result;
@@ -66,6 +67,7 @@ impl SendOperation {
         script.utxo = None;
         script.price = None;
         script.ca = None;
+        script.desc = None;
 
         true
     }
@@ -90,13 +92,13 @@ impl SendOperation {
             .ok_or_else(|| anyhow::anyhow!("Missing amount"))?;
 
         // Check if token exists
-        let token_key = format!("token:{}", tick);
+        let token_key = StateKey::token(tick).map_key();
         if !state_map.state_token_map.contains_key(&token_key) {
             return Err(anyhow::anyhow!("Token {} does not exist", tick));
         }
 
         // Check if sender balance is sufficient
-        let from_balance_key = format!("balance:{}:{}", from, tick);
+        let from_balance_key = StateKey::balance(from, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get(&from_balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -115,7 +117,7 @@ impl SendOperation {
         }
 
         // Check if receiver is in blacklist
-        let blacklist_key = format!("blacklist:{}:{}", to, tick);
+        let blacklist_key = StateKey::blacklist(tick, to).map_key();
         if let Some(blacklist) = state_map.state_blacklist_map.get(&blacklist_key) {
             if let Some(blacklist_data) = blacklist {
                 // Check if in blacklist (judged by reason field)
@@ -157,7 +159,7 @@ impl SendOperation {
         }
 
         // Decrease sender balance
-        let from_balance_key = format!("balance:{}:{}", from, tick);
+        let from_balance_key = StateKey::balance(from, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get_mut(&from_balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -170,7 +172,7 @@ impl SendOperation {
         }
 
         // Increase receiver balance
-        let to_balance_key = format!("balance:{}:{}", to, tick);
+        let to_balance_key = StateKey::balance(to, tick).map_key();
         if let Some(balance) = state_map.state_balance_map.get_mut(&to_balance_key) {
             if let Some(balance_data) = balance {
                 let current_balance = balance_data.balance.parse::<u64>().unwrap_or(0);
@@ -263,13 +265,13 @@ impl SendOperation {
         }
         if let Some(from) = &script.from {
             if let Some(tick) = &script.tick {
-                let key = format!("balance:{}:{}", from, tick);
+                let key = StateKey::balance(from, tick).map_key();
                 state_map.state_balance_map.insert(key, None);
             }
         }
         if let Some(to) = &script.to {
             if let Some(tick) = &script.tick {
-                let key = format!("balance:{}:{}", to, tick);
+                let key = StateKey::balance(to, tick).map_key();
                 state_map.state_balance_map.insert(key, None);
             }
         }
@@ -283,6 +285,35 @@ impl SendOperation {
         _testnet: bool,
     ) -> Result<()> {
         let script = op_data.op_script[index].clone();
-        Self::execute(&script, state_map)
+        if let Some(error) = super::amount_error("send", script.amt.as_deref()) {
+            op_data.op_accept = -1;
+            op_data.op_error = error;
+            return Ok(());
+        }
+        if script
+            .tick
+            .as_deref()
+            .is_some_and(|tick| super::is_token_frozen(state_map, tick))
+        {
+            op_data.op_accept = -1;
+            op_data.op_error = super::OP_ERROR_TOKEN_FROZEN.to_string();
+            return Ok(());
+        }
+        if super::is_self_transfer(&script) {
+            op_data.op_accept = -1;
+            op_data.op_error = super::OP_ERROR_SELF_TRANSFER.to_string();
+            return Ok(());
+        }
+        match Self::execute(&script, state_map) {
+            Ok(_) => {
+                op_data.op_accept = 1;
+                op_data.op_error = "".to_string();
+            }
+            Err(e) => {
+                op_data.op_accept = -1;
+                op_data.op_error = e.to_string();
+            }
+        }
+        Ok(())
     }
 }
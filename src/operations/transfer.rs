@@ -1,4 +1,5 @@
-use crate::operations::{validate_amount, validate_tick_tx_id, validate_tx_id};
+use crate::operations::{apply_ca, validate_amount, validate_tick_tx_id};
+use crate::storage::state_key::StateKey;
 use crate::storage::types::*;
 use crate::utils::address::verify_address;
 use anyhow::Result;
@@ -29,10 +30,8 @@ impl TransferOperation {
         _daa_score: u64,
         _testnet: bool,
     ) -> bool {
-        if let Some(ca) = &mut script.ca {
-            if validate_tx_id(ca) {
-                script.tick = script.ca.clone();
-            }
+        if !apply_ca(script) {
+            return false;
         }
         if script.from.as_ref().map(|s| s.is_empty()).unwrap_or(true)
             || script.to.as_ref().map(|s| s.is_empty()).unwrap_or(true)
@@ -64,19 +63,19 @@ impl TransferOperation {
         }
         if let Some(from) = &op_script.from {
             if let Some(tick) = &op_script.tick {
-                let key_balance_from = format!("{}_{}", from, tick);
+                let key_balance_from = StateKey::balance(from, tick).map_key();
                 state_map.state_balance_map.insert(key_balance_from, None);
             }
         }
         if let Some(to) = &op_script.to {
             if let Some(tick) = &op_script.tick {
-                let key_balance_to = format!("{}_{}", to, tick);
+                let key_balance_to = StateKey::balance(to, tick).map_key();
                 state_map.state_balance_map.insert(key_balance_to, None);
             }
         }
         if let Some(tick) = &op_script.tick {
             if let Some(from) = &op_script.from {
-                let key_blacklist = format!("{}_{}", tick, from);
+                let key_blacklist = StateKey::blacklist(tick, from).map_key();
                 state_map.state_blacklist_map.insert(key_blacklist, None);
             }
         }
@@ -91,6 +90,12 @@ impl TransferOperation {
     ) -> Result<()> {
         let op_script = &mut op_data.op_script[index];
 
+        if let Some(error) = super::amount_error("transfer", op_script.amt.as_deref()) {
+            op_data.op_accept = -1;
+            op_data.op_error = error;
+            return Ok(());
+        }
+
         // Check if token exists
         if let Some(tick) = &op_script.tick {
             let token_result = state_map.state_token_map.get(tick);
@@ -107,10 +112,20 @@ impl TransferOperation {
             }
         }
 
+        if op_script
+            .tick
+            .as_deref()
+            .is_some_and(|tick| super::is_token_frozen(state_map, tick))
+        {
+            op_data.op_accept = -1;
+            op_data.op_error = super::OP_ERROR_TOKEN_FROZEN.to_string();
+            return Ok(());
+        }
+
         // Check blacklist
         if let Some(tick) = &op_script.tick {
             if let Some(from) = &op_script.from {
-                let key_blacklist = format!("{}_{}", tick, from);
+                let key_blacklist = StateKey::blacklist(tick, from).map_key();
                 if state_map
                     .state_blacklist_map
                     .get(&key_blacklist)
@@ -118,16 +133,17 @@ impl TransferOperation {
                     .is_some()
                 {
                     op_data.op_accept = -1;
-                    op_data.op_error = format!("Address '{}' is blacklisted for token '{}'", from, tick);
+                    op_data.op_error =
+                        format!("Address '{}' is blacklisted for token '{}'", from, tick);
                     return Ok(());
                 }
             }
         }
 
         // Validate address
-        if op_script.from == op_script.to {
+        if super::is_self_transfer(op_script) {
             op_data.op_accept = -1;
-            op_data.op_error = "Cannot transfer to the same address".to_string();
+            op_data.op_error = super::OP_ERROR_SELF_TRANSFER.to_string();
             return Ok(());
         }
 
@@ -144,8 +160,8 @@ impl TransferOperation {
         let from = op_script.from.as_ref().unwrap();
         let to = op_script.to.as_ref().unwrap();
 
-        let key_balance_from = format!("{}_{}", from, tick);
-        let key_balance_to = format!("{}_{}", to, tick);
+        let key_balance_from = StateKey::balance(from, tick).map_key();
+        let key_balance_to = StateKey::balance(to, tick).map_key();
         let st_balance_from = state_map
             .state_balance_map
             .get(&key_balance_from)
@@ -184,7 +200,10 @@ impl TransferOperation {
 
         if amt_big > balance_big {
             op_data.op_accept = -1;
-            op_data.op_error = format!("Insufficient balance: required {}, available {}", amt_big, balance_big);
+            op_data.op_error = format!(
+                "Insufficient balance: required {}, available {}",
+                amt_big, balance_big
+            );
             return Ok(());
         } else if amt_big == balance_big && balance_from.locked == "0" {
             n_tick_affc = -1;
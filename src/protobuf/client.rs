@@ -370,13 +370,14 @@ impl KaspaRpcClient {
     pub async fn get_virtual_chain_from_block(
         &mut self,
         start_hash: String,
+        include_accepted_transaction_ids: bool,
     ) -> Result<VirtualChainFromBlockResponse> {
         let request = KaspadRequest {
             id: 19,
             payload: Some(kaspad_request::Payload::GetVirtualChainFromBlockRequest(
                 GetVirtualChainFromBlockRequestMessage {
                     start_hash,
-                    include_accepted_transaction_ids: false,
+                    include_accepted_transaction_ids,
                 },
             )),
         };
@@ -433,5 +434,6 @@ pub struct RpcSyncStatus {
 pub struct VirtualChainFromBlockResponse {
     pub added_chain_block_hashes: Vec<String>,
     pub accepted_transaction_ids: Vec<String>,
+    /// Chain blocks the node reorganized away, highest first
     pub removed_chain_block_hashes: Vec<String>,
 }
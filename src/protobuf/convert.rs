@@ -0,0 +1,77 @@
+use crate::protobuf::protowire::*;
+use crate::storage::types::DataTransactionType;
+use serde_json::{Value, json};
+
+/// Convert a gRPC RpcTransaction into the JSON shape used by ScriptParser::parse_op_data,
+/// field names follow the Go version's protowire JSON encoding
+pub fn rpc_transaction_to_json(tx: &RpcTransaction) -> Value {
+    let inputs: Vec<Value> = tx
+        .inputs
+        .iter()
+        .map(|input| {
+            let previous_outpoint = input.previous_outpoint.as_ref().map(|outpoint| {
+                json!({
+                    "transactionId": outpoint.transaction_id,
+                    "index": outpoint.index,
+                })
+            });
+            json!({
+                "previousOutpoint": previous_outpoint,
+                "signatureScript": input.signature_script,
+                "sequence": input.sequence,
+                "sigOpCount": input.sig_op_count,
+            })
+        })
+        .collect();
+
+    let outputs: Vec<Value> = tx
+        .outputs
+        .iter()
+        .map(|output| {
+            let verbose_data = output.verbose_data.as_ref();
+            json!({
+                "amount": output.amount,
+                "scriptPublicKey": output.script_public_key.as_ref().map(|spk| json!({
+                    "version": spk.version,
+                    "scriptPublicKey": spk.script_public_key,
+                })),
+                "scriptPublicKeyType": verbose_data.map(|v| v.script_public_key_type.clone()),
+                "scriptPublicKeyAddress": verbose_data.map(|v| v.script_public_key_address.clone()),
+            })
+        })
+        .collect();
+
+    let verbose_data = tx.verbose_data.as_ref();
+    json!({
+        "transactionId": verbose_data.map(|v| v.transaction_id.clone()),
+        "hash": verbose_data.map(|v| v.hash.clone()),
+        "blockHash": verbose_data.map(|v| v.block_hash.clone()),
+        "blockTime": verbose_data.map(|v| v.block_time),
+        "version": tx.version,
+        "inputs": inputs,
+        "outputs": outputs,
+        "lockTime": tx.lock_time,
+        "subnetworkId": tx.subnetwork_id,
+        "gas": tx.gas,
+        "payload": tx.payload,
+        "mass": tx.mass,
+    })
+}
+
+/// Build transaction data for the parser from a gRPC RpcTransaction
+pub fn rpc_transaction_to_data(
+    tx: &RpcTransaction,
+    daa_score: u64,
+    block_accept: &str,
+) -> DataTransactionType {
+    DataTransactionType {
+        tx_id: tx
+            .verbose_data
+            .as_ref()
+            .map(|v| v.transaction_id.clone())
+            .unwrap_or_default(),
+        daa_score,
+        block_accept: block_accept.to_string(),
+        data: Some(rpc_transaction_to_json(tx)),
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use protowire::*;
+use tokio_stream::StreamExt;
 use tonic::transport::Channel;
 use tracing::{debug, info, warn};
 
@@ -10,6 +11,7 @@ pub mod protowire {
 
 // Export client module
 pub mod client;
+pub mod convert;
 
 #[derive(Debug, Clone)]
 pub struct ProtobufHandler {
@@ -36,7 +38,8 @@ impl ProtobufHandler {
 
             match &request.payload {
                 Some(protowire::kaspad_request::Payload::GetBlockRequest(req)) => {
-                    self.handle_get_block_request(client, req.clone()).await
+                    self.handle_get_block_request(client, request.id, req.clone())
+                        .await
                 }
                 Some(protowire::kaspad_request::Payload::GetBlockCountRequest(_)) => {
                     self.handle_get_block_count_request(client).await
@@ -73,7 +76,8 @@ impl ProtobufHandler {
                     self.handle_get_current_network_request(client).await
                 }
                 Some(protowire::kaspad_request::Payload::GetBlockDagInfoRequest(_)) => {
-                    self.handle_get_block_dag_info_request(client).await
+                    self.handle_get_block_dag_info_request(client, request.id)
+                        .await
                 }
                 Some(protowire::kaspad_request::Payload::NotifyBlockAddedRequest(req)) => {
                     self.handle_notify_block_added_request(client, req.clone())
@@ -97,8 +101,12 @@ impl ProtobufHandler {
                     self.handle_ping_request(client).await
                 }
                 Some(protowire::kaspad_request::Payload::GetVirtualChainFromBlockRequest(req)) => {
-                    self.handle_get_virtual_chain_from_block_request(client, req.clone())
-                        .await
+                    self.handle_get_virtual_chain_from_block_request(
+                        client,
+                        request.id,
+                        req.clone(),
+                    )
+                    .await
                 }
                 _ => {
                     warn!("Unsupported request type: {:?}", request.payload);
@@ -137,22 +145,29 @@ impl ProtobufHandler {
 
     async fn handle_get_block_request(
         &self,
-        _client: &Channel,
-        _request: protowire::GetBlockRequestMessage,
+        client: &Channel,
+        id: u64,
+        request: protowire::GetBlockRequestMessage,
     ) -> Result<KaspadResponse> {
-        debug!("Handling GetBlock request for hash: {}", _request.hash);
-
-        let response = KaspadResponse {
-            id: 0,
-            payload: Some(protowire::kaspad_response::Payload::GetBlockResponse(
-                protowire::GetBlockResponseMessage {
-                    block: None,
-                    error: None,
-                },
-            )),
-        };
-
-        Ok(response)
+        debug!("Handling GetBlock request for hash: {}", request.hash);
+
+        let response = Self::call(
+            client,
+            KaspadRequest {
+                id,
+                payload: Some(protowire::kaspad_request::Payload::GetBlockRequest(request)),
+            },
+        )
+        .await?;
+        match &response.payload {
+            Some(protowire::kaspad_response::Payload::GetBlockResponse(resp)) => {
+                if let Some(error) = &resp.error {
+                    return Err(anyhow::anyhow!("GetBlock failed: {}", error.message));
+                }
+                Ok(response)
+            }
+            _ => Err(anyhow::anyhow!("Unexpected response type")),
+        }
     }
 
     async fn handle_get_block_count_request(&self, _client: &Channel) -> Result<KaspadResponse> {
@@ -354,31 +369,32 @@ impl ProtobufHandler {
         Ok(response)
     }
 
-    async fn handle_get_block_dag_info_request(&self, _client: &Channel) -> Result<KaspadResponse> {
+    async fn handle_get_block_dag_info_request(
+        &self,
+        client: &Channel,
+        id: u64,
+    ) -> Result<KaspadResponse> {
         debug!("Handling GetBlockDagInfo request");
 
-        let response = KaspadResponse {
-            id: 0,
-            payload: Some(
-                protowire::kaspad_response::Payload::GetBlockDagInfoResponse(
-                    protowire::GetBlockDagInfoResponseMessage {
-                        network_name: "mainnet".to_string(),
-                        block_count: 0,
-                        header_count: 0,
-                        tip_hashes: vec![],
-                        difficulty: 0.0,
-                        past_median_time: 0,
-                        virtual_parent_hashes: vec![],
-                        pruning_point_hash: "".to_string(),
-                        virtual_daa_score: 0,
-                        sink: "".to_string(),
-                        error: None,
-                    },
-                ),
-            ),
-        };
-
-        Ok(response)
+        let response = Self::call(
+            client,
+            KaspadRequest {
+                id,
+                payload: Some(protowire::kaspad_request::Payload::GetBlockDagInfoRequest(
+                    protowire::GetBlockDagInfoRequestMessage {},
+                )),
+            },
+        )
+        .await?;
+        match &response.payload {
+            Some(protowire::kaspad_response::Payload::GetBlockDagInfoResponse(resp)) => {
+                if let Some(error) = &resp.error {
+                    return Err(anyhow::anyhow!("GetBlockDagInfo failed: {}", error.message));
+                }
+                Ok(response)
+            }
+            _ => Err(anyhow::anyhow!("Unexpected response type")),
+        }
     }
 
     async fn handle_notify_block_added_request(
@@ -491,26 +507,49 @@ impl ProtobufHandler {
 
     async fn handle_get_virtual_chain_from_block_request(
         &self,
-        _client: &Channel,
-        _request: protowire::GetVirtualChainFromBlockRequestMessage,
+        client: &Channel,
+        id: u64,
+        request: protowire::GetVirtualChainFromBlockRequestMessage,
     ) -> Result<KaspadResponse> {
-        debug!("Handling GetVirtualChainFromBlock request");
+        debug!(
+            "Handling GetVirtualChainFromBlock request from: {}",
+            request.start_hash
+        );
 
-        let response = KaspadResponse {
-            id: 0,
-            payload: Some(
-                protowire::kaspad_response::Payload::GetVirtualChainFromBlockResponse(
-                    protowire::GetVirtualChainFromBlockResponseMessage {
-                        added_chain_block_hashes: vec![],
-                        removed_chain_block_hashes: vec![],
-                        accepted_transaction_ids: vec![],
-                        error: None,
-                    },
+        let response = Self::call(
+            client,
+            KaspadRequest {
+                id,
+                payload: Some(
+                    protowire::kaspad_request::Payload::GetVirtualChainFromBlockRequest(request),
                 ),
-            ),
-        };
+            },
+        )
+        .await?;
+        match &response.payload {
+            Some(protowire::kaspad_response::Payload::GetVirtualChainFromBlockResponse(resp)) => {
+                if let Some(error) = &resp.error {
+                    return Err(anyhow::anyhow!(
+                        "GetVirtualChainFromBlock failed: {}",
+                        error.message
+                    ));
+                }
+                Ok(response)
+            }
+            _ => Err(anyhow::anyhow!("Unexpected response type")),
+        }
+    }
 
-        Ok(response)
+    /// Send `request` on kaspad's MessageStream and wait for its response. The request side
+    /// stays open until then, as kaspad drops the stream once its client closes it.
+    async fn call(client: &Channel, request: KaspadRequest) -> Result<KaspadResponse> {
+        let mut rpc_client = protowire::rpc_client::RpcClient::new(client.clone());
+        let requests = tokio_stream::once(request).chain(tokio_stream::pending());
+        let mut responses = rpc_client.message_stream(requests).await?.into_inner();
+        responses
+            .message()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Kaspa node closed the stream without a response"))
     }
 
     pub fn init(&self) -> Result<()> {
@@ -0,0 +1,111 @@
+use crate::storage::rocksdb::RocksDBClient;
+use anyhow::Result;
+use rocksdb::{WriteBatch, WriteBatchIterator};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Raw key-value access used by storage logic, implemented by RocksDB and by an in-memory map
+pub trait StorageBackend: Send + Sync {
+    fn get_raw(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    fn put_raw(&self, key: &str, value: &[u8]) -> Result<()>;
+    fn delete_raw(&self, key: &str) -> Result<()>;
+    /// Key-value pairs whose key starts with `prefix`, in key order
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>>;
+    /// Apply all puts and deletes of `batch` atomically
+    fn write_batch(&self, batch: WriteBatch) -> Result<()>;
+}
+
+impl StorageBackend for RocksDBClient {
+    fn get_raw(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        RocksDBClient::get_raw(self, key)
+    }
+
+    fn put_raw(&self, key: &str, value: &[u8]) -> Result<()> {
+        RocksDBClient::put_raw(self, key, value)
+    }
+
+    fn delete_raw(&self, key: &str) -> Result<()> {
+        RocksDBClient::delete_raw(self, key)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        RocksDBClient::scan_prefix(self, prefix)
+    }
+
+    fn write_batch(&self, batch: WriteBatch) -> Result<()> {
+        RocksDBClient::write_batch(self, batch)
+    }
+}
+
+/// In-memory backend for tests, keys are ordered bytewise like RocksDB
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    data: RwLock<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored keys
+    pub fn len(&self) -> usize {
+        self.data.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.read().unwrap().is_empty()
+    }
+}
+
+/// Applies a WriteBatch to the map while holding its write lock
+struct MemoryBatchWriter<'a> {
+    data: &'a mut BTreeMap<String, Vec<u8>>,
+}
+
+impl WriteBatchIterator for MemoryBatchWriter<'_> {
+    fn put(&mut self, key: &[u8], value: &[u8]) {
+        self.data
+            .insert(String::from_utf8_lossy(key).to_string(), value.to_vec());
+    }
+
+    fn delete(&mut self, key: &[u8]) {
+        self.data.remove(String::from_utf8_lossy(key).as_ref());
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get_raw(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.data.read().unwrap().get(key).cloned())
+    }
+
+    fn put_raw(&self, key: &str, value: &[u8]) -> Result<()> {
+        self.data
+            .write()
+            .unwrap()
+            .insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete_raw(&self, key: &str) -> Result<()> {
+        self.data.write().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        Ok(self
+            .data
+            .read()
+            .unwrap()
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+    }
+
+    fn write_batch(&self, batch: WriteBatch) -> Result<()> {
+        let mut data = self.data.write().unwrap();
+        batch.iterate(&mut MemoryBatchWriter { data: &mut data });
+        Ok(())
+    }
+}
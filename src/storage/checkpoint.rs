@@ -0,0 +1,259 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::types::OP_RANGE_BY;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::collections::BTreeSet;
+
+/// Key of the chain head, kept outside the `checkpoint:` prefix
+const CHECKPOINT_HEAD_KEY: &str = "checkpoint_head";
+
+/// Incremental hash feeding a checkpoint
+pub trait CheckpointHasher {
+    fn update(&mut self, data: &[u8]);
+    /// Hex encoded digest
+    fn finalize_hex(self: Box<Self>) -> String;
+}
+
+impl CheckpointHasher for blake3::Hasher {
+    fn update(&mut self, data: &[u8]) {
+        blake3::Hasher::update(self, data);
+    }
+
+    fn finalize_hex(self: Box<Self>) -> String {
+        self.finalize().to_hex().to_string()
+    }
+}
+
+impl CheckpointHasher for sha2::Sha256 {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(self, data);
+    }
+
+    fn finalize_hex(self: Box<Self>) -> String {
+        hex::encode(self.finalize())
+    }
+}
+
+/// Hash algorithm of checkpoints, chosen to match the implementation they are verified against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointHash {
+    #[default]
+    Blake3,
+    Sha256,
+}
+
+impl CheckpointHash {
+    /// Algorithm of checkpoints sealed before it was stored with them
+    fn legacy() -> Self {
+        CheckpointHash::Sha256
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "blake3" => Some(CheckpointHash::Blake3),
+            "sha256" => Some(CheckpointHash::Sha256),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CheckpointHash::Blake3 => "blake3",
+            CheckpointHash::Sha256 => "sha256",
+        }
+    }
+
+    pub fn hasher(&self) -> Box<dyn CheckpointHasher> {
+        match self {
+            CheckpointHash::Blake3 => Box::new(blake3::Hasher::new()),
+            CheckpointHash::Sha256 => Box::new(sha2::Sha256::new()),
+        }
+    }
+}
+
+/// Checkpoint of a completed op_score range, stored under `checkpoint:{range}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeCheckpoint {
+    pub range: u64,
+    /// Previous sealed range, ranges without operations are not part of the chain
+    pub prev_range: Option<u64>,
+    pub checkpoint: String,
+    /// Algorithm the checkpoint was computed with
+    #[serde(default = "CheckpointHash::legacy")]
+    pub hash: CheckpointHash,
+}
+
+/// Last sealed checkpoint and the range still receiving operations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CheckpointHead {
+    last: Option<RangeCheckpoint>,
+    open_range: Option<u64>,
+}
+
+pub fn checkpoint_key(range: u64) -> String {
+    format!("checkpoint:{}", range)
+}
+
+pub fn get_checkpoint(backend: &dyn StorageBackend, range: u64) -> Result<Option<RangeCheckpoint>> {
+    match backend.get_raw(&checkpoint_key(range))? {
+        Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+        None => Ok(None),
+    }
+}
+
+fn get_head(backend: &dyn StorageBackend) -> Result<CheckpointHead> {
+    match backend.get_raw(CHECKPOINT_HEAD_KEY)? {
+        Some(value) => Ok(serde_json::from_slice(&value)?),
+        None => Ok(CheckpointHead::default()),
+    }
+}
+
+/// Nearest range below `range` holding operations, following the checkpoint chain. Ranges above
+/// the last sealed one lead to it.
+pub fn previous_range(backend: &dyn StorageBackend, range: u64) -> Result<Option<u64>> {
+    if let Some(checkpoint) = get_checkpoint(backend, range)? {
+        return Ok(checkpoint.prev_range);
+    }
+    let mut prev = get_head(backend)?.last;
+    while let Some(checkpoint) = prev {
+        if checkpoint.range < range {
+            return Ok(Some(checkpoint.range));
+        }
+        prev = match checkpoint.prev_range {
+            Some(prev_range) => get_checkpoint(backend, prev_range)?,
+            None => None,
+        };
+    }
+    Ok(None)
+}
+
+/// Stored oplist entries of a range, ordered by op_score
+pub fn range_entries(backend: &dyn StorageBackend, range: u64) -> Result<Vec<(u64, Vec<u8>)>> {
+    let prefix = format!("oplist:{}:", range);
+    let mut entries: Vec<(u64, Vec<u8>)> = backend
+        .scan_prefix(&prefix)?
+        .into_iter()
+        .filter_map(|(key, value)| {
+            key[prefix.len()..]
+                .parse::<u64>()
+                .ok()
+                .map(|op_score| (op_score, value))
+        })
+        .collect();
+    entries.sort_by_key(|(op_score, _)| *op_score);
+    Ok(entries)
+}
+
+/// Hash a range's oplist entries chained from the previous range's checkpoint, with the
+/// configured algorithm
+pub fn compute_checkpoint(prev_checkpoint: &str, range: u64, entries: &[(u64, Vec<u8>)]) -> String {
+    compute_checkpoint_with(
+        crate::config::get_checkpoint_hash(),
+        prev_checkpoint,
+        range,
+        entries,
+    )
+}
+
+/// Hash a range's oplist entries chained from the previous range's checkpoint
+pub fn compute_checkpoint_with(
+    hash: CheckpointHash,
+    prev_checkpoint: &str,
+    range: u64,
+    entries: &[(u64, Vec<u8>)],
+) -> String {
+    let mut hasher = hash.hasher();
+    hasher.update(prev_checkpoint.as_bytes());
+    hasher.update(&range.to_be_bytes());
+    for (op_score, value) in entries {
+        hasher.update(&op_score.to_be_bytes());
+        hasher.update(&(value.len() as u64).to_be_bytes());
+        hasher.update(value);
+    }
+    hasher.finalize_hex()
+}
+
+/// Seal every range below the newest range with operations, called once their oplist entries are saved.
+/// Returns the checkpoints sealed by this call.
+pub fn seal_completed_ranges(
+    backend: &dyn StorageBackend,
+    op_score_list: &[u64],
+) -> Result<Vec<RangeCheckpoint>> {
+    let mut head = get_head(backend)?;
+    let mut ranges: BTreeSet<u64> = op_score_list
+        .iter()
+        .map(|op_score| op_score / OP_RANGE_BY)
+        .collect();
+    ranges.extend(head.open_range);
+    if let Some(last) = &head.last {
+        ranges.retain(|range| *range > last.range);
+    }
+    let open_range = match ranges.last() {
+        Some(&range) => range,
+        None => return Ok(Vec::new()),
+    };
+
+    let hash = crate::config::get_checkpoint_hash();
+    let mut batch = WriteBatch::default();
+    let mut sealed = Vec::new();
+    for &range in ranges.range(..open_range) {
+        let entries = range_entries(backend, range)?;
+        if entries.is_empty() {
+            continue;
+        }
+        let prev = head.last.as_ref();
+        let checkpoint = RangeCheckpoint {
+            range,
+            prev_range: prev.map(|prev| prev.range),
+            checkpoint: compute_checkpoint_with(
+                hash,
+                prev.map(|prev| prev.checkpoint.as_str()).unwrap_or(""),
+                range,
+                &entries,
+            ),
+            hash,
+        };
+        batch.put(
+            checkpoint_key(range).as_bytes(),
+            serde_json::to_vec(&checkpoint)?,
+        );
+        head.last = Some(checkpoint.clone());
+        sealed.push(checkpoint);
+    }
+
+    head.open_range = Some(open_range);
+    batch.put(CHECKPOINT_HEAD_KEY.as_bytes(), serde_json::to_vec(&head)?);
+    backend.write_batch(batch)?;
+    Ok(sealed)
+}
+
+/// Drop checkpoints of ranges at or after `op_score_start` when their operations are rolled back,
+/// they are sealed again once the ranges are replayed
+pub fn unseal_from_op_score(backend: &dyn StorageBackend, op_score_start: u64) -> Result<()> {
+    let range_start = op_score_start / OP_RANGE_BY;
+    let mut head = get_head(backend)?;
+    let mut batch = WriteBatch::default();
+
+    while let Some(last) = head.last.take() {
+        if last.range < range_start {
+            head.last = Some(last);
+            break;
+        }
+        batch.delete(checkpoint_key(last.range).as_bytes());
+        head.last = match last.prev_range {
+            Some(prev_range) => get_checkpoint(backend, prev_range)?,
+            None => None,
+        };
+    }
+
+    head.open_range = Some(
+        head.open_range
+            .map_or(range_start, |open_range| open_range.min(range_start)),
+    );
+    batch.put(CHECKPOINT_HEAD_KEY.as_bytes(), serde_json::to_vec(&head)?);
+    backend.write_batch(batch)?;
+    Ok(())
+}
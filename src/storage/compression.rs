@@ -0,0 +1,64 @@
+use crate::config::types::ReplicationConfig;
+use anyhow::Result;
+
+/// Codec applied to values shipped to replicas. Encoded values start with a one byte tag,
+/// so replicas decode them no matter which codec was configured when they were written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationCodec {
+    None,
+    Zstd,
+    Lz4,
+    Snappy,
+}
+
+impl ReplicationCodec {
+    /// Codec selected by the replication config, None while compression is disabled
+    pub fn from_config(config: &ReplicationConfig) -> Result<Self> {
+        if !config.enable_compression {
+            return Ok(Self::None);
+        }
+        match config.compression_codec.as_str() {
+            "zstd" => Ok(Self::Zstd),
+            "lz4" => Ok(Self::Lz4),
+            "snappy" => Ok(Self::Snappy),
+            codec => Err(anyhow::anyhow!("Unsupported replication codec: {}", codec)),
+        }
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Zstd => 1,
+            Self::Lz4 => 2,
+            Self::Snappy => 3,
+        }
+    }
+
+    /// Compress a value for replication, `level` only applies to zstd
+    pub fn encode(self, value: &[u8], level: u8) -> Result<Vec<u8>> {
+        let payload = match self {
+            Self::None => value.to_vec(),
+            Self::Zstd => zstd::bulk::compress(value, level as i32)?,
+            Self::Lz4 => lz4_flex::compress_prepend_size(value),
+            Self::Snappy => snap::raw::Encoder::new().compress_vec(value)?,
+        };
+        let mut encoded = Vec::with_capacity(payload.len() + 1);
+        encoded.push(self.tag());
+        encoded.extend_from_slice(&payload);
+        Ok(encoded)
+    }
+}
+
+/// Restore a value written by `ReplicationCodec::encode`
+pub fn decode_replicated(encoded: &[u8]) -> Result<Vec<u8>> {
+    let (tag, payload) = encoded
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("Empty replicated value"))?;
+    match *tag {
+        0 => Ok(payload.to_vec()),
+        1 => Ok(zstd::stream::decode_all(payload)?),
+        2 => Ok(lz4_flex::decompress_size_prepended(payload)?),
+        3 => Ok(snap::raw::Decoder::new().decompress_vec(payload)?),
+        tag => Err(anyhow::anyhow!("Unknown replication codec tag: {}", tag)),
+    }
+}
@@ -0,0 +1,85 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::types::DataOperationType;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEAD_LETTER_PREFIX: &str = "deadletter:";
+
+/// Batch that was executed in memory but failed to persist, stored under
+/// `deadletter:{op_score_start}:{op_score_end}` so retries of the same batch share one entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterBatch {
+    pub op_score_start: u64,
+    pub op_score_end: u64,
+    pub daa_score_start: u64,
+    pub daa_score_end: u64,
+    pub len_operation: usize,
+    pub error: String,
+    /// Number of times persisting this batch failed
+    pub attempts: u64,
+    pub mts_first: u64,
+    pub mts_last: u64,
+}
+
+fn dead_letter_key(op_score_start: u64, op_score_end: u64) -> String {
+    // Zero padded so entries scan in op_score order
+    format!(
+        "{}{:020}:{:020}",
+        DEAD_LETTER_PREFIX, op_score_start, op_score_end
+    )
+}
+
+/// Record a batch whose persistence failed, counting repeated failures of the same batch
+pub fn record_failed_batch(
+    backend: &dyn StorageBackend,
+    op_data_list: &[DataOperationType],
+    daa_score_start: u64,
+    daa_score_end: u64,
+    error: &anyhow::Error,
+) -> Result<DeadLetterBatch> {
+    let op_score_start = op_data_list.first().map_or(0, |op_data| op_data.op_score);
+    let op_score_end = op_data_list.last().map_or(0, |op_data| op_data.op_score);
+    let key = dead_letter_key(op_score_start, op_score_end);
+    let mts_now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    let entry = match backend.get_raw(&key)? {
+        Some(value) => {
+            let previous: DeadLetterBatch = serde_json::from_slice(&value)?;
+            DeadLetterBatch {
+                error: error.to_string(),
+                attempts: previous.attempts + 1,
+                mts_last: mts_now,
+                ..previous
+            }
+        }
+        None => DeadLetterBatch {
+            op_score_start,
+            op_score_end,
+            daa_score_start,
+            daa_score_end,
+            len_operation: op_data_list.len(),
+            error: error.to_string(),
+            attempts: 1,
+            mts_first: mts_now,
+            mts_last: mts_now,
+        },
+    };
+    backend.put_raw(&key, &serde_json::to_vec(&entry)?)?;
+    Ok(entry)
+}
+
+/// Recorded dead-letter batches ordered by op_score
+pub fn list_dead_letters(backend: &dyn StorageBackend) -> Result<Vec<DeadLetterBatch>> {
+    let mut entries = backend
+        .scan_prefix(DEAD_LETTER_PREFIX)?
+        .into_iter()
+        .map(|(_, value)| serde_json::from_slice(&value).map_err(anyhow::Error::from))
+        .collect::<Result<Vec<DeadLetterBatch>>>()?;
+    entries.sort_by_key(|entry| (entry.op_score_start, entry.op_score_end));
+    Ok(entries)
+}
@@ -1,5 +1,7 @@
 use crate::config::types::{DistributedConfig, ShardConfig};
+use crate::storage::compression::{ReplicationCodec, decode_replicated};
 use crate::storage::rocksdb::RocksDBClient;
+use crate::storage::state_key::StateKey;
 use crate::storage::types::StorageOperation;
 use anyhow::Result;
 use std::collections::HashMap;
@@ -30,6 +32,7 @@ impl DistributedStorage {
         // Create RocksDB configuration
         let _rocks_config = crate::config::types::RocksConfig {
             path: config.node.data_dir.clone(),
+            ..Default::default()
         };
 
         let mut shards = Vec::new();
@@ -37,7 +40,10 @@ impl DistributedStorage {
         // Create RocksDB client for each shard
         for i in 0..config.node.shard_count {
             let shard_path = format!("{}/shard_{}", config.node.data_dir, i);
-            let shard_config = crate::config::types::RocksConfig { path: shard_path };
+            let shard_config = crate::config::types::RocksConfig {
+                path: shard_path,
+                ..Default::default()
+            };
 
             let shard = Arc::new(RocksDBClient::new(shard_config)?);
             shards.push(shard);
@@ -214,10 +220,21 @@ impl DistributedStorage {
         }
     }
 
-    /// Distributed get operation
+    /// Distributed get operation, a value missing from the primary shard is read from a replica
     pub async fn distributed_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
         let shard = self.get_shard_for_key(key).await?;
-        shard.get_raw(key)
+        let primary = shard.get_raw(key);
+        if let Ok(Some(value)) = primary {
+            return Ok(Some(value));
+        }
+
+        // Replicas hold the value encoded with the replication codec
+        for shard_id in self.replica_shard_ids(key).await {
+            if let Ok(Some(encoded)) = self.shards[shard_id].get_raw(key) {
+                return decode_replicated(&encoded).map(Some);
+            }
+        }
+        primary
     }
 
     /// Distributed batch operations
@@ -255,67 +272,61 @@ impl DistributedStorage {
         Ok(())
     }
 
+    /// Shards holding replicas of `key`, the primary shard of the key is never one of them
+    pub async fn replica_shard_ids(&self, key: &str) -> Vec<usize> {
+        let hash_ring = self.hash_ring.read().await;
+        if hash_ring.is_empty() {
+            return Vec::new();
+        }
+        let ring_size = hash_ring.len();
+        let primary_index = (self.calculate_hash(key) % ring_size as u64) as usize;
+        let primary_shard_id = hash_ring[primary_index]
+            .split('_')
+            .nth(1)
+            .and_then(|shard_id| shard_id.parse::<usize>().ok());
+
+        let mut shard_ids = Vec::new();
+        for i in 1..self.config.node.replication_factor {
+            let replica_index = (primary_index + i as usize) % ring_size;
+            let shard_id = replica_index / self.config.hash_ring.virtual_nodes as usize;
+            if shard_id < self.shards.len()
+                && Some(shard_id) != primary_shard_id
+                && !shard_ids.contains(&shard_id)
+            {
+                shard_ids.push(shard_id);
+            }
+        }
+        shard_ids
+    }
+
     /// Data replication
     async fn replicate_data(&self, key: &str, value: &[u8]) -> Result<()> {
-        let hash = self.calculate_hash(key);
-        let ring_size = self.hash_ring.read().await.len() as u64;
-        let primary_index = (hash % ring_size) as usize;
-        let replication_factor = self.config.node.replication_factor;
+        let shard_ids = self.replica_shard_ids(key).await;
+
+        // Replicas receive the value encoded with the configured codec
+        let encoded = ReplicationCodec::from_config(&self.config.replication)?
+            .encode(value, self.config.replication.compression_level)?;
 
         // Execute replication based on replication strategy
         match self.config.replication.strategy.as_str() {
             "sync" => {
                 // Synchronous replication
-                for i in 1..replication_factor {
-                    let replica_index = (primary_index + i as usize) % ring_size as usize;
-                    let shard_id = replica_index / self.config.hash_ring.virtual_nodes as usize;
-
-                    if shard_id < self.shards.len() {
-                        if let Err(e) = self.shards[shard_id].put_raw(key, value) {
-                            warn!("Failed to replicate to shard {}: {}", shard_id, e);
-                            return Err(e);
-                        }
+                for shard_id in shard_ids {
+                    if let Err(e) = self.shards[shard_id].put_raw(key, &encoded) {
+                        warn!("Failed to replicate to shard {}: {}", shard_id, e);
+                        return Err(e);
                     }
                 }
             }
-            "async" => {
-                // Asynchronous replication
-                let key = key.to_string();
-                let value = value.to_vec();
-                let shards = self.shards.clone();
-                let config = self.config.clone();
-                let _hash_ring = self.hash_ring.clone();
-
-                tokio::spawn(async move {
-                    for i in 1..replication_factor {
-                        let replica_index = (primary_index + i as usize) % ring_size as usize;
-                        let shard_id = replica_index / config.hash_ring.virtual_nodes as usize;
-
-                        if shard_id < shards.len() {
-                            if let Err(e) = shards[shard_id].put_raw(&key, &value) {
-                                warn!("Failed to replicate to shard {}: {}", shard_id, e);
-                            }
-                        }
-                    }
-                });
-            }
             _ => {
-                // Default asynchronous replication
+                // Asynchronous replication, also the default
                 let key = key.to_string();
-                let value = value.to_vec();
                 let shards = self.shards.clone();
-                let config = self.config.clone();
-                let _hash_ring = self.hash_ring.clone();
 
                 tokio::spawn(async move {
-                    for i in 1..replication_factor {
-                        let replica_index = (primary_index + i as usize) % ring_size as usize;
-                        let shard_id = replica_index / config.hash_ring.virtual_nodes as usize;
-
-                        if shard_id < shards.len() {
-                            if let Err(e) = shards[shard_id].put_raw(&key, &value) {
-                                warn!("Failed to replicate to shard {}: {}", shard_id, e);
-                            }
+                    for shard_id in shard_ids {
+                        if let Err(e) = shards[shard_id].put_raw(&key, &encoded) {
+                            warn!("Failed to replicate to shard {}: {}", shard_id, e);
                         }
                     }
                 });
@@ -331,7 +342,9 @@ impl DistributedStorage {
             StorageOperation::InsertVSPC(data) => data.block_hash.clone(),
             StorageOperation::InsertOperation(data) => data.tx_hash.clone(),
             StorageOperation::UpdateToken(data) => data.tick.clone(),
-            StorageOperation::UpdateBalance(data) => format!("{}_{}", data.address, data.tick),
+            StorageOperation::UpdateBalance(data) => {
+                StateKey::balance(&data.address, &data.tick).map_key()
+            }
             StorageOperation::UpdateMarket(data) => data.tick.clone(),
             StorageOperation::InsertBlacklist(data) => data.tick.clone(),
             StorageOperation::InsertReservedToken(data) => data.tick.clone(),
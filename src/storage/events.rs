@@ -0,0 +1,100 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::types::*;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+
+const EVENT_PREFIX: &str = "events:";
+const EVENT_SEQ_KEY: &str = "eventseq";
+
+/// Effect of one scan step on storage, replayed by warm standby followers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ScanEvent {
+    /// A scanned batch: its operations and state, plus the runtime entries it appended
+    #[serde(rename_all = "camelCase")]
+    Batch {
+        op_data_list: Vec<DataOperationType>,
+        state_map: Box<DataStateMapType>,
+        vspc_list: Vec<DataVspcType>,
+        rollback: Box<DataRollbackType>,
+        synced: bool,
+    },
+    /// A reorg: the last batch was undone if `undone`, and the runtime VSPC list replaced
+    #[serde(rename_all = "camelCase")]
+    Rollback {
+        undone: bool,
+        vspc_list: Vec<DataVspcType>,
+    },
+}
+
+/// Event log entry stored under `events:{seq}`, seq increases by one per event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventEntry {
+    pub seq: u64,
+    /// op_score_last of the leader before and after the event
+    pub op_score_before: u64,
+    pub op_score_after: u64,
+    pub event: ScanEvent,
+}
+
+fn event_key(seq: u64) -> String {
+    // Zero padded so entries scan in seq order
+    format!("{}{:020}", EVENT_PREFIX, seq)
+}
+
+/// Seq of the last appended event, 0 before the first one
+pub fn last_event_seq(backend: &dyn StorageBackend) -> Result<u64> {
+    match backend.get_raw(EVENT_SEQ_KEY)? {
+        Some(value) => Ok(String::from_utf8_lossy(&value).parse()?),
+        None => Ok(0),
+    }
+}
+
+/// Append an event, dropping the ones older than the last `retention` events
+pub fn append_event(
+    backend: &dyn StorageBackend,
+    event: ScanEvent,
+    op_score_before: u64,
+    op_score_after: u64,
+    retention: u64,
+) -> Result<EventEntry> {
+    let entry = EventEntry {
+        seq: last_event_seq(backend)? + 1,
+        op_score_before,
+        op_score_after,
+        event,
+    };
+    let mut batch = WriteBatch::default();
+    batch.put(event_key(entry.seq).as_bytes(), serde_json::to_vec(&entry)?);
+    batch.put(EVENT_SEQ_KEY.as_bytes(), entry.seq.to_string().as_bytes());
+    if entry.seq > retention {
+        batch.delete(event_key(entry.seq - retention).as_bytes());
+    }
+    backend.write_batch(batch)?;
+    Ok(entry)
+}
+
+/// Up to `limit` events following `after_seq`, in seq order
+pub fn list_events(
+    backend: &dyn StorageBackend,
+    after_seq: u64,
+    limit: usize,
+) -> Result<Vec<EventEntry>> {
+    let mut entries = Vec::new();
+    for seq in (after_seq + 1..).take(limit) {
+        match backend.get_raw(&event_key(seq))? {
+            Some(value) => entries.push(serde_json::from_slice(&value)?),
+            None => break,
+        }
+    }
+    // Followers too far behind can not catch up from the log anymore
+    if entries.is_empty() && limit > 0 && last_event_seq(backend)? > after_seq {
+        return Err(anyhow::anyhow!(
+            "Event {} is no longer retained",
+            after_seq + 1
+        ));
+    }
+    Ok(entries)
+}
@@ -0,0 +1,128 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::state_key::{StateKey, StateKeyKind};
+use crate::storage::types::{DataStateMapType, StateBalanceType};
+use crate::storage::versioned;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+use std::collections::{BTreeSet, HashMap};
+
+const HOLDER_COUNT_PREFIX: &str = "stat_holders_";
+
+fn holder_count_key(tick: &str) -> String {
+    format!("{}{}", HOLDER_COUNT_PREFIX, tick)
+}
+
+/// An address holds a tick while its unlocked or locked amount is positive
+fn is_holder(balance: Option<&StateBalanceType>) -> bool {
+    let positive = |amount: &str| amount.parse::<u128>().unwrap_or(0) > 0;
+    balance.is_some_and(|balance| positive(&balance.balance) || positive(&balance.locked))
+}
+
+/// Stage the holder count changes of the balance writes in `state_map`. Each balance is compared
+/// with the stored one, so the count grows when a balance becomes positive and shrinks when it
+/// drops to zero. A rollback writes the balances back and reverses the changes the same way.
+pub fn stage_balances(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    state_map: &DataStateMapType,
+) -> Result<()> {
+    let mut delta_map: HashMap<String, i64> = HashMap::new();
+    for (map_key, balance) in &state_map.state_balance_map {
+        let Some(key) = StateKey::from_map_key(StateKeyKind::Balance, map_key) else {
+            continue;
+        };
+        let stored = backend
+            .get_raw(&key.encode())?
+            .map(|value| versioned::decode::<StateBalanceType>(&value))
+            .transpose()?;
+        let delta = match (is_holder(stored.as_ref()), is_holder(balance.as_ref())) {
+            (false, true) => 1,
+            (true, false) => -1,
+            _ => continue,
+        };
+        *delta_map.entry(key.tick().to_string()).or_default() += delta;
+    }
+    for (tick, delta) in delta_map {
+        if delta == 0 {
+            continue;
+        }
+        let count = get_holder_count(backend, &tick)?.saturating_add_signed(delta);
+        batch.put(
+            holder_count_key(&tick).as_bytes(),
+            count.to_string().as_bytes(),
+        );
+    }
+    Ok(())
+}
+
+/// Number of addresses holding a positive amount of a tick
+pub fn get_holder_count(backend: &dyn StorageBackend, tick: &str) -> Result<u64> {
+    match backend.get_raw(&holder_count_key(tick))? {
+        Some(value) => Ok(String::from_utf8(value)?.parse()?),
+        None => Ok(0),
+    }
+}
+
+/// Cached holder count of a tick that did not match its balances
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HolderCountCorrection {
+    pub tick: String,
+    pub cached: u64,
+    pub actual: u64,
+}
+
+/// Cached holder counts of every tick
+fn cached_holder_counts(backend: &dyn StorageBackend) -> Result<HashMap<String, u64>> {
+    let mut counts = HashMap::new();
+    for (key, value) in backend.scan_prefix(HOLDER_COUNT_PREFIX)? {
+        let tick = key[HOLDER_COUNT_PREFIX.len()..].to_string();
+        counts.insert(tick, String::from_utf8(value)?.parse()?);
+    }
+    Ok(counts)
+}
+
+/// Holder counts of every tick, recounted from all stored balances
+pub fn count_holders(backend: &dyn StorageBackend) -> Result<HashMap<String, u64>> {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for (key, value) in backend.scan_prefix(StateKeyKind::Balance.prefix())? {
+        let Some(state_key) = StateKey::decode(&key) else {
+            continue;
+        };
+        let balance: StateBalanceType = versioned::decode(&value)?;
+        if is_holder(Some(&balance)) {
+            *counts.entry(state_key.tick().to_string()).or_default() += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Recount the holders of every tick and overwrite the cached counts that drifted, returning
+/// the corrections in tick order. A tick whose cached count changed during the scan is left to
+/// the next pass, as its balances may have been read half way through a batch.
+pub fn reconcile_holder_counts(backend: &dyn StorageBackend) -> Result<Vec<HolderCountCorrection>> {
+    let cached_counts = cached_holder_counts(backend)?;
+    let actual_counts = count_holders(backend)?;
+    let ticks: BTreeSet<&String> = cached_counts.keys().chain(actual_counts.keys()).collect();
+    let mut batch = WriteBatch::default();
+    let mut corrections = Vec::new();
+    for tick in ticks {
+        let cached = cached_counts.get(tick).copied().unwrap_or(0);
+        let actual = actual_counts.get(tick).copied().unwrap_or(0);
+        if cached == actual || get_holder_count(backend, tick)? != cached {
+            continue;
+        }
+        batch.put(
+            holder_count_key(tick).as_bytes(),
+            actual.to_string().as_bytes(),
+        );
+        corrections.push(HolderCountCorrection {
+            tick: tick.clone(),
+            cached,
+            actual,
+        });
+    }
+    if !corrections.is_empty() {
+        backend.write_batch(batch)?;
+    }
+    Ok(corrections)
+}
@@ -1,10 +1,28 @@
+pub mod backend;
+pub mod checkpoint;
+pub mod compression;
+pub mod deadletter;
 pub mod distributed;
+pub mod events;
+pub mod holders;
+pub mod opscore;
+pub mod prune;
+pub mod repair;
 pub mod rocksdb;
 pub mod runtime;
 pub mod state;
+pub mod state_key;
+pub mod stats;
+pub mod supply;
+pub mod tickops;
+pub mod tickstats;
+pub mod tokenindex;
 pub mod types;
+pub mod versioned;
+pub mod vspcarchive;
 
 use crate::config::types::{DistributedConfig, RocksConfig};
+use crate::explorer::RollbackManager;
 use crate::operations::handler::OperationManager;
 use crate::storage::distributed::DistributedStorage;
 use crate::storage::rocksdb::RocksDBClient;
@@ -13,7 +31,8 @@ use crate::storage::state::StateManager;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tracing::{error, info};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
 #[derive(Clone)]
 pub struct StorageManager {
@@ -127,6 +146,7 @@ impl StorageManager {
         // Create default RocksDB configuration for distributed mode
         let rocks_config = RocksConfig {
             path: "./data/standalone".to_string(),
+            ..Default::default()
         };
         let rocksdb = Arc::new(rocksdb::RocksDBClient::new(rocks_config)?);
         let state = Arc::new(state::StateManager::new(Arc::clone(&rocksdb))?);
@@ -141,25 +161,23 @@ impl StorageManager {
         })
     }
 
-    /// Create temporary instance to avoid circular references
-    pub fn new_dummy() -> Self {
-        let rocks_config = RocksConfig {
-            path: "./data/dummy".to_string(),
-        };
-        let rocksdb = rocksdb::RocksDBClient::new(rocks_config).unwrap();
-        let state = state::StateManager::new(Arc::new(rocksdb.clone())).unwrap();
-        let runtime = runtime::RuntimeManager::new(Arc::new(rocksdb.clone())).unwrap();
+    /// Create storage backed by an in-memory RocksDB, never touches the filesystem
+    pub fn new_in_memory() -> Result<Self> {
+        let rocksdb = Arc::new(rocksdb::RocksDBClient::new_in_memory()?);
+        let state = Arc::new(state::StateManager::new(Arc::clone(&rocksdb))?);
+        let runtime = Arc::new(runtime::RuntimeManager::new(Arc::clone(&rocksdb))?);
 
-        Self {
+        Ok(Self {
             distributed: None,
-            rocksdb,
-            state,
-            runtime,
+            rocksdb: rocksdb.as_ref().clone(),
+            state: state.as_ref().clone(),
+            runtime: runtime.as_ref().clone(),
             operation_manager: Arc::new(Mutex::new(None)),
-        }
+        })
     }
 
-    /// Initialize storage, corresponding to Go version's initialization logic
+    /// Initialize storage, corresponding to Go version's initialization logic.
+    /// Idempotent: only missing structures are created, existing state and checkpoints are kept.
     pub async fn init(&mut self) -> Result<()> {
         // Initialize RocksDB
         self.rocksdb.init()?;
@@ -170,6 +188,11 @@ impl StorageManager {
         // Initialize runtime manager
         self.runtime.init()?;
 
+        let indexed = tokenindex::rebuild_token_index(&self.rocksdb)?;
+        if indexed > 0 {
+            info!("Indexed {} tokens by deploy DAA score", indexed);
+        }
+
         info!("Storage initialization completed");
         Ok(())
     }
@@ -209,6 +232,12 @@ impl StorageManager {
         Ok(())
     }
 
+    /// Shutdown storage, giving up after `grace`. Returns false when it did not complete in time
+    pub async fn shutdown_within(self: &Arc<Self>, grace: Duration) -> Result<bool> {
+        let storage = Arc::clone(self);
+        crate::utils::shutdown::run_within(async move { storage.shutdown().await }, grace).await
+    }
+
     /// Get distributed storage instance
     pub fn get_distributed(&self) -> Result<Arc<DistributedStorage>> {
         self.distributed
@@ -250,14 +279,176 @@ impl StorageManager {
         }
     }
 
+    /// Start background disk space monitor for the RocksDB volume
+    pub fn start_disk_monitor(&self) -> tokio::task::JoinHandle<()> {
+        let rocksdb = self.rocksdb.clone();
+        let interval = std::time::Duration::from_secs(rocksdb.get_config().disk_check_interval);
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = rocksdb.check_disk_space() {
+                    error!("RocksDB disk space check failed: {}", e);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    /// Start the background task recounting holder counts every `holderReconcileInterval`
+    /// seconds, None when disabled. Passes are skipped while writes are paused on a stall, so
+    /// the full balance scan never competes with a scanner waiting for compaction.
+    pub fn start_holder_reconciler(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let interval = self.rocksdb.get_config().holder_reconcile_interval;
+        if interval == 0 {
+            return None;
+        }
+        let interval = std::time::Duration::from_secs(interval);
+        let rocksdb = self.rocksdb.clone();
+        Some(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if rocksdb.is_write_paused() {
+                    debug!("Writes paused, skipping holder count reconciliation");
+                    continue;
+                }
+                let backend = rocksdb.clone();
+                let result =
+                    tokio::task::spawn_blocking(move || holders::reconcile_holder_counts(&backend))
+                        .await;
+                match result {
+                    Ok(Ok(corrections)) => {
+                        for correction in corrections {
+                            warn!(
+                                "Corrected holder count of {} from {} to {}",
+                                correction.tick, correction.cached, correction.actual
+                            );
+                        }
+                    }
+                    Ok(Err(e)) => error!("Holder count reconciliation failed: {}", e),
+                    Err(e) => error!("Holder count reconciliation task failed: {}", e),
+                }
+            }
+        }))
+    }
+
+    /// Report the keys a prune at the given horizon DAA score would remove
+    pub fn prune_dry_run(&self, horizon_daa_score: u64) -> Result<prune::PruneReport> {
+        prune::prune_dry_run(&self.rocksdb, horizon_daa_score)
+    }
+
+    /// Remove operation and VSPC history older than the given horizon DAA score
+    pub fn prune(&self, horizon_daa_score: u64) -> Result<prune::PruneReport> {
+        prune::prune(&self.rocksdb, horizon_daa_score)
+    }
+
+    /// Verify that the persisted oplist is strictly ordered by op_score, reporting the first anomaly
+    pub fn verify_op_score_ordering(&self) -> Result<opscore::OpScoreReport> {
+        opscore::verify_ordering(&self.rocksdb)
+    }
+
+    /// Apply leader events on a standby follower, corresponding to the leader's scan steps.
+    /// `from_op_score` is the op_score the follower reached, events must continue from it.
+    /// Returns the op_score after the last applied event.
+    pub async fn apply_follower_events(
+        self: &Arc<Self>,
+        entries: &[events::EventEntry],
+        from_op_score: u64,
+    ) -> Result<u64> {
+        let rollback_manager = RollbackManager::new(Arc::clone(self))?;
+        let (mut seq, _) = self.runtime.get_runtime_follower_cursor().await?;
+        let mut op_score = from_op_score;
+        let mut vspc_list = self.runtime.get_runtime_vspc_last().await?;
+        let mut rollback_list = self.runtime.get_runtime_rollback_last().await?;
+
+        for entry in entries {
+            // Already applied, e.g. fetched again after a failed poll
+            if entry.seq <= seq {
+                continue;
+            }
+            if entry.seq != seq + 1 || entry.op_score_before != op_score {
+                return Err(anyhow::anyhow!(
+                    "Leader event {} (opScore {}) does not follow event {} (opScore {})",
+                    entry.seq,
+                    entry.op_score_before,
+                    seq,
+                    op_score
+                ));
+            }
+
+            match &entry.event {
+                events::ScanEvent::Batch {
+                    op_data_list,
+                    state_map,
+                    vspc_list: vspc_list_next,
+                    rollback,
+                    synced,
+                } => {
+                    rollback_manager
+                        .save_op_state_batch(op_data_list, state_map)
+                        .await?;
+                    runtime::append_runtime_lists(
+                        &mut vspc_list,
+                        &mut rollback_list,
+                        vspc_list_next,
+                        rollback,
+                    );
+                    self.runtime
+                        .set_runtime_rollback_last(&rollback_list)
+                        .await?;
+                    self.runtime.set_runtime_vspc_last(&vspc_list).await?;
+                    if let Some(vspc_last) = vspc_list_next.last() {
+                        self.runtime
+                            .set_runtime_synced(*synced, entry.op_score_after, vspc_last.daa_score)
+                            .await?;
+                        self.runtime
+                            .update_progress(&vspc_last.hash, vspc_last.daa_score)?;
+                        self.runtime
+                            .save_checkpoint(&vspc_last.hash, vspc_last.daa_score)?;
+                    }
+                }
+                events::ScanEvent::Rollback {
+                    undone,
+                    vspc_list: vspc_list_after,
+                } => {
+                    if *undone {
+                        let rollback = rollback_list.pop().ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "Leader event {} undoes a batch the follower lacks",
+                                entry.seq
+                            )
+                        })?;
+                        rollback_manager.rollback_op_state_batch(&rollback).await?;
+                        self.runtime
+                            .set_runtime_rollback_last(&rollback_list)
+                            .await?;
+                    }
+                    vspc_list = vspc_list_after.clone();
+                    self.runtime.set_runtime_vspc_last(&vspc_list).await?;
+                }
+            }
+
+            seq = entry.seq;
+            op_score = entry.op_score_after;
+            self.runtime
+                .set_runtime_follower_cursor(seq, op_score)
+                .await?;
+        }
+
+        Ok(op_score)
+    }
+
     /// Health check
     pub async fn health_check(&self) -> Result<crate::storage::distributed::HealthStatus> {
         if let Some(distributed) = &self.distributed {
             distributed.health_check().await
         } else {
-            // Single-node mode returns simple health status
+            // Single-node mode reports unhealthy when disk space is low
+            let disk_healthy = self
+                .rocksdb
+                .get_disk_status()
+                .map(|s| s.pressure == crate::storage::types::DiskPressure::Normal)
+                .unwrap_or(true);
             Ok(crate::storage::distributed::HealthStatus {
-                is_healthy: true,
+                is_healthy: disk_healthy,
                 healthy_shards: 1,
                 total_shards: 1,
                 health_ratio: 1.0,
@@ -0,0 +1,226 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::checkpoint;
+use crate::storage::state_key::{StateKey, StateKeyKind};
+use crate::storage::types::{DataOperationType, OP_RANGE_BY};
+use crate::storage::versioned;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+
+/// Highest op_score of the committed operations, stored as a decimal string
+pub const MAX_OP_SCORE_KEY: &str = "runtime:max_op_score";
+
+/// Highest committed op_score, None before the first operation is committed
+pub fn get_max_op_score(backend: &dyn StorageBackend) -> Result<Option<u64>> {
+    match backend.get_raw(MAX_OP_SCORE_KEY)? {
+        Some(value) => Ok(Some(String::from_utf8(value)?.parse()?)),
+        None => Ok(None),
+    }
+}
+
+/// Raise the stored maximum to the highest op_score of `op_data_list` in `batch`, so it persists
+/// together with the operations
+pub fn stage_applied(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    op_data_list: &[DataOperationType],
+) -> Result<()> {
+    let Some(batch_max) = op_data_list.iter().map(|op_data| op_data.op_score).max() else {
+        return Ok(());
+    };
+    let stored = get_max_op_score(backend)?;
+    if stored.is_none_or(|stored| batch_max > stored) {
+        batch.put(
+            MAX_OP_SCORE_KEY.as_bytes(),
+            batch_max.to_string().as_bytes(),
+        );
+    }
+    Ok(())
+}
+
+/// Lower the stored maximum in `batch` when the operations at `op_score_list` are rolled back.
+/// Rollbacks undo the newest operations, the new maximum is the highest op_score below them.
+pub fn stage_reverted(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    op_score_list: &[u64],
+) -> Result<()> {
+    let Some(&op_score_start) = op_score_list.iter().min() else {
+        return Ok(());
+    };
+    if get_max_op_score(backend)?.is_none_or(|stored| stored < op_score_start) {
+        return Ok(());
+    }
+    match list_newest(backend, Some(op_score_start), 1, &OpListFilter::default())?
+        .0
+        .first()
+    {
+        Some((op_score, _)) => {
+            batch.put(MAX_OP_SCORE_KEY.as_bytes(), op_score.to_string().as_bytes())
+        }
+        None => batch.delete(MAX_OP_SCORE_KEY.as_bytes()),
+    }
+    Ok(())
+}
+
+/// Oplist entries of a page with their op_scores, and the op_score to continue after
+pub type OpListPage = (Vec<(u64, Vec<u8>)>, Option<u64>);
+
+/// Oplist entries affecting a tick and/or an address, an empty filter matches every entry
+#[derive(Debug, Clone, Default)]
+pub struct OpListFilter {
+    pub tick: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OpListAffc {
+    #[serde(default)]
+    tick_affc: Vec<String>,
+    #[serde(default)]
+    address_affc: Vec<String>,
+}
+
+impl OpListFilter {
+    fn is_empty(&self) -> bool {
+        self.tick.is_none() && self.address.is_none()
+    }
+
+    /// Check the `TICK:n`/`TICK=n` and `{address}_{tick}=amount` entries of an oplist value
+    fn matches(&self, value: &[u8]) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let affc: OpListAffc = serde_json::from_slice(value).unwrap_or_default();
+        let tick_matches = self.tick.as_deref().is_none_or(|tick| {
+            affc.tick_affc.iter().any(|affc| {
+                affc.split([':', '='])
+                    .next()
+                    .is_some_and(|affected| affected.eq_ignore_ascii_case(tick))
+            })
+        });
+        let address_matches = self.address.as_deref().is_none_or(|address| {
+            affc.address_affc.iter().any(|affc| {
+                affc.split('=')
+                    .next()
+                    .and_then(|map_key| StateKey::from_map_key(StateKeyKind::Balance, map_key))
+                    .is_some_and(|key| key.address() == Some(address))
+            })
+        });
+        tick_matches && address_matches
+    }
+}
+
+/// Up to `limit` oplist entries matching `filter` newest first, below the op_score `before` or
+/// from the stored maximum. Ranges are visited along the checkpoint chain, so a page reads only
+/// the ranges it returns. Returns the op_score of the last entry when more remain.
+pub fn list_newest(
+    backend: &dyn StorageBackend,
+    before: Option<u64>,
+    limit: usize,
+    filter: &OpListFilter,
+) -> Result<OpListPage> {
+    let upper = match before {
+        Some(before) => before,
+        None => match get_max_op_score(backend)? {
+            Some(max_op_score) => max_op_score.saturating_add(1),
+            None => return Ok((Vec::new(), None)),
+        },
+    };
+    let mut entries = Vec::new();
+    let mut range = Some(upper / OP_RANGE_BY);
+    while let Some(current) = range {
+        for (op_score, value) in checkpoint::range_entries(backend, current)?
+            .into_iter()
+            .rev()
+        {
+            if op_score >= upper || !filter.matches(&value) {
+                continue;
+            }
+            if entries.len() == limit {
+                let next = entries.last().map(|(op_score, _)| *op_score);
+                return Ok((entries, next));
+            }
+            entries.push((op_score, value));
+        }
+        range = checkpoint::previous_range(backend, current)?;
+    }
+    Ok((entries, None))
+}
+
+/// Result of an op_score ordering verification
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpScoreReport {
+    /// Oplist entries checked before the first anomaly, or all of them
+    pub checked: u64,
+    /// First oplist entry out of order, in the wrong range or without its operation
+    pub anomaly: Option<String>,
+}
+
+/// Check every oplist entry in op_score order: op_scores must strictly increase across ranges
+/// and resolve to an operation stored with the same op_score. Stops at the
+/// first anomaly, which points at a bug in op_score assignment or rollback deletion.
+pub fn verify_ordering(backend: &dyn StorageBackend) -> Result<OpScoreReport> {
+    let mut entries = Vec::new();
+    for (key, value) in backend.scan_prefix("oplist:")? {
+        let parsed = key["oplist:".len()..]
+            .split_once(':')
+            .and_then(|(range, op_score)| {
+                Some((range.parse::<u64>().ok()?, op_score.parse().ok()?))
+            });
+        let Some((range, op_score)) = parsed else {
+            return Ok(OpScoreReport {
+                checked: 0,
+                anomaly: Some(format!("malformed oplist key {}", key)),
+            });
+        };
+        entries.push((range, op_score, key, value));
+    }
+    // Keys are stored in string order, ranges and op_scores compare numerically
+    entries.sort_by_key(|(range, op_score, _, _)| (*range, *op_score));
+
+    // Ordered by range, an op_score outside its range breaks the strictly increasing sequence
+    let mut report = OpScoreReport::default();
+    for (range, op_score, key, value) in entries {
+        let anomaly = if op_score / OP_RANGE_BY != range {
+            Some(format!(
+                "{} is out of order, op_score {} belongs to range {}",
+                key,
+                op_score,
+                op_score / OP_RANGE_BY
+            ))
+        } else {
+            check_operation(backend, op_score, &value)?.map(|issue| format!("{} {}", key, issue))
+        };
+        if anomaly.is_some() {
+            report.anomaly = anomaly;
+            return Ok(report);
+        }
+        report.checked += 1;
+    }
+    Ok(report)
+}
+
+/// Issue of the operation an oplist entry points to, None when it is stored with the entry's op_score
+fn check_operation(
+    backend: &dyn StorageBackend,
+    op_score: u64,
+    oplist_value: &[u8],
+) -> Result<Option<String>> {
+    let entry: serde_json::Value = serde_json::from_slice(oplist_value)?;
+    let Some(tx_id) = entry["tx_id"].as_str() else {
+        return Ok(Some("has no tx_id".to_string()));
+    };
+    let Some(opdata) = backend.get_raw(&format!("opdata:{}", tx_id))? else {
+        return Ok(Some(format!("lost operation {}", tx_id)));
+    };
+    let op_data: DataOperationType = versioned::decode(&opdata)?;
+    if op_data.op_score != op_score {
+        return Ok(Some(format!(
+            "collides with operation {} at op_score {}",
+            tx_id, op_data.op_score
+        )));
+    }
+    Ok(None)
+}
@@ -0,0 +1,111 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::types::*;
+use crate::storage::versioned;
+use crate::storage::vspcarchive;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+use serde::Serialize;
+use tracing::info;
+
+/// Key counts and sizes affected by a prune, returned by both dry-run and real prune
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PruneReport {
+    pub horizon_daa_score: u64,
+    pub dry_run: bool,
+    pub opdata_keys: u64,
+    pub opdata_bytes: u64,
+    pub oplist_keys: u64,
+    pub oplist_bytes: u64,
+    pub vspc_keys: u64,
+    pub vspc_bytes: u64,
+}
+
+impl PruneReport {
+    pub fn total_keys(&self) -> u64 {
+        self.opdata_keys + self.oplist_keys + self.vspc_keys
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.opdata_bytes + self.oplist_bytes + self.vspc_bytes
+    }
+}
+
+/// Select `opdata`/`oplist`/`vspc` keys older than the horizon DAA score.
+/// Shared by dry-run and real prune so both always agree on what would be removed.
+pub fn select_prune_keys(
+    backend: &dyn StorageBackend,
+    horizon_daa_score: u64,
+) -> Result<(Vec<String>, PruneReport)> {
+    let mut keys = Vec::new();
+    let mut report = PruneReport {
+        horizon_daa_score,
+        ..Default::default()
+    };
+
+    for (key, value) in backend.scan_prefix("opdata:")? {
+        let daa_score = match versioned::decode::<DataOperationType>(&value) {
+            Ok(op_data) => op_data.daa_score,
+            Err(_) => continue,
+        };
+        if daa_score < horizon_daa_score {
+            report.opdata_keys += 1;
+            report.opdata_bytes += (key.len() + value.len()) as u64;
+            keys.push(key);
+        }
+    }
+
+    // oplist:{op_range}:{op_score}, op_score is the DAA score times the op score multiplier
+    // plus the index of the operation, so the horizon is scaled the same way
+    let horizon_op_score =
+        horizon_daa_score.saturating_mul(crate::config::get_op_score_multiplier());
+    for (key, value) in backend.scan_prefix("oplist:")? {
+        let op_score = match key.rsplit(':').next().and_then(|s| s.parse::<u64>().ok()) {
+            Some(op_score) => op_score,
+            None => continue,
+        };
+        if op_score < horizon_op_score {
+            report.oplist_keys += 1;
+            report.oplist_bytes += (key.len() + value.len()) as u64;
+            keys.push(key);
+        }
+    }
+
+    for (key, value) in backend.scan_prefix("vspc:")? {
+        let Some(daa_score) = vspcarchive::vspc_daa_score(&value) else {
+            continue;
+        };
+        if daa_score < horizon_daa_score {
+            report.vspc_keys += 1;
+            report.vspc_bytes += (key.len() + value.len()) as u64;
+            keys.push(key);
+        }
+    }
+
+    Ok((keys, report))
+}
+
+/// Report what a prune at the given horizon would remove, without deleting anything
+pub fn prune_dry_run(backend: &dyn StorageBackend, horizon_daa_score: u64) -> Result<PruneReport> {
+    let (_, mut report) = select_prune_keys(backend, horizon_daa_score)?;
+    report.dry_run = true;
+    Ok(report)
+}
+
+/// Delete `opdata`/`oplist`/`vspc` keys older than the horizon DAA score
+pub fn prune(backend: &dyn StorageBackend, horizon_daa_score: u64) -> Result<PruneReport> {
+    let (keys, report) = select_prune_keys(backend, horizon_daa_score)?;
+
+    let mut batch = WriteBatch::default();
+    for key in &keys {
+        batch.delete(key.as_bytes());
+    }
+    backend.write_batch(batch)?;
+
+    info!(
+        "Pruned {} keys ({} bytes) below DAA score {}",
+        report.total_keys(),
+        report.total_bytes(),
+        horizon_daa_score
+    );
+    Ok(report)
+}
@@ -0,0 +1,112 @@
+//! Recovery of a corrupted database. RocksDB repair keeps every record it can still read and
+//! drops the rest, so the repaired database is checked for what was lost. State is only ever
+//! derived by replaying operations, a lost balance or token shows up as a supply discrepancy and
+//! cannot be restored from the remaining data. Any reported damage therefore requires a full
+//! resync into an empty database.
+
+use crate::config::types::RocksConfig;
+use crate::storage::StorageManager;
+use crate::storage::rocksdb::RocksDBClient;
+use crate::storage::state_key::{StateKey, StateKeyKind};
+use crate::storage::supply::{self, SupplyDiscrepancy};
+use crate::storage::types::DataVspcType;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use tracing::{info, warn};
+
+/// State left after a repair
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    /// Keys found per kind once repaired
+    pub recovered_keys: BTreeMap<String, u64>,
+    pub supply_discrepancies: Vec<SupplyDiscrepancy>,
+    pub vspc_issues: Vec<String>,
+    /// Checks that could not complete because the data they read is unreadable
+    pub failed_checks: Vec<String>,
+}
+
+impl RepairReport {
+    /// Whether residual damage was found, which only a full resync recovers from
+    pub fn needs_resync(&self) -> bool {
+        !self.supply_discrepancies.is_empty()
+            || !self.vspc_issues.is_empty()
+            || !self.failed_checks.is_empty()
+    }
+}
+
+/// Repair the database at the configured path, it must not be open
+pub fn repair_db(config: &RocksConfig) -> Result<()> {
+    info!("Repairing RocksDB at {}", config.path);
+    RocksDBClient::repair(config)
+}
+
+/// Issues of the runtime VSPC list, which must be ordered by DAA score without repeated blocks
+pub fn check_vspc_list(vspc_list: &[DataVspcType]) -> Vec<String> {
+    let mut issues = Vec::new();
+    let mut hashes = HashSet::new();
+    for (index, vspc) in vspc_list.iter().enumerate() {
+        if !hashes.insert(vspc.hash.as_str()) {
+            issues.push(format!("block {} is listed more than once", vspc.hash));
+        }
+        if index > 0 && vspc.daa_score <= vspc_list[index - 1].daa_score {
+            issues.push(format!(
+                "block {} at DAA score {} does not follow DAA score {}",
+                vspc.hash,
+                vspc.daa_score,
+                vspc_list[index - 1].daa_score
+            ));
+        }
+    }
+    issues
+}
+
+/// Count what a repair kept and run the supply and VSPC consistency checks on it
+pub async fn check_repaired(storage: &StorageManager) -> Result<RepairReport> {
+    let mut report = RepairReport::default();
+    for (kind, prefix) in [
+        ("tokens", StateKeyKind::Token.prefix()),
+        ("balances", StateKeyKind::Balance.prefix()),
+        ("markets", StateKeyKind::Market.prefix()),
+        ("blacklist", StateKeyKind::Blacklist.prefix()),
+        ("opdata", "opdata:"),
+        ("oplist", "oplist:"),
+        ("checkpoints", "checkpoint:"),
+    ] {
+        let count = storage.rocksdb.scan_prefix(prefix)?.len() as u64;
+        info!("Recovered {} {}", count, kind);
+        report.recovered_keys.insert(kind.to_string(), count);
+    }
+
+    // Each tick is checked on its own, an unreadable token does not hide the others
+    match storage.rocksdb.scan_prefix(StateKeyKind::Token.prefix()) {
+        Ok(tokens) => {
+            for (key, _) in tokens {
+                let Some(state_key) = StateKey::decode(&key) else {
+                    report
+                        .failed_checks
+                        .push(format!("supply: invalid key {}", key));
+                    continue;
+                };
+                let tick = state_key.tick();
+                match supply::verify_supply_consistency(&storage.rocksdb, tick) {
+                    Ok(discrepancy) => report.supply_discrepancies.extend(discrepancy),
+                    Err(e) => report
+                        .failed_checks
+                        .push(format!("supply of {}: {}", tick, e)),
+                }
+            }
+        }
+        Err(e) => report.failed_checks.push(format!("supply: {}", e)),
+    }
+    match storage.runtime.get_runtime_vspc_last().await {
+        Ok(vspc_list) => report.vspc_issues = check_vspc_list(&vspc_list),
+        Err(e) => report.failed_checks.push(format!("vspc: {}", e)),
+    }
+
+    if report.needs_resync() {
+        warn!("Repaired database is damaged, a full resync is required");
+    }
+    Ok(report)
+}
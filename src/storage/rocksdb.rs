@@ -1,15 +1,27 @@
 use crate::config::types::RocksConfig;
 use crate::storage::types::*;
 use anyhow::Result;
-use rocksdb::{DB, Options, WriteBatch};
+use rocksdb::{DB, Env, Options, WriteBatch, WriteOptions};
 use std::path::Path;
-use std::sync::Arc;
-use tracing::{debug, info};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
 
 #[derive(Debug, Clone)]
 pub struct RocksDBClient {
     config: RocksConfig,
     db: Arc<rocksdb::DB>,
+    disk_status: Arc<RwLock<Option<DiskSpaceStatus>>>,
+    write_stall_status: Arc<RwLock<Option<WriteStallStatus>>>,
+    write_stall_count: Arc<AtomicU64>,
+    // Ingestion paused on a write stall until compaction catches up
+    write_paused: Arc<AtomicBool>,
+    write_pause_count: Arc<AtomicU64>,
+    // Auto compaction disabled until the initial sync catches up
+    bulk_load: Arc<AtomicBool>,
+    #[cfg(feature = "fault-injection")]
+    fail_writes: Arc<std::sync::atomic::AtomicBool>,
 }
 
 impl RocksDBClient {
@@ -19,28 +31,87 @@ impl RocksDBClient {
             std::fs::create_dir_all(db_path)?;
         }
 
+        let opts = Self::options(&config);
+        Self::open(config, opts)
+    }
+
+    /// Open a RocksDB kept entirely in memory, nothing is read from or written to disk.
+    /// Each instance is a separate empty database, for tests and throwaway storage.
+    pub fn new_in_memory() -> Result<Self> {
+        static IN_MEMORY_COUNT: AtomicU64 = AtomicU64::new(0);
+        let config = RocksConfig {
+            // Only names the database inside the memory env
+            path: format!(
+                "in-memory/{}",
+                IN_MEMORY_COUNT.fetch_add(1, Ordering::Relaxed)
+            ),
+            ..Default::default()
+        };
+
+        let mut opts = Self::options(&config);
+        opts.set_env(&Env::mem_env()?);
+        Self::open(config, opts)
+    }
+
+    fn options(config: &RocksConfig) -> Options {
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.set_max_open_files(10000);
-        opts.set_use_fsync(true);
+        opts.set_use_fsync(config.use_fsync);
         opts.set_bytes_per_sync(1024 * 1024);
         // Configuration corresponding to Go version
         opts.set_write_buffer_size(256 * 1024 * 1024); // 256MB
         opts.set_max_write_buffer_number(4);
         // Note: set_max_background_compactions is deprecated, RocksDB automatically decides this
         // opts.set_max_background_compactions(4);
+        if config.bulk_load {
+            opts.set_disable_auto_compactions(true);
+        }
+        opts
+    }
 
-        let db = DB::open(&opts, db_path)?;
+    fn open(config: RocksConfig, opts: Options) -> Result<Self> {
+        let db = DB::open(&opts, &config.path)?;
         info!("RocksDB initialized at: {}", config.path);
+        if config.bulk_load {
+            info!("RocksDB bulk load mode: auto compaction disabled until synced");
+        }
 
+        let bulk_load = config.bulk_load;
         Ok(Self {
             config,
             db: Arc::new(db),
+            disk_status: Arc::new(RwLock::new(None)),
+            write_stall_status: Arc::new(RwLock::new(None)),
+            write_stall_count: Arc::new(AtomicU64::new(0)),
+            write_paused: Arc::new(AtomicBool::new(false)),
+            write_pause_count: Arc::new(AtomicU64::new(0)),
+            bulk_load: Arc::new(AtomicBool::new(bulk_load)),
+            #[cfg(feature = "fault-injection")]
+            fail_writes: Arc::new(std::sync::atomic::AtomicBool::new(false)),
         })
     }
 
+    /// Rebuild the database at the configured path from its table and log files, dropping what
+    /// cannot be read. The database must not be open.
+    pub fn repair(config: &RocksConfig) -> Result<()> {
+        DB::repair(&Self::options(config), &config.path)?;
+        info!("RocksDB repaired at: {}", config.path);
+        Ok(())
+    }
+
+    /// Get RocksDB configuration
+    pub fn get_config(&self) -> &RocksConfig {
+        &self.config
+    }
+
     pub fn init(&self) -> Result<()> {
-        // Initialize default state if needed
+        // Only initialize default state if missing, never clobber existing runtime state
+        if self.get_runtime_state()?.is_some() {
+            info!("RocksDB state already initialized");
+            return Ok(());
+        }
+
         let runtime_state = RuntimeState {
             last_processed_block: "".to_string(),
             last_processed_daa_score: 0,
@@ -55,6 +126,204 @@ impl RocksDBClient {
         Ok(())
     }
 
+    /// Check available disk space and inodes on the RocksDB volume
+    pub fn check_disk_space(&self) -> Result<DiskSpaceStatus> {
+        let c_path = std::ffi::CString::new(self.config.path.as_str())?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        let result = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+        if result != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let block_size = stat.f_frsize as u64;
+        let total_bytes = (stat.f_blocks as u64).saturating_mul(block_size);
+        let available_bytes = (stat.f_bavail as u64).saturating_mul(block_size);
+        let total_inodes = stat.f_files as u64;
+        let available_inodes = stat.f_favail as u64;
+
+        let min_free_bytes = self.config.min_free_space_mb.saturating_mul(1024 * 1024);
+        let critical_free_bytes = self
+            .config
+            .critical_free_space_mb
+            .saturating_mul(1024 * 1024);
+
+        // Some filesystems (e.g. btrfs) report zero inodes, skip inode check for them
+        let inodes_checked = total_inodes > 0;
+        let pressure = if available_bytes < critical_free_bytes
+            || (inodes_checked && available_inodes < self.config.critical_free_inodes)
+        {
+            DiskPressure::Critical
+        } else if available_bytes < min_free_bytes
+            || (inodes_checked && available_inodes < self.config.min_free_inodes)
+        {
+            DiskPressure::Low
+        } else {
+            DiskPressure::Normal
+        };
+
+        let status = DiskSpaceStatus {
+            path: self.config.path.clone(),
+            total_bytes,
+            available_bytes,
+            total_inodes,
+            available_inodes,
+            pressure,
+            checked_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        };
+
+        if pressure != DiskPressure::Normal {
+            warn!(
+                "RocksDB disk pressure {:?} at {}: availableMB={} availableInodes={}",
+                pressure,
+                self.config.path,
+                available_bytes / (1024 * 1024),
+                available_inodes
+            );
+        }
+
+        *self.disk_status.write().unwrap() = Some(status.clone());
+        Ok(status)
+    }
+
+    /// Get last checked disk status
+    pub fn get_disk_status(&self) -> Option<DiskSpaceStatus> {
+        self.disk_status.read().unwrap().clone()
+    }
+
+    /// Check if disk space is critically low, checking now if never checked
+    pub fn is_disk_space_critical(&self) -> bool {
+        let status = match self.get_disk_status() {
+            Some(status) => Some(status),
+            None => self.check_disk_space().ok(),
+        };
+        status
+            .map(|s| s.pressure == DiskPressure::Critical)
+            .unwrap_or(false)
+    }
+
+    fn property_u64(&self, name: &str) -> u64 {
+        self.db.property_int_value(name).ok().flatten().unwrap_or(0)
+    }
+
+    /// Check RocksDB write stall indicators against the configured backpressure thresholds
+    pub fn check_write_stall(&self) -> WriteStallStatus {
+        let delayed_write_rate = self.property_u64("rocksdb.actual-delayed-write-rate");
+        let write_stopped = self.property_u64("rocksdb.is-write-stopped") != 0;
+        let pending_compaction_bytes =
+            self.property_u64("rocksdb.estimate-pending-compaction-bytes");
+        let immutable_memtables = self.property_u64("rocksdb.num-immutable-mem-table");
+
+        let stalled = write_stopped
+            || delayed_write_rate > 0
+            || pending_compaction_bytes
+                >= self
+                    .config
+                    .stall_pending_compaction_mb
+                    .saturating_mul(1024 * 1024)
+            || immutable_memtables >= self.config.stall_immutable_memtables;
+
+        let status = WriteStallStatus {
+            delayed_write_rate,
+            write_stopped,
+            pending_compaction_bytes,
+            immutable_memtables,
+            stalled,
+            checked_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        };
+
+        if stalled {
+            self.write_stall_count.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "RocksDB write stall: delayedWriteRate={} stopped={} pendingCompactionMB={} immutableMemtables={}",
+                delayed_write_rate,
+                write_stopped,
+                pending_compaction_bytes / (1024 * 1024),
+                immutable_memtables
+            );
+        }
+
+        *self.write_stall_status.write().unwrap() = Some(status.clone());
+        status
+    }
+
+    /// Get last checked write stall status
+    pub fn get_write_stall_status(&self) -> Option<WriteStallStatus> {
+        self.write_stall_status.read().unwrap().clone()
+    }
+
+    /// Number of write stall checks that found RocksDB stalled
+    pub fn get_write_stall_count(&self) -> u64 {
+        self.write_stall_count.load(Ordering::Relaxed)
+    }
+
+    /// Check write stalls and update the ingestion pause, returns true while paused
+    pub fn check_write_pause(&self) -> bool {
+        let status = self.check_write_stall();
+        self.update_write_pause(&status)
+    }
+
+    /// Pause ingestion on a stalled status and hold the pause until writes flow freely and
+    /// pending compaction dropped below `stallResumePendingCompactionMB`
+    pub fn update_write_pause(&self, status: &WriteStallStatus) -> bool {
+        let paused = if self.write_paused.load(Ordering::Relaxed) {
+            status.write_stopped
+                || status.delayed_write_rate > 0
+                || status.immutable_memtables >= self.config.stall_immutable_memtables
+                || status.pending_compaction_bytes
+                    >= self
+                        .config
+                        .stall_resume_pending_compaction_mb
+                        .saturating_mul(1024 * 1024)
+        } else {
+            status.stalled
+        };
+        let was_paused = self.write_paused.swap(paused, Ordering::Relaxed);
+        if paused && !was_paused {
+            self.write_pause_count.fetch_add(1, Ordering::Relaxed);
+            warn!("RocksDB ingestion paused until compaction catches up");
+        } else if !paused && was_paused {
+            info!(
+                "RocksDB ingestion resumed: pendingCompactionMB={}",
+                status.pending_compaction_bytes / (1024 * 1024)
+            );
+        }
+        paused
+    }
+
+    /// Check if ingestion is paused on a write stall
+    pub fn is_write_paused(&self) -> bool {
+        self.write_paused.load(Ordering::Relaxed)
+    }
+
+    /// Number of times ingestion was paused on a write stall
+    pub fn get_write_pause_count(&self) -> u64 {
+        self.write_pause_count.load(Ordering::Relaxed)
+    }
+
+    /// Check if auto compaction is still disabled for initial sync
+    pub fn is_bulk_load(&self) -> bool {
+        self.bulk_load.load(Ordering::Relaxed)
+    }
+
+    /// Leave bulk load mode: re-enable auto compaction and compact everything written so far
+    pub fn finish_bulk_load(&self) -> Result<()> {
+        if !self.bulk_load.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        info!("RocksDB bulk load finished, compacting ..");
+        self.db
+            .set_options(&[("disable_auto_compactions", "false")])?;
+        self.db.compact_range(None::<&[u8]>, None::<&[u8]>);
+        info!("RocksDB bulk load compaction completed");
+        Ok(())
+    }
+
     pub fn shutdown(&self) -> Result<()> {
         // RocksDB will be closed when Arc is dropped
         info!("RocksDB shutdown completed");
@@ -185,6 +454,12 @@ impl RocksDBClient {
         Ok(())
     }
 
+    /// Stage a runtime data write in `batch`, to persist it together with other keys
+    pub fn stage_runtime_data(batch: &mut WriteBatch, key: &str, value: &str) {
+        let full_key = format!("RTA_{}", key); // Corresponding to Go version keyPrefixRuntime
+        batch.put(full_key.as_bytes(), value.as_bytes());
+    }
+
     pub fn get_runtime_data(&self, key: &str) -> Result<Option<String>> {
         let full_key = format!("RTA_{}", key); // Corresponding to Go version keyPrefixRuntime
         if let Some(data) = self.db.get(full_key.as_bytes())? {
@@ -195,6 +470,12 @@ impl RocksDBClient {
         }
     }
 
+    pub fn delete_runtime_data(&self, key: &str) -> Result<()> {
+        let full_key = format!("RTA_{}", key); // Corresponding to Go version keyPrefixRuntime
+        self.db.delete(full_key.as_bytes())?;
+        Ok(())
+    }
+
     // Blacklist operations
     pub fn set_blacklist(&self, entry: &BlacklistEntry) -> Result<()> {
         let key = format!("blacklist:{}", entry.tick);
@@ -290,10 +571,26 @@ impl RocksDBClient {
 
     /// Execute WriteBatch operation
     pub fn write_batch(&self, batch: WriteBatch) -> Result<()> {
-        self.db.write(batch)?;
+        #[cfg(feature = "fault-injection")]
+        if self.fail_writes.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!("injected write batch failure"));
+        }
+        if self.config.sync_writes {
+            let mut write_options = WriteOptions::new();
+            write_options.set_sync(true);
+            self.db.write_opt(batch, &write_options)?;
+        } else {
+            self.db.write(batch)?;
+        }
         Ok(())
     }
 
+    /// Make every following `write_batch` fail, for persistence failure tests
+    #[cfg(feature = "fault-injection")]
+    pub fn set_fail_writes(&self, fail: bool) {
+        self.fail_writes.store(fail, Ordering::Relaxed);
+    }
+
     // Utility methods
     pub fn is_token_blacklisted(&self, tick: &str) -> Result<bool> {
         Ok(self.get_blacklist(tick)?.is_some())
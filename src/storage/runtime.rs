@@ -1,18 +1,53 @@
 use crate::storage::rocksdb::RocksDBClient;
 use crate::storage::types::*;
+use crate::storage::versioned;
+use crate::storage::vspcarchive;
 use anyhow::Result;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 use tracing::info;
 
 #[derive(Debug, Clone)]
 pub struct RuntimeManager {
     rocksdb: Arc<RocksDBClient>,
+    // In-memory flags shared between the scanner and admin operations
+    scan_lock: Arc<Mutex<()>>,
+    rollback_in_progress: Arc<AtomicBool>,
 }
 
 impl RuntimeManager {
     pub fn new(rocksdb: Arc<RocksDBClient>) -> Result<Self> {
-        Ok(Self { rocksdb })
+        Ok(Self {
+            rocksdb,
+            scan_lock: Arc::new(Mutex::new(())),
+            rollback_in_progress: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Acquire the scan lock, held by the scanner for each batch; holding it pauses scanning
+    pub async fn lock_scanning(&self) -> OwnedMutexGuard<()> {
+        Arc::clone(&self.scan_lock).lock_owned().await
+    }
+
+    /// Mark a manual rollback as started, returns false if one is already in progress
+    pub fn try_begin_rollback(&self) -> bool {
+        self.rollback_in_progress
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// Mark a manual rollback as finished
+    pub fn end_rollback(&self) {
+        self.rollback_in_progress.store(false, Ordering::SeqCst);
+    }
+
+    /// Check if a manual rollback is in progress
+    pub fn is_rollback_in_progress(&self) -> bool {
+        self.rollback_in_progress.load(Ordering::SeqCst)
     }
 
     pub fn init(&self) -> Result<()> {
@@ -91,6 +126,14 @@ impl RuntimeManager {
         Ok(())
     }
 
+    /// Undo the count of operations removed by a rollback
+    pub fn decrement_operations_processed(&self, count: u64) -> Result<()> {
+        let mut state = self.get_runtime_state()?;
+        state.total_operations_processed = state.total_operations_processed.saturating_sub(count);
+        self.update_runtime_state(state)?;
+        Ok(())
+    }
+
     // Sync statistics
     pub fn get_sync_stats(&self) -> Result<SyncStats> {
         let state = self.get_runtime_state()?;
@@ -135,35 +178,117 @@ impl RuntimeManager {
         &self,
         rollback_list: &[DataRollbackType],
     ) -> Result<()> {
-        let value_json = serde_json::to_string(rollback_list)?;
+        let value_json = serde_json::to_string(&versioned::to_values(rollback_list)?)?;
         self.rocksdb.set_runtime_data("ROLLBACKLAST", &value_json)?;
         Ok(())
     }
 
     pub async fn get_runtime_rollback_last(&self) -> Result<Vec<DataRollbackType>> {
         if let Some(value_json) = self.rocksdb.get_runtime_data("ROLLBACKLAST")? {
-            let rollback_list: Vec<DataRollbackType> = serde_json::from_str(&value_json)?;
+            let rollback_list: Vec<DataRollbackType> =
+                versioned::from_values(serde_json::from_str(&value_json)?)?;
             Ok(rollback_list)
         } else {
             Ok(Vec::new())
         }
     }
 
+    /// Persist the runtime VSPC list, archiving its new entries and dropping rolled back ones
+    /// in the same write
     pub async fn set_runtime_vspc_last(&self, vspc_list: &[DataVspcType]) -> Result<()> {
-        let value_json = serde_json::to_string(vspc_list)?;
-        self.rocksdb.set_runtime_data("VSPCLAST", &value_json)?;
+        let previous = self.get_runtime_vspc_last().await?;
+        let value_json = serde_json::to_string(&versioned::to_values(vspc_list)?)?;
+        let mut batch = WriteBatch::default();
+        vspcarchive::stage_runtime_list(&mut batch, &previous, vspc_list)?;
+        RocksDBClient::stage_runtime_data(&mut batch, "VSPCLAST", &value_json);
+        self.rocksdb.write_batch(batch)?;
         Ok(())
     }
 
     pub async fn get_runtime_vspc_last(&self) -> Result<Vec<DataVspcType>> {
         if let Some(value_json) = self.rocksdb.get_runtime_data("VSPCLAST")? {
-            let vspc_list: Vec<DataVspcType> = serde_json::from_str(&value_json)?;
+            let vspc_list: Vec<DataVspcType> =
+                versioned::from_values(serde_json::from_str(&value_json)?)?;
             Ok(vspc_list)
         } else {
             Ok(Vec::new())
         }
     }
 
+    /// Persist the target of a rollback before its data write, so a crash can be resumed
+    pub async fn set_runtime_rollback_pending(&self, pending: &PendingRollback) -> Result<()> {
+        let value_json = serde_json::to_string(pending)?;
+        self.rocksdb
+            .set_runtime_data("ROLLBACKPENDING", &value_json)?;
+        Ok(())
+    }
+
+    pub async fn get_runtime_rollback_pending(&self) -> Result<Option<PendingRollback>> {
+        if let Some(value_json) = self.rocksdb.get_runtime_data("ROLLBACKPENDING")? {
+            Ok(Some(serde_json::from_str(&value_json)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn clear_runtime_rollback_pending(&self) -> Result<()> {
+        self.rocksdb.delete_runtime_data("ROLLBACKPENDING")
+    }
+
+    /// Scanner counters as of the last runtime flush
+    pub async fn get_runtime_scan_counters(&self) -> Result<ScanCounters> {
+        if let Some(value_json) = self.rocksdb.get_runtime_data("SCANCOUNTERS")? {
+            Ok(serde_json::from_str(&value_json)?)
+        } else {
+            Ok(ScanCounters::default())
+        }
+    }
+
+    pub async fn set_runtime_scan_counters(&self, counters: &ScanCounters) -> Result<()> {
+        let value_json = serde_json::to_string(counters)?;
+        self.rocksdb.set_runtime_data("SCANCOUNTERS", &value_json)?;
+        Ok(())
+    }
+
+    /// Last leader event applied by a standby follower as (seq, op_score)
+    pub async fn get_runtime_follower_cursor(&self) -> Result<(u64, u64)> {
+        if let Some(value_json) = self.rocksdb.get_runtime_data("FOLLOWERCURSOR")? {
+            Ok(serde_json::from_str(&value_json)?)
+        } else {
+            Ok((0, 0))
+        }
+    }
+
+    pub async fn set_runtime_follower_cursor(&self, seq: u64, op_score: u64) -> Result<()> {
+        let value_json = serde_json::to_string(&(seq, op_score))?;
+        self.rocksdb
+            .set_runtime_data("FOLLOWERCURSOR", &value_json)?;
+        Ok(())
+    }
+
+    /// Record the op_score multiplier on first start and refuse a different one afterwards,
+    /// op_scores and oplist keys written with another multiplier would no longer line up
+    pub fn check_op_score_multiplier(&self, multiplier: u64) -> Result<()> {
+        let stored = match self.rocksdb.get_runtime_data("OPSCOREMULTIPLIER")? {
+            Some(value) => Some(value.parse::<u64>()?),
+            // Data indexed before the multiplier was recorded used the Go version value
+            None if self.get_runtime_state()?.total_operations_processed > 0 => {
+                Some(OP_SCORE_MULTIPLIER_DEFAULT)
+            }
+            None => None,
+        };
+        match stored {
+            Some(stored) if stored != multiplier => Err(anyhow::anyhow!(
+                "opScoreMultiplier {} does not match {} used by the indexed data, reindex to change it",
+                multiplier,
+                stored
+            )),
+            _ => self
+                .rocksdb
+                .set_runtime_data("OPSCOREMULTIPLIER", &multiplier.to_string()),
+        }
+    }
+
     pub async fn set_runtime_synced(
         &self,
         synced: bool,
@@ -253,6 +378,15 @@ pub struct HealthStatus {
     pub total_operations_processed: u64,
 }
 
+/// Scanner counters kept in memory and persisted by the periodic runtime flush
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanCounters {
+    pub total_vspc_processed: u64,
+    pub total_operations_found: u64,
+    /// Unix seconds of the last scan that reached the node
+    pub last_scan_time: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct SyncStats {
     pub total_blocks_processed: u64,
@@ -265,3 +399,34 @@ pub struct SyncStats {
     pub blocks_per_second: f64,
     pub operations_per_second: f64,
 }
+
+/// Append a processed batch to the runtime VSPC and rollback lists, trimmed to their runtime
+/// limits, corresponding to the runtime list handling of Go version's scan loop
+pub fn append_runtime_lists(
+    vspc_list: &mut Vec<DataVspcType>,
+    rollback_list: &mut Vec<DataRollbackType>,
+    vspc_next: &[DataVspcType],
+    rollback: &DataRollbackType,
+) {
+    vspc_list.extend_from_slice(vspc_next);
+    if vspc_list.len() > LEN_VSPC_LIST_RUNTIME_MAX {
+        let len_start = vspc_list.len() - LEN_VSPC_LIST_RUNTIME_MAX;
+        vspc_list.drain(..len_start);
+    }
+
+    // Keep the batches within LEN_ROLLBACK_LIST_RUNTIME_MAX DAA scores of the last one
+    rollback_list.push(rollback.clone());
+    let len_rollback = rollback_list.len();
+    let mut len_start = 0;
+    for i in (0..len_rollback).rev() {
+        if rollback_list[len_rollback - 1].daa_score_end - rollback_list[i].daa_score_start
+            >= LEN_ROLLBACK_LIST_RUNTIME_MAX as u64
+        {
+            len_start = i;
+            break;
+        }
+    }
+    if len_start > 0 {
+        rollback_list.drain(..len_start);
+    }
+}
@@ -1,11 +1,86 @@
+use crate::explorer::NodeClient;
+use crate::storage::checkpoint;
+use crate::storage::holders;
 use crate::storage::rocksdb::RocksDBClient;
+use crate::storage::state_key::{StateKey, StateKeyKind};
+use crate::storage::tokenindex;
 use crate::storage::types::*;
+use crate::storage::versioned;
+use crate::storage::vspcarchive;
 use anyhow::Result;
+use num_bigint::BigUint;
+use rocksdb::WriteBatch;
 use serde_json;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{debug, info};
 
+/// Final value of each state key written by a batch, None deletes the key
+pub type StateWrites = BTreeMap<String, Option<Vec<u8>>>;
+
+/// Coalesce a state map into the writes of one batch. Operations of a batch all update the same
+/// state map, so every key is written once with the value left by the last operation.
+pub fn collect_state_writes(state_map: &DataStateMapType) -> Result<StateWrites> {
+    let mut writes = StateWrites::new();
+    collect_kind(&mut writes, StateKeyKind::Token, &state_map.state_token_map)?;
+    collect_kind(
+        &mut writes,
+        StateKeyKind::Balance,
+        &state_map.state_balance_map,
+    )?;
+    collect_kind(
+        &mut writes,
+        StateKeyKind::Market,
+        &state_map.state_market_map,
+    )?;
+    collect_kind(
+        &mut writes,
+        StateKeyKind::Blacklist,
+        &state_map.state_blacklist_map,
+    )?;
+    collect_kind(
+        &mut writes,
+        StateKeyKind::MintRate,
+        &state_map.state_mint_rate_map,
+    )?;
+    Ok(writes)
+}
+
+fn collect_kind<T: versioned::Versioned>(
+    writes: &mut StateWrites,
+    kind: StateKeyKind,
+    map: &HashMap<String, Option<T>>,
+) -> Result<()> {
+    for (key, value) in map {
+        let value = value.as_ref().map(versioned::encode).transpose()?;
+        writes.insert(kind.full_key(key), value);
+    }
+    Ok(())
+}
+
+/// Stage coalesced state writes into a batch
+pub fn stage_state_writes(batch: &mut WriteBatch, writes: StateWrites) {
+    for (key, value) in writes {
+        match value {
+            Some(value) => batch.put(key.as_bytes(), value),
+            None => batch.delete(key.as_bytes()),
+        }
+    }
+}
+
+/// Order listings by their price `t_amt / u_amt`, then by listing transaction id
+pub fn compare_listings(a: &StateMarketType, b: &StateMarketType) -> Ordering {
+    let amount = |amount: &str| BigUint::from_str(amount).unwrap_or_default();
+    // Cross multiplied, so prices compare exactly without dividing
+    let a_price = amount(&a.t_amt) * amount(&b.u_amt);
+    let b_price = amount(&b.t_amt) * amount(&a.u_amt);
+    a_price
+        .cmp(&b_price)
+        .then_with(|| a.u_tx_id.cmp(&b.u_tx_id))
+}
+
 #[derive(Debug, Clone)]
 pub struct StateManager {
     rocksdb: Arc<RocksDBClient>,
@@ -31,13 +106,13 @@ impl StateManager {
 
         // Collect all keys that need to be queried, consistent with Go version
         for key in token_map.keys() {
-            key_list.push(format!("{}{}", KEY_PREFIX_STATE_TOKEN, key));
+            key_list.push(StateKeyKind::Token.full_key(key));
         }
 
         // Batch query, corresponding to Go version doGetBatchRocks logic
         for key in key_list {
             if let Ok(Some(data)) = self.rocksdb.get_raw(&key) {
-                if let Ok(decoded) = serde_json::from_slice::<StateTokenType>(&data) {
+                if let Ok(decoded) = versioned::decode::<StateTokenType>(&data) {
                     token_map.insert(decoded.tick.clone(), Some(decoded));
                 }
             }
@@ -57,14 +132,14 @@ impl StateManager {
 
         // Collect all keys that need to be queried, consistent with Go version
         for key in balance_map.keys() {
-            key_list.push(format!("{}{}", KEY_PREFIX_STATE_BALANCE, key));
+            key_list.push(StateKeyKind::Balance.full_key(key));
         }
 
         // Batch query
         for key in key_list {
             if let Ok(Some(data)) = self.rocksdb.get_raw(&key) {
-                if let Ok(decoded) = serde_json::from_slice::<StateBalanceType>(&data) {
-                    let map_key = format!("{}_{}", decoded.address, decoded.tick);
+                if let Ok(decoded) = versioned::decode::<StateBalanceType>(&data) {
+                    let map_key = StateKey::balance(&decoded.address, &decoded.tick).map_key();
                     balance_map.insert(map_key, Some(decoded));
                 }
             }
@@ -84,15 +159,16 @@ impl StateManager {
 
         // Collect all keys that need to be queried, consistent with Go version
         for key in market_map.keys() {
-            key_list.push(format!("{}{}", KEY_PREFIX_STATE_MARKET, key));
+            key_list.push(StateKeyKind::Market.full_key(key));
         }
 
         // Batch query
         for key in key_list {
             if let Ok(Some(data)) = self.rocksdb.get_raw(&key) {
-                if let Ok(decoded) = serde_json::from_slice::<StateMarketType>(&data) {
+                if let Ok(decoded) = versioned::decode::<StateMarketType>(&data) {
                     let map_key =
-                        format!("{}_{}_{}", decoded.tick, decoded.t_addr, decoded.u_tx_id);
+                        StateKey::market(&decoded.tick, &decoded.t_addr, &decoded.u_tx_id)
+                            .map_key();
                     market_map.insert(map_key, Some(decoded));
                 }
             }
@@ -112,14 +188,14 @@ impl StateManager {
 
         // Collect all keys that need to be queried
         for key in blacklist_map.keys() {
-            key_list.push(format!("{}{}", KEY_PREFIX_STATE_BLACKLIST, key));
+            key_list.push(StateKeyKind::Blacklist.full_key(key));
         }
 
         // Batch query
         for key in key_list {
             if let Ok(Some(data)) = self.rocksdb.get_raw(&key) {
-                if let Ok(decoded) = serde_json::from_slice::<StateBlacklistType>(&data) {
-                    let map_key = format!("{}_{}", decoded.tick, decoded.address);
+                if let Ok(decoded) = versioned::decode::<StateBlacklistType>(&data) {
+                    let map_key = StateKey::blacklist(&decoded.tick, &decoded.address).map_key();
                     blacklist_map.insert(map_key, Some(decoded));
                 }
             }
@@ -129,15 +205,34 @@ impl StateManager {
         Ok(duration)
     }
 
+    /// Batch get the mint rate counts of the mint rate limit, keyed by tick
+    pub fn get_state_mint_rate_map(
+        &self,
+        mint_rate_map: &mut HashMap<String, Option<MintRateCount>>,
+    ) -> Result<i64> {
+        let start_time = std::time::Instant::now();
+
+        for (tick, count) in mint_rate_map.iter_mut() {
+            let key = StateKeyKind::MintRate.full_key(tick);
+            if let Some(data) = self.rocksdb.get_raw(&key)? {
+                *count = Some(versioned::decode(&data)?);
+            }
+        }
+
+        let duration = start_time.elapsed().as_millis() as i64;
+        Ok(duration)
+    }
+
     /// Batch save state to RocksDB, corresponding to Go version SaveStateBatchRocksBegin
     pub fn save_state_batch_rocks_begin(&self, state_map: &DataStateMapType) -> Result<i64> {
         let start_time = std::time::Instant::now();
 
-        // Save Token state - use key format compatible with get_token
+        let mut batch = WriteBatch::default();
         for (key, token) in &state_map.state_token_map {
+            tokenindex::stage_token(self.rocksdb.as_ref(), &mut batch, key, token.as_ref())?;
+            // Keep the TokenData view under the key read by get_token
             let full_key = format!("token:{}", key);
             if let Some(token_ref) = token {
-                // Convert to TokenData format
                 let token_data = TokenData {
                     tick: token_ref.tick.clone(),
                     max_supply: token_ref.max.parse().unwrap_or(0),
@@ -155,49 +250,15 @@ impl StateManager {
                     lim: Some(token_ref.lim.clone()),
                     pre: Some(token_ref.pre.clone()),
                 };
-                let value_json = serde_json::to_vec(&token_data)?;
-                self.rocksdb.put_raw(&full_key, &value_json)?;
+                batch.put(full_key.as_bytes(), serde_json::to_vec(&token_data)?);
             } else {
                 // Delete Token - use empty value to indicate deletion
-                self.rocksdb.put_raw(&full_key, &[])?;
-            }
-        }
-
-        // Save Balance state
-        for (key, balance) in &state_map.state_balance_map {
-            let full_key = format!("{}{}", KEY_PREFIX_STATE_BALANCE, key);
-            if let Some(balance_ref) = balance {
-                let value_json = serde_json::to_vec(balance_ref)?;
-                self.rocksdb.put_raw(&full_key, &value_json)?;
-            } else {
-                // Delete Balance - use empty value to indicate deletion
-                self.rocksdb.put_raw(&full_key, &[])?;
-            }
-        }
-
-        // Save Market state
-        for (key, market) in &state_map.state_market_map {
-            let full_key = format!("{}{}", KEY_PREFIX_STATE_MARKET, key);
-            if let Some(market_ref) = market {
-                let value_json = serde_json::to_vec(market_ref)?;
-                self.rocksdb.put_raw(&full_key, &value_json)?;
-            } else {
-                // Delete Market - use empty value to indicate deletion
-                self.rocksdb.put_raw(&full_key, &[])?;
-            }
-        }
-
-        // Save Blacklist state
-        for (key, blacklist) in &state_map.state_blacklist_map {
-            let full_key = format!("{}{}", KEY_PREFIX_STATE_BLACKLIST, key);
-            if let Some(blacklist_ref) = blacklist {
-                let value_json = serde_json::to_vec(blacklist_ref)?;
-                self.rocksdb.put_raw(&full_key, &value_json)?;
-            } else {
-                // Delete Blacklist - use empty value to indicate deletion
-                self.rocksdb.put_raw(&full_key, &[])?;
+                batch.put(full_key.as_bytes(), []);
             }
         }
+        holders::stage_balances(self.rocksdb.as_ref(), &mut batch, state_map)?;
+        stage_state_writes(&mut batch, collect_state_writes(state_map)?);
+        self.rocksdb.write_batch(batch)?;
 
         let duration = start_time.elapsed().as_millis() as i64;
         Ok(duration)
@@ -213,6 +274,7 @@ impl StateManager {
         state_map_to.state_balance_map.clear();
         state_map_to.state_market_map.clear();
         state_map_to.state_blacklist_map.clear();
+        state_map_to.state_mint_rate_map.clear();
 
         // Copy Token state
         for (key, st_token) in &state_map_from.state_token_map {
@@ -261,6 +323,11 @@ impl StateManager {
                 state_map_to.state_blacklist_map.insert(key.clone(), None);
             }
         }
+
+        // Copy mint rate counts
+        state_map_to
+            .state_mint_rate_map
+            .extend(state_map_from.state_mint_rate_map.clone());
     }
 
     // Token state management
@@ -290,10 +357,13 @@ impl StateManager {
         self.rocksdb.get_token(tick)
     }
 
-    pub fn list_tokens(&self) -> Result<Vec<TokenData>> {
-        // This would require iterating over all token keys
-        // For now, return empty vector
-        Ok(Vec::new())
+    /// Get full token state, falling back to TokenData for tokens saved without it
+    pub fn get_state_token(&self, tick: &str) -> Result<Option<StateTokenType>> {
+        let key = StateKey::token(tick).encode();
+        if let Some(data) = self.rocksdb.get_raw(&key)? {
+            return Ok(Some(versioned::decode(&data)?));
+        }
+        Ok(self.get_token(tick)?.map(StateTokenType::from))
     }
 
     // Balance state management
@@ -312,6 +382,31 @@ impl StateManager {
         Ok(())
     }
 
+    /// Balance of `address` for `tick`, None when the address never held the tick
+    pub fn get_state_balance(&self, address: &str, tick: &str) -> Result<Option<StateBalanceType>> {
+        match self
+            .rocksdb
+            .get_raw(&StateKey::balance(address, tick).encode())?
+        {
+            Some(data) => Ok(Some(versioned::decode(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Balances of every tick held by `address`, in tick order
+    pub fn list_balances_for_address(&self, address: &str) -> Result<Vec<StateBalanceType>> {
+        let prefix = StateKeyKind::Balance.full_key(&format!("{}_", address));
+        let mut balances = Vec::new();
+        for (key, value) in self.rocksdb.scan_prefix(&prefix)? {
+            // Addresses may contain '_', so the prefix also matches longer addresses
+            let owned = StateKey::decode(&key).is_some_and(|key| key.address() == Some(address));
+            if owned {
+                balances.push(versioned::decode::<StateBalanceType>(&value)?);
+            }
+        }
+        Ok(balances)
+    }
+
     pub fn get_balance(&self, address: &str, tick: &str) -> Result<Option<BalanceData>> {
         self.rocksdb.get_balance(address, tick)
     }
@@ -381,6 +476,32 @@ impl StateManager {
         Ok(())
     }
 
+    /// Oplist entries of an op_score range in op_score order, at most `OP_RANGE_BY` of them
+    pub fn list_oplist_by_range(&self, range: u64) -> Result<Vec<(u64, Vec<u8>)>> {
+        let mut entries = checkpoint::range_entries(self.rocksdb.as_ref(), range)?;
+        entries.truncate(OP_RANGE_BY as usize);
+        Ok(entries)
+    }
+
+    /// Archived VSPC entry of a processed block at `daa_score`
+    pub fn get_vspc_by_daa_score(&self, daa_score: u64) -> Result<Option<DataVspcType>> {
+        vspcarchive::get_vspc(self.rocksdb.as_ref(), daa_score)
+    }
+
+    /// Active listings of `tick`, cheapest first
+    pub fn list_market_for_tick(&self, tick: &str) -> Result<Vec<StateMarketType>> {
+        // Ticks never contain '_', so the prefix matches this tick only
+        let prefix = StateKeyKind::Market.full_key(&format!("{}_", tick));
+        let mut listings = self
+            .rocksdb
+            .scan_prefix(&prefix)?
+            .into_iter()
+            .map(|(_, value)| versioned::decode::<StateMarketType>(&value))
+            .collect::<Result<Vec<_>>>()?;
+        listings.sort_by(compare_listings);
+        Ok(listings)
+    }
+
     pub fn get_market(&self, tick: &str) -> Result<Option<MarketData>> {
         self.rocksdb.get_market(tick)
     }
@@ -429,10 +550,29 @@ impl StateManager {
         self.rocksdb.get_blacklist(tick)
     }
 
-    pub fn is_blacklisted(&self, tick: &str) -> Result<bool> {
+    pub fn is_token_blacklisted(&self, tick: &str) -> Result<bool> {
         self.rocksdb.is_token_blacklisted(tick)
     }
 
+    /// Blacklist entry of `address` for `tick`, None while the address is not blacklisted
+    pub fn get_state_blacklist(
+        &self,
+        address: &str,
+        tick: &str,
+    ) -> Result<Option<StateBlacklistType>> {
+        match self
+            .rocksdb
+            .get_raw(&StateKey::blacklist(tick, address).encode())?
+        {
+            Some(data) => Ok(Some(versioned::decode(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn is_blacklisted(&self, address: &str, tick: &str) -> Result<bool> {
+        Ok(self.get_state_blacklist(address, tick)?.is_some())
+    }
+
     // Reserved token management
     pub fn add_reserved_token(&self, reserved: ReservedToken) -> Result<()> {
         self.rocksdb.set_reserved_token(&reserved)?;
@@ -503,26 +643,18 @@ impl StateManager {
         Ok(())
     }
 
-    // New method: Get node VSPC list, corresponding to Go version GetNodeVspcList
-    pub async fn get_node_vspc_list(
+    /// Get node VSPC list, corresponding to Go version GetNodeVspcList. At most `limit` chain
+    /// blocks from `daa_score_start` on, and never more than `LEN_VSPC_LIST_MAX + 5`.
+    pub async fn get_node_vspc_list<C: NodeClient>(
         &self,
+        node_client: &C,
         daa_score_start: u64,
         limit: usize,
     ) -> Result<Vec<DataVspcType>> {
-        // TODO: Implement getting VSPC list from Kaspa node
-        // This should call Kaspa RPC API
-        let mut vspc_list = Vec::new();
-
-        // Simulate data, should actually be obtained from node
-        for i in 0..limit {
-            let daa_score = daa_score_start + i as u64;
-            vspc_list.push(DataVspcType {
-                daa_score,
-                hash: format!("hash_{}", daa_score),
-                tx_id_list: Vec::new(),
-            });
-        }
-
+        let limit = limit.min(LEN_VSPC_LIST_MAX + 5);
+        let mut vspc_list = node_client.get_vspc_list(daa_score_start, limit).await?;
+        vspc_list.retain(|vspc| vspc.daa_score >= daa_score_start);
+        vspc_list.truncate(limit);
         Ok(vspc_list)
     }
 
@@ -532,19 +664,9 @@ impl StateManager {
         vspc_list: &[DataVspcType],
     ) -> Result<Vec<DataTransactionType>> {
         // TODO: Implement getting transaction data from Kaspa node
-        let mut tx_data_list = Vec::new();
-
-        for vspc in vspc_list {
-            for tx_id in &vspc.tx_id_list {
-                tx_data_list.push(DataTransactionType {
-                    tx_id: tx_id.clone(),
-                    daa_score: vspc.daa_score,
-                    block_accept: vspc.hash.clone(),
-                    data: None,
-                });
-            }
-        }
-
-        Ok(tx_data_list)
+        Ok(vspc_list
+            .iter()
+            .flat_map(DataVspcType::transaction_list)
+            .collect())
     }
 }
@@ -0,0 +1,195 @@
+use crate::storage::types::{
+    KEY_PREFIX_STATE_BALANCE, KEY_PREFIX_STATE_BLACKLIST, KEY_PREFIX_STATE_MARKET,
+    KEY_PREFIX_STATE_MINT_RATE, KEY_PREFIX_STATE_TOKEN,
+};
+
+/// State key families, each one maps to a Go version key prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateKeyKind {
+    Token,
+    Balance,
+    Market,
+    Blacklist,
+    MintRate,
+}
+
+impl StateKeyKind {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            StateKeyKind::Token => KEY_PREFIX_STATE_TOKEN,
+            StateKeyKind::Balance => KEY_PREFIX_STATE_BALANCE,
+            StateKeyKind::Market => KEY_PREFIX_STATE_MARKET,
+            StateKeyKind::Blacklist => KEY_PREFIX_STATE_BLACKLIST,
+            StateKeyKind::MintRate => KEY_PREFIX_STATE_MINT_RATE,
+        }
+    }
+
+    /// Build the full RocksDB key from a state map key
+    pub fn full_key(&self, map_key: &str) -> String {
+        format!("{}{}", self.prefix(), map_key)
+    }
+}
+
+/// Typed state key, the single place where the on-disk key scheme is encoded and parsed.
+///
+/// The layout is the Go version one, so no migration is needed:
+/// - `sttoken_{tick}`
+/// - `stbalance_{address}_{tick}`
+/// - `stmarket_{tick}_{address}_{utxo_tx_id}`
+/// - `stblacklist_{tick}_{address}`
+///
+/// Only `stmintrate_{tick}`, the mint rate count of a tick, has no Go version counterpart.
+///
+/// Ticks and transaction ids never contain '_', addresses may, so decoding anchors
+/// on the tick/tx id side of each key and leaves the rest to the address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StateKey {
+    Token {
+        tick: String,
+    },
+    Balance {
+        address: String,
+        tick: String,
+    },
+    Market {
+        tick: String,
+        address: String,
+        utxo_tx_id: String,
+    },
+    Blacklist {
+        tick: String,
+        address: String,
+    },
+    MintRate {
+        tick: String,
+    },
+}
+
+impl StateKey {
+    pub fn token(tick: &str) -> Self {
+        StateKey::Token {
+            tick: tick.to_string(),
+        }
+    }
+
+    pub fn balance(address: &str, tick: &str) -> Self {
+        StateKey::Balance {
+            address: address.to_string(),
+            tick: tick.to_string(),
+        }
+    }
+
+    pub fn market(tick: &str, address: &str, utxo_tx_id: &str) -> Self {
+        StateKey::Market {
+            tick: tick.to_string(),
+            address: address.to_string(),
+            utxo_tx_id: utxo_tx_id.to_string(),
+        }
+    }
+
+    pub fn blacklist(tick: &str, address: &str) -> Self {
+        StateKey::Blacklist {
+            tick: tick.to_string(),
+            address: address.to_string(),
+        }
+    }
+
+    pub fn mint_rate(tick: &str) -> Self {
+        StateKey::MintRate {
+            tick: tick.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> StateKeyKind {
+        match self {
+            StateKey::Token { .. } => StateKeyKind::Token,
+            StateKey::Balance { .. } => StateKeyKind::Balance,
+            StateKey::Market { .. } => StateKeyKind::Market,
+            StateKey::Blacklist { .. } => StateKeyKind::Blacklist,
+            StateKey::MintRate { .. } => StateKeyKind::MintRate,
+        }
+    }
+
+    pub fn tick(&self) -> &str {
+        match self {
+            StateKey::Token { tick }
+            | StateKey::Balance { tick, .. }
+            | StateKey::Market { tick, .. }
+            | StateKey::Blacklist { tick, .. }
+            | StateKey::MintRate { tick } => tick,
+        }
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        match self {
+            StateKey::Token { .. } | StateKey::MintRate { .. } => None,
+            StateKey::Balance { address, .. }
+            | StateKey::Market { address, .. }
+            | StateKey::Blacklist { address, .. } => Some(address),
+        }
+    }
+
+    /// Key used in `DataStateMapType` maps and state lines, without the prefix
+    pub fn map_key(&self) -> String {
+        match self {
+            StateKey::Token { tick } => tick.clone(),
+            StateKey::Balance { address, tick } => format!("{}_{}", address, tick),
+            StateKey::Market {
+                tick,
+                address,
+                utxo_tx_id,
+            } => format!("{}_{}_{}", tick, address, utxo_tx_id),
+            StateKey::Blacklist { tick, address } => format!("{}_{}", tick, address),
+            StateKey::MintRate { tick } => tick.clone(),
+        }
+    }
+
+    /// Full RocksDB key
+    pub fn encode(&self) -> String {
+        self.kind().full_key(&self.map_key())
+    }
+
+    /// Parse a full RocksDB key, returns None for unknown prefixes or malformed keys
+    pub fn decode(key: &str) -> Option<Self> {
+        [
+            StateKeyKind::Token,
+            StateKeyKind::Balance,
+            StateKeyKind::Market,
+            StateKeyKind::Blacklist,
+            StateKeyKind::MintRate,
+        ]
+        .into_iter()
+        .find_map(|kind| {
+            key.strip_prefix(kind.prefix())
+                .and_then(|map_key| Self::from_map_key(kind, map_key))
+        })
+    }
+
+    /// Parse a state map key of the given kind
+    pub fn from_map_key(kind: StateKeyKind, map_key: &str) -> Option<Self> {
+        let key = match kind {
+            StateKeyKind::Token => Self::token(map_key),
+            StateKeyKind::Balance => {
+                let (address, tick) = map_key.rsplit_once('_')?;
+                Self::balance(address, tick)
+            }
+            StateKeyKind::Market => {
+                let (tick, rest) = map_key.split_once('_')?;
+                let (address, utxo_tx_id) = rest.rsplit_once('_')?;
+                if utxo_tx_id.is_empty() {
+                    return None;
+                }
+                Self::market(tick, address, utxo_tx_id)
+            }
+            StateKeyKind::Blacklist => {
+                let (tick, address) = map_key.split_once('_')?;
+                Self::blacklist(tick, address)
+            }
+            StateKeyKind::MintRate => Self::mint_rate(map_key),
+        };
+        if key.tick().is_empty() || key.address().is_some_and(str::is_empty) {
+            return None;
+        }
+        Some(key)
+    }
+}
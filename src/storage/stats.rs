@@ -0,0 +1,107 @@
+use crate::storage::StorageManager;
+use crate::storage::backend::StorageBackend;
+use crate::storage::opscore;
+use crate::storage::state_key::StateKeyKind;
+use crate::storage::types::*;
+use crate::storage::versioned;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long scanned aggregates are served before scanning again
+pub const STATS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Window of the per-type operation counts, in milliseconds
+pub const STATS_RECENT_WINDOW_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Aggregate chain-wide KRC-20 metrics
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChainStats {
+    pub total_tokens: u64,
+    pub total_operations: u64,
+    pub operations_24h: BTreeMap<String, u64>,
+    pub op_score: u64,
+}
+
+/// Aggregates that need a key scan, cached by `StatsCache`
+#[derive(Debug, Clone, Default)]
+struct ScannedStats {
+    total_tokens: u64,
+    operations_24h: BTreeMap<String, u64>,
+}
+
+/// Count deployed tokens, and committed operations by type since `since_mts`
+fn scan_stats(backend: &dyn StorageBackend, since_mts: u64) -> Result<ScannedStats> {
+    let mut stats = ScannedStats {
+        total_tokens: backend.scan_prefix(StateKeyKind::Token.prefix())?.len() as u64,
+        ..Default::default()
+    };
+
+    for (_, value) in backend.scan_prefix("opdata:")? {
+        let op_data = match versioned::decode::<DataOperationType>(&value) {
+            Ok(op_data) => op_data,
+            Err(_) => continue,
+        };
+        if op_data.mts_add >= 0 && op_data.mts_add as u64 >= since_mts {
+            let op = op_data
+                .op_script
+                .first()
+                .map(|script| script.op.clone())
+                .unwrap_or_default();
+            *stats.operations_24h.entry(op).or_insert(0) += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Serves `ChainStats`, the operation counter and op_score are read live and scanned aggregates
+/// are cached
+pub struct StatsCache {
+    ttl: Duration,
+    cached: Mutex<Option<(ScannedStats, Instant)>>,
+}
+
+impl StatsCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn get(&self, storage: &StorageManager) -> Result<ChainStats> {
+        let total_operations = storage
+            .runtime
+            .get_runtime_state()
+            .map(|state| state.total_operations_processed)
+            .unwrap_or(0);
+        let op_score = opscore::get_max_op_score(&storage.rocksdb)?.unwrap_or(0);
+
+        let mut cached = self.cached.lock().unwrap();
+        let scanned = match cached.as_ref() {
+            Some((scanned, scanned_at)) if scanned_at.elapsed() < self.ttl => scanned.clone(),
+            _ => {
+                let now_mts = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64;
+                let scanned = scan_stats(
+                    &storage.rocksdb,
+                    now_mts.saturating_sub(STATS_RECENT_WINDOW_MS),
+                )?;
+                *cached = Some((scanned.clone(), Instant::now()));
+                scanned
+            }
+        };
+
+        Ok(ChainStats {
+            total_tokens: scanned.total_tokens,
+            total_operations,
+            operations_24h: scanned.operations_24h,
+            op_score,
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::state_key::{StateKey, StateKeyKind};
+use crate::storage::types::{StateBalanceType, StateTokenType};
+use crate::storage::versioned;
+use anyhow::Result;
+use serde::Serialize;
+
+/// Number of largest holders reported with a supply discrepancy
+pub const SUPPLY_TOP_HOLDERS: usize = 10;
+
+/// Amount held by one address, `balance + locked`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HolderAmount {
+    pub address: String,
+    pub amount: u128,
+}
+
+/// Mismatch of `sum(balance + locked) + burned` against the minted supply of a tick
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplyDiscrepancy {
+    pub tick: String,
+    pub minted: u128,
+    pub burned: u128,
+    /// Sum of `balance + locked` over all holders
+    pub holder_sum: u128,
+    /// `holder_sum + burned - minted`, positive when holders hold more than was minted
+    pub delta: i128,
+    /// Largest holders first, the most likely place of the discrepancy
+    pub top_holders: Vec<HolderAmount>,
+}
+
+fn parse_amount(field: &str, owner: &str, value: &str) -> Result<u128> {
+    if value.is_empty() {
+        return Ok(0);
+    }
+    value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid {} of {}: {:?}", field, owner, value))
+}
+
+/// Mismatch of a token against its holders, None when they match
+fn discrepancy(
+    token: &StateTokenType,
+    mut holders: Vec<HolderAmount>,
+) -> Result<Option<SupplyDiscrepancy>> {
+    let minted = parse_amount("minted", &token.tick, &token.minted)?;
+    let burned = parse_amount("burned", &token.tick, &token.burned)?;
+    let holder_sum: u128 = holders.iter().map(|holder| holder.amount).sum();
+    let delta = holder_sum as i128 + burned as i128 - minted as i128;
+    if delta == 0 {
+        return Ok(None);
+    }
+    holders.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.address.cmp(&b.address)));
+    holders.truncate(SUPPLY_TOP_HOLDERS);
+    Ok(Some(SupplyDiscrepancy {
+        tick: token.tick.clone(),
+        minted,
+        burned,
+        holder_sum,
+        delta,
+        top_holders: holders,
+    }))
+}
+
+/// Holders of `tick`
+fn scan_holders(backend: &dyn StorageBackend, tick: &str) -> Result<Vec<HolderAmount>> {
+    let mut holders = Vec::new();
+    for (key, value) in backend.scan_prefix(StateKeyKind::Balance.prefix())? {
+        let Some(state_key) = StateKey::decode(&key) else {
+            continue;
+        };
+        if state_key.tick() != tick {
+            continue;
+        }
+        let balance: StateBalanceType = versioned::decode(&value)?;
+        let amount = parse_amount("balance", &key, &balance.balance)?
+            + parse_amount("locked", &key, &balance.locked)?;
+        holders.push(HolderAmount {
+            address: balance.address,
+            amount,
+        });
+    }
+    Ok(holders)
+}
+
+/// Compare the stored supply of `tick` with the sum of all its balances. Returns None when they
+/// match, every balance is scanned so this is meant for audits rather than the execute path.
+pub fn verify_supply_consistency(
+    backend: &dyn StorageBackend,
+    tick: &str,
+) -> Result<Option<SupplyDiscrepancy>> {
+    let token: StateTokenType = match backend.get_raw(&StateKey::token(tick).encode())? {
+        Some(value) => versioned::decode(&value)?,
+        None => return Err(anyhow::anyhow!("Token {} does not exist", tick)),
+    };
+    discrepancy(&token, scan_holders(backend, tick)?)
+}
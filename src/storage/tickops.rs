@@ -0,0 +1,98 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::types::DataOperationType;
+use crate::storage::versioned;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+
+const TICK_OPS_PREFIX: &str = "tickops:";
+
+/// Page of a tick's operations and the op_score to continue before, None on the last page
+pub type TickOpsPage = (Vec<DataOperationType>, Option<u64>);
+
+fn tick_ops_prefix(tick: &str) -> String {
+    format!("{}{}:", TICK_OPS_PREFIX, tick)
+}
+
+/// Index entry of an operation under `tickops:{tick}:{op_score}`, holding its tx_id
+fn tick_ops_key(tick: &str, op_score: u64) -> String {
+    // Zero padded so entries scan in op_score order
+    format!("{}{:020}", tick_ops_prefix(tick), op_score)
+}
+
+/// Ticks an operation affected, taken from the `TICK:n` and `TICK=n` entries of its tick_affc
+fn affected_ticks(op_data: &DataOperationType) -> Vec<String> {
+    let Some(ss_info) = op_data.ss_info.as_ref() else {
+        return Vec::new();
+    };
+    let mut ticks: Vec<String> = ss_info
+        .tick_affc
+        .iter()
+        .filter_map(|affc| affc.split([':', '=']).next())
+        .filter(|tick| !tick.is_empty())
+        .map(|tick| tick.to_uppercase())
+        .collect();
+    ticks.sort();
+    ticks.dedup();
+    ticks
+}
+
+/// Index the operations in `batch` under every tick they affected
+pub fn stage_applied(batch: &mut WriteBatch, op_data_list: &[DataOperationType]) {
+    for op_data in op_data_list {
+        for tick in affected_ticks(op_data) {
+            batch.put(
+                tick_ops_key(&tick, op_data.op_score).as_bytes(),
+                op_data.tx_id.as_bytes(),
+            );
+        }
+    }
+}
+
+/// Remove the index entries of rolled back operations in `batch`
+pub fn stage_reverted(batch: &mut WriteBatch, op_data_list: &[DataOperationType]) {
+    for op_data in op_data_list {
+        for tick in affected_ticks(op_data) {
+            batch.delete(tick_ops_key(&tick, op_data.op_score).as_bytes());
+        }
+    }
+}
+
+/// Up to `limit` operations affecting `tick` newest first, below the op_score `before` and
+/// only of `op_type` when given. Entries whose operation was pruned are skipped.
+pub fn list_tick_ops(
+    backend: &dyn StorageBackend,
+    tick: &str,
+    op_type: Option<&str>,
+    before: Option<u64>,
+    limit: usize,
+) -> Result<TickOpsPage> {
+    let prefix = tick_ops_prefix(&tick.to_uppercase());
+    let mut operations: Vec<DataOperationType> = Vec::new();
+    for (key, tx_id) in backend.scan_prefix(&prefix)?.into_iter().rev() {
+        let Ok(op_score) = key[prefix.len()..].parse::<u64>() else {
+            continue;
+        };
+        if before.is_some_and(|before| op_score >= before) {
+            continue;
+        }
+        let tx_id = String::from_utf8_lossy(&tx_id);
+        let Some(value) = backend.get_raw(&format!("opdata:{}", tx_id))? else {
+            continue;
+        };
+        let op_data: DataOperationType = versioned::decode(&value)?;
+        if let Some(op_type) = op_type
+            && !op_data
+                .op_script
+                .first()
+                .is_some_and(|script| script.op.eq_ignore_ascii_case(op_type))
+        {
+            continue;
+        }
+        if operations.len() == limit {
+            let next = operations.last().map(|op_data| op_data.op_score);
+            return Ok((operations, next));
+        }
+        operations.push(op_data);
+    }
+    Ok((operations, None))
+}
@@ -0,0 +1,117 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::types::DataOperationType;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+const TICK_STATS_PREFIX: &str = "tickstats:";
+
+/// Accepted and rejected operation counts of a tick, stored under `tickstats:{tick}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickOpStats {
+    pub tick: String,
+    pub accepted: u64,
+    pub rejected: u64,
+    /// Rejected operations keyed by their op_error
+    pub rejected_by_reason: BTreeMap<String, u64>,
+}
+
+impl TickOpStats {
+    fn apply(&mut self, op_data: &DataOperationType, applied: bool) {
+        let add = |count: &mut u64| {
+            *count = if applied {
+                *count + 1
+            } else {
+                count.saturating_sub(1)
+            }
+        };
+        if op_data.op_accept == 1 {
+            add(&mut self.accepted);
+            return;
+        }
+        add(&mut self.rejected);
+        let reason = self
+            .rejected_by_reason
+            .entry(op_data.op_error.clone())
+            .or_default();
+        add(reason);
+        if *reason == 0 {
+            self.rejected_by_reason.remove(&op_data.op_error);
+        }
+    }
+}
+
+fn tick_stats_key(tick: &str) -> String {
+    format!("{}{}", TICK_STATS_PREFIX, tick)
+}
+
+/// Tick counted for an operation, the ca for issue mode tokens. Operations not yet executed are skipped
+fn counted_tick(op_data: &DataOperationType) -> Option<String> {
+    if op_data.op_accept == 0 {
+        return None;
+    }
+    let script = op_data.op_script.first()?;
+    script
+        .tick
+        .as_ref()
+        .or(script.ca.as_ref())
+        .filter(|tick| !tick.is_empty())
+        .map(|tick| tick.to_uppercase())
+}
+
+fn stage_counts(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    op_data_list: &[DataOperationType],
+    applied: bool,
+) -> Result<()> {
+    let mut stats_map: HashMap<String, TickOpStats> = HashMap::new();
+    for op_data in op_data_list {
+        let Some(tick) = counted_tick(op_data) else {
+            continue;
+        };
+        if !stats_map.contains_key(&tick) {
+            let stats = get_tick_stats(backend, &tick)?.unwrap_or_else(|| TickOpStats {
+                tick: tick.clone(),
+                ..Default::default()
+            });
+            stats_map.insert(tick.clone(), stats);
+        }
+        stats_map.get_mut(&tick).unwrap().apply(op_data, applied);
+    }
+    for (tick, stats) in stats_map {
+        batch.put(
+            tick_stats_key(&tick).as_bytes(),
+            serde_json::to_vec(&stats)?,
+        );
+    }
+    Ok(())
+}
+
+/// Add the counts of executed operations to `batch`, so they persist together with the operations
+pub fn stage_applied(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    op_data_list: &[DataOperationType],
+) -> Result<()> {
+    stage_counts(backend, batch, op_data_list, true)
+}
+
+/// Subtract the counts of rolled back operations in `batch`
+pub fn stage_reverted(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    op_data_list: &[DataOperationType],
+) -> Result<()> {
+    stage_counts(backend, batch, op_data_list, false)
+}
+
+/// Operation counts of a tick, None when no operation of it was executed
+pub fn get_tick_stats(backend: &dyn StorageBackend, tick: &str) -> Result<Option<TickOpStats>> {
+    backend
+        .get_raw(&tick_stats_key(&tick.to_uppercase()))?
+        .map(|value| serde_json::from_slice(&value).map_err(anyhow::Error::from))
+        .transpose()
+}
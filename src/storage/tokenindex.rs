@@ -0,0 +1,109 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::state_key::{StateKey, StateKeyKind};
+use crate::storage::types::{StateTokenType, TokenDeployInfo};
+use crate::storage::versioned;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+
+const TOKEN_BY_DAA_PREFIX: &str = "tokenbydaa:";
+/// Set once tokens persisted before the index existed have been indexed
+const TOKEN_BY_DAA_READY_KEY: &str = "tokenbydaaready";
+
+/// Index entry of a token under `tokenbydaa:{deploy_daa_score}:{tick}`, holding no value
+fn token_by_daa_key(token: &StateTokenType) -> String {
+    // Zero padded so entries scan in deploy DAA score order
+    format!(
+        "{}{:020}:{}",
+        TOKEN_BY_DAA_PREFIX,
+        TokenDeployInfo::from(token).deploy_daa_score,
+        token.tick
+    )
+}
+
+/// Deploy DAA score of an index key
+fn daa_score_of_key(key: &str) -> Option<u64> {
+    key.strip_prefix(TOKEN_BY_DAA_PREFIX)?
+        .split_once(':')?
+        .0
+        .parse()
+        .ok()
+}
+
+/// Stage the index update of a token state write, `token` is None when the token is deleted
+pub fn stage_token(
+    backend: &dyn StorageBackend,
+    batch: &mut WriteBatch,
+    tick: &str,
+    token: Option<&StateTokenType>,
+) -> Result<()> {
+    // A rollback may delete the token or restore it with another deploy score
+    if let Some(value) = backend.get_raw(&StateKey::token(tick).encode())? {
+        let stored: StateTokenType = versioned::decode(&value)?;
+        batch.delete(token_by_daa_key(&stored).as_bytes());
+    }
+    if let Some(token) = token {
+        batch.put(token_by_daa_key(token).as_bytes(), []);
+    }
+    Ok(())
+}
+
+/// Index tokens persisted before the index existed, once per store
+pub fn rebuild_token_index(backend: &dyn StorageBackend) -> Result<usize> {
+    if backend.get_raw(TOKEN_BY_DAA_READY_KEY)?.is_some() {
+        return Ok(0);
+    }
+    let mut batch = WriteBatch::default();
+    let mut count = 0;
+    for (_, value) in backend.scan_prefix(StateKeyKind::Token.prefix())? {
+        let token: StateTokenType = versioned::decode(&value)?;
+        batch.put(token_by_daa_key(&token).as_bytes(), []);
+        count += 1;
+    }
+    batch.put(TOKEN_BY_DAA_READY_KEY.as_bytes(), b"1");
+    backend.write_batch(batch)?;
+    Ok(count)
+}
+
+/// Up to `limit` tokens deployed within `[from_daa, to_daa]` in deploy order, newest first when
+/// `descending`, following the index position `after`. Returns the position of the last token
+/// when more remain.
+pub fn list_tokens_by_daa(
+    backend: &dyn StorageBackend,
+    from_daa: u64,
+    to_daa: u64,
+    after: Option<&str>,
+    limit: usize,
+    descending: bool,
+) -> Result<(Vec<StateTokenType>, Option<String>)> {
+    let mut tokens = Vec::new();
+    let mut keys = backend.scan_prefix(TOKEN_BY_DAA_PREFIX)?;
+    if descending {
+        keys.reverse();
+    }
+    let mut keys = keys
+        .into_iter()
+        .map(|(key, _)| key)
+        .filter(|key| {
+            after.is_none_or(|after| match descending {
+                true => key.as_str() < after,
+                false => key.as_str() > after,
+            })
+        })
+        .filter(|key| {
+            daa_score_of_key(key)
+                .is_some_and(|daa_score| daa_score >= from_daa && daa_score <= to_daa)
+        })
+        .peekable();
+    while let Some(key) = keys.next() {
+        let Some((_, tick)) = key[TOKEN_BY_DAA_PREFIX.len()..].split_once(':') else {
+            continue;
+        };
+        if let Some(value) = backend.get_raw(&StateKey::token(tick).encode())? {
+            tokens.push(versioned::decode(&value)?);
+        }
+        if tokens.len() == limit {
+            return Ok((tokens, keys.peek().map(|_| key)));
+        }
+    }
+    Ok((tokens, None))
+}
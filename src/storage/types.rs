@@ -3,11 +3,23 @@ use serde::{Deserialize, Serialize};
 // Constant definitions from Go version
 pub const OP_RANGE_BY: u64 = 100000;
 
+/// op_score slots per DAA score in the Go version, op_score is daa_score * multiplier + index,
+/// so a DAA score holds at most this many operations
+pub const OP_SCORE_MULTIPLIER_DEFAULT: u64 = 10000;
+/// Largest multiplier, still leaves op_score room for DAA scores up to about 1.8e10
+pub const OP_SCORE_MULTIPLIER_MAX: u64 = 1_000_000_000;
+
+// An oplist range always covers whole DAA scores with the default multiplier, configured
+// multipliers are checked when the config is loaded
+const _: () = assert!(OP_RANGE_BY.is_multiple_of(OP_SCORE_MULTIPLIER_DEFAULT));
+
 // State key prefixes, consistent with Go version
 pub const KEY_PREFIX_STATE_TOKEN: &str = "sttoken_";
 pub const KEY_PREFIX_STATE_BALANCE: &str = "stbalance_";
 pub const KEY_PREFIX_STATE_MARKET: &str = "stmarket_";
 pub const KEY_PREFIX_STATE_BLACKLIST: &str = "stblacklist_";
+// Mint rate counts of the mint rate limit, not part of the Go version
+pub const KEY_PREFIX_STATE_MINT_RATE: &str = "stmintrate_";
 
 // VSPC list related constants
 pub const LEN_VSPC_LIST_MAX: usize = 1200;
@@ -30,6 +42,8 @@ impl From<TokenData> for StateTokenType {
             minted: token.minted_supply,
             burned: "0".to_string(), // Default value
             name: "".to_string(),    // Default value
+            desc: "".to_string(),    // Default value
+            frozen: false,
             tx_id: token.deploy_tx_hash,
             op_add: 0, // Default value
             op_mod: 0, // Default value
@@ -130,6 +144,43 @@ pub struct DataVspcType {
     pub daa_score: u64,
     pub hash: String,
     pub tx_id_list: Vec<String>,
+    /// Hash of the block's selected parent, links the entries into the chain a reorg forks from.
+    /// Empty when the node did not report it.
+    #[serde(default)]
+    pub selected_parent: String,
+}
+
+impl DataVspcType {
+    /// Accepted transactions still to be fetched from the node
+    pub fn transaction_list(&self) -> Vec<DataTransactionType> {
+        self.tx_id_list
+            .iter()
+            .map(|tx_id| DataTransactionType {
+                tx_id: tx_id.clone(),
+                daa_score: self.daa_score,
+                block_accept: self.hash.clone(),
+                data: None,
+            })
+            .collect()
+    }
+}
+
+impl From<&VSPCData> for DataVspcType {
+    /// Keep the accepted transaction ids of the block acceptance data, in acceptance order
+    fn from(vspc_data: &VSPCData) -> Self {
+        Self {
+            daa_score: vspc_data.daa_score,
+            hash: vspc_data.block_hash.clone(),
+            tx_id_list: vspc_data
+                .block_acceptance_data
+                .block_acceptance_data
+                .iter()
+                .filter(|tx| tx.is_accepted)
+                .map(|tx| tx.transaction_hash.clone())
+                .collect(),
+            selected_parent: vspc_data.selected_parent.clone(),
+        }
+    }
 }
 
 // Transaction data structure corresponding to Go version
@@ -159,6 +210,7 @@ pub struct DataScriptType {
     pub mod_type: String,      // Corresponding to Go version's mod field
     pub name: Option<String>,  // Keep Option type
     pub ca: Option<String>,    // Keep Option type
+    pub desc: Option<String>,  // Token description, used by update
 }
 
 // Operation state data structure corresponding to Go version
@@ -181,6 +233,13 @@ pub struct DataStatsType {
     pub address_affc: Vec<String>,
 }
 
+// Mints of a tick counted in the current DAA score window, used by the mint rate limit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintRateCount {
+    pub window_start: u64,
+    pub count: u64,
+}
+
 // Operation data structure corresponding to Go version
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataOperationType {
@@ -201,6 +260,56 @@ pub struct DataOperationType {
     pub ss_info: Option<DataStatsType>, // Keep Option type
 }
 
+/// Fee audit of an operation, expected fee from ScriptParser against the fee actually paid
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub op_type: String,
+    pub daa_score: u64,
+    pub required: u64,
+    pub paid: u64,
+    pub accepted: bool,
+}
+
+impl From<&DataOperationType> for FeeBreakdown {
+    fn from(op_data: &DataOperationType) -> Self {
+        Self {
+            op_type: op_data
+                .op_script
+                .first()
+                .map(|script| script.op.clone())
+                .unwrap_or_default(),
+            daa_score: op_data.daa_score,
+            required: op_data.fee_least,
+            paid: op_data.fee,
+            accepted: op_data.fee >= op_data.fee_least,
+        }
+    }
+}
+
+impl From<&DataOperationType> for OperationData {
+    fn from(op_data: &DataOperationType) -> Self {
+        let script = op_data.op_script.first();
+        Self {
+            operation_type: script.map(|script| script.op.clone()).unwrap_or_default(),
+            tick: script
+                .and_then(|script| script.tick.clone())
+                .unwrap_or_default(),
+            from_address: script.and_then(|script| script.from.clone()),
+            to_address: script.and_then(|script| script.to.clone()),
+            amount: script
+                .and_then(|script| script.amt.as_ref())
+                .and_then(|amt| amt.parse().ok()),
+            tx_hash: op_data.tx_id.clone(),
+            block_hash: op_data.block_accept.clone(),
+            timestamp: op_data.mts_add.max(0) as u64,
+            block_daa_score: op_data.daa_score,
+            daa_score: op_data.daa_score,
+            tx_id: op_data.tx_id.clone(),
+            ..Default::default()
+        }
+    }
+}
+
 // Script data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScriptData {
@@ -230,6 +339,11 @@ pub struct StateTokenType {
     pub minted: String,
     pub burned: String,
     pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    /// Set by the owner's freeze operation, transfers, sends and mints are rejected while set
+    #[serde(default)]
+    pub frozen: bool,
     pub tx_id: String,
     pub op_add: u64,
     pub op_mod: u64,
@@ -251,6 +365,8 @@ impl Default for StateTokenType {
             minted: "0".to_string(),
             burned: "0".to_string(),
             name: String::new(),
+            desc: String::new(),
+            frozen: false,
             tx_id: String::new(),
             op_add: 0,
             op_mod: 0,
@@ -260,6 +376,44 @@ impl Default for StateTokenType {
     }
 }
 
+/// Immutable deploy parameters of a token, separate from its mutable state
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenDeployInfo {
+    pub tick: String,
+    pub name: String,
+    pub max: String,
+    pub lim: String,
+    pub pre: String,
+    pub dec: i32,
+    #[serde(rename = "mod")]
+    pub mod_type: String,
+    pub deployer: String,
+    pub deploy_tx_id: String,
+    pub deploy_op_score: u64,
+    pub deploy_daa_score: u64,
+    pub deploy_mts: i64,
+}
+
+impl From<&StateTokenType> for TokenDeployInfo {
+    fn from(token: &StateTokenType) -> Self {
+        Self {
+            tick: token.tick.clone(),
+            name: token.name.clone(),
+            max: token.max.clone(),
+            lim: token.lim.clone(),
+            pre: token.pre.clone(),
+            dec: token.dec,
+            mod_type: token.mod_type.clone(),
+            deployer: token.from.clone(),
+            deploy_tx_id: token.tx_id.clone(),
+            deploy_op_score: token.op_add,
+            // op_score is daa_score * multiplier + index within the block, as in Go version
+            deploy_daa_score: token.op_add / crate::config::get_op_score_multiplier(),
+            deploy_mts: token.mts_add,
+        }
+    }
+}
+
 // State Balance type corresponding to Go version
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct StateBalanceType {
@@ -449,6 +603,13 @@ pub struct OperationData {
     pub ca: Option<String>, // Add ca field
 }
 
+/// Target of a rollback whose data write may have landed before the runtime state update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingRollback {
+    pub block_hash: String,
+    pub daa_score: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeState {
     pub last_processed_block: String,
@@ -605,6 +766,38 @@ impl RollbackStatus {
     }
 }
 
+/// Disk pressure level of the RocksDB volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiskPressure {
+    Normal,
+    Low,
+    Critical,
+}
+
+/// Disk space and inode usage of the RocksDB volume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskSpaceStatus {
+    pub path: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub total_inodes: u64,
+    pub available_inodes: u64,
+    pub pressure: DiskPressure,
+    pub checked_at: u64,
+}
+
+/// RocksDB write stall indicators, read from DB properties
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteStallStatus {
+    pub delayed_write_rate: u64,
+    pub write_stopped: bool,
+    pub pending_compaction_bytes: u64,
+    pub immutable_memtables: u64,
+    /// Writes are delayed/stopped or a configured threshold is reached
+    pub stalled: bool,
+    pub checked_at: u64,
+}
+
 impl Default for TokenData {
     fn default() -> Self {
         Self {
@@ -707,6 +900,8 @@ pub struct DataStateMapType {
     pub state_balance_map: std::collections::HashMap<String, Option<StateBalanceType>>,
     pub state_market_map: std::collections::HashMap<String, Option<StateMarketType>>,
     pub state_blacklist_map: std::collections::HashMap<String, Option<StateBlacklistType>>,
+    #[serde(default)]
+    pub state_mint_rate_map: std::collections::HashMap<String, Option<MintRateCount>>,
 }
 
 impl DataStateMapType {
@@ -716,6 +911,7 @@ impl DataStateMapType {
             state_balance_map: std::collections::HashMap::new(),
             state_market_map: std::collections::HashMap::new(),
             state_blacklist_map: std::collections::HashMap::new(),
+            state_mint_rate_map: std::collections::HashMap::new(),
         }
     }
 }
@@ -0,0 +1,143 @@
+use crate::storage::types::*;
+use anyhow::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Field carrying the schema version of a persisted JSON object
+pub const SCHEMA_VERSION_FIELD: &str = "v";
+/// Version of blobs written before versioning was added, they carry no version field
+pub const SCHEMA_VERSION_LEGACY: u32 = 1;
+
+/// A struct persisted as a versioned JSON object. Reads upgrade older blobs one version at a time
+/// through `migrate` before deserializing, so a layout change only needs a new migration step.
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// Version written by this build
+    const SCHEMA_VERSION: u32;
+
+    /// Upgrade a blob of `version` to `version + 1`
+    fn migrate(_version: u32, _object: &mut Map<String, Value>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// JSON object of `value` tagged with its schema version
+pub fn to_value<T: Versioned>(value: &T) -> Result<Value> {
+    let mut json = serde_json::to_value(value)?;
+    let object = json.as_object_mut().ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} is not persisted as an object",
+            std::any::type_name::<T>()
+        )
+    })?;
+    object.insert(SCHEMA_VERSION_FIELD.to_string(), T::SCHEMA_VERSION.into());
+    Ok(json)
+}
+
+pub fn encode<T: Versioned>(value: &T) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&to_value(value)?)?)
+}
+
+/// Migrate a persisted JSON object to the current schema version and deserialize it
+pub fn from_value<T: Versioned>(mut json: Value) -> Result<T> {
+    let type_name = std::any::type_name::<T>();
+    let object = json
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Persisted {} is not an object", type_name))?;
+    let version = match object.remove(SCHEMA_VERSION_FIELD) {
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Invalid schema version of {}: {}", type_name, version)
+            })?,
+        None => SCHEMA_VERSION_LEGACY,
+    };
+    if version > T::SCHEMA_VERSION {
+        return Err(anyhow::anyhow!(
+            "{} schema version {} is newer than the supported version {}",
+            type_name,
+            version,
+            T::SCHEMA_VERSION
+        ));
+    }
+    for from in version..T::SCHEMA_VERSION {
+        T::migrate(from, object)?;
+    }
+    Ok(serde_json::from_value(json)?)
+}
+
+pub fn decode<T: Versioned>(data: &[u8]) -> Result<T> {
+    from_value(serde_json::from_slice(data)?)
+}
+
+/// Versioned objects of a persisted list, each element carries its own version
+pub fn to_values<T: Versioned>(values: &[T]) -> Result<Vec<Value>> {
+    values.iter().map(to_value).collect()
+}
+
+pub fn from_values<T: Versioned>(values: Vec<Value>) -> Result<Vec<T>> {
+    values.into_iter().map(from_value).collect()
+}
+
+// Version 2 is the first tagged layout, version 1 blobs only differ where a migration says so
+
+impl Versioned for StateTokenType {
+    const SCHEMA_VERSION: u32 = 3;
+
+    fn migrate(version: u32, object: &mut Map<String, Value>) -> Result<()> {
+        // Tokens deployed before update existed carry no desc
+        if version == 1 {
+            object
+                .entry("desc")
+                .or_insert_with(|| Value::String(String::new()));
+        }
+        // Tokens saved before freeze existed were never frozen
+        if version == 2 {
+            object.entry("frozen").or_insert(Value::Bool(false));
+        }
+        Ok(())
+    }
+}
+
+impl Versioned for StateBalanceType {
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+impl Versioned for StateMarketType {
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+impl Versioned for StateBlacklistType {
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+impl Versioned for MintRateCount {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl Versioned for DataOperationType {
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+impl Versioned for DataRollbackType {
+    const SCHEMA_VERSION: u32 = 2;
+}
+
+impl Versioned for DataVspcType {
+    const SCHEMA_VERSION: u32 = 3;
+
+    fn migrate(version: u32, object: &mut Map<String, Value>) -> Result<()> {
+        // Entries saved before the selected parent was captured have no linkage
+        if version == 2 {
+            object
+                .entry("selected_parent")
+                .or_insert_with(|| Value::String(String::new()));
+        }
+        Ok(())
+    }
+}
+
+impl Versioned for VSPCData {
+    const SCHEMA_VERSION: u32 = 2;
+}
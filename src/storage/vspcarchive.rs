@@ -0,0 +1,58 @@
+use crate::storage::backend::StorageBackend;
+use crate::storage::types::{DataVspcType, VSPCData};
+use crate::storage::versioned;
+use anyhow::Result;
+use rocksdb::WriteBatch;
+
+/// Archived VSPC entry of a DAA score. Legacy `VSPCData` entries share the prefix, keyed by
+/// block hash.
+fn vspc_key(daa_score: u64) -> String {
+    format!("vspc:{}", daa_score)
+}
+
+/// DAA score of a `vspc:` value, archived entries and legacy ones alike
+pub fn vspc_daa_score(value: &[u8]) -> Option<u64> {
+    match versioned::decode::<DataVspcType>(value) {
+        Ok(vspc) => Some(vspc.daa_score),
+        Err(_) => versioned::decode::<VSPCData>(value)
+            .ok()
+            .map(|vspc_data| vspc_data.daa_score),
+    }
+}
+
+/// Archived VSPC entry of `daa_score`, None when no processed block has that score
+pub fn get_vspc(backend: &dyn StorageBackend, daa_score: u64) -> Result<Option<DataVspcType>> {
+    match backend.get_raw(&vspc_key(daa_score))? {
+        Some(value) => Ok(Some(versioned::decode(&value)?)),
+        None => Ok(None),
+    }
+}
+
+/// Stage the archive changes of replacing the runtime VSPC list `previous` with `current`.
+/// Entries missing from `current` at or after its first DAA score were rolled back, those
+/// before it only aged out of the runtime window and stay archived.
+pub fn stage_runtime_list(
+    batch: &mut WriteBatch,
+    previous: &[DataVspcType],
+    current: &[DataVspcType],
+) -> Result<()> {
+    let contains = |list: &[DataVspcType], vspc: &DataVspcType| {
+        list.iter()
+            .any(|entry| entry.daa_score == vspc.daa_score && entry.hash == vspc.hash)
+    };
+    let kept_from = current.first().map_or(0, |vspc| vspc.daa_score);
+    for vspc in previous {
+        if vspc.daa_score >= kept_from && !contains(current, vspc) {
+            batch.delete(vspc_key(vspc.daa_score).as_bytes());
+        }
+    }
+    for vspc in current {
+        if !contains(previous, vspc) {
+            batch.put(
+                vspc_key(vspc.daa_score).as_bytes(),
+                versioned::encode(vspc)?,
+            );
+        }
+    }
+    Ok(())
+}
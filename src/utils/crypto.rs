@@ -23,6 +23,14 @@ pub fn checksum(data: &[u8]) -> u32 {
     data.iter().fold(0u32, |acc, &byte| acc.wrapping_add(byte as u32))
 }
 
+/// Constant-time byte comparison, avoids leaking the matching prefix length through timing
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,6 +53,13 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token_longer"));
+    }
+
     #[test]
     fn test_checksum() {
         let data = b"test";
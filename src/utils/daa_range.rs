@@ -0,0 +1,72 @@
+use anyhow::Result;
+
+/// Mainnet DAA score ranges, consistent with Go version
+pub const DAA_SCORE_RANGE_MAINNET: [[u64; 2]; 2] = [[83441551, 83525600], [90090600, u64::MAX]];
+
+/// Indexed DAA score ranges, each `[start, end]` is inclusive on both edges.
+/// Ranges are sorted and disjoint once validated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaaScoreRanges {
+    ranges: Vec<[u64; 2]>,
+}
+
+impl DaaScoreRanges {
+    pub fn new(ranges: Vec<[u64; 2]>) -> Self {
+        Self { ranges }
+    }
+
+    pub fn mainnet() -> Self {
+        Self::new(DAA_SCORE_RANGE_MAINNET.to_vec())
+    }
+
+    /// Ranges used by the scanner, the configured ones only apply on testnet, as in the Go version
+    pub fn for_network(configured: &[[u64; 2]], testnet: bool) -> Self {
+        if !testnet || configured.is_empty() {
+            Self::mainnet()
+        } else {
+            Self::new(configured.to_vec())
+        }
+    }
+
+    /// Check every range is non-empty and starts after the previous one ends
+    pub fn validate(&self) -> Result<()> {
+        for (i, range) in self.ranges.iter().enumerate() {
+            if range[0] >= range[1] {
+                return Err(anyhow::anyhow!(
+                    "Invalid daa_score_range[{}]: start must be less than end",
+                    i
+                ));
+            }
+            if i > 0 && range[0] <= self.ranges[i - 1][1] {
+                return Err(anyhow::anyhow!(
+                    "Invalid daa_score_range[{}]: must start after the previous range ends",
+                    i
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Start of the first range, where scanning begins
+    pub fn first_start(&self) -> Option<u64> {
+        self.ranges.first().map(|range| range[0])
+    }
+
+    pub fn contains(&self, daa_score: u64) -> bool {
+        self.ranges
+            .iter()
+            .any(|range| daa_score >= range[0] && daa_score <= range[1])
+    }
+
+    /// `daa_score` itself when indexed, otherwise the start of the next range, None past the last range
+    pub fn next_valid(&self, daa_score: u64) -> Option<u64> {
+        self.ranges
+            .iter()
+            .find(|range| daa_score <= range[1])
+            .map(|range| daa_score.max(range[0]))
+    }
+}
@@ -0,0 +1,50 @@
+/// Protocol header carried by every KASPLEX envelope, matched case-insensitively
+pub const PROTOCOL_HEADER: &str = "KASPLEX";
+
+/// Opening of the KASPLEX envelope in a P2SH redeem script: `OP_FALSE OP_IF` followed by a
+/// push of the protocol header. Variants only differ in the opcode pushing the header.
+/// OP_RETURN outputs are never spendable by a reveal, so they carry no envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeVariant {
+    /// `OP_DATA_7`, the standard encoding, corresponding to Go version
+    DirectPush,
+    /// `OP_PUSHDATA1 0x07`
+    PushData1,
+    /// `OP_PUSHDATA2 0x0007`
+    PushData2,
+}
+
+/// Supported variants, checked in order
+pub const ENVELOPE_VARIANTS: [EnvelopeVariant; 3] = [
+    EnvelopeVariant::DirectPush,
+    EnvelopeVariant::PushData1,
+    EnvelopeVariant::PushData2,
+];
+
+impl EnvelopeVariant {
+    /// Hex of the opcodes preceding the header
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::DirectPush => "006307",
+            Self::PushData1 => "00634c07",
+            Self::PushData2 => "00634d0700",
+        }
+    }
+
+    /// Match the envelope opening at `n` of a lowercase hex script, returns the variant and the
+    /// position after the header, None for an unknown opening or header
+    pub fn parse(script: &str, n: usize) -> Option<(Self, usize)> {
+        let rest = script.get(n..)?;
+        ENVELOPE_VARIANTS.into_iter().find_map(|variant| {
+            let prefix = variant.prefix();
+            let header = rest
+                .strip_prefix(prefix)?
+                .get(..PROTOCOL_HEADER.len() * 2)?;
+            let header = String::from_utf8(hex::decode(header).ok()?).ok()?;
+            if !header.eq_ignore_ascii_case(PROTOCOL_HEADER) {
+                return None;
+            }
+            Some((variant, n + prefix.len() + PROTOCOL_HEADER.len() * 2))
+        })
+    }
+}
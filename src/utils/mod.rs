@@ -1,6 +1,10 @@
 pub mod address;
 pub mod batch;
 pub mod crypto;
+pub mod daa_range;
+pub mod envelope;
 pub mod script;
 pub mod script_builder;
 pub mod script_parser;
+pub mod shutdown;
+pub mod utxo;
@@ -94,6 +94,7 @@ pub fn parse_script(_script_str: &str) -> Result<DataScriptType> {
         mod_type: "".to_string(),
         name: None,
         ca: None,
+        desc: None,
     };
 
     Ok(script)
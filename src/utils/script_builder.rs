@@ -90,6 +90,27 @@ impl ScriptBuilder {
         Ok(hex::encode(json_str.as_bytes()))
     }
 
+    /// Build token metadata update script
+    /// Field order: p, op, tick, name, desc
+    pub fn build_update_script(tick: &str, name: &str, desc: &str) -> Result<String> {
+        let json_str = format!(
+            r#"{{"p":"KRC-20","op":"update","tick":"{}","name":{},"desc":{}}}"#,
+            tick,
+            serde_json::to_string(name)?,
+            serde_json::to_string(desc)?
+        );
+
+        Ok(hex::encode(json_str.as_bytes()))
+    }
+
+    /// Build token freeze script
+    /// Field order: p, op, tick
+    pub fn build_freeze_script(tick: &str) -> Result<String> {
+        let json_str = format!(r#"{{"p":"KRC-20","op":"freeze","tick":"{}"}}"#, tick);
+
+        Ok(hex::encode(json_str.as_bytes()))
+    }
+
     /// Build blacklist script
     /// Field order: p, op, tick, blacklist
     pub fn build_blacklist_script(tick: &str, blacklist: &str) -> Result<String> {
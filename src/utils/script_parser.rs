@@ -1,15 +1,21 @@
+use crate::operations::metrics::{ScriptRejection, record_script_rejection};
 use crate::storage::types::*;
+use crate::utils::envelope::EnvelopeVariant;
+use crate::utils::utxo::{self, UtxoIndex};
 use anyhow::Result;
 use blake2::{Blake2b, Digest};
+use kaspa_addresses::{Address, Prefix, Version};
 use serde_json;
 use std::collections::HashMap;
+use tracing::debug;
 
 /// Script parser, corresponding to Go version script parsing functionality
 pub struct ScriptParser;
 
 impl ScriptParser {
-    /// Parse P2SH transaction input script, corresponding to Go version parseScriptInput
-    pub fn parse_script_input(script: &str) -> Result<(bool, Vec<String>)> {
+    /// Parse P2SH transaction input script, corresponding to Go version parseScriptInput. The
+    /// sender address is one of the `testnet` network.
+    pub fn parse_script_input(script: &str, testnet: bool) -> Result<(bool, Vec<String>)> {
         let script = script.to_lowercase();
         let len_script = script.len();
 
@@ -219,20 +225,9 @@ impl ScriptParser {
         }
 
         // Check protocol header, corresponding to Go version protocol header validation
-        if len_script < (n + 22) {
-            return Ok((false, Vec::new()));
-        }
-        flag = script[n..n + 6].to_string();
-        n += 6;
-        if flag != "006307" {
-            return Ok((false, Vec::new()));
-        }
-        flag = script[n..n + 14].to_string();
-        n += 14;
-        let decoded = hex::decode(&flag)?;
-        let header = String::from_utf8(decoded)?.to_uppercase();
-        if header != "KASPLEX" {
-            return Ok((false, Vec::new()));
+        match EnvelopeVariant::parse(&script, n) {
+            Some((_, new_n)) => n = new_n,
+            None => return Ok((false, Vec::new())),
         }
 
         // Get parameters and JSON data
@@ -288,9 +283,9 @@ impl ScriptParser {
 
         // Get sender address
         let from = if multisig {
-            Self::conv_k_pub_to_p2sh(&k_pub, false) // Temporarily hardcoded testnet=false
+            Self::conv_k_pub_to_p2sh(&k_pub, testnet)
         } else {
-            Self::conv_k_pub_to_addr(&k_pub, false) // Temporarily hardcoded testnet=false
+            Self::conv_k_pub_to_addr(&k_pub, testnet)
         };
 
         Ok((true, vec![from, p0, p1, p2, script_sig]))
@@ -317,12 +312,15 @@ impl ScriptParser {
             return Ok(None);
         }
 
+        // Operations are collected in input order, the node lists inputs by their position in the
+        // transaction. This order defines the position of each script in op_script and therefore
+        // the order in which they are applied, as in Go version
         let mut op_script = Vec::new();
         let mut script_sig = String::new();
 
         for (i, input) in inputs.iter().enumerate() {
             if let Some(signature_script) = input.get("signatureScript").and_then(|v| v.as_str()) {
-                let (is_op, script_info) = Self::parse_script_input(signature_script)?;
+                let (is_op, script_info) = Self::parse_script_input(signature_script, testnet)?;
                 if !is_op || script_info[0].is_empty() {
                     continue;
                 }
@@ -346,11 +344,29 @@ impl ScriptParser {
                     }
                 }
 
-                // Validate script
-                if !Self::validate_p(&script.p)
-                    || !Self::validate_op(&script.op)
-                    || !Self::validate_ascii(&script.to.clone().unwrap_or_default())
-                {
+                // Reject oversized fields before they can reach state
+                if let Err(e) = crate::operations::validate_field_sizes(&script) {
+                    debug!("Skipping script of tx {}: {}", tx_data.tx_id, e);
+                    record_script_rejection(ScriptRejection::Oversized);
+                    continue;
+                }
+
+                // Validate script, unregistered operations only pass when leniency is configured
+                let op_valid =
+                    Self::validate_op(&script.op) || crate::config::is_unknown_op_allowed();
+                let rejection = if !Self::validate_p(&script.p) {
+                    Some(ScriptRejection::BadProtocol)
+                } else if !op_valid {
+                    Some(ScriptRejection::BadOp)
+                } else if !Self::envelope_op_matches(&script.op, &script_info[3]) {
+                    Some(ScriptRejection::OpMismatch)
+                } else if !Self::validate_ascii(&script.to.clone().unwrap_or_default()) {
+                    Some(ScriptRejection::NonAscii)
+                } else {
+                    None
+                };
+                if let Some(rejection) = rejection {
+                    record_script_rejection(rejection);
                     continue;
                 }
 
@@ -359,6 +375,7 @@ impl ScriptParser {
 
                 // Validate operation
                 if !Self::validate_operation(&script, &tx_data.tx_id, tx_data.daa_score, testnet) {
+                    record_script_rejection(ScriptRejection::InvalidOperation);
                     continue;
                 }
 
@@ -368,8 +385,8 @@ impl ScriptParser {
                     continue;
                 }
 
-                // Check if it is a recyclable operation
-                if !Self::is_op_recycle(&script.op) {
+                // Recyclable operations need a valid primary operation from input 0
+                if op_script.is_empty() || !Self::is_op_recycle(&script.op) {
                     continue;
                 }
 
@@ -413,9 +430,21 @@ impl ScriptParser {
     pub fn parse_op_data_list(
         tx_data_list: &[DataTransactionType],
         testnet: bool,
+    ) -> Result<Vec<DataOperationType>> {
+        Self::parse_op_data_list_with_multiplier(
+            tx_data_list,
+            testnet,
+            crate::config::get_op_score_multiplier(),
+        )
+    }
+
+    /// Parse operation data list, scoring operations with `multiplier` slots per DAA score
+    pub fn parse_op_data_list_with_multiplier(
+        tx_data_list: &[DataTransactionType],
+        testnet: bool,
+        multiplier: u64,
     ) -> Result<Vec<DataOperationType>> {
         let mut op_data_map = HashMap::new();
-        let mut tx_id_map = HashMap::new();
 
         for tx_data in tx_data_list {
             if let Ok(Some(op_data)) = Self::parse_op_data(tx_data, testnet) {
@@ -427,67 +456,54 @@ impl ScriptParser {
                         Self::get_operation_fee(&first_script.op, op_data.daa_score);
                 }
 
-                if op_data.fee_least > 0 {
-                    // Collect input transaction IDs
-                    if let Some(tx_json) = &tx_data.data {
-                        if let Some(inputs) = tx_json.get("inputs").and_then(|v| v.as_array()) {
-                            for input in inputs {
-                                if let Some(tx_id) = input
-                                    .get("previousOutpoint")
-                                    .and_then(|p| p.get("transactionId"))
-                                    .and_then(|v| v.as_str())
-                                {
-                                    tx_id_map.insert(tx_id.to_string(), true);
-                                }
-                            }
-                        }
-                    }
-                }
-
                 op_data_map.insert(op_data.tx_id.clone(), op_data);
             }
         }
 
+        // Transactions of one DAA score are scored in tx_id order, the node may return them in any
+        // order and reprocessing after a reorg must assign the same op_scores
+        let mut ordered_tx_list: Vec<&DataTransactionType> = tx_data_list.iter().collect();
+        for same_daa_score in ordered_tx_list.chunk_by_mut(|a, b| a.daa_score == b.daa_score) {
+            same_daa_score.sort_by(|a, b| a.tx_id.cmp(&b.tx_id));
+        }
+
         // Calculate operation score and fee
         let mut op_data_list = Vec::new();
         let mut daa_score_now = 0u64;
         let mut op_score = 0u64;
+        // First op_score of the DAA score after daa_score_now
+        let mut op_score_next = 0u64;
+        // Chained transactions spend outputs created earlier in the batch
+        let utxo_index = UtxoIndex::from_transactions(tx_data_list);
 
-        for tx_data in tx_data_list {
+        for tx_data in ordered_tx_list {
             if let Some(op_data) = op_data_map.get_mut(&tx_data.tx_id) {
                 if daa_score_now != tx_data.daa_score {
                     daa_score_now = tx_data.daa_score;
-                    op_score = daa_score_now * 10000;
+                    op_score_next = daa_score_now
+                        .checked_add(1)
+                        .and_then(|daa_score| daa_score.checked_mul(multiplier))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "op_score of DAA score {} overflows with opScoreMultiplier {}",
+                                daa_score_now,
+                                multiplier
+                            )
+                        })?;
+                    op_score = op_score_next - multiplier;
+                }
+                // Any further operation would take the op_score of the next DAA score
+                if op_score >= op_score_next {
+                    return Err(anyhow::anyhow!(
+                        "DAA score {} has more than {} operations, a larger opScoreMultiplier requires reindexing",
+                        daa_score_now,
+                        multiplier
+                    ));
                 }
 
                 op_data.op_score = op_score;
 
-                // Calculate actual fee
-                if op_data.fee_least > 0 {
-                    let mut amount_in = 0u64;
-                    let mut amount_out = 0u64;
-
-                    // Calculate output amount
-                    if let Some(tx_json) = &tx_data.data {
-                        if let Some(outputs) = tx_json.get("outputs").and_then(|v| v.as_array()) {
-                            for output in outputs {
-                                if let Some(amount) = output.get("amount").and_then(|v| v.as_u64())
-                                {
-                                    amount_out += amount;
-                                }
-                            }
-                        }
-                    }
-
-                    // Calculate input amount (simplified implementation)
-                    amount_in = amount_out + op_data.fee_least;
-
-                    if amount_in <= amount_out {
-                        op_data.fee = 0;
-                    } else {
-                        op_data.fee = amount_in - amount_out;
-                    }
-                }
+                Self::calculate_fee(op_data, tx_data, &utxo_index);
 
                 op_data_list.push(op_data.clone());
                 op_score += 1;
@@ -497,6 +513,65 @@ impl ScriptParser {
         Ok(op_data_list)
     }
 
+    /// Actual fee of a fee paying operation, spent input amounts minus output amounts.
+    /// The fee stays unknown (0) while `utxo_index` cannot resolve every input, which
+    /// rejects the operation rather than assuming the required fee was paid.
+    fn calculate_fee(
+        op_data: &mut DataOperationType,
+        tx_data: &DataTransactionType,
+        utxo_index: &UtxoIndex,
+    ) {
+        if op_data.fee_least == 0 {
+            return;
+        }
+        let Some(tx_json) = &tx_data.data else {
+            op_data.fee = op_data.fee_least;
+            return;
+        };
+        op_data.fee = utxo_index.amount_in(tx_json).map_or(0, |amount_in| {
+            amount_in.saturating_sub(utxo::amount_out(tx_json))
+        });
+    }
+
+    /// Recalculate the fees of parsed operations against `utxo_index`, once inputs the batch
+    /// did not create were resolved
+    pub fn calculate_fees(
+        op_data_list: &mut [DataOperationType],
+        tx_data_list: &[DataTransactionType],
+        utxo_index: &UtxoIndex,
+    ) {
+        let tx_data_map: HashMap<&str, &DataTransactionType> = tx_data_list
+            .iter()
+            .map(|tx_data| (tx_data.tx_id.as_str(), tx_data))
+            .collect();
+        for op_data in op_data_list {
+            if let Some(tx_data) = tx_data_map.get(op_data.tx_id.as_str()) {
+                Self::calculate_fee(op_data, tx_data, utxo_index);
+            }
+        }
+    }
+
+    /// Input transactions of fee paying operations missing from `utxo_index`
+    pub fn fee_input_tx_ids(
+        op_data_list: &[DataOperationType],
+        tx_data_list: &[DataTransactionType],
+        utxo_index: &UtxoIndex,
+    ) -> Vec<String> {
+        let tx_data_map: HashMap<&str, &DataTransactionType> = tx_data_list
+            .iter()
+            .map(|tx_data| (tx_data.tx_id.as_str(), tx_data))
+            .collect();
+        let mut tx_id_list: Vec<String> = op_data_list
+            .iter()
+            .filter(|op_data| op_data.fee_least > 0)
+            .filter_map(|op_data| tx_data_map.get(op_data.tx_id.as_str())?.data.as_ref())
+            .flat_map(|tx_json| utxo_index.missing_inputs(tx_json))
+            .collect();
+        tx_id_list.sort();
+        tx_id_list.dedup();
+        tx_id_list
+    }
+
     /// Script collection extension, corresponding to Go version ScriptCollectEx
     fn script_collect_ex(
         i: usize,
@@ -577,30 +652,33 @@ impl ScriptParser {
     /// Convert script hash to P2SH address, corresponding to Go version ConvKPubToP2sh
     fn conv_k_pub_to_p2sh(k_pub: &str, testnet: bool) -> String {
         let decoded = hex::decode(k_pub).unwrap_or_default();
-        let addr = Self::encode_bech32(&decoded, testnet);
-        if testnet {
-            format!("kaspatest:{}", addr)
-        } else {
-            format!("kaspa:{}", addr)
-        }
+        Self::encode_bech32(Version::ScriptHash, &decoded, testnet)
     }
 
-    /// Convert public key to address, corresponding to Go version ConvKPubToAddr
+    /// Convert public key to address, corresponding to Go version ConvKPubToAddr. 32 byte keys
+    /// are Schnorr keys, 33 byte ones ECDSA keys.
     fn conv_k_pub_to_addr(k_pub: &str, testnet: bool) -> String {
         let decoded = hex::decode(k_pub).unwrap_or_default();
-        let addr = Self::encode_bech32(&decoded, testnet);
-        if testnet {
-            format!("kaspatest:{}", addr)
-        } else {
-            format!("kaspa:{}", addr)
-        }
+        let version = match decoded.len() {
+            33 => Version::PubKeyECDSA,
+            _ => Version::PubKey,
+        };
+        Self::encode_bech32(version, &decoded, testnet)
     }
 
-    /// Bech32 encoding implementation
-    fn encode_bech32(data: &[u8], _testnet: bool) -> String {
-        // Simplified Bech32 encoding implementation
-        // In actual applications, a dedicated Bech32 library should be used
-        hex::encode(data)
+    /// Kaspa address of a payload: the version byte (0x00 Schnorr, 0x01 ECDSA, 0x08 P2SH) and
+    /// the payload in the Kaspa Bech32 charset, with the 8 character checksum over the network
+    /// prefix. Empty for payloads of the wrong length.
+    fn encode_bech32(version: Version, payload: &[u8], testnet: bool) -> String {
+        if payload.len() != version.public_key_len() {
+            return String::new();
+        }
+        let prefix = if testnet {
+            Prefix::Testnet
+        } else {
+            Prefix::Mainnet
+        };
+        Address::new(prefix, version, payload).to_string()
     }
 
     fn validate_p(p: &str) -> bool {
@@ -613,6 +691,12 @@ impl ScriptParser {
         crate::operations::validate_op(&mut op_lower)
     }
 
+    /// An envelope parameter naming an operation has to name the operation of the JSON payload,
+    /// other parameter values are not checked
+    fn envelope_op_matches(op: &str, envelope_param: &str) -> bool {
+        !Self::validate_op(envelope_param) || envelope_param.eq_ignore_ascii_case(op)
+    }
+
     fn validate_ascii(s: &str) -> bool {
         crate::operations::validate_ascii(s)
     }
@@ -679,7 +763,20 @@ impl ScriptParser {
                 daa_score,
                 testnet,
             ),
-            _ => true, // Other operations temporarily return true
+            "update" => crate::operations::update::UpdateOperation::validate(
+                &mut script.clone(),
+                tx_id,
+                daa_score,
+                testnet,
+            ),
+            "freeze" => crate::operations::freeze::FreezeOperation::validate(
+                &mut script.clone(),
+                tx_id,
+                daa_score,
+                testnet,
+            ),
+            // Unregistered operations only pass when leniency is configured
+            _ => crate::config::is_unknown_op_allowed(),
         }
     }
 
@@ -741,6 +838,13 @@ impl ScriptParser {
                     300000000 // Old version fee
                 }
             }
+            "update" | "freeze" => {
+                if daa_score >= 110165000 {
+                    100000000 // New version fee
+                } else {
+                    50000000 // Old version fee
+                }
+            }
             _ => 0,
         }
     }
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Run a shutdown step, giving up after `grace`. Returns false when the step did not finish in
+/// time. The step runs on the blocking pool so a step stuck in a synchronous call, like a RocksDB
+/// flush, can not hold up the caller's timer.
+pub async fn run_within<F>(step: F, grace: Duration) -> Result<bool>
+where
+    F: Future<Output = Result<()>> + Send + 'static,
+{
+    let handle = tokio::runtime::Handle::current();
+    let task = tokio::task::spawn_blocking(move || handle.block_on(step));
+    match tokio::time::timeout(grace, task).await {
+        Ok(result) => {
+            result??;
+            Ok(true)
+        }
+        Err(_) => Ok(false),
+    }
+}
+
+/// Ask a task to stop through its `stop` flag and wait up to `grace` for it to finish on its own,
+/// so it completes the write in progress. Returns the task's output, or None when it panicked or
+/// did not finish in time and was aborted.
+pub async fn stop_task<T>(
+    stop: &AtomicBool,
+    mut handle: JoinHandle<T>,
+    grace: Duration,
+) -> Option<T> {
+    stop.store(true, Ordering::SeqCst);
+    match tokio::time::timeout(grace, &mut handle).await {
+        Ok(result) => result.ok(),
+        Err(_) => {
+            handle.abort();
+            None
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::storage::types::DataTransactionType;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Output amounts by transaction id, resolving the inputs of fee paying transactions.
+/// Holds the outputs of the current batch first, so chained transactions resolve without the node.
+#[derive(Debug, Clone, Default)]
+pub struct UtxoIndex {
+    outputs: HashMap<String, Vec<u64>>,
+}
+
+/// Output amount, the node reports it either as a number or as a decimal string
+fn output_amount(output: &Value) -> Option<u64> {
+    match output.get("amount")? {
+        Value::Number(amount) => amount.as_u64(),
+        Value::String(amount) => amount.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Previous outpoints `(transactionId, index)` spent by a transaction
+fn previous_outpoints(tx_json: &Value) -> impl Iterator<Item = (&str, usize)> {
+    tx_json
+        .get("inputs")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|input| {
+            let outpoint = input.get("previousOutpoint")?;
+            let tx_id = outpoint.get("transactionId")?.as_str()?;
+            let index = outpoint.get("index").and_then(|v| v.as_u64()).unwrap_or(0);
+            Some((tx_id, index as usize))
+        })
+}
+
+/// Sum of the output amounts of a transaction
+pub fn amount_out(tx_json: &Value) -> u64 {
+    tx_json
+        .get("outputs")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(output_amount)
+        .sum()
+}
+
+impl UtxoIndex {
+    /// Index of the outputs created by `tx_data_list`
+    pub fn from_transactions(tx_data_list: &[DataTransactionType]) -> Self {
+        let mut index = Self::default();
+        index.insert_transactions(tx_data_list);
+        index
+    }
+
+    /// Add the outputs of transactions carrying data, others are skipped
+    pub fn insert_transactions(&mut self, tx_data_list: &[DataTransactionType]) {
+        for tx_data in tx_data_list {
+            if let Some(tx_json) = &tx_data.data {
+                self.insert(&tx_data.tx_id, tx_json);
+            }
+        }
+    }
+
+    /// Add the outputs of one transaction
+    pub fn insert(&mut self, tx_id: &str, tx_json: &Value) {
+        let amounts = tx_json
+            .get("outputs")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .map(|output| output_amount(output).unwrap_or(0))
+            .collect();
+        self.outputs.insert(tx_id.to_string(), amounts);
+    }
+
+    /// Number of indexed transactions
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.outputs.clear();
+    }
+
+    /// Amount of output `index` of `tx_id`, None when the transaction is not indexed
+    pub fn output_amount(&self, tx_id: &str, index: usize) -> Option<u64> {
+        self.outputs.get(tx_id)?.get(index).copied()
+    }
+
+    /// Sum of the amounts a transaction spends, None while any input is unresolved or the
+    /// transaction data lists no previous outpoints
+    pub fn amount_in(&self, tx_json: &Value) -> Option<u64> {
+        let mut outpoints = previous_outpoints(tx_json).peekable();
+        outpoints.peek()?;
+        outpoints
+            .map(|(tx_id, index)| self.output_amount(tx_id, index))
+            .sum()
+    }
+
+    /// Transactions whose outputs a transaction spends but that are not indexed
+    pub fn missing_inputs(&self, tx_json: &Value) -> Vec<String> {
+        previous_outpoints(tx_json)
+            .filter(|(tx_id, _)| !self.outputs.contains_key(*tx_id))
+            .map(|(tx_id, _)| tx_id.to_string())
+            .collect()
+    }
+}
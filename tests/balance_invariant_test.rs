@@ -0,0 +1,235 @@
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::operations::invariant::BalanceInvariant;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+use std::sync::Arc;
+
+const TEST_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const OTHER_ADDRESS: &str =
+    "kaspatest:qzk5hv7svdmnxqgmepqjmvzzlcmlsx3qpg2wr6pmt6mqe2u3tx5fuy0ve0vs2";
+
+fn list_op(amt: &str) -> DataOperationType {
+    DataOperationType {
+        tx_id: "list_tx".to_string(),
+        daa_score: 2000,
+        block_accept: "block_hash".to_string(),
+        fee: 0,
+        fee_least: 0,
+        mts_add: 0,
+        op_score: 2000 * 10000,
+        op_accept: 0,
+        op_error: String::new(),
+        op_script: vec![DataScriptType {
+            p: "KRC-20".to_string(),
+            op: "list".to_string(),
+            from: Some(TEST_ADDRESS.to_string()),
+            to: None,
+            tick: Some("LOCK".to_string()),
+            max: None,
+            lim: None,
+            pre: None,
+            dec: None,
+            amt: Some(amt.to_string()),
+            utxo: None,
+            price: Some("1.5".to_string()),
+            mod_type: "".to_string(),
+            name: None,
+            ca: None,
+            desc: None,
+        }],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: Some(DataStatsType {
+            tick_affc: vec![],
+            address_affc: vec![],
+        }),
+    }
+}
+
+/// State of a token whose only holder owns the whole minted supply
+fn holder_state() -> DataStateMapType {
+    let mut state_map = DataStateMapType::new();
+    state_map.state_token_map.insert(
+        "LOCK".to_string(),
+        Some(StateTokenType {
+            tick: "LOCK".to_string(),
+            max: "1000".to_string(),
+            minted: "100".to_string(),
+            burned: "0".to_string(),
+            ..Default::default()
+        }),
+    );
+    state_map.state_balance_map.insert(
+        balance_key(),
+        Some(StateBalanceType {
+            address: TEST_ADDRESS.to_string(),
+            tick: "LOCK".to_string(),
+            dec: 8,
+            balance: "100".to_string(),
+            locked: "0".to_string(),
+            op_mod: 0,
+        }),
+    );
+    state_map
+}
+
+fn balance_key() -> String {
+    StateKey::balance(TEST_ADDRESS, "LOCK").map_key()
+}
+
+fn amounts(state_map: &DataStateMapType) -> (String, String) {
+    let balance = state_map.state_balance_map[&balance_key()]
+        .as_ref()
+        .unwrap();
+    (balance.balance.clone(), balance.locked.clone())
+}
+
+#[test]
+fn test_list_keeps_balance_invariant() {
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    let operation_manager = OperationManager::new(&storage);
+
+    let mut state_map = holder_state();
+    let mut op_data = list_op("60");
+    operation_manager
+        .execute_op_script_list(&mut op_data, &mut state_map, true)
+        .unwrap();
+    assert_eq!(amounts(&state_map), ("40".to_string(), "60".to_string()));
+
+    // Listing the rest locks the whole balance
+    let mut op_data = list_op("40");
+    operation_manager
+        .execute_op_script_list(&mut op_data, &mut state_map, true)
+        .unwrap();
+    assert_eq!(amounts(&state_map), ("0".to_string(), "100".to_string()));
+}
+
+#[test]
+fn test_list_over_locking_is_rejected() {
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    let operation_manager = OperationManager::new(&storage);
+
+    let mut state_map = holder_state();
+    let mut op_data = list_op("101");
+    operation_manager
+        .execute_op_script_list(&mut op_data, &mut state_map, true)
+        .unwrap();
+    assert_eq!(op_data.op_accept, -1);
+    assert_eq!(op_data.op_error, "Insufficient balance for list");
+    assert_eq!(amounts(&state_map), ("100".to_string(), "0".to_string()));
+}
+
+#[test]
+fn test_rejected_ops_do_not_halt_the_batch() {
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    let operation_manager = OperationManager::new(&storage);
+
+    let mut send = list_op("200");
+    send.tx_id = "send_tx".to_string();
+    send.op_script[0].op = "send".to_string();
+    send.op_script[0].to = Some(OTHER_ADDRESS.to_string());
+    send.op_script[0].price = None;
+    let mut batch = vec![list_op("200"), send, list_op("60")];
+
+    // Over-balance operations are rejected and the rest of the batch still executes
+    let mut state_map = holder_state();
+    operation_manager
+        .execute_batch(&mut batch, &mut state_map, "", true)
+        .unwrap();
+    let results: Vec<(i8, &str)> = batch
+        .iter()
+        .map(|op_data| (op_data.op_accept, op_data.op_error.as_str()))
+        .collect();
+    assert_eq!(
+        results,
+        [
+            (-1, "Insufficient balance for list"),
+            (-1, "Insufficient balance for send"),
+            (1, ""),
+        ]
+    );
+    assert_eq!(amounts(&state_map), ("40".to_string(), "60".to_string()));
+}
+
+#[test]
+fn test_invariant_violation_rejects_the_operation() {
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    let operation_manager = OperationManager::new(&storage);
+
+    // Burn lowers the supply before it finds the balance short, which breaks the invariant
+    let mut burn = list_op("200");
+    burn.tx_id = "burn_tx".to_string();
+    burn.op_script[0].op = "burn".to_string();
+    burn.op_script[0].price = None;
+    let mut batch = vec![burn, list_op("60")];
+
+    let mut state_map = holder_state();
+    if let Some(Some(token)) = state_map.state_token_map.get_mut("LOCK") {
+        token.minted = "500".to_string();
+    }
+    operation_manager
+        .execute_batch(&mut batch, &mut state_map, "", true)
+        .unwrap();
+    assert_eq!(batch[0].op_accept, -1);
+    assert_eq!(
+        batch[0].op_error,
+        "Invariant violated: holders of LOCK changed by 0 but supply by -200"
+    );
+    assert_eq!(batch[1].op_accept, 1);
+
+    // The rejected burn left no trace on the supply
+    let token = state_map.state_token_map["LOCK"].as_ref().unwrap();
+    assert_eq!(token.minted, "500");
+    assert_eq!(amounts(&state_map), ("40".to_string(), "60".to_string()));
+}
+
+#[test]
+fn test_balance_invariant_detects_broken_mutations() {
+    let mut keys = DataStateMapType::new();
+    keys.state_token_map.insert("LOCK".to_string(), None);
+    keys.state_balance_map.insert(balance_key(), None);
+    let state_map = holder_state();
+    let invariant = BalanceInvariant::capture(&keys, &state_map);
+    assert!(invariant.check(&state_map).is_ok());
+
+    let balance =
+        |state_map: &DataStateMapType| state_map.state_balance_map[&balance_key()].clone().unwrap();
+
+    // Locking without releasing the balance creates tokens
+    let mut over_locked = state_map.clone();
+    let mut entry = balance(&over_locked);
+    entry.locked = "60".to_string();
+    entry.balance = "50".to_string();
+    over_locked
+        .state_balance_map
+        .insert(balance_key(), Some(entry));
+    let err = invariant.check(&over_locked).unwrap_err();
+    assert!(err.to_string().contains("more than its balance released"));
+
+    // Crediting a holder without minting breaks the supply
+    let mut credited = state_map.clone();
+    let mut entry = balance(&credited);
+    entry.balance = "150".to_string();
+    credited
+        .state_balance_map
+        .insert(balance_key(), Some(entry));
+    let err = invariant.check(&credited).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("holders of LOCK changed by 50 but supply by 0")
+    );
+
+    // Burning from a holder keeps the invariant once the token records it
+    let mut burned = credited.clone();
+    let mut entry = balance(&burned);
+    entry.balance = "70".to_string();
+    burned.state_balance_map.insert(balance_key(), Some(entry));
+    if let Some(Some(token)) = burned.state_token_map.get_mut("LOCK") {
+        token.burned = "30".to_string();
+    }
+    assert!(invariant.check(&burned).is_ok());
+}
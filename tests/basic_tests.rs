@@ -1,4 +1,5 @@
 use kaspa_indexer_rust::config::types::*;
+use kaspa_indexer_rust::storage::state_key::{StateKey, StateKeyKind};
 use kaspa_indexer_rust::storage::types::*;
 
 #[test]
@@ -115,3 +116,30 @@ fn test_storage_operation_clone() {
         _ => panic!("Expected UpdateToken operations"),
     }
 }
+
+#[test]
+fn test_balance_key_with_underscore_address() {
+    // Splitting at every '_' used to cut this address in half
+    let address = "kaspa:test_address";
+    let key = StateKey::balance(address, "TEST");
+    assert_eq!(key.map_key(), "kaspa:test_address_TEST");
+    assert_eq!(
+        StateKey::from_map_key(StateKeyKind::Balance, &key.map_key()),
+        Some(key)
+    );
+
+    let key = StateKey::blacklist("TEST", address);
+    assert_eq!(
+        StateKey::from_map_key(StateKeyKind::Blacklist, &key.map_key()),
+        Some(key)
+    );
+
+    assert_eq!(
+        StateKey::from_map_key(StateKeyKind::Balance, "no-delimiter"),
+        None
+    );
+    assert_eq!(
+        StateKey::from_map_key(StateKeyKind::Balance, "address_"),
+        None
+    );
+}
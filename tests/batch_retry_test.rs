@@ -0,0 +1,118 @@
+use kaspa_indexer_rust::config::apply_batch_retry;
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::explorer::rollback::execute_batch_with_retry;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::backend::{MemoryBackend, StorageBackend};
+use kaspa_indexer_rust::storage::types::{DataStateMapType, StateTokenType};
+use rocksdb::WriteBatch;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Backend whose first `failures` batch writes fail
+struct FlakyBackend {
+    inner: MemoryBackend,
+    failures: u32,
+    writes: AtomicU32,
+}
+
+impl FlakyBackend {
+    fn new(failures: u32) -> Self {
+        Self {
+            inner: MemoryBackend::new(),
+            failures,
+            writes: AtomicU32::new(0),
+        }
+    }
+}
+
+impl StorageBackend for FlakyBackend {
+    fn get_raw(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.inner.get_raw(key)
+    }
+
+    fn put_raw(&self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+        self.inner.put_raw(key, value)
+    }
+
+    fn delete_raw(&self, key: &str) -> anyhow::Result<()> {
+        self.inner.delete_raw(key)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+        self.inner.scan_prefix(prefix)
+    }
+
+    fn write_batch(&self, batch: WriteBatch) -> anyhow::Result<()> {
+        if self.writes.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(anyhow::anyhow!("write stalled"));
+        }
+        self.inner.write_batch(batch)
+    }
+}
+
+async fn put_items(backend: &FlakyBackend) -> anyhow::Result<()> {
+    let items: Vec<String> = (0..4).map(|i| format!("item:{}", i)).collect();
+    execute_batch_with_retry(backend, &items, 2, |item, batch| {
+        batch.put(item.as_bytes(), b"1");
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_batch_retries_honor_configured_limit() {
+    apply_batch_retry(3, 1);
+
+    // Three failed writes are retried, the first chunk lands on the fourth attempt
+    let backend = FlakyBackend::new(3);
+    put_items(&backend).await.unwrap();
+    assert_eq!(backend.writes.load(Ordering::SeqCst), 5);
+    assert_eq!(backend.inner.len(), 4);
+
+    // One failure more than the limit gives the batch up without writing later chunks
+    let backend = FlakyBackend::new(4);
+    let err = put_items(&backend).await.unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Failed to execute batch after 3 retries: write stalled")
+    );
+    assert_eq!(backend.writes.load(Ordering::SeqCst), 4);
+    assert!(backend.inner.is_empty());
+
+    // Without retries the first failure is returned
+    apply_batch_retry(0, 1);
+    let backend = FlakyBackend::new(1);
+    assert!(put_items(&backend).await.is_err());
+    assert_eq!(backend.writes.load(Ordering::SeqCst), 1);
+    apply_batch_retry(5, 10);
+
+    // The scanner's state writes ride out a write stall the same way
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    let mut state_map = DataStateMapType::new();
+    state_map.state_token_map.insert(
+        "RETRY".to_string(),
+        Some(StateTokenType {
+            tick: "RETRY".to_string(),
+            max: "1000".to_string(),
+            ..Default::default()
+        }),
+    );
+    storage.rocksdb.set_fail_writes(true);
+    let recover = {
+        let storage = Arc::clone(&storage);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            storage.rocksdb.set_fail_writes(false);
+        })
+    };
+    rollback_manager
+        .save_op_state_batch(&[], &state_map)
+        .await
+        .unwrap();
+    recover.await.unwrap();
+    assert!(storage.state.get_state_token("RETRY").unwrap().is_some());
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use kaspa_indexer_rust::operations::*;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+
+const OWNER_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const OTHER_ADDRESS: &str =
+    "kaspatest:qpqz2vxj23kvh0m73ta2jjn2u4cv4tlufqns2eap8mxyyt0rvrxy6ejkful67";
+const CA: &str = "7f3c2b9a1d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8";
+
+fn transfer_script(ca: &str) -> DataScriptType {
+    DataScriptType {
+        p: "KRC-20".to_string(),
+        op: "transfer".to_string(),
+        from: Some(OWNER_ADDRESS.to_string()),
+        to: Some(OTHER_ADDRESS.to_string()),
+        tick: None,
+        max: None,
+        lim: None,
+        pre: None,
+        dec: None,
+        amt: Some("100".to_string()),
+        utxo: None,
+        price: None,
+        mod_type: "".to_string(),
+        name: None,
+        ca: Some(ca.to_string()),
+        desc: None,
+    }
+}
+
+fn op_data(script: DataScriptType) -> DataOperationType {
+    let mut op_data = common::op_data(1000, &script.op, "", "", "");
+    op_data.tx_id = "transfer_tx".to_string();
+    op_data.mts_add = 1700000000;
+    op_data.op_script = vec![script];
+    op_data
+}
+
+/// State of a token issued in issue mode, keyed by its deploy tx_id
+fn issued_state_map() -> DataStateMapType {
+    let mut state_map = DataStateMapType::new();
+    let token = StateTokenType {
+        tick: CA.to_string(),
+        max: "0".to_string(),
+        lim: "0".to_string(),
+        dec: 8,
+        mod_type: "issue".to_string(),
+        minted: "1000".to_string(),
+        from: OWNER_ADDRESS.to_string(),
+        to: OWNER_ADDRESS.to_string(),
+        op_add: 1000 * 10000,
+        op_mod: 1000 * 10000,
+        mts_add: 1600000000,
+        mts_mod: 1600000000,
+        ..Default::default()
+    };
+    state_map
+        .state_token_map
+        .insert(CA.to_string(), Some(token));
+    for (address, balance) in [(OWNER_ADDRESS, "1000"), (OTHER_ADDRESS, "0")] {
+        state_map.state_balance_map.insert(
+            StateKey::balance(address, CA).map_key(),
+            Some(StateBalanceType {
+                address: address.to_string(),
+                tick: CA.to_string(),
+                dec: 8,
+                balance: balance.to_string(),
+                locked: "0".to_string(),
+                op_mod: 0,
+            }),
+        );
+    }
+    state_map
+}
+
+fn balance(state_map: &DataStateMapType, address: &str) -> String {
+    state_map.state_balance_map[&StateKey::balance(address, CA).map_key()]
+        .as_ref()
+        .unwrap()
+        .balance
+        .clone()
+}
+
+#[test]
+fn test_transfer_by_valid_ca_accepted() {
+    let mut state_map = issued_state_map();
+    // The contract address is normalized to the lowercase key of the token
+    let mut script = transfer_script(&CA.to_uppercase());
+    assert!(TransferOperation::validate(
+        &mut script,
+        "transfer_tx",
+        2000,
+        true
+    ));
+    assert_eq!(script.tick.as_deref(), Some(CA));
+
+    let mut op_data = op_data(script);
+    TransferOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, 1, "{}", op_data.op_error);
+    assert_eq!(balance(&state_map, OWNER_ADDRESS), "900");
+    assert_eq!(balance(&state_map, OTHER_ADDRESS), "100");
+}
+
+#[test]
+fn test_malformed_or_unknown_ca_rejected() {
+    for ca in [&CA[1..], "not-a-contract-address", &CA.replace('f', "g")] {
+        let mut script = transfer_script(ca);
+        script.tick = Some("ICED".to_string());
+        assert!(
+            !TransferOperation::validate(&mut script, "transfer_tx", 2000, true),
+            "{}",
+            ca
+        );
+    }
+
+    // Well formed, but no token was issued under it
+    let mut state_map = issued_state_map();
+    let unknown = CA.replace('7', "0");
+    let mut script = transfer_script(&unknown);
+    assert!(TransferOperation::validate(
+        &mut script,
+        "transfer_tx",
+        2000,
+        true
+    ));
+    state_map.state_token_map.insert(unknown.clone(), None);
+    let mut op_data = op_data(script);
+    TransferOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, -1);
+    assert_eq!(op_data.op_error, format!("Token '{}' not found", unknown));
+    assert_eq!(balance(&state_map, OWNER_ADDRESS), "1000");
+}
@@ -0,0 +1,58 @@
+use kaspa_indexer_rust::config::{apply_checkpoint_hash, get_checkpoint_hash};
+use kaspa_indexer_rust::storage::backend::{MemoryBackend, StorageBackend};
+use kaspa_indexer_rust::storage::checkpoint::{
+    self, CheckpointHash, RangeCheckpoint, compute_checkpoint_with,
+};
+
+/// Seal range 1 of a fresh store holding the same two operations
+fn seal_range() -> RangeCheckpoint {
+    let memory = MemoryBackend::new();
+    memory.put_raw("oplist:1:100000", b"a").unwrap();
+    memory.put_raw("oplist:1:100001", b"b").unwrap();
+    memory.put_raw("oplist:2:200000", b"c").unwrap();
+    let sealed = checkpoint::seal_completed_ranges(&memory, &[100000, 200000]).unwrap();
+    assert_eq!(sealed.len(), 1);
+    sealed[0].clone()
+}
+
+#[test]
+fn test_checkpoint_hash_deterministic_and_configurable() {
+    let entries = [(100000, b"a".to_vec()), (100001, b"b".to_vec())];
+    assert_eq!(get_checkpoint_hash(), CheckpointHash::Blake3);
+
+    // The same state seals the same checkpoint in every run
+    let first = seal_range();
+    assert_eq!(first, seal_range());
+    assert_eq!(first.hash, CheckpointHash::Blake3);
+    assert_eq!(
+        first.checkpoint,
+        compute_checkpoint_with(CheckpointHash::Blake3, "", 1, &entries)
+    );
+
+    apply_checkpoint_hash(CheckpointHash::Sha256);
+    let sha256 = seal_range();
+    assert_eq!(sha256, seal_range());
+    assert_eq!(sha256.hash, CheckpointHash::Sha256);
+    assert_eq!(
+        sha256.checkpoint,
+        compute_checkpoint_with(CheckpointHash::Sha256, "", 1, &entries)
+    );
+    assert_ne!(sha256.checkpoint, first.checkpoint);
+    apply_checkpoint_hash(CheckpointHash::Blake3);
+
+    assert_eq!(
+        CheckpointHash::from_name("SHA256"),
+        Some(CheckpointHash::Sha256)
+    );
+    assert_eq!(CheckpointHash::from_name("md5"), None);
+}
+
+#[test]
+fn test_checkpoints_without_hash_read_as_sha256() {
+    let stored: RangeCheckpoint =
+        serde_json::from_str(r#"{"range":1,"prev_range":null,"checkpoint":"00"}"#).unwrap();
+    assert_eq!(stored.hash, CheckpointHash::Sha256);
+
+    let json = serde_json::to_value(&stored).unwrap();
+    assert_eq!(json["hash"], "sha256");
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::{setup_storage, spawn_server};
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::storage::checkpoint::{self, RangeCheckpoint};
+use kaspa_indexer_rust::storage::types::*;
+use std::sync::Arc;
+
+const TEST_ADDRESS: &str = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+fn op_data(daa_score: u64, index: u64) -> DataOperationType {
+    let mut op_data = common::op_data(0, "mint", "TEST", TEST_ADDRESS, TEST_ADDRESS);
+    op_data.tx_id = format!("mint_{}_{}", daa_score, index);
+    op_data.daa_score = daa_score;
+    op_data.op_score = daa_score * 10000 + index;
+    op_data.op_accept = 1;
+    op_data.op_script[0].amt = Some("1000".to_string());
+    op_data.ss_info = None;
+    op_data
+}
+
+#[tokio::test]
+async fn test_checkpoint_chain_across_ranges() {
+    let storage = setup_storage("./test_data/checkpoint_chain").await;
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+
+    // op_score / OP_RANGE_BY: DAA scores 100-101 fall in range 10, 120 in 12, 130 in 13
+    for batch in [
+        vec![op_data(100, 0), op_data(101, 1)],
+        vec![op_data(120, 0)],
+        vec![op_data(130, 0)],
+    ] {
+        rollback_manager
+            .save_op_data_batch_rocks(&batch)
+            .await
+            .unwrap();
+    }
+
+    let first = checkpoint::get_checkpoint(&storage.rocksdb, 10)
+        .unwrap()
+        .unwrap();
+    let second = checkpoint::get_checkpoint(&storage.rocksdb, 12)
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.prev_range, None);
+    assert_eq!(second.prev_range, Some(10));
+    assert_eq!(
+        second.checkpoint,
+        checkpoint::compute_checkpoint(
+            &first.checkpoint,
+            12,
+            &checkpoint::range_entries(&storage.rocksdb, 12).unwrap()
+        )
+    );
+    // Ranges without operations and the open range are not sealed
+    assert!(
+        checkpoint::get_checkpoint(&storage.rocksdb, 11)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        checkpoint::get_checkpoint(&storage.rocksdb, 13)
+            .unwrap()
+            .is_none()
+    );
+
+    // Unsealing for a rollback and replaying yields the same chain
+    checkpoint::unseal_from_op_score(&storage.rocksdb, 120 * 10000).unwrap();
+    assert!(
+        checkpoint::get_checkpoint(&storage.rocksdb, 12)
+            .unwrap()
+            .is_none()
+    );
+    rollback_manager
+        .save_op_data_batch_rocks(&[op_data(130, 1)])
+        .await
+        .unwrap();
+    assert_eq!(
+        checkpoint::get_checkpoint(&storage.rocksdb, 12).unwrap(),
+        Some(second.clone())
+    );
+
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let response = reqwest::get(format!("http://{}/v1/checkpoint/12", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let served: RangeCheckpoint = serde_json::from_value(body["data"].clone()).unwrap();
+    assert_eq!(served, second);
+
+    let response = reqwest::get(format!("http://{}/v1/checkpoint/11", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_checkpoint_detects_tampering() {
+    let storage = setup_storage("./test_data/checkpoint_tamper").await;
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    for batch in [
+        vec![op_data(100, 0)],
+        vec![op_data(120, 0)],
+        vec![op_data(130, 0)],
+    ] {
+        rollback_manager
+            .save_op_data_batch_rocks(&batch)
+            .await
+            .unwrap();
+    }
+    let first = checkpoint::get_checkpoint(&storage.rocksdb, 10)
+        .unwrap()
+        .unwrap();
+    let second = checkpoint::get_checkpoint(&storage.rocksdb, 12)
+        .unwrap()
+        .unwrap();
+
+    // Rewrite the earlier range's operation state in place
+    let key = format!("oplist:10:{}", 100 * 10000);
+    let value = storage.rocksdb.get_raw(&key).unwrap().unwrap();
+    let tampered = String::from_utf8(value)
+        .unwrap()
+        .replace("1000", "9000")
+        .into_bytes();
+    storage.rocksdb.put_raw(&key, &tampered).unwrap();
+
+    // A verifier recomputing the chain diverges at the tampered range and every range after it
+    let first_recomputed = checkpoint::compute_checkpoint(
+        "",
+        10,
+        &checkpoint::range_entries(&storage.rocksdb, 10).unwrap(),
+    );
+    let second_recomputed = checkpoint::compute_checkpoint(
+        &first_recomputed,
+        12,
+        &checkpoint::range_entries(&storage.rocksdb, 12).unwrap(),
+    );
+    assert_ne!(first_recomputed, first.checkpoint);
+    assert_ne!(second_recomputed, second.checkpoint);
+}
@@ -0,0 +1,229 @@
+//! Storage, operation and HTTP fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use kaspa_indexer_rust::config::types::{CacheControlConfig, RocksConfig};
+use kaspa_indexer_rust::explorer::rollback::{
+    ROLLBACK_STATISTICS_CACHE_TTL, RollbackCandidateCache, RollbackStatisticsCache,
+};
+use kaspa_indexer_rust::http::node_tip::NodeTipCache;
+use kaspa_indexer_rust::http::{HttpState, build_router_with_state};
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::stats::{STATS_CACHE_TTL, StatsCache};
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::utils::address::encode_address;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Runs the wrapped closure when dropped, restoring process-wide config even if a test panics
+pub struct OnDrop<F: FnMut()>(pub F);
+
+impl<F: FnMut()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        (self.0)()
+    }
+}
+
+pub fn address(seed: u8) -> String {
+    encode_address(&[seed; 32], true).unwrap()
+}
+
+/// Operation of `tick` at DAA score 1000 + `index`, paying a fee large enough for any op
+pub fn op_data(index: u64, op: &str, tick: &str, from: &str, to: &str) -> DataOperationType {
+    DataOperationType {
+        tx_id: format!("{}_tx_{}", op, index),
+        daa_score: 1000 + index,
+        block_accept: "block_hash".to_string(),
+        fee: 100000000000,
+        fee_least: 0,
+        mts_add: 0,
+        op_score: (1000 + index) * 10000,
+        op_accept: 0,
+        op_error: String::new(),
+        op_script: vec![DataScriptType {
+            p: "KRC-20".to_string(),
+            op: op.to_string(),
+            from: Some(from.to_string()),
+            to: Some(to.to_string()),
+            tick: Some(tick.to_string()),
+            max: None,
+            lim: None,
+            pre: None,
+            dec: None,
+            amt: None,
+            utxo: None,
+            price: None,
+            mod_type: "".to_string(),
+            name: None,
+            ca: None,
+            desc: None,
+        }],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: Some(DataStatsType {
+            tick_affc: vec![],
+            address_affc: vec![],
+        }),
+    }
+}
+
+/// Deploy of `tick` with a mint limit of 1000 and the given premint and decimals
+pub fn deploy(index: u64, tick: &str, owner: &str, pre: &str, dec: &str) -> DataOperationType {
+    let mut op_data = op_data(index, "deploy", tick, owner, owner);
+    let script = &mut op_data.op_script[0];
+    script.max = Some("21000000".to_string());
+    script.lim = Some("1000".to_string());
+    script.pre = Some(pre.to_string());
+    script.dec = Some(dec.to_string());
+    op_data
+}
+
+pub fn transfer(index: u64, tick: &str, from: &str, to: &str, amt: &str) -> DataOperationType {
+    let mut op_data = op_data(index, "transfer", tick, from, to);
+    op_data.op_script[0].amt = Some(amt.to_string());
+    op_data
+}
+
+/// Accepted operation at `daa_score` without scripts or fee, for tests of the stored op records
+pub fn committed_op(daa_score: u64) -> DataOperationType {
+    let mut op_data = op_data(0, "", "", "", "");
+    op_data.tx_id = format!("tx_{}", daa_score);
+    op_data.daa_score = daa_score;
+    op_data.fee = 0;
+    op_data.op_score = daa_score * 10000;
+    op_data.op_accept = 1;
+    op_data.op_script.clear();
+    op_data.ss_info = None;
+    op_data
+}
+
+/// Execute one batch and persist its state
+pub fn execute(
+    storage: &StorageManager,
+    operation_manager: &OperationManager,
+    op_data_list: &mut [DataOperationType],
+) -> DataRollbackType {
+    let (mut state_map, _) = operation_manager.prepare_state_batch(op_data_list).unwrap();
+    let (rollback, _) = operation_manager
+        .execute_batch(op_data_list, &mut state_map, "", true)
+        .unwrap();
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map)
+        .unwrap();
+    rollback
+}
+
+/// Execute one batch whose operations must all be accepted and persist its state
+pub fn execute_accepted(
+    storage: &StorageManager,
+    operation_manager: &OperationManager,
+    mut op_data_list: Vec<DataOperationType>,
+) -> DataRollbackType {
+    let rollback = execute(storage, operation_manager, &mut op_data_list);
+    for op_data in &op_data_list {
+        assert_eq!(op_data.op_accept, 1, "{}", op_data.op_error);
+    }
+    rollback
+}
+
+pub fn balance(storage: &StorageManager, address: &str, tick: &str) -> Option<String> {
+    let key = StateKey::balance(address, tick).map_key();
+    let mut balance_map = [(key.clone(), None)].into_iter().collect();
+    storage
+        .state
+        .get_state_balance_map(&mut balance_map)
+        .unwrap();
+    balance_map[&key]
+        .as_ref()
+        .map(|balance| balance.balance.clone())
+}
+
+/// Balance entry of `address` for `tick` as it is keyed in a state map
+pub fn balance_state(
+    address: &str,
+    tick: &str,
+    balance: &str,
+) -> (String, Option<StateBalanceType>) {
+    let balance = StateBalanceType {
+        address: address.to_string(),
+        tick: tick.to_string(),
+        balance: balance.to_string(),
+        locked: "0".to_string(),
+        ..Default::default()
+    };
+    (StateKey::balance(address, tick).map_key(), Some(balance))
+}
+
+/// Initialized RocksDB storage at `path`, removing what an earlier run left there
+pub async fn setup_storage(path: &str) -> Arc<StorageManager> {
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    Arc::new(storage)
+}
+
+/// Populate the runtime VSPC list and checkpoint so that DAA score 100 is a valid rollback point
+pub async fn seed_rollback_target(storage: &StorageManager) {
+    let vspc_list: Vec<DataVspcType> = [100, 200]
+        .into_iter()
+        .map(|daa_score| DataVspcType {
+            daa_score,
+            hash: format!("block_hash_{}", daa_score),
+            tx_id_list: vec![],
+            selected_parent: String::new(),
+        })
+        .collect();
+    storage
+        .runtime
+        .set_runtime_vspc_last(&vspc_list)
+        .await
+        .unwrap();
+    storage
+        .runtime
+        .save_checkpoint("block_hash_200", 200)
+        .unwrap();
+}
+
+/// Server state over `storage` with the defaults of the shipped configuration
+pub fn http_state(storage: Arc<StorageManager>) -> HttpState {
+    HttpState {
+        kaspa_rest_base_url: "http://localhost".to_string(),
+        storage,
+        admin_token: String::new(),
+        node_tip: Arc::new(NodeTipCache::disabled()),
+        stats: Arc::new(StatsCache::new(STATS_CACHE_TTL)),
+        max_page_size: 500,
+        cache_control: CacheControlConfig::default(),
+        debug_endpoints: false,
+        rollback_candidates: Arc::new(RollbackCandidateCache::new(Duration::from_secs(10))),
+        rollback_stats: Arc::new(RollbackStatisticsCache::new(ROLLBACK_STATISTICS_CACHE_TTL)),
+        read_permits: Arc::new(Semaphore::new(64)),
+    }
+}
+
+/// Serve `router` on an ephemeral local port
+pub async fn serve(router: axum::Router) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router.into_make_service())
+            .await
+            .unwrap();
+    });
+    addr
+}
+
+/// Serve the full router over `storage` with the default server state
+pub async fn spawn_server(storage: Arc<StorageManager>) -> SocketAddr {
+    serve(build_router_with_state(http_state(storage))).await
+}
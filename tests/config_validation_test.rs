@@ -0,0 +1,279 @@
+use kaspa_indexer_rust::config::types::Config;
+use kaspa_indexer_rust::config::{apply_consistency_mode, validate_config};
+
+fn config_with_peers(nodes: &[&str]) -> Config {
+    let mut config = Config::default();
+    config.rest.kaspa_rest_base_url = "https://api-tn10.kaspa.org".to_string();
+    config.distributed.node.enabled = true;
+    config.distributed.node.port = 9090;
+    config.distributed.node.nodes = nodes.iter().map(|node| node.to_string()).collect();
+    config
+}
+
+fn peer_error(nodes: &[&str]) -> String {
+    validate_config(&config_with_peers(nodes))
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_distributed_peers_accepted() {
+    let config = config_with_peers(&[
+        "10.0.0.2:9090",
+        "node_3@indexer-3.internal:9090",
+        "[fd00::4]:9090",
+        // Same port on this machine is fine as long as it is not this node
+        "127.0.0.1:9091",
+    ]);
+    validate_config(&config).unwrap();
+}
+
+#[test]
+fn test_distributed_peer_malformed_address() {
+    for peer in [
+        "10.0.0.2",
+        "10.0.0.2:",
+        "10.0.0.2:0",
+        ":9090",
+        "10.0.0.2:99999",
+        "[fd00::4:9090",
+        "bad host:9090",
+    ] {
+        assert_eq!(
+            peer_error(&["10.0.0.5:9090", peer]),
+            format!(
+                "distributed.node.nodes[1] '{}' is not a valid host:port address",
+                peer
+            ),
+            "{} accepted",
+            peer
+        );
+    }
+    assert_eq!(
+        peer_error(&["@10.0.0.2:9090"]),
+        "distributed.node.nodes[0] '@10.0.0.2:9090' has an empty node id"
+    );
+}
+
+#[test]
+fn test_distributed_peer_self_reference() {
+    for peer in [
+        "127.0.0.1:9090",
+        "localhost:9090",
+        "node_2@[::1]:9090",
+        "0.0.0.0:9090",
+    ] {
+        assert_eq!(
+            peer_error(&[peer]),
+            format!(
+                "distributed.node.nodes[0] '{}' is this node's own address (port 9090)",
+                peer
+            )
+        );
+    }
+    assert_eq!(
+        peer_error(&["10.0.0.2:9090", "node_2@10.0.0.2:9090"]),
+        "distributed.node.nodes[1] 'node_2@10.0.0.2:9090' duplicates an earlier peer address"
+    );
+}
+
+#[test]
+fn test_replication_codec_validated() {
+    let mut config = config_with_peers(&[]);
+    for codec in ["zstd", "lz4", "snappy"] {
+        config.distributed.replication.compression_codec = codec.to_string();
+        validate_config(&config).unwrap();
+    }
+    config.distributed.replication.compression_codec = "gzip".to_string();
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "distributed.replication.compression_codec must be one of: zstd, lz4, snappy"
+    );
+}
+
+#[test]
+fn test_tick_reserved_entries_validated() {
+    let reserved = "NACHO_kaspa:qzrsq2mfj9sf7uye3u5q7juejzlr0axk5jz9fpg4vqe76erdyvxxze84k9nk7";
+    let mut config = config_with_peers(&[]);
+    config.startup.tick_reserved = vec![reserved.to_string()];
+    validate_config(&config).unwrap();
+
+    // Addresses must be valid for the configured network
+    config.startup.is_testnet = true;
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        format!(
+            "Invalid reserved token '{}': 'kaspa:qzrsq2mfj9sf7uye3u5q7juejzlr0axk5jz9fpg4vqe76erdyvxxze84k9nk7' is not a valid Kaspa address",
+            reserved
+        )
+    );
+    config.startup.is_testnet = false;
+
+    for (entry, error) in [
+        (
+            "NACHO_kaspa:notanaddress",
+            "Invalid reserved token 'NACHO_kaspa:notanaddress': 'kaspa:notanaddress' is not a valid Kaspa address",
+        ),
+        (
+            "N1_kaspa:qzrsq2mfj9sf7uye3u5q7juejzlr0axk5jz9fpg4vqe76erdyvxxze84k9nk7",
+            "Invalid reserved token 'N1_kaspa:qzrsq2mfj9sf7uye3u5q7juejzlr0axk5jz9fpg4vqe76erdyvxxze84k9nk7': 'N1' is not a valid tick",
+        ),
+        (
+            "NACHO",
+            "Invalid reserved token format 'NACHO': must be in format 'TICK_ADDRESS'",
+        ),
+    ] {
+        config.startup.tick_reserved = vec![entry.to_string()];
+        assert_eq!(validate_config(&config).unwrap_err().to_string(), error);
+    }
+
+    // The list size is bounded
+    config.startup.tick_reserved = vec![reserved.to_string(); 3];
+    config.startup.tick_reserved_max = 2;
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "tickReserved has 3 entries, more than tickReservedMax (2)"
+    );
+}
+
+#[test]
+fn test_enabled_op_types_must_be_known() {
+    let mut config = config_with_peers(&[]);
+    config.startup.enabled_op_types = vec!["deploy".to_string(), "Transfer".to_string()];
+    validate_config(&config).unwrap();
+
+    config.startup.enabled_op_types.push("swap".to_string());
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "enabledOpTypes contains unknown operation type 'swap'"
+    );
+}
+
+#[test]
+fn test_checkpoint_hash_must_be_known() {
+    let mut config = config_with_peers(&[]);
+    config.startup.checkpoint_hash = "sha256".to_string();
+    validate_config(&config).unwrap();
+
+    config.startup.checkpoint_hash = "md5".to_string();
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "checkpointHash must be one of: blake3, sha256"
+    );
+}
+
+#[test]
+fn test_batch_retry_backoff_must_be_positive() {
+    let mut config = config_with_peers(&[]);
+    config.startup.batch_max_retries = 0;
+    validate_config(&config).unwrap();
+
+    config.startup.batch_retry_backoff_ms = 0;
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "batchRetryBackoffMs must be greater than 0"
+    );
+}
+
+#[test]
+fn test_op_score_multiplier_must_align_with_op_ranges() {
+    let mut config = config_with_peers(&[]);
+    for multiplier in [10000, 50000, 100000, 1_000_000] {
+        config.startup.op_score_multiplier = multiplier;
+        validate_config(&config).unwrap();
+    }
+
+    config.startup.op_score_multiplier = 30000;
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "opScoreMultiplier must divide or be a multiple of 100000"
+    );
+}
+
+#[test]
+fn test_max_concurrent_reads_must_be_positive() {
+    let mut config = config_with_peers(&[]);
+    config.http.max_concurrent_reads = 0;
+    assert!(
+        validate_config(&config)
+            .unwrap_err()
+            .to_string()
+            .starts_with("http.max_concurrent_reads must be between 1 and ")
+    );
+}
+
+#[test]
+fn test_admin_bind_must_not_overlap_public_listener() {
+    let mut config = config_with_peers(&[]);
+    config.http.admin_bind = "127.0.0.1:8081".to_string();
+    assert!(validate_config(&config).is_ok());
+
+    config.http.admin_bind = "127.0.0.1:8080".to_string();
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "http.admin_bind (127.0.0.1:8080) overlaps the public listener 0.0.0.0:8080"
+    );
+
+    // The public port is still free on another interface
+    config.http.bind = "10.0.0.1".to_string();
+    assert!(validate_config(&config).is_ok());
+
+    config.http.admin_bind = "localhost".to_string();
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "Invalid http.admin_bind address: localhost"
+    );
+}
+
+#[test]
+fn test_stall_resume_threshold_must_not_exceed_stall_threshold() {
+    let mut config = config_with_peers(&[]);
+    config.rocksdb.stall_resume_pending_compaction_mb = config.rocksdb.stall_pending_compaction_mb;
+    assert!(validate_config(&config).is_ok());
+
+    config.rocksdb.stall_resume_pending_compaction_mb = 70000;
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "rocksdb.stallResumePendingCompactionMB (70000) cannot be greater than stallPendingCompactionMB (65536)"
+    );
+}
+
+#[test]
+fn test_consistency_mode_sets_durability_settings() {
+    let mut config = config_with_peers(&[]);
+    config.startup.consistency_mode = "fast".to_string();
+    apply_consistency_mode(&mut config);
+    assert!(!config.rocksdb.sync_writes);
+    assert!(!config.rocksdb.use_fsync);
+    assert!(config.rocksdb.bulk_load);
+    assert_eq!(config.rocksdb.stall_pending_compaction_mb, 256 * 1024);
+    assert_eq!(
+        config.rocksdb.stall_resume_pending_compaction_mb,
+        128 * 1024
+    );
+    assert_eq!(config.startup.runtime_flush_interval_ms, 30000);
+    validate_config(&config).unwrap();
+
+    config.startup.consistency_mode = "safe".to_string();
+    apply_consistency_mode(&mut config);
+    assert!(config.rocksdb.sync_writes);
+    assert!(config.rocksdb.use_fsync);
+    assert!(!config.rocksdb.bulk_load);
+    assert_eq!(config.rocksdb.stall_pending_compaction_mb, 64 * 1024);
+    assert_eq!(config.rocksdb.stall_resume_pending_compaction_mb, 32 * 1024);
+    assert_eq!(config.startup.runtime_flush_interval_ms, 1000);
+    validate_config(&config).unwrap();
+
+    // Without a mode every setting keeps its configured value
+    let mut config = config_with_peers(&[]);
+    config.rocksdb.use_fsync = false;
+    apply_consistency_mode(&mut config);
+    assert!(!config.rocksdb.use_fsync);
+    assert!(!config.rocksdb.sync_writes);
+
+    config.startup.consistency_mode = "durable".to_string();
+    assert_eq!(
+        validate_config(&config).unwrap_err().to_string(),
+        "consistencyMode must be one of: fast, safe"
+    );
+}
@@ -0,0 +1,99 @@
+use kaspa_indexer_rust::config::types::Config;
+use kaspa_indexer_rust::config::validate_config;
+use kaspa_indexer_rust::utils::daa_range::DaaScoreRanges;
+
+fn ranges() -> DaaScoreRanges {
+    DaaScoreRanges::new(vec![[100, 200], [300, 400]])
+}
+
+#[test]
+fn test_daa_score_ranges_contains() {
+    let ranges = ranges();
+    // Below, edges, within, between and above the ranges
+    for (daa_score, expected) in [
+        (0, false),
+        (99, false),
+        (100, true),
+        (150, true),
+        (200, true),
+        (201, false),
+        (299, false),
+        (300, true),
+        (400, true),
+        (401, false),
+        (u64::MAX, false),
+    ] {
+        assert_eq!(ranges.contains(daa_score), expected, "{}", daa_score);
+    }
+}
+
+#[test]
+fn test_daa_score_ranges_next_valid() {
+    let ranges = ranges();
+    for (daa_score, expected) in [
+        (0, Some(100)),
+        (100, Some(100)),
+        (200, Some(200)),
+        (201, Some(300)),
+        (299, Some(300)),
+        (350, Some(350)),
+        (400, Some(400)),
+        (401, None),
+    ] {
+        assert_eq!(ranges.next_valid(daa_score), expected, "{}", daa_score);
+    }
+    assert_eq!(DaaScoreRanges::new(vec![]).next_valid(0), None);
+    assert_eq!(ranges.first_start(), Some(100));
+}
+
+#[test]
+fn test_daa_score_ranges_validate() {
+    ranges().validate().unwrap();
+    DaaScoreRanges::new(vec![]).validate().unwrap();
+    assert_eq!(
+        DaaScoreRanges::new(vec![[200, 200]])
+            .validate()
+            .unwrap_err()
+            .to_string(),
+        "Invalid daa_score_range[0]: start must be less than end"
+    );
+    // Touching, overlapping and unordered ranges
+    for ranges in [
+        vec![[100, 200], [200, 300]],
+        vec![[100, 250], [200, 300]],
+        vec![[300, 400], [100, 200]],
+    ] {
+        assert_eq!(
+            DaaScoreRanges::new(ranges)
+                .validate()
+                .unwrap_err()
+                .to_string(),
+            "Invalid daa_score_range[1]: must start after the previous range ends"
+        );
+    }
+
+    let mut config = Config::default();
+    config.rest.kaspa_rest_base_url = "https://api-tn10.kaspa.org".to_string();
+    config.startup.daa_score_range = vec![[100, 250], [200, 300]];
+    assert!(validate_config(&config).is_err());
+}
+
+#[test]
+fn test_daa_score_ranges_for_network() {
+    let configured = [[100, 200]];
+    assert_eq!(
+        DaaScoreRanges::for_network(&configured, true),
+        DaaScoreRanges::new(vec![[100, 200]])
+    );
+    // Mainnet always scans the Go version ranges
+    assert_eq!(
+        DaaScoreRanges::for_network(&configured, false),
+        DaaScoreRanges::mainnet()
+    );
+    assert_eq!(
+        DaaScoreRanges::for_network(&[], true),
+        DaaScoreRanges::mainnet()
+    );
+    assert!(DaaScoreRanges::mainnet().contains(83441551));
+    assert!(!DaaScoreRanges::mainnet().contains(83525601));
+}
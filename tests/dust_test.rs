@@ -0,0 +1,70 @@
+mod common;
+
+use common::{OnDrop, address, deploy, execute, execute_accepted, op_data, transfer};
+use kaspa_indexer_rust::config::apply_balance_dust_threshold;
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::operations::{make_st_line_balance, make_st_line_token};
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use std::sync::Arc;
+
+const DUST_THRESHOLD: u64 = 100;
+
+fn balance(storage: &StorageManager, address: &str) -> Option<String> {
+    common::balance(storage, address, "DUST")
+}
+
+fn burned(storage: &StorageManager) -> String {
+    storage
+        .state
+        .get_state_token("DUST")
+        .unwrap()
+        .unwrap()
+        .burned
+}
+
+#[tokio::test]
+async fn test_sub_dust_remainder_removes_balance() {
+    apply_balance_dust_threshold(DUST_THRESHOLD);
+    let _reset = OnDrop(|| apply_balance_dust_threshold(0));
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let (owner, alice, bob) = (address(1), address(2), address(3));
+
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![
+            deploy(0, "DUST", &owner, "0", "0"),
+            op_data(1, "mint", "DUST", &alice, &alice),
+        ],
+    );
+    assert_eq!(balance(&storage, &alice).as_deref(), Some("1000"));
+
+    // A remainder at the threshold is kept
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![transfer(2, "DUST", &alice, &bob, "900")],
+    );
+    assert_eq!(balance(&storage, &alice).as_deref(), Some("100"));
+    assert_eq!(burned(&storage), "0");
+
+    // Below it the remainder is burned and the sender's entry deleted
+    let mut op_data_list = vec![transfer(3, "DUST", &alice, &bob, "60")];
+    execute(&storage, &operation_manager, &mut op_data_list);
+    assert_eq!(op_data_list[0].op_accept, 1, "{}", op_data_list[0].op_error);
+    assert_eq!(balance(&storage, &alice), None);
+    assert_eq!(balance(&storage, &bob).as_deref(), Some("960"));
+    assert_eq!(burned(&storage), "40");
+
+    // The stored st lines describe the state after the sweep
+    let token = storage.state.get_state_token("DUST").unwrap().unwrap();
+    let alice_key = StateKey::balance(&alice, "DUST").map_key();
+    let st_after = &op_data_list[0].st_after;
+    assert!(st_after.contains(&make_st_line_balance(&alice_key, None)));
+    assert!(st_after.contains(&make_st_line_token("DUST", Some(&token), false)));
+    assert_eq!(st_after.len(), 3);
+}
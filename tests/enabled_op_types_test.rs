@@ -0,0 +1,48 @@
+mod common;
+
+use common::{OnDrop, address, deploy, execute, op_data, transfer};
+use kaspa_indexer_rust::config::apply_enabled_op_types;
+use kaspa_indexer_rust::operations::OP_ERROR_OP_DISABLED;
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use std::sync::Arc;
+
+fn balance(storage: &StorageManager, address: &str) -> Option<String> {
+    common::balance(storage, address, "SKIP")
+}
+
+#[tokio::test]
+async fn test_disabled_mints_skipped_while_transfers_execute() {
+    apply_enabled_op_types(&["deploy".to_string(), "transfer".to_string()]);
+    let _reset = OnDrop(|| apply_enabled_op_types(&[]));
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let (owner, alice) = (address(1), address(2));
+
+    // The deploy premints 1000 to the owner
+    let mut deploy_list = [deploy(0, "SKIP", &owner, "1000", "8")];
+    execute(&storage, &operation_manager, &mut deploy_list);
+    assert_eq!(deploy_list[0].op_accept, 1, "{}", deploy_list[0].op_error);
+
+    let mut op_data_list = [
+        op_data(1, "mint", "SKIP", &alice, &alice),
+        transfer(2, "SKIP", &owner, &alice, "400"),
+    ];
+    let rollback = execute(&storage, &operation_manager, &mut op_data_list);
+
+    // The mint is kept in the batch with its op_score but never executed
+    let mint = &op_data_list[0];
+    assert_eq!(mint.op_accept, 0);
+    assert_eq!(mint.op_error, OP_ERROR_OP_DISABLED);
+    assert!(mint.st_after.is_empty());
+    assert_eq!(rollback.op_score_list, vec![1001 * 10000, 1002 * 10000]);
+
+    let transfer = &op_data_list[1];
+    assert_eq!(transfer.op_accept, 1, "{}", transfer.op_error);
+    assert_eq!(balance(&storage, &owner).as_deref(), Some("600"));
+    assert_eq!(balance(&storage, &alice).as_deref(), Some("400"));
+    let token = storage.state.get_state_token("SKIP").unwrap().unwrap();
+    assert_eq!(token.minted, "1000");
+}
@@ -0,0 +1,67 @@
+use kaspa_indexer_rust::operations::metrics::get_script_rejections;
+use kaspa_indexer_rust::storage::types::DataTransactionType;
+use kaspa_indexer_rust::utils::script_parser::ScriptParser;
+
+const TEST_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else {
+        format!("4c{:02x}{}", len, data_hex)
+    }
+}
+
+/// Transaction revealing a mint script, with an optional envelope parameter after the payload
+fn transaction(envelope_param: Option<&str>) -> DataTransactionType {
+    let payload = serde_json::json!({
+        "p": "KRC-20",
+        "op": "mint",
+        "tick": "ENVL",
+        "to": TEST_ADDRESS,
+        "mod_type": "",
+    });
+    let param = envelope_param
+        .map(|param| format!("51{}", push_data(&hex::encode(param))))
+        .unwrap_or_default();
+    let redeem_script = format!(
+        "20{}ac006307{}00{}{}68",
+        "ab".repeat(32),
+        hex::encode("kasplex"),
+        push_data(&hex::encode(payload.to_string())),
+        param
+    );
+    DataTransactionType {
+        tx_id: "tx_envelope".to_string(),
+        daa_score: 110165001,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({
+            "inputs": [{
+                "index": 0,
+                "signatureScript": format!(
+                    "{}{}",
+                    push_data(&"00".repeat(65)),
+                    push_data(&redeem_script)
+                ),
+            }],
+            "outputs": [],
+        })),
+    }
+}
+
+#[test]
+fn test_envelope_op_must_match_payload_op() {
+    let parse = |envelope_param| ScriptParser::parse_op_data(&transaction(envelope_param), true);
+
+    assert!(parse(None).unwrap().is_some());
+    assert!(parse(Some("mint")).unwrap().is_some());
+    assert!(parse(Some("MINT")).unwrap().is_some());
+    // Parameters not naming an operation are not cross-checked
+    assert!(parse(Some("text/plain")).unwrap().is_some());
+    assert_eq!(get_script_rejections().op_mismatch, 0);
+
+    assert!(parse(Some("transfer")).unwrap().is_none());
+    assert_eq!(get_script_rejections().op_mismatch, 1);
+}
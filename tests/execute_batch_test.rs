@@ -0,0 +1,247 @@
+mod common;
+
+use common::address;
+use kaspa_indexer_rust::config::types::RocksConfig;
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::state;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::storage::versioned;
+use std::sync::Arc;
+
+/// Transfer of SEQ, all transfers of a test share one DAA score
+fn transfer(index: u64, from: &str, to: &str, amt: &str) -> DataOperationType {
+    let mut op_data = common::transfer(index, "SEQ", from, to, amt);
+    op_data.daa_score = 2000;
+    op_data.op_score = 2000 * 10000 + index;
+    op_data.fee = 0;
+    op_data
+}
+
+fn balance(state_map: &DataStateMapType, address: &str) -> Option<String> {
+    state_map.state_balance_map[&StateKey::balance(address, "SEQ").map_key()]
+        .as_ref()
+        .map(|balance| balance.balance.clone())
+}
+
+#[tokio::test]
+async fn test_execute_batch_applies_operations_sequentially() {
+    let path = "./test_data/execute_batch_sequential";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let (alice, bob, carol) = (address(1), address(2), address(3));
+
+    // Only alice holds a balance before the batch
+    let token = StateTokenType {
+        tick: "SEQ".to_string(),
+        max: "1000".to_string(),
+        lim: "100".to_string(),
+        minted: "100".to_string(),
+        dec: 8,
+        from: alice.clone(),
+        to: alice.clone(),
+        ..Default::default()
+    };
+    let alice_balance = StateBalanceType {
+        address: alice.clone(),
+        tick: "SEQ".to_string(),
+        dec: 8,
+        balance: "100".to_string(),
+        locked: "0".to_string(),
+        op_mod: 0,
+    };
+    storage
+        .rocksdb
+        .put_raw(
+            &StateKey::token("SEQ").encode(),
+            &serde_json::to_vec(&token).unwrap(),
+        )
+        .unwrap();
+    storage
+        .rocksdb
+        .put_raw(
+            &StateKey::balance(&alice, "SEQ").encode(),
+            &serde_json::to_vec(&alice_balance).unwrap(),
+        )
+        .unwrap();
+
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let mut op_data_list = vec![
+        // Against the pre-batch snapshot both sends from alice would pass
+        transfer(0, &alice, &bob, "60"),
+        transfer(1, &alice, &carol, "60"),
+        // Bob holds nothing before the batch, only the first send funds him
+        transfer(2, &bob, &carol, "50"),
+    ];
+    let (mut state_map, _) = operation_manager
+        .prepare_state_batch(&op_data_list)
+        .unwrap();
+    assert_eq!(balance(&state_map, &bob), None);
+
+    let (rollback, _) = operation_manager
+        .execute_batch(&mut op_data_list, &mut state_map, "", true)
+        .unwrap();
+
+    assert_eq!(op_data_list[0].op_accept, 1);
+    assert_eq!(op_data_list[1].op_accept, -1);
+    assert_eq!(
+        op_data_list[1].op_error,
+        "Insufficient balance: required 60, available 40"
+    );
+    assert_eq!(op_data_list[2].op_accept, 1);
+    assert_eq!(balance(&state_map, &alice).as_deref(), Some("40"));
+    assert_eq!(balance(&state_map, &bob).as_deref(), Some("10"));
+    assert_eq!(balance(&state_map, &carol).as_deref(), Some("50"));
+
+    // The rollback keeps the pre-batch snapshot
+    assert_eq!(
+        balance(&rollback.state_map_before, &alice).as_deref(),
+        Some("100")
+    );
+    assert_eq!(balance(&rollback.state_map_before, &bob), None);
+}
+
+fn deploy(tx_id: &str, daa_score: u64, from: &str, max: &str) -> DataOperationType {
+    let mut op_data = transfer(0, from, from, "0");
+    op_data.tx_id = tx_id.to_string();
+    op_data.daa_score = daa_score;
+    op_data.op_score = daa_score * 10000;
+    op_data.fee = 100000000000;
+    let script = &mut op_data.op_script[0];
+    script.op = "deploy".to_string();
+    script.tick = Some("DUPE".to_string());
+    script.max = Some(max.to_string());
+    script.lim = Some("1000".to_string());
+    script.pre = Some("0".to_string());
+    script.dec = Some("8".to_string());
+    script.amt = None;
+    op_data
+}
+
+#[tokio::test]
+async fn test_duplicate_deploy_rejected() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let (alice, bob) = (address(1), address(2));
+
+    // First deploy is persisted like a completed batch
+    let mut op_data_list = vec![deploy("deploy_tx_1", 1000, &alice, "21000000")];
+    let (mut state_map, _) = operation_manager
+        .prepare_state_batch(&op_data_list)
+        .unwrap();
+    operation_manager
+        .execute_batch(&mut op_data_list, &mut state_map, "", true)
+        .unwrap();
+    assert_eq!(op_data_list[0].op_accept, 1);
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map)
+        .unwrap();
+    let original = storage.state.get_state_token("DUPE").unwrap().unwrap();
+
+    // A later batch redeploys the tick with another supply and owner, twice in the same batch
+    let mut op_data_list = vec![
+        deploy("deploy_tx_2", 2000, &bob, "1000"),
+        deploy("deploy_tx_3", 2000, &bob, "1000"),
+    ];
+    op_data_list[1].op_score += 1;
+    let (mut state_map, _) = operation_manager
+        .prepare_state_batch(&op_data_list)
+        .unwrap();
+    operation_manager
+        .execute_batch(&mut op_data_list, &mut state_map, "", true)
+        .unwrap();
+    for op_data in &op_data_list {
+        assert_eq!(op_data.op_accept, -1);
+        assert_eq!(op_data.op_error, "tick already deployed");
+    }
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map)
+        .unwrap();
+
+    let token = storage.state.get_state_token("DUPE").unwrap().unwrap();
+    assert_eq!(token, original);
+    assert_eq!(token.max, "21000000");
+    assert_eq!(token.to, alice);
+    assert_eq!(token.tx_id, "deploy_tx_1");
+}
+
+#[tokio::test]
+async fn test_batch_writes_each_state_key_once() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let alice = address(1);
+
+    let mut deploy_list = vec![deploy("deploy_tx", 1000, &alice, "21000000")];
+    let (mut state_map, _) = operation_manager.prepare_state_batch(&deploy_list).unwrap();
+    operation_manager
+        .execute_batch(&mut deploy_list, &mut state_map, "", true)
+        .unwrap();
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map)
+        .unwrap();
+
+    // Three mints of the same tick in one batch
+    let mut op_data_list: Vec<DataOperationType> = (0..3)
+        .map(|index| {
+            let mut op_data = transfer(index, &alice, &alice, "0");
+            op_data.tx_id = format!("mint_tx_{}", index);
+            op_data.fee = 100000000;
+            let script = &mut op_data.op_script[0];
+            script.op = "mint".to_string();
+            script.tick = Some("DUPE".to_string());
+            script.amt = None;
+            op_data
+        })
+        .collect();
+    let (mut state_map, _) = operation_manager
+        .prepare_state_batch(&op_data_list)
+        .unwrap();
+    operation_manager
+        .execute_batch(&mut op_data_list, &mut state_map, "", true)
+        .unwrap();
+    for op_data in &op_data_list {
+        assert_eq!(op_data.op_accept, 1, "{}", op_data.op_error);
+        let token_lines = op_data
+            .st_after
+            .iter()
+            .filter(|line| line.starts_with("sttoken_"))
+            .count();
+        assert_eq!(token_lines, 1);
+    }
+
+    let writes = state::collect_state_writes(&state_map).unwrap();
+    let token_writes: Vec<_> = writes
+        .iter()
+        .filter(|(key, _)| key.starts_with("sttoken_"))
+        .collect();
+    assert_eq!(token_writes.len(), 1);
+    let (key, value) = token_writes[0];
+    assert_eq!(key, "sttoken_DUPE");
+    let token: StateTokenType = versioned::decode(value.as_ref().unwrap()).unwrap();
+    assert_eq!(token.minted, "3000");
+
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map)
+        .unwrap();
+    let token = storage.state.get_state_token("DUPE").unwrap().unwrap();
+    assert_eq!(token.minted, "3000");
+    assert_eq!(
+        common::balance(&storage, &alice, "DUPE").as_deref(),
+        Some("3000")
+    );
+}
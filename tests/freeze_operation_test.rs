@@ -0,0 +1,222 @@
+mod common;
+
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::operations::*;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::storage::versioned;
+use std::sync::Arc;
+
+const OWNER_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const OTHER_ADDRESS: &str =
+    "kaspatest:qpqz2vxj23kvh0m73ta2jjn2u4cv4tlufqns2eap8mxyyt0rvrxy6ejkful67";
+
+fn script(op: &str, from: &str) -> DataScriptType {
+    DataScriptType {
+        p: "KRC-20".to_string(),
+        op: op.to_string(),
+        from: Some(from.to_string()),
+        to: Some(OTHER_ADDRESS.to_string()),
+        tick: Some("ICED".to_string()),
+        max: None,
+        lim: None,
+        pre: None,
+        dec: None,
+        amt: Some("100".to_string()),
+        utxo: None,
+        price: None,
+        mod_type: "".to_string(),
+        name: None,
+        ca: None,
+        desc: None,
+    }
+}
+
+fn op_data(script: DataScriptType, daa_score: u64) -> DataOperationType {
+    let mut op_data = common::op_data(0, &script.op, "", "", "");
+    op_data.tx_id = format!("{}_tx_{}", script.op, daa_score);
+    op_data.daa_score = daa_score;
+    op_data.mts_add = 1700000000;
+    op_data.op_score = daa_score * 10000;
+    op_data.op_script = vec![script];
+    op_data
+}
+
+fn freeze(from: &str, daa_score: u64, state_map: &mut DataStateMapType) -> DataOperationType {
+    let mut script = script("freeze", from);
+    assert!(FreezeOperation::validate(
+        &mut script,
+        "freeze_tx",
+        daa_score,
+        true
+    ));
+    let mut op_data = op_data(script, daa_score);
+    FreezeOperation::do_operation(0, &mut op_data, state_map, true).unwrap();
+    op_data
+}
+
+fn transfer(daa_score: u64, state_map: &mut DataStateMapType) -> DataOperationType {
+    let mut op_data = op_data(script("transfer", OWNER_ADDRESS), daa_score);
+    TransferOperation::do_operation(0, &mut op_data, state_map, true).unwrap();
+    op_data
+}
+
+fn deployed_state_map() -> DataStateMapType {
+    let mut state_map = DataStateMapType::new();
+    let token = StateTokenType {
+        tick: "ICED".to_string(),
+        max: "21000000".to_string(),
+        lim: "1000".to_string(),
+        dec: 8,
+        minted: "1000".to_string(),
+        from: OWNER_ADDRESS.to_string(),
+        to: OWNER_ADDRESS.to_string(),
+        op_add: 1000 * 10000,
+        op_mod: 1000 * 10000,
+        mts_add: 1600000000,
+        mts_mod: 1600000000,
+        ..Default::default()
+    };
+    state_map
+        .state_token_map
+        .insert("ICED".to_string(), Some(token));
+    for (address, balance) in [(OWNER_ADDRESS, "1000"), (OTHER_ADDRESS, "0")] {
+        state_map.state_balance_map.insert(
+            StateKey::balance(address, "ICED").map_key(),
+            Some(StateBalanceType {
+                address: address.to_string(),
+                tick: "ICED".to_string(),
+                dec: 8,
+                balance: balance.to_string(),
+                locked: "0".to_string(),
+                op_mod: 0,
+            }),
+        );
+    }
+    state_map
+}
+
+fn token(state_map: &DataStateMapType) -> &StateTokenType {
+    state_map.state_token_map["ICED"].as_ref().unwrap()
+}
+
+fn balance(state_map: &DataStateMapType, address: &str) -> String {
+    state_map.state_balance_map[&StateKey::balance(address, "ICED").map_key()]
+        .as_ref()
+        .unwrap()
+        .balance
+        .clone()
+}
+
+#[test]
+fn test_freeze_blocks_transfers_until_unfrozen() {
+    let mut state_map = deployed_state_map();
+    let frozen = freeze(OWNER_ADDRESS, 2000, &mut state_map);
+    assert_eq!(frozen.op_accept, 1);
+    assert!(token(&state_map).frozen);
+
+    let rejected = transfer(2001, &mut state_map);
+    assert_eq!(rejected.op_accept, -1);
+    assert_eq!(rejected.op_error, OP_ERROR_TOKEN_FROZEN);
+    assert_eq!(balance(&state_map, OWNER_ADDRESS), "1000");
+
+    let mut send = op_data(script("send", OWNER_ADDRESS), 2002);
+    SendOperation::do_operation(0, &mut send, &mut state_map, true).unwrap();
+    assert_eq!(send.op_accept, -1);
+    assert_eq!(send.op_error, OP_ERROR_TOKEN_FROZEN);
+
+    let mut mint = op_data(script("mint", OWNER_ADDRESS), 2003);
+    MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+    assert_eq!(mint.op_accept, -1);
+    assert_eq!(mint.op_error, OP_ERROR_TOKEN_FROZEN);
+    assert_eq!(token(&state_map).minted, "1000");
+
+    // A second freeze toggles the flag back
+    let unfrozen = freeze(OWNER_ADDRESS, 2004, &mut state_map);
+    assert_eq!(unfrozen.op_accept, 1);
+    assert!(!token(&state_map).frozen);
+
+    let accepted = transfer(2005, &mut state_map);
+    assert_eq!(accepted.op_accept, 1, "{}", accepted.op_error);
+    assert_eq!(balance(&state_map, OWNER_ADDRESS), "900");
+    assert_eq!(balance(&state_map, OTHER_ADDRESS), "100");
+}
+
+#[test]
+fn test_freeze_by_non_owner_rejected() {
+    let mut state_map = deployed_state_map();
+    let before = token(&state_map).clone();
+    let op_data = freeze(OTHER_ADDRESS, 2000, &mut state_map);
+    assert_eq!(op_data.op_accept, -1);
+    assert_eq!(op_data.op_error, "no ownership");
+    assert_eq!(token(&state_map), &before);
+
+    assert_eq!(transfer(2001, &mut state_map).op_accept, 1);
+}
+
+#[test]
+fn test_freeze_rollback_restores_token() {
+    let mut state_map = deployed_state_map();
+    let before = token(&state_map).clone();
+    let op_data = freeze(OWNER_ADDRESS, 2000, &mut state_map);
+    assert_eq!(op_data.op_accept, 1);
+    assert_eq!(token(&state_map).op_mod, op_data.op_score);
+
+    FreezeOperation::rollback(&op_data, &mut state_map).unwrap();
+    assert_eq!(token(&state_map), &before);
+
+    // Tokens saved before freeze existed are read back as not frozen
+    let mut json = versioned::to_value(&before).unwrap();
+    json.as_object_mut().unwrap().remove("frozen");
+    json[versioned::SCHEMA_VERSION_FIELD] = 2.into();
+    let migrated: StateTokenType = versioned::from_value(json).unwrap();
+    assert!(!migrated.frozen);
+}
+
+#[tokio::test]
+async fn test_rollback_to_block_restores_frozen_flag() {
+    let storage = common::setup_storage("./test_data/freeze_rollback").await;
+
+    // Freeze, unfreeze, then a rejected freeze, all above the rollback target
+    let mut state_map = deployed_state_map();
+    let before = token(&state_map).clone();
+    let op_data_list = [
+        freeze(OWNER_ADDRESS, 2000, &mut state_map),
+        freeze(OWNER_ADDRESS, 2001, &mut state_map),
+        freeze(OTHER_ADDRESS, 2002, &mut state_map),
+    ];
+    let op_accept: Vec<i8> = op_data_list
+        .iter()
+        .map(|op_data| op_data.op_accept)
+        .collect();
+    assert_eq!(op_accept, [1, 1, -1]);
+
+    let token_key = StateKey::token("ICED").encode();
+    storage
+        .rocksdb
+        .put_raw(&token_key, &versioned::encode(token(&state_map)).unwrap())
+        .unwrap();
+    for op_data in &op_data_list {
+        storage
+            .rocksdb
+            .put_raw(
+                &format!("opdata:{}", op_data.tx_id),
+                &versioned::encode(op_data).unwrap(),
+            )
+            .unwrap();
+    }
+
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    rollback_manager
+        .rollback_data_to_block("block_hash", 1000)
+        .await
+        .unwrap();
+
+    let value = storage.rocksdb.get_raw(&token_key).unwrap().unwrap();
+    assert_eq!(versioned::decode::<StateTokenType>(&value).unwrap(), before);
+    for op_data in &op_data_list {
+        let key = format!("opdata:{}", op_data.tx_id);
+        assert!(storage.rocksdb.get_raw(&key).unwrap().is_none());
+    }
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use common::{address, deploy, execute_accepted, op_data, transfer};
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::holders::{
+    HolderCountCorrection, get_holder_count, reconcile_holder_counts,
+};
+use std::sync::Arc;
+
+fn holders(storage: &StorageManager) -> u64 {
+    get_holder_count(&storage.rocksdb, "HOLD").unwrap()
+}
+
+async fn deployed_storage() -> (Arc<StorageManager>, OperationManager) {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let owner = address(1);
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![deploy(0, "HOLD", &owner, "0", "8")],
+    );
+    assert_eq!(holders(&storage), 0);
+    (storage, operation_manager)
+}
+
+#[tokio::test]
+async fn test_new_holders_counted() {
+    let (storage, operation_manager) = deployed_storage().await;
+    let (alice, bob) = (address(2), address(3));
+
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![op_data(1, "mint", "HOLD", &alice, &alice)],
+    );
+    assert_eq!(holders(&storage), 1);
+
+    // A second mint of the same holder does not count it again
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![op_data(2, "mint", "HOLD", &alice, &alice)],
+    );
+    assert_eq!(holders(&storage), 1);
+
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![transfer(3, "HOLD", &alice, &bob, "500")],
+    );
+    assert_eq!(holders(&storage), 2);
+}
+
+#[tokio::test]
+async fn test_emptied_holder_uncounted_and_restored_on_rollback() {
+    let (storage, operation_manager) = deployed_storage().await;
+    let (alice, bob) = (address(2), address(3));
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![
+            op_data(1, "mint", "HOLD", &alice, &alice),
+            op_data(2, "mint", "HOLD", &bob, &bob),
+        ],
+    );
+    assert_eq!(holders(&storage), 2);
+
+    // Bob sends the whole balance to alice, who already holds the tick
+    let state_map_before = execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![transfer(3, "HOLD", &bob, &alice, "1000")],
+    )
+    .state_map_before;
+    assert_eq!(holders(&storage), 1);
+
+    // Restoring the state before the batch reverses its holder changes
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map_before)
+        .unwrap();
+    assert_eq!(holders(&storage), 2);
+}
+
+#[tokio::test]
+async fn test_transfer_between_holders_keeps_count() {
+    let (storage, operation_manager) = deployed_storage().await;
+    let (alice, bob) = (address(2), address(3));
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![
+            op_data(1, "mint", "HOLD", &alice, &alice),
+            op_data(2, "mint", "HOLD", &bob, &bob),
+        ],
+    );
+    assert_eq!(holders(&storage), 2);
+
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![
+            transfer(3, "HOLD", &alice, &bob, "400"),
+            transfer(4, "HOLD", &bob, &alice, "100"),
+        ],
+    );
+    assert_eq!(holders(&storage), 2);
+}
+
+#[tokio::test]
+async fn test_reconciliation_corrects_drifted_count() {
+    let (storage, operation_manager) = deployed_storage().await;
+    let (alice, bob) = (address(2), address(3));
+    execute_accepted(
+        &storage,
+        &operation_manager,
+        vec![
+            op_data(1, "mint", "HOLD", &alice, &alice),
+            op_data(2, "mint", "HOLD", &bob, &bob),
+        ],
+    );
+    assert!(
+        reconcile_holder_counts(&storage.rocksdb)
+            .unwrap()
+            .is_empty()
+    );
+
+    // A count left behind by a partial write
+    storage.rocksdb.put_raw("stat_holders_HOLD", b"5").unwrap();
+    assert_eq!(
+        reconcile_holder_counts(&storage.rocksdb).unwrap(),
+        vec![HolderCountCorrection {
+            tick: "HOLD".to_string(),
+            cached: 5,
+            actual: 2,
+        }]
+    );
+    assert_eq!(holders(&storage), 2);
+    assert!(
+        reconcile_holder_counts(&storage.rocksdb)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,475 @@
+mod common;
+
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::http::{
+    HttpState, build_internal_router, build_public_router, build_router_with_state,
+};
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::deadletter;
+use kaspa_indexer_rust::storage::holders;
+use kaspa_indexer_rust::storage::types::{
+    DataOperationType, DataRollbackType, DataStateMapType, DataVspcType,
+};
+use kaspa_indexer_rust::storage::versioned;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const ADMIN_TOKEN: &str = "test_admin_token";
+
+async fn setup_storage(path: &str) -> Arc<StorageManager> {
+    let storage = common::setup_storage(path).await;
+    common::seed_rollback_target(&storage).await;
+    storage
+}
+
+async fn spawn_server(storage: Arc<StorageManager>) -> SocketAddr {
+    spawn_router(storage, build_router_with_state).await
+}
+
+async fn spawn_router(
+    storage: Arc<StorageManager>,
+    build_router: fn(HttpState) -> axum::Router,
+) -> SocketAddr {
+    let mut state = common::http_state(storage);
+    state.admin_token = ADMIN_TOKEN.to_string();
+    common::serve(build_router(state)).await
+}
+
+async fn post_rollback(addr: SocketAddr, token: Option<&str>) -> reqwest::Response {
+    let mut request = reqwest::Client::new()
+        .post(format!("http://{}/v1/admin/rollback", addr))
+        .json(&serde_json::json!({
+            "target_daa_score": 100,
+            "target_block_hash": "block_hash_100",
+        }));
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_admin_rollback_unauthorized() {
+    let storage = setup_storage("./test_data/http_admin_unauthorized").await;
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    let response = post_rollback(addr, None).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = post_rollback(addr, Some("wrong_token")).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // Checkpoint must be untouched
+    let (_, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(daa_score, 200);
+}
+
+#[tokio::test]
+async fn test_admin_rollback_in_progress() {
+    let storage = setup_storage("./test_data/http_admin_in_progress").await;
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    assert!(storage.runtime.try_begin_rollback());
+    let response = post_rollback(addr, Some(ADMIN_TOKEN)).await;
+    assert_eq!(response.status().as_u16(), 409);
+    storage.runtime.end_rollback();
+}
+
+async fn get_data(addr: SocketAddr, path: &str) -> serde_json::Value {
+    let response = reqwest::get(format!("http://{}{}", addr, path))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200, "{}", path);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["data"].clone()
+}
+
+#[tokio::test]
+async fn test_admin_rollback_success() {
+    let path = "./test_data/http_admin_success";
+    let storage = setup_storage(path).await;
+    let (alice, bob) = (common::address(1), common::address(2));
+
+    // Alice holds the tick at the rollback target
+    let mut state_map = DataStateMapType::new();
+    state_map
+        .state_balance_map
+        .extend([common::balance_state(&alice, "TEST", "100")]);
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map)
+        .unwrap();
+
+    // Committed above the target: a transfer making bob a holder and a rejected transfer
+    let mut op_data_list = vec![
+        common::transfer(0, "TEST", &alice, &bob, "60"),
+        common::transfer(1, "TEST", &bob, &alice, "500"),
+    ];
+    for (index, op_data) in op_data_list.iter_mut().enumerate() {
+        op_data.daa_score = 150;
+        op_data.op_score = 150 * 10000 + index as u64;
+    }
+    op_data_list[0].op_accept = 1;
+    op_data_list[1].op_accept = -1;
+    op_data_list[1].op_error = "insufficient balance".to_string();
+    let mut state_map = DataStateMapType::new();
+    state_map.state_balance_map.extend([
+        common::balance_state(&alice, "TEST", "40"),
+        common::balance_state(&bob, "TEST", "60"),
+    ]);
+    RollbackManager::new(Arc::clone(&storage))
+        .unwrap()
+        .save_op_state_batch(&op_data_list, &state_map)
+        .await
+        .unwrap();
+
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let stats = get_data(addr, "/v1/stats").await;
+    assert_eq!(stats["total_operations"], 2);
+    assert_eq!(stats["op_score"], 150 * 10000 + 1);
+    let tick_stats = get_data(addr, "/v1/krc20/token/TEST/stats").await;
+    assert_eq!(tick_stats["accepted"], 1);
+    assert_eq!(tick_stats["rejected"], 1);
+    assert_eq!(
+        holders::get_holder_count(&storage.rocksdb, "TEST").unwrap(),
+        2
+    );
+
+    let response = post_rollback(addr, Some(ADMIN_TOKEN)).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["data"]["total_operations"].is_u64());
+
+    let (block_hash, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_hash_100");
+    assert_eq!(daa_score, 100);
+    assert!(!storage.runtime.is_rollback_in_progress());
+
+    // The operation records are gone and the state is back at the target
+    for op_data in &op_data_list {
+        let key = format!("opdata:{}", op_data.tx_id);
+        assert!(storage.rocksdb.get_raw(&key).unwrap().is_none());
+    }
+    assert!(storage.rocksdb.scan_prefix("oplist:").unwrap().is_empty());
+    assert_eq!(
+        common::balance(&storage, &alice, "TEST").as_deref(),
+        Some("100")
+    );
+    assert_eq!(
+        common::balance(&storage, &bob, "TEST").as_deref(),
+        Some("0")
+    );
+    assert_eq!(
+        holders::get_holder_count(&storage.rocksdb, "TEST").unwrap(),
+        1
+    );
+
+    let tick_stats = get_data(addr, "/v1/krc20/token/TEST/stats").await;
+    assert_eq!(tick_stats["accepted"], 0);
+    assert_eq!(tick_stats["rejected"], 0);
+    let stats = get_data(addr, "/v1/stats").await;
+    assert_eq!(stats["total_operations"], 0);
+    assert_eq!(stats["op_score"], 0);
+}
+
+#[tokio::test]
+async fn test_admin_auth_middleware() {
+    let storage = setup_storage("./test_data/http_admin_auth").await;
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    // Missing token
+    let response = post_rollback(addr, None).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // Wrong token, including a prefix of the correct one
+    let response = post_rollback(addr, Some("wrong_token")).await;
+    assert_eq!(response.status().as_u16(), 401);
+    let response = post_rollback(addr, Some("test_admin")).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // Correct token reaches the handler
+    let response = post_rollback(addr, Some(ADMIN_TOKEN)).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Public read endpoints stay unauthenticated
+    let response = reqwest::get(format!("http://{}/v1/info", addr)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn test_admin_bind_separates_listeners() {
+    let storage = setup_storage("./test_data/http_admin_bind").await;
+    let public_addr = spawn_router(Arc::clone(&storage), build_public_router).await;
+    let admin_addr = spawn_router(Arc::clone(&storage), build_internal_router).await;
+    let status = |addr: SocketAddr, path: &str| {
+        let request = reqwest::Client::new()
+            .get(format!("http://{}{}", addr, path))
+            .header("Authorization", format!("Bearer {}", ADMIN_TOKEN));
+        async move { request.send().await.unwrap().status().as_u16() }
+    };
+
+    // The public listener serves no admin or metrics routes, even with the admin token
+    assert_eq!(status(public_addr, "/v1/admin/rollback/stats").await, 404);
+    assert_eq!(status(public_addr, "/v1/admin/deadletter").await, 404);
+    assert_eq!(status(public_addr, "/v1/metrics/operations").await, 404);
+    let response = post_rollback(public_addr, Some(ADMIN_TOKEN)).await;
+    assert_eq!(response.status().as_u16(), 404);
+    assert_eq!(status(public_addr, "/v1/info").await, 200);
+
+    // The admin listener serves them, still behind the admin token, and nothing public
+    assert_eq!(status(admin_addr, "/v1/admin/rollback/stats").await, 200);
+    assert_eq!(status(admin_addr, "/v1/metrics/operations").await, 200);
+    let response = post_rollback(admin_addr, None).await;
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(status(admin_addr, "/v1/info").await, 404);
+}
+
+#[tokio::test]
+async fn test_admin_prune_dry_run() {
+    let storage = setup_storage("./test_data/http_admin_prune").await;
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/v1/admin/prune/dry-run", addr))
+        .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+        .json(&serde_json::json!({ "horizon_daa_score": 1000 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["dry_run"], true);
+    assert_eq!(body["data"]["horizon_daa_score"], 1000);
+    assert!(body["data"]["opdata_keys"].is_u64());
+}
+
+#[tokio::test]
+async fn test_admin_deadletter_listing() {
+    let path = "./test_data/http_admin_deadletter";
+    let storage = setup_storage(path).await;
+    deadletter::record_failed_batch(
+        &storage.rocksdb,
+        &[],
+        100,
+        200,
+        &anyhow::anyhow!("write failed"),
+    )
+    .unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    let url = format!("http://{}/v1/admin/deadletter", addr);
+    let response = reqwest::get(&url).await.unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let entries = body["data"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["daaScoreStart"], 100);
+    assert_eq!(entries[0]["daaScoreEnd"], 200);
+    assert_eq!(entries[0]["error"], "write failed");
+    assert_eq!(entries[0]["attempts"], 1);
+}
+
+fn accepted_op(tx_id: &str, block_accept: &str, daa_score: u64) -> DataOperationType {
+    DataOperationType {
+        tx_id: tx_id.to_string(),
+        daa_score,
+        block_accept: block_accept.to_string(),
+        fee: 0,
+        fee_least: 0,
+        mts_add: 0,
+        op_score: daa_score * 10000,
+        op_accept: 1,
+        op_error: String::new(),
+        op_script: vec![],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: None,
+    }
+}
+
+async fn get_candidates(addr: SocketAddr, query: &str) -> Vec<serde_json::Value> {
+    let response = reqwest::Client::new()
+        .get(format!(
+            "http://{}/v1/admin/rollback/candidates{}",
+            addr, query
+        ))
+        .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["data"].as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_admin_rollback_candidates() {
+    let path = "./test_data/http_admin_candidates";
+    let storage = setup_storage(path).await;
+    let vspc_list: Vec<DataVspcType> = [100, 150, 200]
+        .into_iter()
+        .map(|daa_score| DataVspcType {
+            daa_score,
+            hash: format!("block_hash_{}", daa_score),
+            tx_id_list: vec![],
+            selected_parent: String::new(),
+        })
+        .collect();
+    storage
+        .runtime
+        .set_runtime_vspc_last(&vspc_list)
+        .await
+        .unwrap();
+    for op_data in [
+        accepted_op("tx_a", "block_hash_100", 100),
+        accepted_op("tx_b", "block_hash_150", 150),
+        accepted_op("tx_c", "block_hash_150", 150),
+        accepted_op("tx_d", "block_hash_200", 200),
+    ] {
+        storage
+            .rocksdb
+            .put_raw(
+                &format!("opdata:{}", op_data.tx_id),
+                &versioned::encode(&op_data).unwrap(),
+            )
+            .unwrap();
+    }
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    let url = format!("http://{}/v1/admin/rollback/candidates", addr);
+    let response = reqwest::get(&url).await.unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    // The last processed block is no rollback target, the rest are listed newest first
+    let candidates = get_candidates(addr, "").await;
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0]["block_hash"], "block_hash_150");
+    assert_eq!(candidates[0]["daa_score"], 150);
+    assert_eq!(candidates[0]["operation_count"], 2);
+    assert_eq!(candidates[0]["rollback_operation_count"], 1);
+    assert_eq!(candidates[1]["block_hash"], "block_hash_100");
+    assert_eq!(candidates[1]["operation_count"], 1);
+    assert_eq!(candidates[1]["rollback_operation_count"], 3);
+    assert!(candidates[1]["estimated_bytes"].as_u64() > candidates[0]["estimated_bytes"].as_u64());
+    assert!(candidates[1]["estimated_duration_ms"].is_u64());
+
+    let candidates = get_candidates(addr, "?max_blocks=1").await;
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0]["block_hash"], "block_hash_150");
+
+    // A listed candidate is a valid rollback target, after which the list is refreshed
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/v1/admin/rollback", addr))
+        .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+        .json(&serde_json::json!({
+            "target_daa_score": candidates[0]["daa_score"],
+            "target_block_hash": candidates[0]["block_hash"],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let candidates = get_candidates(addr, "").await;
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0]["block_hash"], "block_hash_100");
+    std::fs::remove_dir_all(path).ok();
+}
+
+async fn get_rollback_stats(addr: SocketAddr) -> serde_json::Value {
+    let response = reqwest::Client::new()
+        .get(format!("http://{}/v1/admin/rollback/stats", addr))
+        .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["data"].clone()
+}
+
+#[tokio::test]
+async fn test_admin_rollback_stats() {
+    let path = "./test_data/http_admin_rollback_stats";
+    let storage = setup_storage(path).await;
+    let vspc_list: Vec<DataVspcType> = [100, 150, 200]
+        .into_iter()
+        .map(|daa_score| DataVspcType {
+            daa_score,
+            hash: format!("block_hash_{}", daa_score),
+            tx_id_list: vec![],
+            selected_parent: String::new(),
+        })
+        .collect();
+    storage
+        .runtime
+        .set_runtime_vspc_last(&vspc_list)
+        .await
+        .unwrap();
+    let op_data_list = [
+        accepted_op("tx_a", "block_hash_100", 100),
+        accepted_op("tx_b", "block_hash_150", 150),
+        accepted_op("tx_c", "block_hash_200", 200),
+    ];
+    for op_data in &op_data_list {
+        storage
+            .rocksdb
+            .put_raw(
+                &format!("opdata:{}", op_data.tx_id),
+                &versioned::encode(op_data).unwrap(),
+            )
+            .unwrap();
+    }
+    // Rollback data of the last two batches
+    let rollback_list: Vec<DataRollbackType> = op_data_list[1..]
+        .iter()
+        .map(|op_data| {
+            DataRollbackType::new(
+                DataStateMapType::new(),
+                DataStateMapType::new(),
+                vec![op_data.op_score],
+                vec![op_data.tx_id.clone()],
+                op_data.daa_score,
+                op_data.daa_score,
+                String::new(),
+                String::new(),
+                op_data.op_score,
+            )
+        })
+        .collect();
+    storage
+        .runtime
+        .set_runtime_rollback_last(&rollback_list)
+        .await
+        .unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    let url = format!("http://{}/v1/admin/rollback/stats", addr);
+    let response = reqwest::get(&url).await.unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    let stats = get_rollback_stats(addr).await;
+    assert_eq!(stats["total_operations"], 3);
+    assert_eq!(stats["completed_operations"], 0);
+    assert_eq!(stats["rollback_candidates"], 2);
+    assert_eq!(stats["rollback_history_count"], 2);
+    assert!(stats["last_rollback_timestamp"].as_u64().unwrap() > 0);
+
+    // Cached statistics are refreshed once a rollback completed
+    let response = post_rollback(addr, Some(ADMIN_TOKEN)).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let stats = get_rollback_stats(addr).await;
+    assert_eq!(stats["rollback_candidates"], 0);
+    std::fs::remove_dir_all(path).ok();
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use kaspa_indexer_rust::config::types::CacheControlConfig;
+use kaspa_indexer_rust::http::build_router_with_state;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::{DataOperationType, DataStateMapType, StateBalanceType};
+use kaspa_indexer_rust::storage::versioned;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const LAST_PROCESSED_DAA_SCORE: u64 = 5000;
+const FINAL_TX: &str = "0a0e7c29b5f4d1e3a8c6b2d94f17e05c3b8a6d2f41e9c7b05a3d8f6e2c1b4a90";
+const RECENT_TX: &str = "5d21f8a3c6e94b07d2a1f5c8e3b6d9a04c7f2e1b8a5d3c6f9e0b2a4d7c1f8e36";
+
+async fn spawn_server(storage: Arc<StorageManager>) -> SocketAddr {
+    let mut state = common::http_state(storage);
+    state.cache_control = CacheControlConfig {
+        info_max_age: 7,
+        final_op_max_age: 3600,
+        finality_depth: 1000,
+    };
+    common::serve(build_router_with_state(state)).await
+}
+
+fn save_op(storage: &StorageManager, tx_id: &str, daa_score: u64) {
+    let op_data = DataOperationType {
+        tx_id: tx_id.to_string(),
+        daa_score,
+        block_accept: "block_hash".to_string(),
+        fee: 100000000,
+        fee_least: 0,
+        mts_add: 0,
+        op_score: daa_score * 10000,
+        op_accept: 1,
+        op_error: String::new(),
+        op_script: vec![],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: None,
+    };
+    storage
+        .rocksdb
+        .put_raw(
+            &format!("opdata:{}", tx_id),
+            &versioned::encode(&op_data).unwrap(),
+        )
+        .unwrap();
+}
+
+async fn cache_control(addr: SocketAddr, path: &str) -> Option<String> {
+    let response = reqwest::get(format!("http://{}{}", addr, path))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200, "{}", path);
+    response
+        .headers()
+        .get("cache-control")
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_cache_control_per_endpoint() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    storage
+        .runtime
+        .save_checkpoint("block_hash", LAST_PROCESSED_DAA_SCORE)
+        .unwrap();
+    let storage = Arc::new(storage);
+    save_op(&storage, FINAL_TX, LAST_PROCESSED_DAA_SCORE - 1000);
+    save_op(&storage, RECENT_TX, LAST_PROCESSED_DAA_SCORE - 999);
+    let mut state_map = DataStateMapType::new();
+    state_map.state_balance_map.insert(
+        "kaspatest:qz_KASP".to_string(),
+        Some(StateBalanceType {
+            address: "kaspatest:qz".to_string(),
+            tick: "KASP".to_string(),
+            dec: 8,
+            balance: "100".to_string(),
+            locked: "0".to_string(),
+            op_mod: 0,
+        }),
+    );
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    assert_eq!(
+        cache_control(addr, "/v1/info").await.as_deref(),
+        Some("public, max-age=7")
+    );
+
+    // Operations are only immutable once beyond the finality depth
+    assert_eq!(
+        cache_control(addr, &format!("/v1/krc20/op/{}", FINAL_TX))
+            .await
+            .as_deref(),
+        Some("public, max-age=3600, immutable")
+    );
+    assert_eq!(
+        cache_control(addr, &format!("/v1/krc20/op/{}", RECENT_TX))
+            .await
+            .as_deref(),
+        Some("no-cache")
+    );
+
+    // Live balances are never cached
+    for path in [
+        "/v1/krc20/address/kaspatest:qz/tokenlist",
+        "/v1/krc20/address/kaspatest:qz/token/KASP",
+    ] {
+        assert_eq!(
+            cache_control(addr, path).await.as_deref(),
+            Some("no-store"),
+            "{}",
+            path
+        );
+    }
+}
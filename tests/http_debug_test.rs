@@ -0,0 +1,112 @@
+mod common;
+
+use kaspa_indexer_rust::http::build_router_with_state;
+use kaspa_indexer_rust::storage::StorageManager;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const KEY_HEX: &str = "abababababababababababababababababababababababababababababababab";
+
+async fn spawn_server(debug_endpoints: bool) -> SocketAddr {
+    let mut state = common::http_state(Arc::new(StorageManager::new_in_memory().unwrap()));
+    state.debug_endpoints = debug_endpoints;
+    common::serve(build_router_with_state(state)).await
+}
+
+/// Encode a data push, corresponding to the length prefixes handled by parse_script_input
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else if len <= 255 {
+        format!("4c{:02x}{}", len, data_hex)
+    } else {
+        format!("4d{:02x}{:02x}{}", len & 0xff, len >> 8, data_hex)
+    }
+}
+
+/// Commit-reveal signature script of a single key carrying `payload`
+fn signature_script(payload: &str) -> String {
+    let redeem_script = format!(
+        "20{}ac006307{}00{}68",
+        KEY_HEX,
+        hex::encode("kasplex"),
+        push_data(&hex::encode(payload))
+    );
+    format!(
+        "{}{}",
+        push_data(&"00".repeat(65)),
+        push_data(&redeem_script)
+    )
+}
+
+async fn parse_script(
+    addr: SocketAddr,
+    script_hex: &str,
+    testnet: bool,
+) -> (u16, serde_json::Value) {
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/v1/debug/parse-script", addr))
+        .json(&serde_json::json!({ "script_hex": script_hex, "testnet": testnet }))
+        .send()
+        .await
+        .unwrap();
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_parse_script_returns_parsed_fields() {
+    let addr = spawn_server(true).await;
+    let payload = r#"{"p":"KRC-20","op":"mint","tick":"DBUG","mod_type":""}"#;
+    let script = signature_script(payload);
+
+    let (status, body) = parse_script(addr, &script, true).await;
+    assert_eq!(status, 200);
+    let data = &body["data"];
+    assert_eq!(data["is_op"], true);
+    assert_eq!(
+        data["from"],
+        "kaspatest:qz46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46k0vynt8sd"
+    );
+    assert_eq!(data["p0"], payload);
+    assert_eq!(data["script_sig"], format!("20{}ac", KEY_HEX));
+    assert_eq!(data["fields"]["op"], "mint");
+    assert_eq!(data["fields"]["tick"], "DBUG");
+    assert!(data["fields_error"].is_null());
+
+    let (_, body) = parse_script(addr, &script, false).await;
+    assert_eq!(
+        body["data"]["from"],
+        "kaspa:qz46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46kw2zgyepf"
+    );
+
+    // A payload the indexer can not decode is still reported with the parsed script
+    let (status, body) = parse_script(addr, &signature_script(r#"{"op":1}"#), true).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["data"]["is_op"], true);
+    assert!(body["data"]["fields"].is_null());
+    assert!(body["data"]["fields_error"].is_string());
+}
+
+#[tokio::test]
+async fn test_parse_script_rejects_invalid_scripts() {
+    let addr = spawn_server(true).await;
+
+    // Too short to hold a redeem script
+    let (status, body) = parse_script(addr, "00", true).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["data"]["is_op"], false);
+    assert!(body["data"]["fields"].is_null());
+
+    let (status, body) = parse_script(addr, &"zz".repeat(100), true).await;
+    assert_eq!(status, 400);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_debug_endpoints_disabled_by_default() {
+    let addr = spawn_server(false).await;
+    let (status, _) = parse_script(addr, &signature_script("{}"), true).await;
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use futures_util::stream::{self, Stream};
+use kaspa_indexer_rust::http::build_router_with_state;
+use kaspa_indexer_rust::http::node_tip::NodeTipCache;
+use kaspa_indexer_rust::protobuf::protowire::rpc_server::{Rpc, RpcServer};
+use kaspa_indexer_rust::protobuf::protowire::*;
+use kaspa_indexer_rust::storage::StorageManager;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tonic::{Request, Response, Status, Streaming};
+
+/// Mock Kaspa node answering GetBlockDagInfo with a fixed virtual DAA score
+struct MockNode {
+    virtual_daa_score: u64,
+    calls: Arc<AtomicUsize>,
+}
+
+#[tonic::async_trait]
+impl Rpc for MockNode {
+    type MessageStreamStream =
+        Pin<Box<dyn Stream<Item = Result<KaspadResponse, Status>> + Send + 'static>>;
+
+    async fn message_stream(
+        &self,
+        request: Request<Streaming<KaspadRequest>>,
+    ) -> Result<Response<Self::MessageStreamStream>, Status> {
+        let request = request.into_inner().message().await?.unwrap();
+        let Some(kaspad_request::Payload::GetBlockDagInfoRequest(_)) = request.payload else {
+            return Err(Status::unimplemented("unexpected request"));
+        };
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let response = KaspadResponse {
+            id: request.id,
+            payload: Some(kaspad_response::Payload::GetBlockDagInfoResponse(
+                GetBlockDagInfoResponseMessage {
+                    virtual_daa_score: self.virtual_daa_score,
+                    ..Default::default()
+                },
+            )),
+        };
+        Ok(Response::new(Box::pin(stream::iter([Ok(response)]))))
+    }
+}
+
+async fn spawn_mock_node(virtual_daa_score: u64, calls: Arc<AtomicUsize>) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = stream::unfold(listener, |listener| async move {
+        let stream = listener.accept().await.map(|(stream, _)| stream);
+        Some((stream, listener))
+    });
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(RpcServer::new(MockNode {
+                virtual_daa_score,
+                calls,
+            }))
+            .serve_with_incoming(incoming),
+    );
+    addr
+}
+
+async fn spawn_server(storage: Arc<StorageManager>, node_tip: NodeTipCache) -> SocketAddr {
+    let mut state = common::http_state(storage);
+    state.node_tip = Arc::new(node_tip);
+    common::serve(build_router_with_state(state)).await
+}
+
+async fn setup_storage(path: &str, last_processed_daa_score: u64) -> Arc<StorageManager> {
+    let storage = common::setup_storage(path).await;
+    storage
+        .runtime
+        .save_checkpoint("block_hash", last_processed_daa_score)
+        .unwrap();
+    storage
+}
+
+async fn get_info(addr: SocketAddr) -> serde_json::Value {
+    let response = reqwest::get(format!("http://{}/v1/info", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["data"].clone()
+}
+
+#[tokio::test]
+async fn test_info_reports_lag_from_node_tip() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let node_addr = spawn_mock_node(1500, Arc::clone(&calls)).await;
+    let storage = setup_storage("./test_data/http_info_lag", 1200).await;
+    let node_tip = NodeTipCache::new(format!("http://{}", node_addr), Duration::from_secs(60));
+    let addr = spawn_server(storage, node_tip).await;
+
+    let data = get_info(addr).await;
+    assert_eq!(data["status"], "ok");
+    assert_eq!(data["node_daa_score"], 1500);
+    assert_eq!(data["last_processed_daa_score"], 1200);
+    assert_eq!(data["lag"], 300);
+    assert_eq!(data["checkpoint_hash"], "blake3");
+
+    // The second call is served from the cache
+    let data = get_info(addr).await;
+    assert_eq!(data["lag"], 300);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_info_without_node() {
+    let storage = setup_storage("./test_data/http_info_no_node", 1200).await;
+    let addr = spawn_server(storage, NodeTipCache::disabled()).await;
+
+    let data = get_info(addr).await;
+    assert_eq!(data["status"], "ok");
+    assert!(data["node_daa_score"].is_null());
+    assert_eq!(data["last_processed_daa_score"], 1200);
+    assert!(data["lag"].is_null());
+}
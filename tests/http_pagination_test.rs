@@ -0,0 +1,124 @@
+mod common;
+
+use kaspa_indexer_rust::http::build_router_with_state;
+use kaspa_indexer_rust::http::pagination::{PAGE_LIMIT_DEFAULT, Pagination};
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::events::{self, ScanEvent};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const MAX_PAGE_SIZE: usize = 5;
+
+async fn spawn_server(storage: Arc<StorageManager>) -> SocketAddr {
+    let mut state = common::http_state(storage);
+    state.max_page_size = MAX_PAGE_SIZE;
+    common::serve(build_router_with_state(state)).await
+}
+
+#[test]
+fn test_pagination_limit_is_clamped() {
+    let pagination = Pagination::parse(None, None, 500).unwrap();
+    assert_eq!(pagination.limit, PAGE_LIMIT_DEFAULT);
+    assert_eq!(pagination.cursor, None);
+
+    assert_eq!(
+        Pagination::parse(Some("10000"), None, 500).unwrap().limit,
+        500
+    );
+    assert_eq!(Pagination::parse(Some("0"), None, 500).unwrap().limit, 1);
+    // The default never exceeds a smaller configured maximum
+    assert_eq!(Pagination::parse(None, None, 20).unwrap().limit, 20);
+    assert!(Pagination::parse(Some("-1"), None, 500).is_err());
+
+    let cursor = Pagination::encode_cursor("tick_KASP");
+    let pagination = Pagination::parse(None, Some(&cursor), 500).unwrap();
+    assert_eq!(pagination.cursor.as_deref(), Some("tick_KASP"));
+    assert!(Pagination::parse(None, Some("not-a-cursor"), 500).is_err());
+}
+
+#[tokio::test]
+async fn test_list_endpoints_enforce_pagination() {
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    for _ in 0..8 {
+        let event = ScanEvent::Rollback {
+            undone: false,
+            vspc_list: vec![],
+        };
+        events::append_event(&storage.rocksdb, event, 0, 0, 100).unwrap();
+    }
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    // Over-max limits are clamped to the configured maximum
+    let url = format!("http://{}/v1/events?after=0&limit=1000", addr);
+    let response = reqwest::get(&url).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["data"].as_array().unwrap().len(), MAX_PAGE_SIZE);
+
+    // Invalid cursors and limits are rejected on every list endpoint
+    for path in [
+        "/v1/krc20/tokenlist",
+        "/v1/krc20/oplist",
+        "/v1/krc20/market/KASP",
+        "/v1/krc20/address/kaspatest:qz/tokenlist",
+        "/v1/events",
+    ] {
+        let url = format!("http://{}{}?cursor=zz", addr, path);
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status().as_u16(), 400, "{}", path);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "invalid cursor: zz");
+
+        let url = format!("http://{}{}?limit=many", addr, path);
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status().as_u16(), 400, "{}", path);
+
+        let url = format!(
+            "http://{}{}?limit=1000&cursor={}",
+            addr,
+            path,
+            Pagination::encode_cursor("KASP")
+        );
+        let response = reqwest::get(&url).await.unwrap();
+        // The oplist cursor is an op_score, a tick is not a position in it
+        let expected = if path == "/v1/krc20/oplist" { 400 } else { 200 };
+        assert_eq!(response.status().as_u16(), expected, "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn test_tokenlist_clamps_over_max_limit() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let owner = common::address(1);
+    let deploy_list = ["TOKA", "TOKB", "TOKC", "TOKD", "TOKE", "TOKF", "TOKG"]
+        .into_iter()
+        .enumerate()
+        .map(|(index, tick)| common::deploy(index as u64, tick, &owner, "0", "8"))
+        .collect();
+    common::execute_accepted(&storage, &operation_manager, deploy_list);
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    // The tokenlist shares the extractor of every list endpoint, a limit above
+    // http.max_page_size is clamped rather than rejected
+    let url = format!("http://{}/v1/krc20/tokenlist?limit=1000", addr);
+    let response = reqwest::get(&url).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let items = body["data"]["items"].as_array().unwrap();
+    assert_eq!(items.len(), MAX_PAGE_SIZE);
+    assert_eq!(items[0]["tick"], "TOKG");
+    assert_eq!(body["data"]["hasMore"], true);
+
+    let url = format!(
+        "http://{}/v1/krc20/tokenlist?limit=1000&cursor={}",
+        addr,
+        body["data"]["nextCursor"].as_str().unwrap()
+    );
+    let body: serde_json::Value = reqwest::get(&url).await.unwrap().json().await.unwrap();
+    assert_eq!(body["data"]["items"].as_array().unwrap().len(), 2);
+    assert_eq!(body["data"]["hasMore"], false);
+}
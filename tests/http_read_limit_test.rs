@@ -0,0 +1,72 @@
+mod common;
+
+use kaspa_indexer_rust::http::build_router_with_state;
+use kaspa_indexer_rust::storage::StorageManager;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+const MAX_CONCURRENT_READS: usize = 2;
+
+async fn spawn_server(read_permits: Arc<Semaphore>) -> SocketAddr {
+    let mut state = common::http_state(Arc::new(StorageManager::new_in_memory().unwrap()));
+    state.read_permits = read_permits;
+    common::serve(build_router_with_state(state)).await
+}
+
+/// Status codes of `count` concurrent requests to `path`
+async fn concurrent_statuses(addr: SocketAddr, path: &str, count: usize) -> Vec<u16> {
+    let requests = (0..count).map(|_| async move {
+        reqwest::get(format!("http://{}{}", addr, path))
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    });
+    futures_util::future::join_all(requests).await
+}
+
+#[tokio::test]
+async fn test_reads_beyond_limit_are_shed() {
+    let read_permits = Arc::new(Semaphore::new(MAX_CONCURRENT_READS));
+    let addr = spawn_server(Arc::clone(&read_permits)).await;
+
+    assert_eq!(
+        concurrent_statuses(addr, "/v1/krc20/tokenlist", MAX_CONCURRENT_READS).await,
+        vec![200; MAX_CONCURRENT_READS]
+    );
+
+    // While the limit is taken by reads in flight, further reads fail fast instead of queuing
+    let in_flight = Arc::clone(&read_permits)
+        .acquire_many_owned(MAX_CONCURRENT_READS as u32)
+        .await
+        .unwrap();
+    let statuses = tokio::time::timeout(
+        Duration::from_secs(5),
+        concurrent_statuses(addr, "/v1/krc20/tokenlist", 5),
+    )
+    .await
+    .unwrap();
+    assert_eq!(statuses, vec![503; 5]);
+    let response = reqwest::get(format!("http://{}/v1/stats", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 503);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "too many concurrent reads");
+
+    // Health and info stay reachable under read load
+    assert_eq!(
+        concurrent_statuses(addr, "/v1/health", 3).await,
+        vec![200; 3]
+    );
+    assert_eq!(concurrent_statuses(addr, "/v1/info", 3).await, vec![200; 3]);
+
+    drop(in_flight);
+    assert_eq!(
+        concurrent_statuses(addr, "/v1/krc20/tokenlist", 1).await,
+        vec![200]
+    );
+    assert_eq!(read_permits.available_permits(), MAX_CONCURRENT_READS);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::{setup_storage, spawn_server};
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::storage::types::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const TEST_ADDRESS: &str = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+fn token_state(tick: &str) -> StateTokenType {
+    StateTokenType {
+        tick: tick.to_string(),
+        max: "21000000".to_string(),
+        lim: "1000".to_string(),
+        pre: "0".to_string(),
+        dec: 8,
+        mod_type: "".to_string(),
+        from: TEST_ADDRESS.to_string(),
+        to: TEST_ADDRESS.to_string(),
+        minted: "0".to_string(),
+        burned: "0".to_string(),
+        name: "".to_string(),
+        desc: String::new(),
+        frozen: false,
+        tx_id: format!("deploy_{}", tick),
+        op_add: 0,
+        op_mod: 0,
+        mts_add: 0,
+        mts_mod: 0,
+    }
+}
+
+fn op_data(op: &str, tick: &str, daa_score: u64, mts_add: i64) -> DataOperationType {
+    let mut op_data = common::op_data(0, op, tick, TEST_ADDRESS, TEST_ADDRESS);
+    op_data.tx_id = format!("{}_{}_{}", op, tick, daa_score);
+    op_data.daa_score = daa_score;
+    op_data.mts_add = mts_add;
+    op_data.op_score = daa_score * 10000;
+    op_data.op_accept = 1;
+    op_data.ss_info = None;
+    op_data
+}
+
+#[tokio::test]
+async fn test_stats_reflect_committed_operations() {
+    // Counters persist, start from an empty database
+    let storage = setup_storage("./test_data/http_stats").await;
+
+    let mut state_map = DataStateMapType {
+        state_token_map: HashMap::new(),
+        state_balance_map: HashMap::new(),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+    for tick in ["AAAA", "BBBB"] {
+        state_map
+            .state_token_map
+            .insert(tick.to_string(), Some(token_state(tick)));
+    }
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+
+    let now_mts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    let two_days_ago = now_mts - 2 * 24 * 60 * 60 * 1000;
+    let op_data_list = vec![
+        op_data("transfer", "AAAA", 900, two_days_ago),
+        op_data("deploy", "AAAA", 1000, now_mts - 1000),
+        op_data("deploy", "BBBB", 1001, now_mts - 900),
+        op_data("mint", "AAAA", 1002, now_mts - 800),
+        op_data("mint", "AAAA", 1003, now_mts - 700),
+        op_data("mint", "BBBB", 1004, now_mts - 600),
+    ];
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    rollback_manager
+        .save_op_data_batch_rocks(&op_data_list)
+        .await
+        .unwrap();
+
+    let addr = spawn_server(storage).await;
+    let response = reqwest::get(format!("http://{}/v1/stats", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let data = &body["data"];
+
+    assert_eq!(data["total_tokens"], 2);
+    assert_eq!(data["total_operations"], 6);
+    assert_eq!(
+        data["operations_24h"],
+        serde_json::json!({ "deploy": 2, "mint": 3 })
+    );
+    assert_eq!(data["op_score"], 1004 * 10000);
+}
@@ -0,0 +1,605 @@
+mod common;
+
+use common::{setup_storage, spawn_server};
+use kaspa_indexer_rust::config::apply_tick_reserved;
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::operations::deploy::DeployOperation;
+use kaspa_indexer_rust::operations::mint::MintOperation;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::tickstats::{self, TickOpStats};
+use kaspa_indexer_rust::storage::types::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const TEST_ADDRESS: &str = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const MINT_TX: &str = "3b9f0c8e2a7d4f61b5c0e9a8d3f27b64c1e05a9d8b3f6c2e7a4d0b9f5c8e1a27";
+
+fn script(op: &str) -> DataScriptType {
+    DataScriptType {
+        p: "KRC-20".to_string(),
+        op: op.to_string(),
+        from: Some(TEST_ADDRESS.to_string()),
+        to: Some(TEST_ADDRESS.to_string()),
+        tick: Some("DEPLY".to_string()),
+        max: Some("21000000".to_string()),
+        lim: Some("1000".to_string()),
+        pre: Some("0".to_string()),
+        dec: Some("8".to_string()),
+        amt: None,
+        utxo: None,
+        price: None,
+        mod_type: "".to_string(),
+        name: None,
+        ca: None,
+        desc: None,
+    }
+}
+
+fn op_data(tx_id: &str, daa_score: u64, fee: u64, op_script: DataScriptType) -> DataOperationType {
+    let mut op_data = common::op_data(0, &op_script.op, "", "", "");
+    op_data.tx_id = tx_id.to_string();
+    op_data.daa_score = daa_score;
+    op_data.fee = fee;
+    op_data.mts_add = 1700000000;
+    op_data.op_score = daa_score * 10000;
+    op_data.op_script = vec![op_script];
+    op_data
+}
+
+#[tokio::test]
+async fn test_token_deploy_info_after_mint() {
+    let storage = setup_storage("./test_data/http_token_deploy").await;
+
+    let mut state_map = DataStateMapType {
+        state_token_map: HashMap::new(),
+        state_balance_map: HashMap::new(),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+
+    // Deploy then mint
+    let deploy_script = script("deploy");
+    let mut deploy = op_data("deploy_tx", 1000, 100000000000, deploy_script.clone());
+    DeployOperation::do_operation(&deploy_script, &mut deploy, &mut state_map, true).unwrap();
+    assert_eq!(deploy.op_accept, 1);
+
+    let mut mint = op_data("mint_tx", 1001, 100000000, script("mint"));
+    MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+    assert_eq!(mint.op_accept, 1);
+
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+    let addr = spawn_server(storage).await;
+
+    let response = reqwest::get(format!("http://{}/v1/krc20/token/deply", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let data = &body["data"];
+
+    // Mutable state reflects the mint
+    assert_eq!(data["minted"], "1000");
+    assert_eq!(data["burned"], "0");
+    assert_eq!(data["owner"], TEST_ADDRESS);
+
+    // Deploy info matches the original deploy script
+    let deploy_info = &data["deploy"];
+    assert_eq!(deploy_info["tick"], "DEPLY");
+    assert_eq!(deploy_info["max"], deploy_script.max.unwrap());
+    assert_eq!(deploy_info["lim"], deploy_script.lim.unwrap());
+    assert_eq!(deploy_info["pre"], deploy_script.pre.unwrap());
+    assert_eq!(deploy_info["dec"], 8);
+    assert_eq!(deploy_info["mod"], "");
+    assert_eq!(deploy_info["deployer"], TEST_ADDRESS);
+    assert_eq!(deploy_info["deploy_tx_id"], "deploy_tx");
+    assert_eq!(deploy_info["deploy_daa_score"], 1000);
+
+    let response = reqwest::get(format!("http://{}/v1/krc20/token/NONE", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "tick not found");
+    assert!(body.get("reserved").is_none());
+
+    // A reserved tick not deployed yet names the address it is reserved for
+    apply_tick_reserved(&[format!("RSVD_{}", TEST_ADDRESS)]);
+    let response = reqwest::get(format!("http://{}/v1/krc20/token/rsvd", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["reserved"], true);
+    assert_eq!(body["reservedAddress"], TEST_ADDRESS);
+}
+
+#[tokio::test]
+async fn test_op_fee_breakdown_for_underpaid_mint() {
+    let storage = setup_storage("./test_data/http_op_fee_breakdown").await;
+
+    let mut state_map = DataStateMapType::new();
+    let deploy_script = script("deploy");
+    let mut deploy = op_data("deploy_tx", 1000, 100000000000, deploy_script.clone());
+    DeployOperation::do_operation(&deploy_script, &mut deploy, &mut state_map, true).unwrap();
+    assert_eq!(deploy.op_accept, 1);
+
+    // Half of the mint fee the parser expects for this DAA score
+    let mut mint = op_data(MINT_TX, 110165000, 50000000, script("mint"));
+    mint.fee_least = 100000000;
+    MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+    assert_eq!(mint.op_accept, -1);
+    assert_eq!(mint.op_error, "fee not enough");
+
+    let breakdown = FeeBreakdown::from(&mint);
+    assert_eq!(
+        breakdown,
+        FeeBreakdown {
+            op_type: "mint".to_string(),
+            daa_score: 110165000,
+            required: 100000000,
+            paid: 50000000,
+            accepted: false,
+        }
+    );
+
+    storage
+        .rocksdb
+        .put_raw(
+            &format!("opdata:{}", MINT_TX),
+            &serde_json::to_vec(&mint).unwrap(),
+        )
+        .unwrap();
+    let addr = spawn_server(storage).await;
+
+    // Transaction ids are matched case-insensitively
+    let response = reqwest::get(format!(
+        "http://{}/v1/krc20/op/{}",
+        addr,
+        MINT_TX.to_uppercase()
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["op"]["op_error"], "fee not enough");
+    let fee: FeeBreakdown = serde_json::from_value(body["data"]["fee"].clone()).unwrap();
+    assert_eq!(fee, breakdown);
+
+    assert_eq!(body["data"]["op"]["op_score"], mint.op_score);
+
+    let unknown = MINT_TX.replace('b', "c");
+    let response = reqwest::get(format!("http://{}/v1/krc20/op/{}", addr, unknown))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+
+    for id in ["unknown_tx", &MINT_TX[1..], &MINT_TX.replace('b', "g")] {
+        let response = reqwest::get(format!("http://{}/v1/krc20/op/{}", addr, id))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 400, "{}", id);
+    }
+}
+
+#[tokio::test]
+async fn test_token_stats_count_accepted_and_rejected_ops() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let mut state_map = DataStateMapType::new();
+
+    // Supply for exactly two mints
+    let mut deploy_script = script("deploy");
+    deploy_script.max = Some("2000".to_string());
+    let mut deploy = op_data("deploy_tx", 1000, 100000000000, deploy_script.clone());
+    DeployOperation::do_operation(&deploy_script, &mut deploy, &mut state_map, true).unwrap();
+    let mut op_data_list = vec![deploy];
+    for (tx_id, daa_score, fee) in [
+        ("mint_1", 1001, 100000000),
+        ("mint_underpaid", 1002, 50000000),
+        ("mint_2", 1003, 100000000),
+        ("mint_exhausted", 1004, 100000000),
+    ] {
+        let mut mint = op_data(tx_id, daa_score, fee, script("mint"));
+        MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+        op_data_list.push(mint);
+    }
+
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    rollback_manager
+        .save_op_data_batch_rocks(&op_data_list)
+        .await
+        .unwrap();
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+
+    let expected = TickOpStats {
+        tick: "DEPLY".to_string(),
+        accepted: 3,
+        rejected: 2,
+        rejected_by_reason: [
+            ("fee not enough".to_string(), 1),
+            ("mint finished".to_string(), 1),
+        ]
+        .into_iter()
+        .collect(),
+    };
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let response = reqwest::get(format!("http://{}/v1/krc20/token/deply/stats", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["rejectedByReason"]["mint finished"], 1);
+    let stats: TickOpStats = serde_json::from_value(body["data"].clone()).unwrap();
+    assert_eq!(stats, expected);
+
+    // Rolling back the last two mints reverts their counts
+    let rollback = DataRollbackType {
+        state_map_before: DataStateMapType::new(),
+        state_map_after: DataStateMapType::new(),
+        op_score_list: vec![1003 * 10000, 1004 * 10000],
+        tx_id_list: vec!["mint_2".to_string(), "mint_exhausted".to_string()],
+        daa_score_start: 1003,
+        daa_score_end: 1004,
+        checkpoint_before: String::new(),
+        checkpoint_after: String::new(),
+        op_score_last: 1004 * 10000,
+    };
+    rollback_manager
+        .rollback_op_state_batch(&rollback)
+        .await
+        .unwrap();
+    let stats = tickstats::get_tick_stats(&storage.rocksdb, "DEPLY")
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.accepted, 2);
+    assert_eq!(stats.rejected, 1);
+    assert!(!stats.rejected_by_reason.contains_key("mint finished"));
+
+    let response = reqwest::get(format!("http://{}/v1/krc20/token/NONE/stats", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+async fn get_tokenlist(addr: SocketAddr, query: &str) -> serde_json::Value {
+    let response = reqwest::get(format!("http://{}/v1/krc20/tokenlist?{}", addr, query))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["data"].clone()
+}
+
+fn ticks(data: &serde_json::Value) -> Vec<String> {
+    data["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["tick"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_tokenlist_filters_by_deploy_daa_score() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let mut state_map = DataStateMapType::new();
+
+    for (tick, daa_score) in [
+        ("EARLY", 1000),
+        ("MIDA", 2000),
+        ("MIDB", 2500),
+        ("LATE", 4000),
+    ] {
+        let mut deploy_script = script("deploy");
+        deploy_script.tick = Some(tick.to_string());
+        let mut deploy = op_data(tick, daa_score, 100000000000, deploy_script.clone());
+        DeployOperation::do_operation(&deploy_script, &mut deploy, &mut state_map, true).unwrap();
+        assert_eq!(deploy.op_accept, 1);
+    }
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    // Newest deploys come first by default
+    let data = get_tokenlist(addr, "").await;
+    assert_eq!(ticks(&data), vec!["LATE", "MIDB", "MIDA", "EARLY"]);
+    assert_eq!(data["hasMore"], false);
+    let data = get_tokenlist(addr, "order=asc").await;
+    assert_eq!(ticks(&data), vec!["EARLY", "MIDA", "MIDB", "LATE"]);
+
+    let data = get_tokenlist(addr, "from_daa=2000&to_daa=3000&order=asc").await;
+    assert_eq!(ticks(&data), vec!["MIDA", "MIDB"]);
+    assert_eq!(data["items"][0]["deploy"]["deploy_daa_score"], 2000);
+    assert_eq!(
+        ticks(&get_tokenlist(addr, "from_daa=2500").await),
+        vec!["LATE", "MIDB"]
+    );
+    assert!(ticks(&get_tokenlist(addr, "to_daa=999").await).is_empty());
+
+    // Pages of the window continue from the returned cursor
+    let data = get_tokenlist(addr, "from_daa=1000&to_daa=3000&order=asc&limit=2").await;
+    assert_eq!(ticks(&data), vec!["EARLY", "MIDA"]);
+    assert_eq!(data["hasMore"], true);
+    let query = format!(
+        "from_daa=1000&to_daa=3000&order=asc&limit=2&cursor={}",
+        data["nextCursor"].as_str().unwrap()
+    );
+    let data = get_tokenlist(addr, &query).await;
+    assert_eq!(ticks(&data), vec!["MIDB"]);
+    assert_eq!(data["hasMore"], false);
+
+    // Newest first pages backwards through the index
+    let data = get_tokenlist(addr, "limit=3").await;
+    assert_eq!(ticks(&data), vec!["LATE", "MIDB", "MIDA"]);
+    assert_eq!(data["hasMore"], true);
+    let query = format!(
+        "limit=3&cursor={}",
+        data["nextCursor"].as_str().unwrap()
+    );
+    let data = get_tokenlist(addr, &query).await;
+    assert_eq!(ticks(&data), vec!["EARLY"]);
+    assert_eq!(data["hasMore"], false);
+
+    for query in ["from_daa=3000&to_daa=2000", "order=newest", "limit=many"] {
+        let response = reqwest::get(format!("http://{}/v1/krc20/tokenlist?{}", addr, query))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 400, "{}", query);
+    }
+
+    // Rolling back a deploy drops the token from the index
+    let mut state_map_before = DataStateMapType::new();
+    state_map_before
+        .state_token_map
+        .insert("MIDA".to_string(), None);
+    let rollback = DataRollbackType {
+        state_map_before,
+        state_map_after: DataStateMapType::new(),
+        op_score_list: vec![],
+        tx_id_list: vec![],
+        daa_score_start: 2000,
+        daa_score_end: 2000,
+        checkpoint_before: String::new(),
+        checkpoint_after: String::new(),
+        op_score_last: 2000 * 10000,
+    };
+    RollbackManager::new(Arc::clone(&storage))
+        .unwrap()
+        .rollback_op_state_batch(&rollback)
+        .await
+        .unwrap();
+    let data = get_tokenlist(addr, "from_daa=2000&to_daa=3000").await;
+    assert_eq!(ticks(&data), vec!["MIDB"]);
+}
+
+#[tokio::test]
+async fn test_address_tokenlist_lists_held_balances() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let other = "kaspatest:qpqz2vxj23kvh0m73ta2jjn2u4cv4tlufqns2eap8mxyyt0rvrxy6ejkful67";
+
+    let mut state_map = DataStateMapType::new();
+    for (address, tick, balance, locked) in [
+        (TEST_ADDRESS, "ALPHA", "500", "0"),
+        (TEST_ADDRESS, "BETA", "0", "0"),
+        (TEST_ADDRESS, "GAMMA", "0", "300"),
+        (TEST_ADDRESS, "DELTA", "70", "0"),
+        (other, "ALPHA", "9", "0"),
+    ] {
+        state_map.state_balance_map.insert(
+            format!("{}_{}", address, tick),
+            Some(StateBalanceType {
+                address: address.to_string(),
+                tick: tick.to_string(),
+                dec: 8,
+                balance: balance.to_string(),
+                locked: locked.to_string(),
+                op_mod: 0,
+            }),
+        );
+    }
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let get = |query: String| async move {
+        let response = reqwest::get(format!("http://{}/v1/krc20/address/{}", addr, query))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        body["data"].clone()
+    };
+
+    // Zero balances are left out, locked amounts still count as held
+    let data = get(format!("{}/tokenlist", TEST_ADDRESS)).await;
+    assert_eq!(ticks(&data), vec!["ALPHA", "DELTA", "GAMMA"]);
+    assert_eq!(data["items"][0]["balance"], "500");
+    assert_eq!(data["items"][0]["dec"], 8);
+    assert_eq!(data["items"][2]["locked"], "300");
+    assert_eq!(data["hasMore"], false);
+
+    let data = get(format!(
+        "{}/tokenlist?includeZero=true&limit=2",
+        TEST_ADDRESS
+    ))
+    .await;
+    assert_eq!(ticks(&data), vec!["ALPHA", "BETA"]);
+    assert_eq!(data["hasMore"], true);
+    let data = get(format!(
+        "{}/tokenlist?includeZero=true&limit=2&cursor={}",
+        TEST_ADDRESS,
+        data["nextCursor"].as_str().unwrap()
+    ))
+    .await;
+    assert_eq!(ticks(&data), vec!["DELTA", "GAMMA"]);
+    assert_eq!(data["hasMore"], false);
+
+    let data = get(format!("{}/tokenlist", other)).await;
+    assert_eq!(ticks(&data), vec!["ALPHA"]);
+
+    // An address without tokens is an empty list
+    let data = get("kaspatest:unknown/tokenlist".to_string()).await;
+    assert!(ticks(&data).is_empty());
+    assert_eq!(data["hasMore"], false);
+
+    // Single balances, the tick is normalized
+    let data = get(format!("{}/token/gamma", TEST_ADDRESS)).await;
+    assert_eq!(data["tick"], "GAMMA");
+    assert_eq!(data["balance"], "0");
+    assert_eq!(data["locked"], "300");
+    assert_eq!(data["dec"], 8);
+    assert_eq!(data["op_mod"], 0);
+    for (path, status) in [
+        (format!("{}/token/NACHO", TEST_ADDRESS), 404),
+        (format!("{}/token/ALPHA", "kaspatest:unknown"), 404),
+        (format!("{}/token/AL", TEST_ADDRESS), 400),
+        (format!("{}/token/ALPHA1", TEST_ADDRESS), 400),
+    ] {
+        let response = reqwest::get(format!("http://{}/v1/krc20/address/{}", addr, path))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), status, "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn test_market_lists_cheapest_listings_first() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let mut state_map = DataStateMapType::new();
+    // (tick, utxo tx id, listed amount, asked price)
+    for (tick, u_tx_id, u_amt, t_amt) in [
+        ("KASP", "tx_c", "1000", "5000"),
+        ("KASP", "tx_a", "200", "400"),
+        ("KASP", "tx_b", "300", "3000"),
+        ("KASP", "tx_d", "100", "200"),
+        ("KASPA", "tx_e", "100", "1"),
+    ] {
+        let listing = StateMarketType {
+            tick: tick.to_string(),
+            t_addr: TEST_ADDRESS.to_string(),
+            u_tx_id: u_tx_id.to_string(),
+            u_addr: "kaspatest:utxo".to_string(),
+            u_amt: u_amt.to_string(),
+            u_script: String::new(),
+            t_amt: t_amt.to_string(),
+            op_add: 1000,
+        };
+        state_map.state_market_map.insert(
+            format!("{}_{}_{}", tick, TEST_ADDRESS, u_tx_id),
+            Some(listing),
+        );
+    }
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let get = |query: String| async move {
+        let response = reqwest::get(format!("http://{}/v1/krc20/market/{}", addr, query))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        body["data"].clone()
+    };
+    let tx_ids = |data: &serde_json::Value| -> Vec<String> {
+        data["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["u_tx_id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Equal prices fall back to the listing transaction id
+    let data = get("kasp".to_string()).await;
+    assert_eq!(tx_ids(&data), vec!["tx_a", "tx_d", "tx_c", "tx_b"]);
+    assert_eq!(data["items"][0]["u_amt"], "200");
+    assert_eq!(data["items"][0]["t_amt"], "400");
+    assert_eq!(data["items"][0]["t_addr"], TEST_ADDRESS);
+    assert_eq!(data["items"][0]["u_addr"], "kaspatest:utxo");
+    assert_eq!(data["items"][0]["op_add"], 1000);
+    assert_eq!(data["hasMore"], false);
+
+    let data = get("KASP?limit=3".to_string()).await;
+    assert_eq!(tx_ids(&data), vec!["tx_a", "tx_d", "tx_c"]);
+    assert_eq!(data["hasMore"], true);
+    let data = get(format!(
+        "KASP?limit=3&cursor={}",
+        data["nextCursor"].as_str().unwrap()
+    ))
+    .await;
+    assert_eq!(tx_ids(&data), vec!["tx_b"]);
+    assert_eq!(data["hasMore"], false);
+
+    let data = get("NACHO".to_string()).await;
+    assert!(tx_ids(&data).is_empty());
+    assert_eq!(data["hasMore"], false);
+
+    for tick in ["KAS", "KASP1", "TOOLONGTICK"] {
+        let response = reqwest::get(format!("http://{}/v1/krc20/market/{}", addr, tick))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 400, "{}", tick);
+    }
+}
+
+#[tokio::test]
+async fn test_blacklist_lookup_by_address_and_tick() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let ca = MINT_TX;
+
+    let mut state_map = DataStateMapType::new();
+    state_map.state_blacklist_map.insert(
+        format!("{}_{}", ca, TEST_ADDRESS),
+        Some(StateBlacklistType {
+            tick: ca.to_string(),
+            address: TEST_ADDRESS.to_string(),
+            op_add: 1234 * 10000,
+        }),
+    );
+    storage.state.save_state_batch_rocks_begin(&state_map).unwrap();
+    assert!(storage.state.is_blacklisted(TEST_ADDRESS, ca).unwrap());
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let get = |path: String| async move {
+        let response = reqwest::get(format!("http://{}/v1/krc20/blacklist/{}", addr, path))
+            .await
+            .unwrap();
+        let status = response.status().as_u16();
+        let body: serde_json::Value = response.json().await.unwrap();
+        (status, body)
+    };
+
+    // The contract address is matched case-insensitively
+    let (status, body) = get(format!("{}_{}", TEST_ADDRESS, ca.to_uppercase())).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["data"]["blacklisted"], true);
+    assert_eq!(body["data"]["op_add"], 1234 * 10000);
+    assert_eq!(body["data"]["tick"], ca);
+
+    let (status, body) = get(format!("{}?address={}", ca, TEST_ADDRESS)).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["data"]["blacklisted"], true);
+
+    let other = "kaspatest:qpqz2vxj23kvh0m73ta2jjn2u4cv4tlufqns2eap8mxyyt0rvrxy6ejkful67";
+    let (status, body) = get(format!("{}_{}", other, ca)).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["data"]["blacklisted"], false);
+    assert!(body["data"]["op_add"].is_null());
+
+    for path in [
+        ca.to_string(),
+        format!("{}_{}", TEST_ADDRESS, &ca[1..]),
+        format!("_{}", ca),
+        format!("{}_{}?address={}", TEST_ADDRESS, ca, other),
+    ] {
+        let (status, body) = get(path.clone()).await;
+        assert_eq!(status, 400, "{}", path);
+        assert!(body["error"].is_string());
+    }
+}
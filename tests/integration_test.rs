@@ -1,4 +1,11 @@
-use kaspa_indexer_rust::config::types::{RocksConfig, StartupConfig};
+use kaspa_indexer_rust::config::types::{
+    RocksConfig, StartupConfig, default_allow_unknown_ops, default_balance_dust_threshold,
+    default_batch_max_retries, default_batch_retry_backoff_ms, default_checkpoint_hash,
+    default_consistency_mode, default_enabled_op_types, default_node_reset_pause_ms,
+    default_op_score_multiplier, default_runtime_flush_interval_ms, default_scan_interval_ms,
+    default_tick_ignored, default_tick_reserved_expiry_daa, default_tick_reserved_max,
+    default_tx_fetch_concurrency, default_vspc_check_window,
+};
 use kaspa_indexer_rust::explorer::{Explorer, ExplorerInterface};
 use kaspa_indexer_rust::operations::handler::OperationManager;
 use kaspa_indexer_rust::storage::StorageManager;
@@ -11,6 +18,7 @@ async fn test_storage_initialization() {
     // Test storage initialization
     let rocks_config = RocksConfig {
         path: "./test_data/rocksdb".to_string(),
+        ..Default::default()
     };
 
     let storage = StorageManager::new(rocks_config, None).await;
@@ -21,11 +29,32 @@ async fn test_storage_initialization() {
     assert!(init_result.is_ok(), "Storage init should succeed");
 }
 
+#[tokio::test]
+async fn test_in_memory_storage_never_touches_filesystem() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let path = storage.rocksdb.get_config().path.clone();
+    assert!(!std::path::Path::new(&path).exists());
+    assert!(!std::path::Path::new("./in-memory").exists());
+
+    storage.rocksdb.put_raw("key", b"value").unwrap();
+    assert_eq!(
+        storage.rocksdb.get_raw("key").unwrap().as_deref(),
+        Some(&b"value"[..])
+    );
+
+    // Every instance starts empty
+    let other = StorageManager::new_in_memory().unwrap();
+    assert_ne!(other.rocksdb.get_config().path, path);
+    assert!(other.rocksdb.get_raw("key").unwrap().is_none());
+}
+
 #[tokio::test]
 async fn test_explorer_initialization() {
     // Test explorer initialization
     let rocks_config = RocksConfig {
         path: "./test_data/rocksdb_explorer".to_string(),
+        ..Default::default()
     };
 
     let storage = StorageManager::new(rocks_config, None).await.unwrap();
@@ -40,8 +69,24 @@ async fn test_explorer_initialization() {
         tick_reserved: vec![
             "NACHO_kaspa:qzrsq2mfj9sf7uye3u5q7juejzlr0axk5jz9fpg4vqe76erdyvxxze84k9nk7".to_string(),
         ],
+        tick_reserved_max: default_tick_reserved_max(),
+        tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+        balance_dust_threshold: default_balance_dust_threshold(),
+        enabled_op_types: default_enabled_op_types(),
+        allow_unknown_ops: default_allow_unknown_ops(),
+        checkpoint_hash: default_checkpoint_hash(),
+        batch_max_retries: default_batch_max_retries(),
+        batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+        consistency_mode: default_consistency_mode(),
+        tick_ignored: default_tick_ignored(),
         kaspa_node_url: "http://localhost:16110".to_string(),
         is_testnet: true,
+        scan_interval_ms: default_scan_interval_ms(),
+        vspc_check_window: default_vspc_check_window(),
+        tx_fetch_concurrency: default_tx_fetch_concurrency(),
+        node_reset_pause_ms: default_node_reset_pause_ms(),
+        op_score_multiplier: default_op_score_multiplier(),
+        runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
     };
 
     let explorer = Explorer::new(Arc::clone(&storage_arc), startup_config, false);
@@ -57,12 +102,13 @@ async fn test_operation_manager() {
     // Test operation manager
     let rocks_config = RocksConfig {
         path: "./test_data/rocksdb_ops".to_string(),
+        ..Default::default()
     };
 
     let storage = StorageManager::new(rocks_config, None).await.unwrap();
     let storage_arc = Arc::new(storage);
 
-    let operation_manager = OperationManager::new(storage_arc.clone());
+    let operation_manager = OperationManager::new(&storage_arc);
     assert!(true, "Operation manager initialization should succeed");
 
     // Use operation manager
@@ -94,6 +140,26 @@ async fn test_operation_manager() {
     }
 }
 
+#[tokio::test]
+async fn test_operation_manager_does_not_keep_storage_alive() {
+    let rocks_config = RocksConfig {
+        path: "./test_data/rocksdb_ops_weak".to_string(),
+        ..Default::default()
+    };
+    let storage = Arc::new(StorageManager::new(rocks_config, None).await.unwrap());
+    storage.set_operation_manager_arc(Arc::new(OperationManager::new(&storage)));
+    let operation_manager = storage.get_operation_manager().unwrap();
+    assert!(operation_manager.prepare_state_batch(&[]).is_ok());
+
+    // Registering the manager must not create a storage -> manager -> storage cycle
+    let storage_weak = Arc::downgrade(&storage);
+    drop(storage);
+    assert!(storage_weak.upgrade().is_none());
+
+    let err = operation_manager.prepare_state_batch(&[]).unwrap_err();
+    assert!(err.to_string().contains("Storage manager dropped"));
+}
+
 #[tokio::test]
 async fn test_vspc_scanner() {
     // Test VSPC scanner
@@ -101,6 +167,7 @@ async fn test_vspc_scanner() {
 
     let rocks_config = RocksConfig {
         path: "./test_data/rocksdb_scanner".to_string(),
+        ..Default::default()
     };
 
     let storage = StorageManager::new(rocks_config, None).await.unwrap();
@@ -110,8 +177,24 @@ async fn test_vspc_scanner() {
         hysteresis: 10,
         daa_score_range: vec![[83441551, 83525600]],
         tick_reserved: vec![],
+        tick_reserved_max: default_tick_reserved_max(),
+        tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+        balance_dust_threshold: default_balance_dust_threshold(),
+        enabled_op_types: default_enabled_op_types(),
+        allow_unknown_ops: default_allow_unknown_ops(),
+        checkpoint_hash: default_checkpoint_hash(),
+        batch_max_retries: default_batch_max_retries(),
+        batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+        consistency_mode: default_consistency_mode(),
+        tick_ignored: default_tick_ignored(),
         kaspa_node_url: "http://localhost:16110".to_string(),
         is_testnet: true,
+        scan_interval_ms: default_scan_interval_ms(),
+        vspc_check_window: default_vspc_check_window(),
+        tx_fetch_concurrency: default_tx_fetch_concurrency(),
+        node_reset_pause_ms: default_node_reset_pause_ms(),
+        op_score_multiplier: default_op_score_multiplier(),
+        runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
     };
 
     let scanner = VSPCScanner::new(storage_arc, startup_config, false);
@@ -147,6 +230,7 @@ async fn test_state_manager() {
 
     let rocks_config = RocksConfig {
         path: "./test_data/rocksdb_state".to_string(),
+        ..Default::default()
     };
 
     // Directly create RocksDB client
@@ -263,6 +347,7 @@ async fn test_end_to_end_workflow() {
     // End-to-end workflow test
     let rocks_config = RocksConfig {
         path: "./test_data/rocksdb_e2e".to_string(),
+        ..Default::default()
     };
 
     let storage = StorageManager::new(rocks_config, None).await.unwrap();
@@ -272,8 +357,24 @@ async fn test_end_to_end_workflow() {
         hysteresis: 10,
         daa_score_range: vec![[83441551, 83525600]],
         tick_reserved: vec![],
+        tick_reserved_max: default_tick_reserved_max(),
+        tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+        balance_dust_threshold: default_balance_dust_threshold(),
+        enabled_op_types: default_enabled_op_types(),
+        allow_unknown_ops: default_allow_unknown_ops(),
+        checkpoint_hash: default_checkpoint_hash(),
+        batch_max_retries: default_batch_max_retries(),
+        batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+        consistency_mode: default_consistency_mode(),
+        tick_ignored: default_tick_ignored(),
         kaspa_node_url: "http://localhost:16110".to_string(),
         is_testnet: true,
+        scan_interval_ms: default_scan_interval_ms(),
+        vspc_check_window: default_vspc_check_window(),
+        tx_fetch_concurrency: default_tx_fetch_concurrency(),
+        node_reset_pause_ms: default_node_reset_pause_ms(),
+        op_score_multiplier: default_op_score_multiplier(),
+        runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
     };
 
     // Initialize explorer
@@ -293,6 +394,255 @@ async fn test_end_to_end_workflow() {
     assert!(scan_stats.is_ok(), "Should be able to get scan stats");
 }
 
+#[tokio::test]
+async fn test_scanner_pauses_on_low_disk_space() {
+    // Mock a low-space condition by requiring more free space than any volume has
+    use kaspa_indexer_rust::explorer::scanner::VSPCScanner;
+    use kaspa_indexer_rust::storage::types::DiskPressure;
+
+    let rocks_config = RocksConfig {
+        path: "./test_data/rocksdb_disk".to_string(),
+        min_free_space_mb: u64::MAX,
+        critical_free_space_mb: u64::MAX,
+        ..Default::default()
+    };
+
+    let storage = StorageManager::new(rocks_config, None).await.unwrap();
+    let storage_arc = Arc::new(storage);
+
+    let disk_status = storage_arc.rocksdb.check_disk_space().unwrap();
+    assert_eq!(disk_status.pressure, DiskPressure::Critical);
+
+    let health = storage_arc.health_check().await.unwrap();
+    assert!(!health.is_healthy, "Storage should be unhealthy on low disk space");
+
+    let startup_config = StartupConfig {
+        hysteresis: 10,
+        daa_score_range: vec![[83441551, 83525600]],
+        tick_reserved: vec![],
+        tick_reserved_max: default_tick_reserved_max(),
+        tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+        balance_dust_threshold: default_balance_dust_threshold(),
+        enabled_op_types: default_enabled_op_types(),
+        allow_unknown_ops: default_allow_unknown_ops(),
+        checkpoint_hash: default_checkpoint_hash(),
+        batch_max_retries: default_batch_max_retries(),
+        batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+        consistency_mode: default_consistency_mode(),
+        tick_ignored: default_tick_ignored(),
+        kaspa_node_url: "http://localhost:16110".to_string(),
+        is_testnet: true,
+        scan_interval_ms: default_scan_interval_ms(),
+        vspc_check_window: default_vspc_check_window(),
+        tx_fetch_concurrency: default_tx_fetch_concurrency(),
+        node_reset_pause_ms: default_node_reset_pause_ms(),
+        op_score_multiplier: default_op_score_multiplier(),
+        runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
+    };
+
+    let mut scanner = VSPCScanner::new(storage_arc, startup_config, false).unwrap();
+    assert!(!scanner.is_paused());
+    assert!(
+        scanner.check_disk_pressure(),
+        "Scanner should refuse new batches on low disk space"
+    );
+    assert!(scanner.is_paused(), "Scanner should be paused");
+}
+
+#[tokio::test]
+async fn test_scanner_backs_off_on_write_stall() {
+    // Simulate a stall signal with thresholds every database already reaches
+    use kaspa_indexer_rust::explorer::scanner::VSPCScanner;
+
+    let startup_config = StartupConfig {
+        hysteresis: 10,
+        daa_score_range: vec![[83441551, 83525600]],
+        tick_reserved: vec![],
+        tick_reserved_max: default_tick_reserved_max(),
+        tick_reserved_expiry_daa: default_tick_reserved_expiry_daa(),
+        balance_dust_threshold: default_balance_dust_threshold(),
+        enabled_op_types: default_enabled_op_types(),
+        allow_unknown_ops: default_allow_unknown_ops(),
+        checkpoint_hash: default_checkpoint_hash(),
+        batch_max_retries: default_batch_max_retries(),
+        batch_retry_backoff_ms: default_batch_retry_backoff_ms(),
+        consistency_mode: default_consistency_mode(),
+        tick_ignored: default_tick_ignored(),
+        kaspa_node_url: "http://localhost:16110".to_string(),
+        is_testnet: true,
+        scan_interval_ms: default_scan_interval_ms(),
+        vspc_check_window: default_vspc_check_window(),
+        tx_fetch_concurrency: default_tx_fetch_concurrency(),
+        node_reset_pause_ms: default_node_reset_pause_ms(),
+        op_score_multiplier: default_op_score_multiplier(),
+        runtime_flush_interval_ms: default_runtime_flush_interval_ms(),
+    };
+
+    let rocks_config = RocksConfig {
+        path: "./test_data/rocksdb_stall".to_string(),
+        stall_pending_compaction_mb: 0,
+        stall_immutable_memtables: 0,
+        stall_backoff_ms: 250,
+        ..Default::default()
+    };
+    let storage = Arc::new(StorageManager::new(rocks_config, None).await.unwrap());
+    let scanner = VSPCScanner::new(Arc::clone(&storage), startup_config.clone(), false).unwrap();
+
+    assert_eq!(storage.rocksdb.get_write_stall_count(), 0);
+    assert_eq!(
+        scanner.check_write_backpressure(),
+        Some(std::time::Duration::from_millis(250)),
+        "Scanner should back off while writes are stalled"
+    );
+    assert_eq!(storage.rocksdb.get_write_stall_count(), 1);
+    assert!(storage.rocksdb.get_write_stall_status().unwrap().stalled);
+
+    // Default thresholds on an idle database do not slow the scanner
+    let rocks_config = RocksConfig {
+        path: "./test_data/rocksdb_no_stall".to_string(),
+        ..Default::default()
+    };
+    let storage = Arc::new(StorageManager::new(rocks_config, None).await.unwrap());
+    let scanner = VSPCScanner::new(Arc::clone(&storage), startup_config, false).unwrap();
+
+    assert_eq!(scanner.check_write_backpressure(), None);
+    assert_eq!(storage.rocksdb.get_write_stall_count(), 0);
+}
+
+#[tokio::test]
+async fn test_storage_init_is_idempotent() {
+    // Calling init on an already-populated DB must keep checkpoint and token state
+    use kaspa_indexer_rust::storage::types::TokenData;
+
+    let rocks_config = RocksConfig {
+        path: "./test_data/rocksdb_reinit".to_string(),
+        ..Default::default()
+    };
+
+    let mut storage = StorageManager::new(rocks_config.clone(), None).await.unwrap();
+    storage.init().await.unwrap();
+
+    storage
+        .runtime
+        .save_checkpoint("block_hash_reinit", 83441600)
+        .unwrap();
+    storage
+        .state
+        .update_token(TokenData {
+            tick: "REINIT".to_string(),
+            max_supply: 1000,
+            minted_supply: "100".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    // Re-run init on the same instance and on a fresh instance, simulating a restart
+    storage.init().await.unwrap();
+    drop(storage);
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+
+    let (block_hash, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_hash_reinit");
+    assert_eq!(daa_score, 83441600);
+
+    let token = storage.state.get_token("REINIT").unwrap();
+    assert!(token.is_some(), "Token state should survive re-init");
+    assert_eq!(token.unwrap().minted_supply, "100");
+}
+
+#[tokio::test]
+async fn test_prune_dry_run_matches_prune() {
+    use kaspa_indexer_rust::config::get_op_score_multiplier;
+    use kaspa_indexer_rust::storage::types::{DataOperationType, OP_RANGE_BY, VSPCData};
+
+    let path = "./test_data/rocksdb_prune";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+
+    // Three blocks of history, the horizon keeps only the newest one
+    // Real op_scores are the DAA score times the multiplier plus the operation index
+    let op_score = |daa_score: u64| daa_score * get_op_score_multiplier() + 1;
+    let oplist_key = |daa_score: u64| {
+        let op_score = op_score(daa_score);
+        format!("oplist:{}:{}", op_score / OP_RANGE_BY, op_score)
+    };
+    for daa_score in [100u64, 200, 300] {
+        let tx_id = format!("tx_{}", daa_score);
+        let op_data = DataOperationType {
+            tx_id: tx_id.clone(),
+            daa_score,
+            block_accept: format!("block_hash_{}", daa_score),
+            fee: 0,
+            fee_least: 0,
+            mts_add: 0,
+            op_score: op_score(daa_score),
+            op_accept: 1,
+            op_error: String::new(),
+            op_script: vec![],
+            script_sig: String::new(),
+            st_before: vec![],
+            st_after: vec![],
+            checkpoint: String::new(),
+            ss_info: None,
+        };
+        let vspc = VSPCData {
+            block_hash: format!("block_hash_{}", daa_score),
+            daa_score,
+            ..Default::default()
+        };
+        let rocksdb = &storage.rocksdb;
+        rocksdb
+            .put_raw(
+                &format!("opdata:{}", tx_id),
+                &serde_json::to_vec(&op_data).unwrap(),
+            )
+            .unwrap();
+        rocksdb.put_raw(&oplist_key(daa_score), b"{}").unwrap();
+        rocksdb
+            .put_raw(
+                &format!("vspc:block_hash_{}", daa_score),
+                &serde_json::to_vec(&vspc).unwrap(),
+            )
+            .unwrap();
+    }
+
+    let dry_run = storage.prune_dry_run(300).unwrap();
+    assert!(dry_run.dry_run);
+    assert_eq!(dry_run.opdata_keys, 2);
+    assert_eq!(dry_run.oplist_keys, 2);
+    assert_eq!(dry_run.vspc_keys, 2);
+    assert!(dry_run.total_bytes() > 0);
+
+    // Dry-run must not delete anything
+    assert!(storage.rocksdb.get_raw("opdata:tx_100").unwrap().is_some());
+
+    let pruned = storage.prune(300).unwrap();
+    assert!(!pruned.dry_run);
+    assert_eq!(pruned.opdata_keys, dry_run.opdata_keys);
+    assert_eq!(pruned.opdata_bytes, dry_run.opdata_bytes);
+    assert_eq!(pruned.oplist_keys, dry_run.oplist_keys);
+    assert_eq!(pruned.oplist_bytes, dry_run.oplist_bytes);
+    assert_eq!(pruned.vspc_keys, dry_run.vspc_keys);
+    assert_eq!(pruned.vspc_bytes, dry_run.vspc_bytes);
+
+    let rocksdb = &storage.rocksdb;
+    assert!(rocksdb.get_raw("opdata:tx_100").unwrap().is_none());
+    assert!(rocksdb.get_raw(&oplist_key(200)).unwrap().is_none());
+    assert!(rocksdb.get_raw("vspc:block_hash_200").unwrap().is_none());
+    assert!(rocksdb.get_raw("opdata:tx_300").unwrap().is_some());
+    assert!(rocksdb.get_raw(&oplist_key(300)).unwrap().is_some());
+    assert!(rocksdb.get_raw("vspc:block_hash_300").unwrap().is_some());
+
+    // Nothing left below the horizon
+    assert_eq!(storage.prune_dry_run(300).unwrap().total_keys(), 0);
+}
+
 // Helper function to clean up test data
 async fn cleanup_test_data() {
     let test_dirs = vec![
@@ -302,6 +652,11 @@ async fn cleanup_test_data() {
         "./test_data/rocksdb_scanner",
         "./test_data/rocksdb_state",
         "./test_data/rocksdb_e2e",
+        "./test_data/rocksdb_disk",
+        "./test_data/rocksdb_stall",
+        "./test_data/rocksdb_no_stall",
+        "./test_data/rocksdb_reinit",
+        "./test_data/rocksdb_prune",
     ];
 
     for dir in test_dirs {
@@ -311,6 +666,28 @@ async fn cleanup_test_data() {
     }
 }
 
+#[test]
+fn test_steady_state_delay() {
+    use kaspa_indexer_rust::explorer::scanner::VSPCScanner;
+    use std::time::Duration;
+
+    let target = default_scan_interval_ms();
+    assert_eq!(
+        VSPCScanner::steady_state_delay(target, 350),
+        Some(Duration::from_millis(500))
+    );
+
+    // A scan that took the whole interval or longer gets no delay
+    assert_eq!(VSPCScanner::steady_state_delay(target, target), None);
+    assert_eq!(VSPCScanner::steady_state_delay(target, 5000), None);
+    assert_eq!(VSPCScanner::steady_state_delay(target, u64::MAX), None);
+    assert_eq!(VSPCScanner::steady_state_delay(0, 0), None);
+    assert_eq!(
+        VSPCScanner::steady_state_delay(u64::MAX, 0),
+        Some(Duration::from_millis(u64::MAX))
+    );
+}
+
 #[tokio::test]
 async fn test_cleanup() {
     // Test cleanup functionality
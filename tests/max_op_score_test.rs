@@ -0,0 +1,199 @@
+mod common;
+
+use common::{committed_op, spawn_server};
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::http::pagination::Pagination;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::opscore::get_max_op_score;
+use kaspa_indexer_rust::storage::types::*;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+fn rollback_of(op_data_list: &[DataOperationType]) -> DataRollbackType {
+    let state_map = || DataStateMapType {
+        state_token_map: HashMap::new(),
+        state_balance_map: HashMap::new(),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+    DataRollbackType {
+        state_map_before: state_map(),
+        state_map_after: state_map(),
+        op_score_list: op_data_list.iter().map(|op| op.op_score).collect(),
+        tx_id_list: op_data_list.iter().map(|op| op.tx_id.clone()).collect(),
+        daa_score_start: 0,
+        daa_score_end: 0,
+        checkpoint_before: String::new(),
+        checkpoint_after: String::new(),
+        op_score_last: 0,
+    }
+}
+
+/// op_scores of an oplist page and its next cursor
+async fn oplist_page(addr: SocketAddr, query: &str) -> (Vec<u64>, Option<String>) {
+    let body: serde_json::Value =
+        reqwest::get(format!("http://{}/v1/krc20/oplist?{}", addr, query))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+    let data = &body["data"];
+    let op_scores = data["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["op_score"].as_u64().unwrap())
+        .collect();
+    assert_eq!(data["hasMore"], data["nextCursor"].is_string());
+    (op_scores, data["nextCursor"].as_str().map(str::to_string))
+}
+
+#[tokio::test]
+async fn test_max_op_score_follows_committed_operations() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    assert_eq!(get_max_op_score(&storage.rocksdb).unwrap(), None);
+
+    // Batches spanning several op_score ranges
+    let first = vec![committed_op(105), committed_op(118)];
+    let second = vec![committed_op(131), committed_op(119)];
+    for batch in [&first, &second] {
+        rollback_manager
+            .save_op_data_batch_rocks(batch)
+            .await
+            .unwrap();
+    }
+    assert_eq!(get_max_op_score(&storage.rocksdb).unwrap(), Some(1310000));
+
+    // The oplist starts at the stored maximum and pages newest first
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let (op_scores, next) = oplist_page(addr, "limit=2").await;
+    assert_eq!(op_scores, vec![1310000, 1190000]);
+    assert_eq!(next, Some(Pagination::encode_cursor("1190000")));
+    let (op_scores, next) = oplist_page(addr, &format!("limit=2&cursor={}", next.unwrap())).await;
+    assert_eq!(op_scores, vec![1180000, 1050000]);
+    assert_eq!(next, None);
+
+    let body: serde_json::Value = reqwest::get(format!("http://{}/v1/stats", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"]["op_score"], 1310000);
+
+    // Rollbacks lower the maximum to the newest remaining operation
+    rollback_manager
+        .rollback_op_state_batch(&rollback_of(&second))
+        .await
+        .unwrap();
+    assert_eq!(get_max_op_score(&storage.rocksdb).unwrap(), Some(1180000));
+    let (op_scores, _) = oplist_page(addr, "limit=1").await;
+    assert_eq!(op_scores, vec![1180000]);
+
+    rollback_manager
+        .rollback_op_state_batch(&rollback_of(&first))
+        .await
+        .unwrap();
+    assert_eq!(get_max_op_score(&storage.rocksdb).unwrap(), None);
+    assert_eq!(oplist_page(addr, "").await, (vec![], None));
+}
+
+#[tokio::test]
+async fn test_oplist_filters_by_tick_and_address() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let alice = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+    let bob = "kaspatest:qpqz2vxj23kvh0m73ta2jjn2u4cv4tlufqns2eap8mxyyt0rvrxy6ejkful67";
+
+    let affecting = |daa_score: u64, tick: &str, addresses: &[&str]| {
+        let mut op_data = committed_op(daa_score);
+        op_data.ss_info = Some(DataStatsType {
+            tick_affc: vec![format!("{}:1", tick)],
+            address_affc: addresses
+                .iter()
+                .map(|address| format!("{}_{}=100", address, tick))
+                .collect(),
+        });
+        op_data
+    };
+    RollbackManager::new(Arc::clone(&storage))
+        .unwrap()
+        .save_op_data_batch_rocks(&[
+            affecting(101, "KASP", &[alice]),
+            affecting(102, "NACHO", &[alice, bob]),
+            affecting(103, "KASP", &[bob]),
+            affecting(104, "KASP", &[alice]),
+        ])
+        .await
+        .unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    let (op_scores, next) = oplist_page(addr, "tick=kasp&limit=2").await;
+    assert_eq!(op_scores, vec![1040000, 1030000]);
+    let (op_scores, next) =
+        oplist_page(addr, &format!("tick=kasp&limit=2&cursor={}", next.unwrap())).await;
+    assert_eq!(op_scores, vec![1010000]);
+    assert_eq!(next, None);
+
+    let (op_scores, _) = oplist_page(addr, &format!("address={}", bob)).await;
+    assert_eq!(op_scores, vec![1030000, 1020000]);
+    let (op_scores, _) = oplist_page(addr, &format!("tick=NACHO&address={}", alice)).await;
+    assert_eq!(op_scores, vec![1020000]);
+    let (op_scores, _) = oplist_page(addr, "tick=NONE").await;
+    assert!(op_scores.is_empty());
+}
+
+#[tokio::test]
+async fn test_archive_oplist_returns_whole_range() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    // Ranges of OP_RANGE_BY op_scores hold ten DAA scores each
+    RollbackManager::new(Arc::clone(&storage))
+        .unwrap()
+        .save_op_data_batch_rocks(&[
+            committed_op(119),
+            committed_op(110),
+            committed_op(115),
+            committed_op(120),
+        ])
+        .await
+        .unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let get = |oprange: &str| {
+        let url = format!("http://{}/v1/archive/oplist/{}", addr, oprange);
+        async move {
+            let response = reqwest::get(url).await.unwrap();
+            let status = response.status().as_u16();
+            let body: serde_json::Value = response.json().await.unwrap();
+            (status, body)
+        }
+    };
+
+    let (status, body) = get("11").await;
+    assert_eq!(status, 200);
+    let items = body["data"]["items"].as_array().unwrap();
+    let op_scores: Vec<u64> = items
+        .iter()
+        .map(|item| item["op_score"].as_u64().unwrap())
+        .collect();
+    assert_eq!(op_scores, vec![1100000, 1150000, 1190000]);
+    assert_eq!(items[0]["tx_id"], "tx_110");
+
+    let (status, body) = get("9").await;
+    assert_eq!(status, 200);
+    assert!(body["data"]["items"].as_array().unwrap().is_empty());
+
+    for oprange in ["eleven", "-1", "1.5"] {
+        let (status, body) = get(oprange).await;
+        assert_eq!(status, 400, "{}", oprange);
+        assert!(body["error"].is_string());
+    }
+}
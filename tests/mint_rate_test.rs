@@ -0,0 +1,159 @@
+use kaspa_indexer_rust::config::types::MintRateLimitConfig;
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::storage::versioned;
+use kaspa_indexer_rust::utils::address::encode_address;
+use std::sync::Arc;
+
+fn mint(daa_score: u64, to: &str) -> DataOperationType {
+    DataOperationType {
+        tx_id: format!("mint_tx_{}", daa_score),
+        daa_score,
+        block_accept: "block_hash".to_string(),
+        fee: 100000000,
+        fee_least: 0,
+        mts_add: 0,
+        op_score: daa_score * 10000,
+        op_accept: 0,
+        op_error: String::new(),
+        op_script: vec![DataScriptType {
+            p: "KRC-20".to_string(),
+            op: "mint".to_string(),
+            from: Some(to.to_string()),
+            to: Some(to.to_string()),
+            tick: Some("RATE".to_string()),
+            max: None,
+            lim: None,
+            pre: None,
+            dec: None,
+            amt: None,
+            utxo: None,
+            price: None,
+            mod_type: "".to_string(),
+            name: None,
+            ca: None,
+            desc: None,
+        }],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: Some(DataStatsType {
+            tick_affc: vec![],
+            address_affc: vec![],
+        }),
+    }
+}
+
+fn stored_mint_rate(storage: &StorageManager) -> Option<MintRateCount> {
+    storage
+        .rocksdb
+        .get_raw(&StateKey::mint_rate("RATE").encode())
+        .unwrap()
+        .map(|value| versioned::decode(&value).unwrap())
+}
+
+#[tokio::test]
+async fn test_mint_rate_rolls_back_with_batch() {
+    kaspa_indexer_rust::config::apply_mint_rate_limit(&MintRateLimitConfig {
+        window: 100,
+        max_mints: 3,
+        tick_limits: vec![],
+    });
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    let address = encode_address(&[1; 32], true).unwrap();
+
+    let mut state_map = DataStateMapType::new();
+    state_map.state_token_map.insert(
+        "RATE".to_string(),
+        Some(StateTokenType {
+            tick: "RATE".to_string(),
+            max: "1000000".to_string(),
+            lim: "1000".to_string(),
+            minted: "0".to_string(),
+            dec: 8,
+            from: address.clone(),
+            to: address.clone(),
+            ..Default::default()
+        }),
+    );
+    rollback_manager
+        .save_op_state_batch(&[], &state_map)
+        .await
+        .unwrap();
+
+    let run_batch = |op_data_list: Vec<DataOperationType>| {
+        let operation_manager = &operation_manager;
+        let rollback_manager = &rollback_manager;
+        async move {
+            let mut op_data_list = op_data_list;
+            let (mut state_map, _) = operation_manager
+                .prepare_state_batch(&op_data_list)
+                .unwrap();
+            let (rollback, _) = operation_manager
+                .execute_batch(&mut op_data_list, &mut state_map, "", true)
+                .unwrap();
+            rollback_manager
+                .save_op_state_batch(&op_data_list, &state_map)
+                .await
+                .unwrap();
+            (op_data_list, rollback)
+        }
+    };
+
+    // The first batch counts two mints of the window
+    let (op_data_list, _) = run_batch(vec![mint(1000, &address), mint(1001, &address)]).await;
+    assert!(op_data_list.iter().all(|op_data| op_data.op_accept == 1));
+    let counted = stored_mint_rate(&storage);
+    assert_eq!(
+        counted,
+        Some(MintRateCount {
+            window_start: 1000,
+            count: 2,
+        })
+    );
+
+    // The second batch fills the window, its last mint is rate limited
+    let batch = vec![mint(1050, &address), mint(1051, &address)];
+    let (applied, rollback) = run_batch(batch.clone()).await;
+    let results = |op_data_list: &[DataOperationType]| -> Vec<(i8, String)> {
+        op_data_list
+            .iter()
+            .map(|op_data| (op_data.op_accept, op_data.op_error.clone()))
+            .collect()
+    };
+    assert_eq!(
+        results(&applied),
+        [(1, String::new()), (-1, "mint rate limited".to_string())]
+    );
+    let mint_rate_after = stored_mint_rate(&storage);
+
+    // A reorg undoes the second batch together with its mint count
+    rollback_manager
+        .rollback_op_state_batch(&rollback)
+        .await
+        .unwrap();
+    assert_eq!(stored_mint_rate(&storage), counted);
+
+    // Replaying it gives the same result
+    let (replayed, _) = run_batch(batch).await;
+    assert_eq!(results(&replayed), results(&applied));
+    assert_eq!(
+        replayed
+            .iter()
+            .map(|op_data| &op_data.st_after)
+            .collect::<Vec<_>>(),
+        applied
+            .iter()
+            .map(|op_data| &op_data.st_after)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(stored_mint_rate(&storage), mint_rate_after);
+}
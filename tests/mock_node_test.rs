@@ -0,0 +1,875 @@
+use kaspa_indexer_rust::config::types::{
+    AlertsConfig, CacheControlConfig, RocksConfig, StandbyConfig, StartupConfig,
+    default_vspc_check_window,
+};
+use kaspa_indexer_rust::explorer::mock_node::{MockBlock, MockNodeClient, MockTransaction};
+use kaspa_indexer_rust::explorer::rollback::{
+    ROLLBACK_STATISTICS_CACHE_TTL, RollbackCandidateCache, RollbackStatisticsCache,
+};
+use kaspa_indexer_rust::explorer::{Follower, NodeClient, VSPCScanner};
+use kaspa_indexer_rust::http::node_tip::{NODE_TIP_CACHE_TTL, NodeTipCache};
+use kaspa_indexer_rust::http::{HttpState, build_router_with_state};
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::deadletter;
+use kaspa_indexer_rust::storage::runtime::ScanCounters;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::stats::{STATS_CACHE_TTL, StatsCache};
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::utils::shutdown::stop_task;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+const TEST_ADDRESS: &str = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const DAA_SCORE_BASE: u64 = 110165000;
+
+/// Encode a data push, corresponding to the length prefixes handled by parse_script_input
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else if len <= 255 {
+        format!("4c{:02x}{}", len, data_hex)
+    } else {
+        format!("4d{:02x}{:02x}{}", len & 0xff, len >> 8, data_hex)
+    }
+}
+
+/// Verbose transaction JSON with one P2SH commit-reveal input carrying a KRC-20 payload
+fn transaction(tx_id: &str, payload: serde_json::Value) -> MockTransaction {
+    let redeem_script = format!(
+        "20{}ac006307{}00{}68",
+        "ab".repeat(32),
+        hex::encode("kasplex"),
+        push_data(&hex::encode(payload.to_string()))
+    );
+    let signature_script = format!(
+        "{}{}",
+        push_data(&"00".repeat(65)),
+        push_data(&redeem_script)
+    );
+    MockTransaction {
+        tx_id: tx_id.to_string(),
+        data: serde_json::json!({
+            "inputs": [{
+                "previousOutpoint": { "transactionId": funding_tx_id(tx_id), "index": 0 },
+                "signatureScript": signature_script,
+            }],
+            "outputs": [],
+        }),
+    }
+}
+
+fn deploy(tx_id: &str) -> MockTransaction {
+    transaction(
+        tx_id,
+        serde_json::json!({
+            "p": "KRC-20",
+            "op": "deploy",
+            "tick": "MOCK",
+            "max": "2100000000000000",
+            "lim": "100000000000",
+            "to": TEST_ADDRESS,
+            "mod_type": "",
+        }),
+    )
+}
+
+fn mint(tx_id: &str) -> MockTransaction {
+    transaction(
+        tx_id,
+        serde_json::json!({
+            "p": "KRC-20",
+            "op": "mint",
+            "tick": "MOCK",
+            "to": TEST_ADDRESS,
+            "mod_type": "",
+        }),
+    )
+}
+
+fn funding_tx_id(tx_id: &str) -> String {
+    format!("fund_{}", tx_id)
+}
+
+/// Block accepting `transactions`, each after the transaction funding its fee
+fn block(offset: u64, hash: &str, transactions: Vec<MockTransaction>) -> MockBlock {
+    MockBlock {
+        daa_score: DAA_SCORE_BASE + offset,
+        hash: hash.to_string(),
+        transactions: transactions
+            .into_iter()
+            .flat_map(|tx| [funding(&tx.tx_id), tx])
+            .collect(),
+    }
+}
+
+/// Transaction creating the output whose amount pays the fee of `tx_id`
+fn funding(tx_id: &str) -> MockTransaction {
+    MockTransaction {
+        tx_id: funding_tx_id(tx_id),
+        data: serde_json::json!({
+            "inputs": [],
+            "outputs": [{ "amount": 2000_00000000u64 }],
+        }),
+    }
+}
+
+fn startup_config() -> StartupConfig {
+    StartupConfig {
+        hysteresis: 0,
+        is_testnet: true,
+        scan_interval_ms: 0,
+        node_reset_pause_ms: 0,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_scanner_deploy_mint_reorg() {
+    let path = "./test_data/mock_node_reorg";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    node.push_block(block(1, "block_1", vec![mint("tx_mint_1")]));
+
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+
+    scanner.scan_vspc_batch().await.unwrap();
+    let token = storage.state.get_state_token("MOCK").unwrap().unwrap();
+    assert_eq!(token.minted, "100000000000");
+    assert!(
+        storage
+            .rocksdb
+            .get_raw("opdata:tx_mint_1")
+            .unwrap()
+            .is_some()
+    );
+
+    // Block 1 is replaced, its mint disappears and two other mints are accepted instead
+    node.reorg(
+        DAA_SCORE_BASE + 1,
+        vec![
+            block(1, "block_1_reorg", vec![mint("tx_mint_2")]),
+            block(2, "block_2", vec![mint("tx_mint_3")]),
+        ],
+    );
+
+    // The first scan detects the reorg and rolls back, the next one replays the new chain
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(
+        storage
+            .rocksdb
+            .get_raw("opdata:tx_mint_1")
+            .unwrap()
+            .is_none()
+    );
+    scanner.scan_vspc_batch().await.unwrap();
+
+    let token = storage.state.get_state_token("MOCK").unwrap().unwrap();
+    assert_eq!(token.minted, "200000000000");
+    let balance_key = StateKey::balance(TEST_ADDRESS, "MOCK").encode();
+    let balance: StateBalanceType =
+        serde_json::from_slice(&storage.rocksdb.get_raw(&balance_key).unwrap().unwrap()).unwrap();
+    assert_eq!(balance.balance, "200000000000");
+    for tx_id in ["tx_deploy", "tx_mint_2", "tx_mint_3"] {
+        let key = format!("opdata:{}", tx_id);
+        let op_data: DataOperationType =
+            serde_json::from_slice(&storage.rocksdb.get_raw(&key).unwrap().unwrap()).unwrap();
+        assert_eq!(op_data.op_accept, 1, "{} not accepted", tx_id);
+    }
+    assert!(
+        storage
+            .rocksdb
+            .get_raw("opdata:tx_mint_1")
+            .unwrap()
+            .is_none()
+    );
+
+    let (block_hash, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_2");
+    assert_eq!(daa_score, DAA_SCORE_BASE + 2);
+}
+
+#[tokio::test]
+async fn test_fee_input_before_scanned_chain_leaves_fee_unknown() {
+    let storage = new_storage("/tmp/test_mock_node_fee_unknown").await;
+
+    // The deploy spends an output accepted below the scanned DAA score range, which the node
+    // can not look up without its accepting block
+    let node = MockNodeClient::new();
+    node.push_block(MockBlock {
+        daa_score: DAA_SCORE_BASE,
+        hash: "block_0".to_string(),
+        transactions: vec![funding("tx_deploy")],
+    });
+    node.push_block(MockBlock {
+        daa_score: DAA_SCORE_BASE + 1,
+        hash: "block_1".to_string(),
+        transactions: vec![deploy("tx_deploy")],
+    });
+
+    let config = StartupConfig {
+        daa_score_range: vec![[DAA_SCORE_BASE + 1, DAA_SCORE_BASE + 100]],
+        ..startup_config()
+    };
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), config, true, node).unwrap();
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+
+    let op_data: DataOperationType = serde_json::from_slice(
+        &storage
+            .rocksdb
+            .get_raw("opdata:tx_deploy")
+            .unwrap()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(op_data.fee, 0);
+    assert_eq!(op_data.op_accept, -1);
+    assert_eq!(op_data.op_error, "fee unknown");
+    assert!(storage.state.get_state_token("MOCK").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_fee_input_cached_from_earlier_batch() {
+    let storage = new_storage("./test_data/mock_node_fee_cached").await;
+
+    // The mint's fee input is accepted one batch before the mint itself
+    let node = MockNodeClient::new();
+    let mut block_0 = block(0, "block_0", vec![deploy("tx_deploy")]);
+    block_0.transactions.push(funding("tx_mint"));
+    node.push_block(block_0);
+
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(storage.state.get_state_token("MOCK").unwrap().is_some());
+
+    node.push_block(MockBlock {
+        daa_score: DAA_SCORE_BASE + 1,
+        hash: "block_1".to_string(),
+        transactions: vec![mint("tx_mint")],
+    });
+    scanner.scan_vspc_batch().await.unwrap();
+
+    let op_data: DataOperationType =
+        serde_json::from_slice(&storage.rocksdb.get_raw("opdata:tx_mint").unwrap().unwrap())
+            .unwrap();
+    assert_eq!(op_data.fee, 2000_00000000);
+    assert_eq!(op_data.op_accept, 1, "{}", op_data.op_error);
+    let token = storage.state.get_state_token("MOCK").unwrap().unwrap();
+    assert_eq!(token.minted, "100000000000");
+}
+
+#[tokio::test]
+async fn test_reorg_divergence_matches_selected_parent_fork() {
+    let path = "./test_data/mock_node_selected_parent";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let node = MockNodeClient::new();
+    for offset in 0..4 {
+        node.push_block(block(offset, &format!("block_{}", offset), vec![]));
+    }
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+
+    // Persisted entries link to the previous block of the chain
+    let vspc_list_prev = storage.runtime.get_runtime_vspc_last().await.unwrap();
+    assert_eq!(vspc_list_prev.len(), 4);
+    assert_eq!(vspc_list_prev[0].selected_parent, "");
+    for pair in vspc_list_prev.windows(2) {
+        assert_eq!(pair[1].selected_parent, pair[0].hash);
+    }
+
+    // Blocks 2 and 3 are replaced by a chain forking from block 1
+    node.reorg(
+        DAA_SCORE_BASE + 2,
+        vec![
+            block(2, "block_2_reorg", vec![]),
+            block(3, "block_3_reorg", vec![]),
+        ],
+    );
+    let vspc_list_next = node.get_vspc_list(DAA_SCORE_BASE, 100).await.unwrap();
+    let (rollback_daa_score, _) =
+        VSPCScanner::check_rollback_next(&vspc_list_prev, &vspc_list_next, DAA_SCORE_BASE);
+    let fork = VSPCScanner::find_vspc_fork(&vspc_list_prev, &vspc_list_next).unwrap();
+    assert_eq!(fork.hash, "block_1");
+    assert_eq!(rollback_daa_score, DAA_SCORE_BASE + 2);
+    let diverged = vspc_list_prev
+        .iter()
+        .find(|vspc| vspc.daa_score == rollback_daa_score)
+        .unwrap();
+    assert_eq!(diverged.selected_parent, fork.hash);
+
+    // Without a reorg there is no divergence to trace
+    assert!(VSPCScanner::find_vspc_fork(&vspc_list_prev, &vspc_list_prev).is_none());
+}
+
+/// Scan 300 empty blocks, then replace only the oldest of them, 299 DAA scores behind the tip.
+/// Returns the runtime VSPC list length after the next scan.
+async fn scan_deep_reorg(path: &str, vspc_check_window: usize) -> usize {
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let node = MockNodeClient::new();
+    for offset in 0..300 {
+        node.push_block(block(offset, &format!("block_{}", offset), vec![]));
+    }
+    let config = StartupConfig {
+        vspc_check_window,
+        ..startup_config()
+    };
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), config, true, node.clone()).unwrap();
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+    assert_eq!(
+        storage.runtime.get_runtime_vspc_last().await.unwrap().len(),
+        300
+    );
+
+    let mut blocks = vec![block(0, "block_0_reorg", vec![])];
+    for offset in 1..301 {
+        blocks.push(block(offset, &format!("block_{}", offset), vec![]));
+    }
+    node.reorg(DAA_SCORE_BASE, blocks);
+    scanner.scan_vspc_batch().await.unwrap();
+    storage.runtime.get_runtime_vspc_last().await.unwrap().len()
+}
+
+#[tokio::test]
+async fn test_node_vspc_list_from_fixed_chain() {
+    let storage = new_storage("/tmp/test_mock_node_vspc_list").await;
+    let node = MockNodeClient::new();
+    for offset in 0..(LEN_VSPC_LIST_MAX as u64 + 10) {
+        let transactions = match offset {
+            3 => vec![deploy("deploy_tx"), mint("mint_tx")],
+            _ => vec![],
+        };
+        node.push_block(block(offset, &format!("block_{}", offset), transactions));
+    }
+
+    let vspc_list = storage
+        .state
+        .get_node_vspc_list(&node, DAA_SCORE_BASE + 2, 3)
+        .await
+        .unwrap();
+    let daa_scores: Vec<u64> = vspc_list.iter().map(|vspc| vspc.daa_score).collect();
+    assert_eq!(
+        daa_scores,
+        vec![DAA_SCORE_BASE + 2, DAA_SCORE_BASE + 3, DAA_SCORE_BASE + 4]
+    );
+    assert_eq!(vspc_list[1].hash, "block_3");
+    assert_eq!(
+        vspc_list[1].tx_id_list,
+        vec!["fund_deploy_tx", "deploy_tx", "fund_mint_tx", "mint_tx"]
+    );
+    assert!(vspc_list[0].tx_id_list.is_empty());
+
+    // The list is capped whatever limit is asked for
+    let vspc_list = storage
+        .state
+        .get_node_vspc_list(&node, DAA_SCORE_BASE, usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(vspc_list.len(), LEN_VSPC_LIST_MAX + 5);
+    assert_eq!(vspc_list[0].daa_score, DAA_SCORE_BASE);
+}
+
+#[tokio::test]
+async fn test_vspc_check_window_detects_deep_reorg() {
+    // The default window only rescans the last 200 DAA scores, the chain blocks the node
+    // reports removed still reach the replaced block and roll the batch back
+    let len_vspc = scan_deep_reorg(
+        "./test_data/mock_node_window_default",
+        default_vspc_check_window(),
+    )
+    .await;
+    assert_eq!(len_vspc, 0);
+
+    // A wider window reaches the replaced block by itself
+    let len_vspc = scan_deep_reorg("./test_data/mock_node_window_wide", 400).await;
+    assert_eq!(len_vspc, 0);
+}
+
+#[tokio::test]
+async fn test_persistence_failure_recorded_in_dead_letter() {
+    let path = "./test_data/mock_node_dead_letter";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    node.push_block(block(1, "block_1", vec![mint("tx_mint_1")]));
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+
+    // The batch executes in memory but cannot be written
+    storage.rocksdb.set_fail_writes(true);
+    let err = scanner.scan_vspc_batch().await.unwrap_err();
+    assert!(err.to_string().contains("injected"));
+    assert!(storage.state.get_state_token("MOCK").unwrap().is_none());
+
+    let entries = deadletter::list_dead_letters(&storage.rocksdb).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].op_score_start, DAA_SCORE_BASE * 10000);
+    assert_eq!(entries[0].op_score_end, (DAA_SCORE_BASE + 1) * 10000);
+    assert_eq!(entries[0].daa_score_start, DAA_SCORE_BASE);
+    assert_eq!(entries[0].daa_score_end, DAA_SCORE_BASE + 1);
+    assert_eq!(entries[0].len_operation, 2);
+    assert_eq!(entries[0].error, err.to_string());
+    assert_eq!(entries[0].attempts, 1);
+
+    // Retrying the same batch counts another attempt on the same entry
+    scanner.scan_vspc_batch().await.unwrap_err();
+    let entries = deadletter::list_dead_letters(&storage.rocksdb).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].attempts, 2);
+
+    // Once writes recover the batch is persisted, the dead letter stays for diagnosis
+    storage.rocksdb.set_fail_writes(false);
+    scanner.scan_vspc_batch().await.unwrap();
+    let token = storage.state.get_state_token("MOCK").unwrap().unwrap();
+    assert_eq!(token.minted, "100000000000");
+    let entries = deadletter::list_dead_letters(&storage.rocksdb).unwrap();
+    assert_eq!(entries.len(), 1);
+}
+
+/// Log sink shared with the test, captures the scanner's tracing output
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl LogBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+#[tokio::test]
+async fn test_node_tip_regression_pauses_scanner() {
+    let path = "./test_data/mock_node_tip_regression";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let logs = LogBuffer::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    node.push_block(block(1, "block_1", vec![mint("tx_mint_1")]));
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(!scanner.is_node_regressed());
+
+    // The node was re-synced and reports a tip below what was already indexed
+    node.set_tip_daa_score(Some(DAA_SCORE_BASE));
+    node.push_block(block(2, "block_2", vec![mint("tx_mint_2")]));
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(scanner.is_node_regressed());
+    let (block_hash, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_1");
+    assert_eq!(daa_score, DAA_SCORE_BASE + 1);
+    assert!(
+        logs.contents()
+            .contains("explorer.scan paused: node tip DAA score")
+    );
+
+    // Scanning continues once the node has caught up again
+    node.set_tip_daa_score(None);
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(!scanner.is_node_regressed());
+    let (block_hash, _) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_2");
+    assert!(logs.contents().contains("explorer.scan resumed"));
+}
+
+/// Sync 120 blocks into a fresh store, returns every key/value after the scanner caught up
+async fn sync_chain(path: &str, bulk_load: bool) -> Vec<(String, Vec<u8>)> {
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        bulk_load,
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    for offset in 1..120 {
+        let transactions = if offset % 10 == 0 {
+            vec![mint(&format!("tx_mint_{}", offset))]
+        } else {
+            vec![]
+        };
+        node.push_block(block(offset, &format!("block_{}", offset), transactions));
+    }
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+
+    // A full batch is still initial sync, bulk load stays on
+    scanner.scan_vspc_batch().await.unwrap();
+    assert_eq!(storage.rocksdb.is_bulk_load(), bulk_load);
+
+    // The next batch is short, the scanner is synced and leaves bulk load
+    node.push_block(block(120, "block_120", vec![mint("tx_mint_120")]));
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(!storage.rocksdb.is_bulk_load());
+
+    storage.rocksdb.scan_prefix("").unwrap()
+}
+
+#[tokio::test]
+async fn test_bulk_load_sync_matches_normal_sync() {
+    let normal = sync_chain("./test_data/mock_node_sync_normal", false).await;
+    let bulk = sync_chain("./test_data/mock_node_sync_bulk", true).await;
+    assert!(normal.iter().any(|(key, _)| key == "opdata:tx_mint_120"));
+    assert_eq!(bulk, normal);
+}
+
+async fn new_storage(path: &str) -> Arc<StorageManager> {
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    Arc::new(storage)
+}
+
+/// Keys a follower must share with its leader, the event log and follower cursor are per instance
+fn replicated_keys(storage: &StorageManager) -> Vec<(String, Vec<u8>)> {
+    let mut entries = storage.rocksdb.scan_prefix("").unwrap();
+    entries.retain(|(key, _)| {
+        !key.starts_with("events:") && key != "eventseq" && key != "RTA_FOLLOWERCURSOR"
+    });
+    entries
+}
+
+#[tokio::test]
+async fn test_standby_follower_applies_events_and_promotes() {
+    let leader = new_storage("./test_data/mock_node_standby_leader").await;
+    let follower_storage = new_storage("./test_data/mock_node_standby_follower").await;
+
+    // The leader scans a deploy and a mint, then rolls the mint back on a reorg
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    node.push_block(block(1, "block_1", vec![mint("tx_mint_1")]));
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&leader), startup_config(), true, node.clone())
+            .unwrap()
+            .with_event_retention(100);
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+    node.reorg(
+        DAA_SCORE_BASE + 1,
+        vec![
+            block(1, "block_1_reorg", vec![mint("tx_mint_2")]),
+            block(2, "block_2", vec![]),
+        ],
+    );
+    scanner.scan_vspc_batch().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+
+    let state = HttpState {
+        kaspa_rest_base_url: "http://localhost".to_string(),
+        storage: Arc::clone(&leader),
+        admin_token: String::new(),
+        node_tip: Arc::new(NodeTipCache::new(
+            "http://localhost:16110".to_string(),
+            NODE_TIP_CACHE_TTL,
+        )),
+        stats: Arc::new(StatsCache::new(STATS_CACHE_TTL)),
+        max_page_size: 500,
+        cache_control: CacheControlConfig::default(),
+        debug_endpoints: false,
+        rollback_candidates: Arc::new(RollbackCandidateCache::new(Duration::from_secs(10))),
+        rollback_stats: Arc::new(RollbackStatisticsCache::new(ROLLBACK_STATISTICS_CACHE_TTL)),
+        read_permits: Arc::new(Semaphore::new(64)),
+    };
+    let router = build_router_with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, router.into_make_service())
+            .await
+            .unwrap();
+    });
+
+    let config = StandbyConfig {
+        enabled: true,
+        leader_url: format!("http://{}", addr),
+        poll_interval_ms: 10,
+        failover_timeout_ms: 200,
+        event_retention: 0,
+    };
+    let mut follower = Follower::new(Arc::clone(&follower_storage), config)
+        .await
+        .unwrap();
+    assert_eq!(follower.poll_once().await.unwrap(), Some(3));
+    assert_eq!(follower.poll_once().await.unwrap(), Some(0));
+    assert_eq!(replicated_keys(&follower_storage), replicated_keys(&leader));
+
+    // The leader goes away, the follower promotes from the op_score it reached
+    server.abort();
+    let op_score = follower.run().await.unwrap();
+    let rollback_list = leader.runtime.get_runtime_rollback_last().await.unwrap();
+    assert_eq!(op_score, rollback_list.last().unwrap().op_score_last);
+
+    node.push_block(block(3, "block_3", vec![mint("tx_mint_3")]));
+    let mut scanner = VSPCScanner::with_node_client(
+        Arc::clone(&follower_storage),
+        startup_config(),
+        true,
+        node.clone(),
+    )
+    .unwrap();
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+
+    let token = follower_storage
+        .state
+        .get_state_token("MOCK")
+        .unwrap()
+        .unwrap();
+    assert_eq!(token.minted, "200000000000");
+    let op_data: DataOperationType = serde_json::from_slice(
+        &follower_storage
+            .rocksdb
+            .get_raw("opdata:tx_mint_3")
+            .unwrap()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(op_data.op_accept, 1);
+    assert_eq!(op_data.op_score, (DAA_SCORE_BASE + 3) * 10000);
+    assert!(op_data.op_score > op_score);
+    let (block_hash, _) = follower_storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_3");
+}
+
+#[tokio::test]
+async fn test_runtime_flush_survives_crash_after_idle() {
+    let storage = new_storage("./test_data/mock_node_runtime_flush").await;
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    node.push_block(block(1, "block_1", vec![mint("tx_mint_1")]));
+    let config = StartupConfig {
+        runtime_flush_interval_ms: 1000,
+        ..startup_config()
+    };
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), config.clone(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+
+    // Batches do not persist the counters, the flush is not due yet
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(!scanner.flush_runtime_state_if_due().await.unwrap());
+    assert_eq!(
+        storage.runtime.get_runtime_scan_counters().await.unwrap(),
+        ScanCounters::default()
+    );
+
+    // An idle scan finds no new block, the timer still flushes
+    scanner.scan_vspc_batch().await.unwrap();
+    assert!(scanner.flush_runtime_state_if_due().await.unwrap());
+    let stats = scanner.get_stats().unwrap();
+    assert!(stats.last_scan_time > 0);
+
+    // Crash without shutdown, a new scanner recovers the flushed counters
+    drop(scanner);
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), config, true, node.clone()).unwrap();
+    scanner.init().await.unwrap();
+    let recovered = scanner.get_stats().unwrap();
+    assert_eq!(recovered.total_vspc_processed, 2);
+    assert_eq!(recovered.total_operations_found, 2);
+    assert_eq!(recovered.last_scan_time, stats.last_scan_time);
+}
+
+#[tokio::test]
+async fn test_scanner_stops_before_storage_shutdown() {
+    let storage = new_storage("./test_data/mock_node_shutdown").await;
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    node.push_block(block(1, "block_1", vec![mint("tx_mint_1")]));
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap();
+    scanner.init().await.unwrap();
+    let stop = scanner.stop_handle();
+    let handle = tokio::spawn(async move {
+        scanner.start_scanning().await.unwrap();
+        scanner.stop_scanning().await.unwrap();
+        scanner
+    });
+    while storage
+        .rocksdb
+        .get_raw("opdata:tx_mint_1")
+        .unwrap()
+        .is_none()
+    {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The scanning loop returns on its own instead of being aborted mid batch
+    let scanner = stop_task(&stop, handle, Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert!(!scanner.get_stats().unwrap().is_scanning);
+    // No batch holds the scan lock when storage shuts down
+    assert!(
+        tokio::time::timeout(Duration::ZERO, storage.runtime.lock_scanning())
+            .await
+            .is_ok()
+    );
+    assert!(
+        storage
+            .shutdown_within(Duration::from_secs(5))
+            .await
+            .unwrap()
+    );
+}
+
+/// Webhook recording the JSON bodies POSTed to it
+async fn spawn_webhook() -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&received);
+    let router = axum::Router::new().route(
+        "/alerts",
+        axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+            let recorder = Arc::clone(&recorder);
+            async move { recorder.lock().unwrap().push(body) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router.into_make_service())
+            .await
+            .unwrap();
+    });
+    (format!("http://{}/alerts", addr), received)
+}
+
+#[tokio::test]
+async fn test_deep_reorg_posts_alert() {
+    let storage = new_storage("./test_data/mock_node_reorg_alert").await;
+    let (webhook_url, received) = spawn_webhook().await;
+    let node = MockNodeClient::new();
+    node.push_block(block(0, "block_0", vec![deploy("tx_deploy")]));
+    node.push_block(block(1, "block_1", vec![mint("tx_mint_1")]));
+    node.push_block(block(2, "block_2", vec![]));
+    let alerts = AlertsConfig {
+        webhook_url,
+        reorg_depth: 1,
+        ..Default::default()
+    };
+    let mut scanner =
+        VSPCScanner::with_node_client(Arc::clone(&storage), startup_config(), true, node.clone())
+            .unwrap()
+            .with_alerts(alerts);
+    scanner.init().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+
+    // A reorg replacing a single block stays below the threshold
+    node.reorg(DAA_SCORE_BASE + 2, vec![block(2, "block_2_reorg", vec![])]);
+    scanner.scan_vspc_batch().await.unwrap();
+    scanner.scan_vspc_batch().await.unwrap();
+
+    // Blocks 1 and 2 are replaced
+    node.reorg(
+        DAA_SCORE_BASE + 1,
+        vec![
+            block(1, "block_1_reorg", vec![mint("tx_mint_2")]),
+            block(2, "block_2_reorg_2", vec![]),
+        ],
+    );
+    scanner.scan_vspc_batch().await.unwrap();
+
+    for _ in 0..100 {
+        if !received.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    let alert = &received[0];
+    assert_eq!(alert["event"], "reorg");
+    assert_eq!(alert["daaScore"], DAA_SCORE_BASE + 1);
+    assert_eq!(alert["depth"], 2);
+    assert_eq!(alert["forkHash"], "block_0");
+    assert_eq!(alert["forkDaaScore"], DAA_SCORE_BASE);
+    assert!(alert["timestamp"].as_u64().unwrap() > 0);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::operations::metrics;
+use kaspa_indexer_rust::storage::types::*;
+
+const TEST_ADDRESS: &str = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+fn op_data(op: &str, index: u64) -> DataOperationType {
+    let mut op_data = common::op_data(0, op, "TIME", TEST_ADDRESS, TEST_ADDRESS);
+    op_data.tx_id = format!("{}_tx_{}", op, index);
+    op_data.op_score += index;
+    let script = &mut op_data.op_script[0];
+    script.max = Some("21000000".to_string());
+    script.lim = Some("1000".to_string());
+    op_data
+}
+
+#[tokio::test]
+async fn test_execute_operation_records_duration() {
+    let storage = common::setup_storage("./test_data/op_metrics").await;
+    let operation_manager = OperationManager::new(&storage);
+    let mut state_map = DataStateMapType::new();
+
+    // Disabled metrics record nothing
+    metrics::apply_op_metrics_enabled(false);
+    metrics::reset_op_duration_histogram();
+    let mut deploy = op_data("deploy", 0);
+    operation_manager
+        .execute_operation(0, &mut deploy, &mut state_map, true)
+        .unwrap();
+    assert!(metrics::get_op_duration_histogram().is_empty());
+
+    metrics::apply_op_metrics_enabled(true);
+    let mut state_map = DataStateMapType::new();
+    let mut deploy = op_data("deploy", 1);
+    operation_manager
+        .execute_operation(0, &mut deploy, &mut state_map, true)
+        .unwrap();
+    for index in 2..5 {
+        let mut mint = op_data("mint", index);
+        operation_manager
+            .execute_operation(0, &mut mint, &mut state_map, true)
+            .unwrap();
+    }
+
+    let histogram = metrics::get_op_duration_histogram();
+    assert_eq!(histogram.len(), 2);
+    assert_eq!(histogram["deploy"].count, 1);
+    assert_eq!(histogram["mint"].count, 3);
+    for samples in histogram.values() {
+        assert_eq!(
+            samples.buckets.len(),
+            metrics::OP_DURATION_BUCKETS_US.len() + 1
+        );
+        assert_eq!(samples.buckets.iter().sum::<u64>(), samples.count);
+        assert!(samples.sum_us >= samples.max_us);
+    }
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::committed_op;
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_verify_op_score_ordering_flags_first_anomaly() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    rollback_manager
+        .save_op_data_batch_rocks(&[committed_op(105), committed_op(118), committed_op(131)])
+        .await
+        .unwrap();
+
+    let report = storage.verify_op_score_ordering().unwrap();
+    assert_eq!(report.checked, 3);
+    assert_eq!(report.anomaly, None);
+
+    // An op_score stored in a later range than the entries following it
+    let (key, value) = storage.rocksdb.scan_prefix("oplist:11:").unwrap().remove(0);
+    storage.rocksdb.delete_raw(&key).unwrap();
+    storage
+        .rocksdb
+        .put_raw("oplist:13:1180000", &value)
+        .unwrap();
+    let report = storage.verify_op_score_ordering().unwrap();
+    assert_eq!(report.checked, 1);
+    assert_eq!(
+        report.anomaly.as_deref(),
+        Some("oplist:13:1180000 is out of order, op_score 1180000 belongs to range 11")
+    );
+
+    // An entry left behind by a lost operation
+    storage.rocksdb.delete_raw("oplist:13:1180000").unwrap();
+    storage.rocksdb.put_raw(&key, &value).unwrap();
+    storage.rocksdb.delete_raw("opdata:tx_118").unwrap();
+    let report = storage.verify_op_score_ordering().unwrap();
+    assert_eq!(
+        report.anomaly.as_deref(),
+        Some("oplist:11:1180000 lost operation tx_118")
+    );
+}
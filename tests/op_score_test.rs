@@ -0,0 +1,217 @@
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::utils::script_parser::ScriptParser;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const DAA_SCORE: u64 = 110165001;
+const TEST_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else {
+        format!("4c{:02x}{}", len, data_hex)
+    }
+}
+
+/// Mint transaction revealing a KRC-20 script, as in script_parser_test
+fn mint_transaction(tx_id: String, daa_score: u64) -> DataTransactionType {
+    let payload = serde_json::json!({
+        "p": "KRC-20",
+        "op": "mint",
+        "tick": "BUSY",
+        "to": TEST_ADDRESS,
+        "mod_type": "",
+    });
+    transaction(tx_id, daa_score, payload)
+}
+
+fn transaction(tx_id: String, daa_score: u64, payload: serde_json::Value) -> DataTransactionType {
+    let redeem_script = format!(
+        "20{}ac006307{}00{}68",
+        "ab".repeat(32),
+        hex::encode("kasplex"),
+        push_data(&hex::encode(payload.to_string()))
+    );
+    DataTransactionType {
+        tx_id,
+        daa_score,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({
+            "inputs": [{
+                "previousOutpoint": { "transactionId": "funding_tx", "index": 0 },
+                "signatureScript": format!(
+                    "{}{}",
+                    push_data(&"00".repeat(65)),
+                    push_data(&redeem_script)
+                ),
+            }],
+            "outputs": [],
+        })),
+    }
+}
+
+/// `len_busy` operations in one DAA score followed by one in the next
+fn busy_transactions(len_busy: u64) -> Vec<DataTransactionType> {
+    let mut tx_data_list: Vec<DataTransactionType> = (0..len_busy)
+        .map(|i| mint_transaction(format!("busy_tx_{}", i), DAA_SCORE))
+        .collect();
+    tx_data_list.push(mint_transaction("next_tx".to_string(), DAA_SCORE + 1));
+    tx_data_list
+}
+
+fn assert_strictly_increasing(op_data_list: &[DataOperationType], multiplier: u64) {
+    for pair in op_data_list.windows(2) {
+        assert!(pair[0].op_score < pair[1].op_score);
+    }
+    for op_data in op_data_list {
+        // Every op_score stays within the slots of its own DAA score
+        assert_eq!(op_data.op_score / multiplier, op_data.daa_score);
+    }
+}
+
+#[test]
+fn test_op_scores_unique_across_busy_daa_score() {
+    // The Go version layout holds exactly 10000 operations per DAA score
+    let op_data_list = ScriptParser::parse_op_data_list_with_multiplier(
+        &busy_transactions(OP_SCORE_MULTIPLIER_DEFAULT),
+        true,
+        OP_SCORE_MULTIPLIER_DEFAULT,
+    )
+    .unwrap();
+    assert_eq!(op_data_list.len(), 10001);
+    assert_strictly_increasing(&op_data_list, OP_SCORE_MULTIPLIER_DEFAULT);
+    assert_eq!(op_data_list[9999].op_score, DAA_SCORE * 10000 + 9999);
+    assert_eq!(op_data_list[10000].op_score, (DAA_SCORE + 1) * 10000);
+
+    // One more would collide with the next DAA score
+    let err = ScriptParser::parse_op_data_list_with_multiplier(
+        &busy_transactions(OP_SCORE_MULTIPLIER_DEFAULT + 1),
+        true,
+        OP_SCORE_MULTIPLIER_DEFAULT,
+    )
+    .unwrap_err();
+    assert!(err.to_string().starts_with(&format!(
+        "DAA score {} has more than 10000 operations",
+        DAA_SCORE
+    )));
+
+    let op_data_list = ScriptParser::parse_op_data_list_with_multiplier(
+        &busy_transactions(OP_SCORE_MULTIPLIER_DEFAULT + 1),
+        true,
+        100000,
+    )
+    .unwrap();
+    assert_strictly_increasing(&op_data_list, 100000);
+    assert_eq!(op_data_list[10001].op_score, (DAA_SCORE + 1) * 100000);
+
+    // An op_score beyond u64 is refused rather than wrapped
+    let err = ScriptParser::parse_op_data_list_with_multiplier(
+        &[mint_transaction("huge_tx".to_string(), u64::MAX / 1000)],
+        true,
+        OP_SCORE_MULTIPLIER_MAX,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("overflows"), "{}", err);
+}
+
+/// Deploy of a tick with room for two mints, then three mints in one DAA score, all paying
+/// their fees from the output of a funding transaction
+fn contested_transactions() -> Vec<DataTransactionType> {
+    let funding = DataTransactionType {
+        tx_id: "funding_tx".to_string(),
+        daa_score: DAA_SCORE,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({
+            "inputs": [],
+            "outputs": [{ "amount": 2000_00000000u64 }],
+        })),
+    };
+    let mut tx_data_list = vec![funding];
+    tx_data_list.push(transaction(
+        "deploy_tx".to_string(),
+        DAA_SCORE,
+        serde_json::json!({
+            "p": "KRC-20",
+            "op": "deploy",
+            "tick": "RACE",
+            "max": "200000000000",
+            "lim": "100000000000",
+            "to": TEST_ADDRESS,
+            "mod_type": "",
+        }),
+    ));
+    for tx_id in ["mint_tx_c", "mint_tx_a", "mint_tx_b"] {
+        let payload = serde_json::json!({
+            "p": "KRC-20",
+            "op": "mint",
+            "tick": "RACE",
+            "to": TEST_ADDRESS,
+            "mod_type": "",
+        });
+        tx_data_list.push(transaction(tx_id.to_string(), DAA_SCORE + 1, payload));
+    }
+    tx_data_list
+}
+
+/// op_score and op_accept of every operation by tx_id, once executed in op_score order
+fn execute_parsed(tx_data_list: &[DataTransactionType]) -> HashMap<String, (u64, i8)> {
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    let operation_manager = OperationManager::new(&storage);
+    let mut op_data_list = ScriptParser::parse_op_data_list(tx_data_list, true).unwrap();
+    let (mut state_map, _) = operation_manager
+        .prepare_state_batch(&op_data_list)
+        .unwrap();
+    operation_manager
+        .execute_batch(&mut op_data_list, &mut state_map, "", true)
+        .unwrap();
+    op_data_list
+        .into_iter()
+        .map(|op_data| (op_data.tx_id, (op_data.op_score, op_data.op_accept)))
+        .collect()
+}
+
+#[test]
+fn test_op_scores_independent_of_node_order() {
+    let tx_data_list = contested_transactions();
+    let mut reordered = tx_data_list.clone();
+    reordered[2..].reverse();
+
+    let executed = execute_parsed(&tx_data_list);
+    assert_eq!(executed, execute_parsed(&reordered));
+    // Within a DAA score the operations are scored by tx_id, so the first two mints win
+    assert_eq!(executed["deploy_tx"], (DAA_SCORE * 10000, 1));
+    assert_eq!(executed["mint_tx_a"], ((DAA_SCORE + 1) * 10000, 1));
+    assert_eq!(executed["mint_tx_b"], ((DAA_SCORE + 1) * 10000 + 1, 1));
+    assert_eq!(executed["mint_tx_c"].0, (DAA_SCORE + 1) * 10000 + 2);
+    assert_ne!(executed["mint_tx_c"].1, 1);
+}
+
+#[tokio::test]
+async fn test_op_score_multiplier_fixed_once_data_exists() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+
+    // Recorded on first start, a different value is refused afterwards
+    storage.runtime.check_op_score_multiplier(100000).unwrap();
+    storage.runtime.check_op_score_multiplier(100000).unwrap();
+    assert_eq!(
+        storage
+            .runtime
+            .check_op_score_multiplier(10000)
+            .unwrap_err()
+            .to_string(),
+        "opScoreMultiplier 10000 does not match 100000 used by the indexed data, reindex to change it"
+    );
+
+    // Data indexed before the multiplier was recorded used the Go version value
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    storage.runtime.increment_operations_processed(1).unwrap();
+    assert!(storage.runtime.check_op_score_multiplier(100000).is_err());
+    storage.runtime.check_op_score_multiplier(10000).unwrap();
+}
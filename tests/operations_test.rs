@@ -19,6 +19,7 @@ fn test_deploy_operation_validation() {
         mod_type: "".to_string(),
         name: None,
         ca: None,
+        desc: None,
     };
 
     let result = DeployOperation::validate(&mut script, "test_tx_id", 110165001, false);
@@ -45,6 +46,7 @@ fn test_mint_operation_validation() {
         mod_type: "".to_string(),
         name: None,
         ca: None,
+        desc: None,
     };
 
     let result = MintOperation::validate(&mut script, "test_tx_id", 110165001, false);
@@ -71,6 +73,7 @@ fn test_transfer_operation_validation() {
         mod_type: "".to_string(),
         name: None,
         ca: None,
+        desc: None,
     };
 
     let result = TransferOperation::validate(&mut script, "test_tx_id", 110165001, false);
@@ -123,3 +126,380 @@ fn test_validation_functions() {
         "Empty amount should fail"
     );
 }
+
+#[test]
+fn test_mint_rate_limit() {
+    use kaspa_indexer_rust::config::types::MintRateLimitConfig;
+    use std::collections::HashMap;
+
+    let address = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+    kaspa_indexer_rust::config::apply_mint_rate_limit(&MintRateLimitConfig {
+        window: 100,
+        max_mints: 0,
+        tick_limits: vec!["RATE_2".to_string()],
+    });
+
+    let mut state_map = DataStateMapType {
+        state_token_map: HashMap::new(),
+        state_balance_map: HashMap::new(),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+    state_map.state_token_map.insert(
+        "RATE".to_string(),
+        Some(StateTokenType {
+            tick: "RATE".to_string(),
+            max: "1000000".to_string(),
+            lim: "1000".to_string(),
+            pre: "0".to_string(),
+            dec: 8,
+            mod_type: "".to_string(),
+            from: address.to_string(),
+            to: address.to_string(),
+            minted: "0".to_string(),
+            burned: "0".to_string(),
+            name: "".to_string(),
+            desc: String::new(),
+            frozen: false,
+            tx_id: "deploy_tx".to_string(),
+            op_add: 0,
+            op_mod: 0,
+            mts_add: 0,
+            mts_mod: 0,
+        }),
+    );
+
+    let mut mint = |daa_score: u64| -> DataOperationType {
+        let mut op_data = DataOperationType {
+            tx_id: format!("mint_tx_{}", daa_score),
+            daa_score,
+            block_accept: "block_hash".to_string(),
+            fee: 100000000,
+            fee_least: 0,
+            mts_add: 0,
+            op_score: daa_score * 10000,
+            op_accept: 0,
+            op_error: String::new(),
+            op_script: vec![DataScriptType {
+                p: "KRC-20".to_string(),
+                op: "mint".to_string(),
+                from: Some(address.to_string()),
+                to: Some(address.to_string()),
+                tick: Some("RATE".to_string()),
+                max: None,
+                lim: None,
+                pre: None,
+                dec: None,
+                amt: None,
+                utxo: None,
+                price: None,
+                mod_type: "".to_string(),
+                name: None,
+                ca: None,
+                desc: None,
+            }],
+            script_sig: String::new(),
+            st_before: vec![],
+            st_after: vec![],
+            checkpoint: String::new(),
+            ss_info: Some(DataStatsType {
+                tick_affc: vec![],
+                address_affc: vec![],
+            }),
+        };
+        MintOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+        op_data
+    };
+
+    // Two mints fit in the window, the third is rejected
+    assert_eq!(mint(1000).op_accept, 1);
+    assert_eq!(mint(1001).op_accept, 1);
+    let limited = mint(1002);
+    assert_eq!(limited.op_accept, -1);
+    assert_eq!(limited.op_error, "mint rate limited");
+
+    // Next window accepts mints again
+    assert_eq!(mint(1100).op_accept, 1);
+}
+
+#[test]
+fn test_tick_ignored_config() {
+    use kaspa_indexer_rust::config::types::{StartupConfig, default_tick_ignored};
+    use std::collections::HashMap;
+
+    let address = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+    let deploy = |tick: &str| -> DataOperationType {
+        let script = DataScriptType {
+            p: "KRC-20".to_string(),
+            op: "deploy".to_string(),
+            from: Some(address.to_string()),
+            to: Some(address.to_string()),
+            tick: Some(tick.to_string()),
+            max: Some("21000000".to_string()),
+            lim: Some("1000".to_string()),
+            pre: Some("0".to_string()),
+            dec: Some("8".to_string()),
+            amt: None,
+            utxo: None,
+            price: None,
+            mod_type: "".to_string(),
+            name: None,
+            ca: None,
+            desc: None,
+        };
+        let mut op_data = DataOperationType {
+            tx_id: format!("deploy_tx_{}", tick),
+            daa_score: 1000,
+            block_accept: "block_hash".to_string(),
+            fee: 100000000000,
+            fee_least: 0,
+            mts_add: 0,
+            op_score: 1000 * 10000,
+            op_accept: 0,
+            op_error: String::new(),
+            op_script: vec![script.clone()],
+            script_sig: String::new(),
+            st_before: vec![],
+            st_after: vec![],
+            checkpoint: String::new(),
+            ss_info: Some(DataStatsType {
+                tick_affc: vec![],
+                address_affc: vec![],
+            }),
+        };
+        let mut state_map = DataStateMapType {
+            state_token_map: HashMap::new(),
+            state_balance_map: HashMap::new(),
+            state_market_map: HashMap::new(),
+            state_blacklist_map: HashMap::new(),
+            state_mint_rate_map: HashMap::new(),
+        };
+        DeployOperation::do_operation(&script, &mut op_data, &mut state_map, true).unwrap();
+        op_data
+    };
+
+    // Omitted list falls back to the default set
+    let startup: StartupConfig = toml::from_str(
+        r#"
+        hysteresis = 3
+        daaScoreRange = []
+        tickReserved = []
+        kaspaNodeURL = "http://localhost:16110"
+        isTestnet = true
+        "#,
+    )
+    .unwrap();
+    assert_eq!(startup.tick_ignored, default_tick_ignored());
+    kaspa_indexer_rust::config::apply_tick_ignored(&startup.tick_ignored);
+    let ignored = deploy("USDT");
+    assert_eq!(ignored.op_accept, -1);
+    assert_eq!(ignored.op_error, "tick ignored");
+    assert_eq!(deploy("IGNR").op_accept, 1);
+
+    // Configured list adds a tick without recompiling
+    let startup: StartupConfig = toml::from_str(
+        r#"
+        hysteresis = 3
+        daaScoreRange = []
+        tickReserved = []
+        tickIgnored = ["USDT", "ignr"]
+        kaspaNodeURL = "http://localhost:16110"
+        isTestnet = true
+        "#,
+    )
+    .unwrap();
+    kaspa_indexer_rust::config::apply_tick_ignored(&startup.tick_ignored);
+    let ignored = deploy("IGNR");
+    assert_eq!(ignored.op_accept, -1);
+    assert_eq!(ignored.op_error, "tick ignored");
+
+    kaspa_indexer_rust::config::apply_tick_ignored(&default_tick_ignored());
+}
+
+#[test]
+fn test_mint_large_supply() {
+    use std::collections::HashMap;
+
+    let address = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+    let max = "99999999999999999999999999999999";
+    let lim = "50000000000000000000000"; // Above u64::MAX
+    let script = |op: &str| DataScriptType {
+        p: "KRC-20".to_string(),
+        op: op.to_string(),
+        from: Some(address.to_string()),
+        to: Some(address.to_string()),
+        tick: Some("HUGE".to_string()),
+        max: Some(max.to_string()),
+        lim: Some(lim.to_string()),
+        pre: Some("0".to_string()),
+        dec: Some("8".to_string()),
+        amt: None,
+        utxo: None,
+        price: None,
+        mod_type: "".to_string(),
+        name: None,
+        ca: None,
+        desc: None,
+    };
+    let op_data = |op: &str, daa_score: u64, fee: u64| DataOperationType {
+        tx_id: format!("{}_tx_{}", op, daa_score),
+        daa_score,
+        block_accept: "block_hash".to_string(),
+        fee,
+        fee_least: 0,
+        mts_add: 0,
+        op_score: daa_score * 10000,
+        op_accept: 0,
+        op_error: String::new(),
+        op_script: vec![script(op)],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: Some(DataStatsType {
+            tick_affc: vec![],
+            address_affc: vec![],
+        }),
+    };
+
+    let mut state_map = DataStateMapType {
+        state_token_map: HashMap::new(),
+        state_balance_map: HashMap::new(),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+    let mut deploy = op_data("deploy", 1000, 100000000000);
+    DeployOperation::do_operation(&script("deploy"), &mut deploy, &mut state_map, true).unwrap();
+    assert_eq!(deploy.op_accept, 1);
+
+    let balance_key = format!("{}_HUGE", address);
+    let minted = |state_map: &DataStateMapType| {
+        state_map.state_token_map["HUGE"]
+            .as_ref()
+            .unwrap()
+            .minted
+            .clone()
+    };
+    let mut mint = op_data("mint", 1001, 100000000);
+    MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+    assert_eq!(mint.op_accept, 1);
+    let mut mint = op_data("mint", 1002, 100000000);
+    MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+    assert_eq!(mint.op_accept, 1);
+    assert_eq!(minted(&state_map), "100000000000000000000000");
+    assert_eq!(
+        state_map.state_balance_map[&balance_key].as_ref().unwrap().balance,
+        "100000000000000000000000"
+    );
+
+    // The last mint is capped at max, then minting is finished
+    state_map
+        .state_token_map
+        .get_mut("HUGE")
+        .unwrap()
+        .as_mut()
+        .unwrap()
+        .minted = "99999999999999999999999999999990".to_string();
+    let mut mint = op_data("mint", 1003, 100000000);
+    MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+    assert_eq!(mint.op_accept, 1);
+    assert_eq!(minted(&state_map), max);
+    assert_eq!(
+        state_map.state_balance_map[&balance_key].as_ref().unwrap().balance,
+        "100000000000000000000009"
+    );
+
+    let mut mint = op_data("mint", 1004, 100000000);
+    MintOperation::do_operation(0, &mut mint, &mut state_map, true).unwrap();
+    assert_eq!(mint.op_accept, -1);
+    assert_eq!(mint.op_error, "mint finished");
+}
+
+#[test]
+fn test_self_transfer_is_rejected() {
+    use std::collections::HashMap;
+
+    let address = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+    let balance_key = format!("{}_SELF", address);
+    let mut state_map = DataStateMapType {
+        state_token_map: HashMap::from([(
+            "SELF".to_string(),
+            Some(StateTokenType {
+                tick: "SELF".to_string(),
+                max: "1000000".to_string(),
+                lim: "1000".to_string(),
+                dec: 8,
+                minted: "1000".to_string(),
+                burned: "0".to_string(),
+                ..Default::default()
+            }),
+        )]),
+        state_balance_map: HashMap::from([(
+            balance_key.clone(),
+            Some(StateBalanceType {
+                address: address.to_string(),
+                tick: "SELF".to_string(),
+                dec: 8,
+                balance: "1000".to_string(),
+                locked: "0".to_string(),
+                op_mod: 0,
+            }),
+        )]),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+    let op_data = |op: &str| DataOperationType {
+        tx_id: format!("{}_tx", op),
+        daa_score: 1000,
+        block_accept: "block_hash".to_string(),
+        fee: 100000000,
+        fee_least: 0,
+        mts_add: 0,
+        op_score: 1000 * 10000,
+        op_accept: 0,
+        op_error: String::new(),
+        op_script: vec![DataScriptType {
+            p: "KRC-20".to_string(),
+            op: op.to_string(),
+            from: Some(address.to_string()),
+            to: Some(address.to_string()),
+            tick: Some("SELF".to_string()),
+            max: None,
+            lim: None,
+            pre: None,
+            dec: None,
+            amt: Some("100".to_string()),
+            utxo: None,
+            price: None,
+            mod_type: "".to_string(),
+            name: None,
+            ca: None,
+            desc: None,
+        }],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: Some(DataStatsType {
+            tick_affc: vec![],
+            address_affc: vec![],
+        }),
+    };
+    assert!(is_self_transfer(&op_data("transfer").op_script[0]));
+
+    let mut transfer = op_data("transfer");
+    TransferOperation::do_operation(0, &mut transfer, &mut state_map, true).unwrap();
+    let mut send = op_data("send");
+    SendOperation::do_operation(0, &mut send, &mut state_map, true).unwrap();
+    for op in [&transfer, &send] {
+        assert_eq!(op.op_accept, -1);
+        assert_eq!(op.op_error, OP_ERROR_SELF_TRANSFER);
+    }
+
+    let balance = state_map.state_balance_map[&balance_key].as_ref().unwrap();
+    assert_eq!(balance.balance, "1000");
+    assert_eq!(balance.locked, "0");
+}
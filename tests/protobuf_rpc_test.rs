@@ -0,0 +1,184 @@
+use futures_util::stream::{self, Stream};
+use kaspa_indexer_rust::explorer::scanner::VSPCScanner;
+use kaspa_indexer_rust::protobuf::client::KaspaRpcClient;
+use kaspa_indexer_rust::protobuf::protowire::rpc_server::{Rpc, RpcServer};
+use kaspa_indexer_rust::protobuf::protowire::*;
+use kaspa_indexer_rust::storage::types::DataVspcType;
+use std::pin::Pin;
+use tokio::net::TcpListener;
+use tonic::{Request, Response, Status, Streaming};
+
+/// kaspad stand-in knowing only "block_1", whose virtual chain reorganized "block_2" away
+struct MockKaspad;
+
+fn block(request: GetBlockRequestMessage) -> kaspad_response::Payload {
+    if request.hash != "block_1" {
+        return kaspad_response::Payload::GetBlockResponse(GetBlockResponseMessage {
+            block: None,
+            error: Some(RpcError {
+                message: format!("block {} not found", request.hash),
+            }),
+        });
+    }
+    kaspad_response::Payload::GetBlockResponse(GetBlockResponseMessage {
+        block: Some(RpcBlock {
+            header: Some(RpcBlockHeader {
+                daa_score: 101,
+                ..Default::default()
+            }),
+            transactions: vec![],
+            verbose_data: Some(RpcBlockVerboseData {
+                hash: request.hash,
+                selected_parent_hash: "block_0".to_string(),
+                ..Default::default()
+            }),
+        }),
+        error: None,
+    })
+}
+
+fn virtual_chain(request: GetVirtualChainFromBlockRequestMessage) -> kaspad_response::Payload {
+    let mut response = GetVirtualChainFromBlockResponseMessage {
+        removed_chain_block_hashes: vec!["block_2".to_string()],
+        added_chain_block_hashes: vec!["block_2b".to_string(), "block_3b".to_string()],
+        accepted_transaction_ids: vec![],
+        error: None,
+    };
+    if request.start_hash != "block_1" {
+        response = GetVirtualChainFromBlockResponseMessage {
+            error: Some(RpcError {
+                message: format!("block {} not found", request.start_hash),
+            }),
+            ..Default::default()
+        };
+    } else if request.include_accepted_transaction_ids {
+        response.accepted_transaction_ids = vec![RpcAcceptedTransactionIds {
+            accepting_block_hash: "block_2b".to_string(),
+            accepted_transaction_ids: vec!["tx_1".to_string(), "tx_2".to_string()],
+        }];
+    }
+    kaspad_response::Payload::GetVirtualChainFromBlockResponse(response)
+}
+
+#[tonic::async_trait]
+impl Rpc for MockKaspad {
+    type MessageStreamStream =
+        Pin<Box<dyn Stream<Item = Result<KaspadResponse, Status>> + Send + 'static>>;
+
+    async fn message_stream(
+        &self,
+        request: Request<Streaming<KaspadRequest>>,
+    ) -> Result<Response<Self::MessageStreamStream>, Status> {
+        let mut requests = request.into_inner();
+        let request = requests.message().await?.unwrap();
+        let payload = match request.payload {
+            Some(kaspad_request::Payload::GetBlockRequest(request)) => block(request),
+            Some(kaspad_request::Payload::GetVirtualChainFromBlockRequest(request)) => {
+                virtual_chain(request)
+            }
+            _ => return Err(Status::unimplemented("unexpected request")),
+        };
+        let response = KaspadResponse {
+            id: request.id,
+            payload: Some(payload),
+        };
+        Ok(Response::new(Box::pin(stream::iter([Ok(response)]))))
+    }
+}
+
+async fn connect_mock_kaspad() -> KaspaRpcClient {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = stream::unfold(listener, |listener| async move {
+        let stream = listener.accept().await.map(|(stream, _)| stream);
+        Some((stream, listener))
+    });
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(RpcServer::new(MockKaspad))
+            .serve_with_incoming(incoming),
+    );
+
+    let mut client = KaspaRpcClient::new();
+    client.connect(format!("http://{}", addr)).await.unwrap();
+    client
+}
+
+#[tokio::test]
+async fn test_get_block_over_grpc() {
+    let mut client = connect_mock_kaspad().await;
+
+    let block = client
+        .get_block("block_1".to_string())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.header.unwrap().daa_score, 101);
+    let verbose_data = block.verbose_data.unwrap();
+    assert_eq!(verbose_data.hash, "block_1");
+    assert_eq!(verbose_data.selected_parent_hash, "block_0");
+
+    // Node errors surface as errors rather than an empty block
+    let err = client.get_block("block_9".to_string()).await.unwrap_err();
+    assert!(
+        err.to_string().contains("block block_9 not found"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_virtual_chain_from_block_over_grpc() {
+    let mut client = connect_mock_kaspad().await;
+
+    let chain = client
+        .get_virtual_chain_from_block("block_1".to_string(), true)
+        .await
+        .unwrap();
+    assert_eq!(chain.added_chain_block_hashes, ["block_2b", "block_3b"]);
+    assert_eq!(chain.removed_chain_block_hashes, ["block_2"]);
+    assert_eq!(chain.accepted_transaction_ids, ["tx_1", "tx_2"]);
+
+    // Accepted transaction ids are only sent when asked for
+    let chain = client
+        .get_virtual_chain_from_block("block_1".to_string(), false)
+        .await
+        .unwrap();
+    assert_eq!(chain.added_chain_block_hashes, ["block_2b", "block_3b"]);
+    assert!(chain.accepted_transaction_ids.is_empty());
+
+    let err = client
+        .get_virtual_chain_from_block("block_9".to_string(), false)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("block block_9 not found"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_check_rollback_removed() {
+    let vspc_list_prev: Vec<DataVspcType> = (1..=3)
+        .map(|i| DataVspcType {
+            daa_score: 100 + i,
+            hash: format!("block_{}", i),
+            tx_id_list: vec![],
+            selected_parent: String::new(),
+        })
+        .collect();
+
+    // Removed hashes come highest first, the rollback starts at the oldest of them
+    let removed = vec!["block_3".to_string(), "block_2".to_string()];
+    assert_eq!(
+        VSPCScanner::check_rollback_removed(&vspc_list_prev, &removed),
+        102
+    );
+    let removed = vec!["block_0".to_string()];
+    assert_eq!(
+        VSPCScanner::check_rollback_removed(&vspc_list_prev, &removed),
+        0
+    );
+    assert_eq!(VSPCScanner::check_rollback_removed(&vspc_list_prev, &[]), 0);
+}
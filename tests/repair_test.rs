@@ -0,0 +1,130 @@
+use kaspa_indexer_rust::config::types::RocksConfig;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::repair::{check_repaired, check_vspc_list, repair_db};
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::storage::versioned;
+use kaspa_indexer_rust::utils::address::encode_address;
+
+fn vspc(daa_score: u64, hash: &str) -> DataVspcType {
+    DataVspcType {
+        daa_score,
+        hash: hash.to_string(),
+        tx_id_list: vec![],
+        selected_parent: String::new(),
+    }
+}
+
+fn balance(seed: u8, amount: &str) -> StateBalanceType {
+    StateBalanceType {
+        address: encode_address(&[seed; 32], true).unwrap(),
+        tick: "FIXD".to_string(),
+        dec: 8,
+        balance: amount.to_string(),
+        locked: "0".to_string(),
+        op_mod: 0,
+    }
+}
+
+async fn open(rocks_config: &RocksConfig) -> StorageManager {
+    let mut storage = StorageManager::new(rocks_config.clone(), None)
+        .await
+        .unwrap();
+    storage.init().await.unwrap();
+    storage
+}
+
+#[tokio::test]
+async fn test_repaired_db_reports_residual_damage() {
+    let path = "./test_data/repair_db";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+
+    {
+        let storage = open(&rocks_config).await;
+        let mut state_map = DataStateMapType::new();
+        let token = StateTokenType {
+            tick: "FIXD".to_string(),
+            max: "21000000".to_string(),
+            minted: "1000".to_string(),
+            ..Default::default()
+        };
+        state_map
+            .state_token_map
+            .insert("FIXD".to_string(), Some(token));
+        for balance in [balance(1, "700"), balance(2, "300")] {
+            state_map.state_balance_map.insert(
+                StateKey::balance(&balance.address, "FIXD").map_key(),
+                Some(balance),
+            );
+        }
+        storage
+            .state
+            .save_state_batch_rocks_begin(&state_map)
+            .unwrap();
+        storage
+            .runtime
+            .set_runtime_vspc_last(&[vspc(100, "a"), vspc(101, "b")])
+            .await
+            .unwrap();
+
+        // Damage that survives the repair: one balance lost its value
+        let damaged = balance(2, "0");
+        storage
+            .rocksdb
+            .put_raw(
+                &StateKey::balance(&damaged.address, "FIXD").encode(),
+                &versioned::encode(&damaged).unwrap(),
+            )
+            .unwrap();
+        // and another token record is unreadable
+        storage
+            .rocksdb
+            .put_raw(&StateKey::token("BADT").encode(), b"\xff")
+            .unwrap();
+        storage.shutdown().await.unwrap();
+    }
+
+    // A lost manifest is rebuilt from the table and log files
+    for entry in std::fs::read_dir(path).unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_name().to_string_lossy().starts_with("MANIFEST-") {
+            std::fs::remove_file(entry.path()).unwrap();
+        }
+    }
+    repair_db(&rocks_config).unwrap();
+
+    let storage = open(&rocks_config).await;
+    let report = check_repaired(&storage).await.unwrap();
+    assert_eq!(report.recovered_keys["tokens"], 2);
+    assert_eq!(report.recovered_keys["balances"], 2);
+    assert!(report.vspc_issues.is_empty());
+    // The unreadable token fails its own check without hiding the other tick
+    assert_eq!(report.failed_checks.len(), 1);
+    assert!(
+        report.failed_checks[0].starts_with("supply of BADT: "),
+        "{:?}",
+        report.failed_checks
+    );
+    assert_eq!(report.supply_discrepancies.len(), 1);
+    assert_eq!(report.supply_discrepancies[0].tick, "FIXD");
+    assert_eq!(report.supply_discrepancies[0].delta, -300);
+    assert!(report.needs_resync());
+    storage.shutdown().await.unwrap();
+    std::fs::remove_dir_all(path).ok();
+}
+
+#[test]
+fn test_vspc_list_order_checked() {
+    assert!(check_vspc_list(&[vspc(100, "a"), vspc(102, "b")]).is_empty());
+    assert_eq!(
+        check_vspc_list(&[vspc(100, "a"), vspc(100, "b"), vspc(101, "a")]),
+        vec![
+            "block b at DAA score 100 does not follow DAA score 100".to_string(),
+            "block a is listed more than once".to_string(),
+        ]
+    );
+}
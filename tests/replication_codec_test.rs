@@ -0,0 +1,111 @@
+use kaspa_indexer_rust::config::types::{DistributedConfig, ReplicationConfig, ShardConfig};
+use kaspa_indexer_rust::storage::compression::{ReplicationCodec, decode_replicated};
+use kaspa_indexer_rust::storage::distributed::DistributedStorage;
+
+fn replication_config(codec: &str) -> ReplicationConfig {
+    ReplicationConfig {
+        compression_codec: codec.to_string(),
+        ..Default::default()
+    }
+}
+
+fn sample_values() -> Vec<Vec<u8>> {
+    let op_data = br#"{"tx_id":"tx","daa_score":110165000,"op_accept":1}"#.repeat(64);
+    let noise: Vec<u8> = (0..4096u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect();
+    vec![Vec::new(), b"x".to_vec(), op_data, noise]
+}
+
+#[test]
+fn test_replication_codecs_round_trip() {
+    for codec_name in ["zstd", "lz4", "snappy"] {
+        let config = replication_config(codec_name);
+        let codec = ReplicationCodec::from_config(&config).unwrap();
+        assert_ne!(codec, ReplicationCodec::None);
+        for value in sample_values() {
+            let encoded = codec.encode(&value, config.compression_level).unwrap();
+            assert_eq!(
+                decode_replicated(&encoded).unwrap(),
+                value,
+                "{} corrupted a {} byte value",
+                codec_name,
+                value.len()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_replication_codec_selection() {
+    // Disabled compression ships values unchanged behind the tag byte
+    let config = ReplicationConfig {
+        enable_compression: false,
+        ..replication_config("lz4")
+    };
+    let codec = ReplicationCodec::from_config(&config).unwrap();
+    assert_eq!(codec, ReplicationCodec::None);
+    let encoded = codec.encode(b"value", 6).unwrap();
+    assert_eq!(&encoded[1..], b"value");
+    assert_eq!(decode_replicated(&encoded).unwrap(), b"value");
+
+    assert!(ReplicationCodec::from_config(&replication_config("gzip")).is_err());
+    assert!(decode_replicated(&[]).is_err());
+    assert!(decode_replicated(&[9, 1, 2]).is_err());
+}
+
+#[tokio::test]
+async fn test_distributed_reads_decode_replicas() {
+    let mut config = DistributedConfig::default();
+    let data_dir =
+        std::env::temp_dir().join(format!("replication_codec_test_{}", std::process::id()));
+    config.node.data_dir = data_dir.to_string_lossy().to_string();
+    config.node.shard_count = 3;
+    config.node.replication_factor = 3;
+    config.shards = (0..3)
+        .map(|shard_id| ShardConfig {
+            shard_id,
+            ..Default::default()
+        })
+        .collect();
+    config.hash_ring.virtual_nodes = 4;
+    config.replication = ReplicationConfig {
+        strategy: "sync".to_string(),
+        ..replication_config("zstd")
+    };
+    let storage = DistributedStorage::new(config).await.unwrap();
+
+    let keys: Vec<String> = (0..32).map(|i| format!("key_{}", i)).collect();
+    for key in &keys {
+        storage
+            .distributed_insert(key, format!("value of {}", key).as_bytes())
+            .await
+            .unwrap();
+    }
+
+    // Replicas never overwrite the plain value on the primary shard
+    for key in &keys {
+        let value = storage.distributed_get(key).await.unwrap();
+        assert_eq!(value, Some(format!("value of {}", key).into_bytes()));
+    }
+
+    // A value lost on the primary shard is decoded from a replica
+    let mut recovered = 0;
+    for key in &keys {
+        storage
+            .get_shard_for_key(key)
+            .await
+            .unwrap()
+            .delete_raw(key)
+            .unwrap();
+        let value = storage.distributed_get(key).await.unwrap();
+        if storage.replica_shard_ids(key).await.is_empty() {
+            assert_eq!(value, None);
+        } else {
+            assert_eq!(value, Some(format!("value of {}", key).into_bytes()));
+            recovered += 1;
+        }
+    }
+    assert!(recovered > 0);
+    let _ = std::fs::remove_dir_all(data_dir);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::explorer::rollback::{get_rollback_timings, record_rollback_duration};
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn op_data(tx_id: &str, daa_score: u64, index: u64) -> DataOperationType {
+    let mut op_data = common::committed_op(daa_score);
+    op_data.tx_id = tx_id.to_string();
+    op_data.block_accept = format!("block_hash_{}", daa_score);
+    op_data.op_score += index;
+    op_data
+}
+
+#[tokio::test]
+async fn test_rollback_estimate_scales_with_operation_count() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let vspc_list: Vec<DataVspcType> = [100, 200, 300, 400]
+        .into_iter()
+        .map(|daa_score| DataVspcType {
+            daa_score,
+            hash: format!("block_hash_{}", daa_score),
+            tx_id_list: vec![],
+            selected_parent: String::new(),
+        })
+        .collect();
+    storage
+        .runtime
+        .set_runtime_vspc_last(&vspc_list)
+        .await
+        .unwrap();
+    storage
+        .runtime
+        .save_checkpoint("block_hash_400", 400)
+        .unwrap();
+    let storage = Arc::new(storage);
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+
+    // One operation after block 300, six after block 200 and none more after block 100
+    let mut op_data_list = vec![op_data("tx_a", 100, 0)];
+    op_data_list.extend((0..3).map(|index| op_data(&format!("tx_b{}", index), 300, index)));
+    op_data_list.extend((0..2).map(|index| op_data(&format!("tx_c{}", index), 250, index)));
+    op_data_list.push(op_data("tx_d", 400, 0));
+    rollback_manager
+        .save_op_data_batch_rocks(&op_data_list)
+        .await
+        .unwrap();
+
+    let candidates = rollback_manager.get_rollback_candidates(10).await.unwrap();
+    let costs: Vec<(u64, usize)> = candidates
+        .iter()
+        .map(|candidate| (candidate.daa_score, candidate.rollback_operation_count))
+        .collect();
+    assert_eq!(costs, vec![(300, 1), (200, 6), (100, 6)]);
+    assert!(candidates[1].estimated_bytes > candidates[0].estimated_bytes);
+    assert_eq!(candidates[1].estimated_bytes, candidates[2].estimated_bytes);
+
+    // Before any rollback is timed the default time per operation is assumed
+    let per_operation = get_rollback_timings().average_operation_duration();
+    for candidate in &candidates {
+        assert_eq!(
+            candidate.estimated_duration,
+            per_operation * candidate.rollback_operation_count as u32
+        );
+    }
+
+    // Timed rollbacks replace the default
+    record_rollback_duration(10, Duration::from_millis(50));
+    let candidates = rollback_manager.get_rollback_candidates(10).await.unwrap();
+    assert_eq!(candidates[0].estimated_duration, Duration::from_millis(5));
+    assert_eq!(candidates[1].estimated_duration, Duration::from_millis(30));
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::holders;
+use kaspa_indexer_rust::storage::tickstats;
+use kaspa_indexer_rust::storage::types::{DataStateMapType, OperationData, PendingRollback};
+use std::sync::Arc;
+
+async fn setup_storage(path: &str) -> Arc<StorageManager> {
+    let storage = common::setup_storage(path).await;
+    common::seed_rollback_target(&storage).await;
+
+    // One operation above the rollback target
+    let operation = OperationData {
+        operation_type: "mint".to_string(),
+        tick: "TEST".to_string(),
+        from_address: None,
+        to_address: None,
+        amount: None,
+        tx_hash: "tx_150".to_string(),
+        block_hash: "block_hash_150".to_string(),
+        timestamp: 0,
+        block_daa_score: 150,
+        script: None,
+        is_testnet: true,
+        daa_score: 150,
+        tx_id: "tx_150".to_string(),
+        ca: None,
+    };
+    storage
+        .rocksdb
+        .put_raw("opdata:tx_150", &serde_json::to_vec(&operation).unwrap())
+        .unwrap();
+
+    storage
+}
+
+#[tokio::test]
+async fn test_resume_rollback_after_crash() {
+    let storage = setup_storage("./test_data/rollback_resume").await;
+
+    // Crash after the data write, before the runtime state update
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    rollback_manager
+        .rollback_data_to_block("block_hash_100", 100)
+        .await
+        .unwrap();
+    drop(rollback_manager);
+
+    assert!(storage.rocksdb.get_raw("opdata:tx_150").unwrap().is_none());
+    let (_, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(daa_score, 200);
+    assert_eq!(
+        storage
+            .runtime
+            .get_runtime_rollback_pending()
+            .await
+            .unwrap(),
+        Some(PendingRollback {
+            block_hash: "block_hash_100".to_string(),
+            daa_score: 100,
+        })
+    );
+
+    // Startup finishes the rollback
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    assert!(
+        rollback_manager
+            .resume_interrupted_rollback()
+            .await
+            .unwrap()
+    );
+    let (block_hash, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_hash_100");
+    assert_eq!(daa_score, 100);
+    assert!(!storage.runtime.is_syncing().unwrap());
+    assert!(
+        storage
+            .runtime
+            .get_runtime_rollback_pending()
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Resuming again is a no-op
+    assert!(
+        !rollback_manager
+            .resume_interrupted_rollback()
+            .await
+            .unwrap()
+    );
+    let (block_hash, daa_score) = storage.runtime.get_checkpoint().unwrap();
+    assert_eq!(block_hash, "block_hash_100");
+    assert_eq!(daa_score, 100);
+}
+
+#[tokio::test]
+async fn test_completed_rollback_clears_marker() {
+    let storage = setup_storage("./test_data/rollback_resume_complete").await;
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    rollback_manager
+        .rollback_to_block("block_hash_100", 100)
+        .await
+        .unwrap();
+
+    assert!(
+        storage
+            .runtime
+            .get_runtime_rollback_pending()
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        !rollback_manager
+            .resume_interrupted_rollback()
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_rollback_to_block_deletes_oplist_entries() {
+    let storage = setup_storage("./test_data/rollback_oplist").await;
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+
+    // An operation saved by the scanner above the rollback target
+    let (alice, bob) = (common::address(1), common::address(2));
+    let mut op_data = common::transfer(0, "TEST", &alice, &bob, "100");
+    op_data.daa_score = 150;
+    op_data.op_score = 150 * 10000 + 3;
+    rollback_manager
+        .save_op_state_batch(&[op_data], &DataStateMapType::new())
+        .await
+        .unwrap();
+    let oplist_key = "oplist:15:1500003";
+    assert!(storage.rocksdb.get_raw(oplist_key).unwrap().is_some());
+    let total_operations = || {
+        storage
+            .runtime
+            .get_runtime_state()
+            .unwrap()
+            .total_operations_processed
+    };
+    assert_eq!(total_operations(), 1);
+
+    rollback_manager
+        .rollback_to_block("block_hash_100", 100)
+        .await
+        .unwrap();
+    assert!(
+        storage
+            .rocksdb
+            .get_raw("opdata:transfer_tx_0")
+            .unwrap()
+            .is_none()
+    );
+    assert!(storage.rocksdb.get_raw(oplist_key).unwrap().is_none());
+    assert!(storage.rocksdb.scan_prefix("oplist:").unwrap().is_empty());
+    // The legacy record of setup_storage is rolled back too
+    assert_eq!(total_operations(), 0);
+}
+
+#[tokio::test]
+async fn test_rollback_to_block_reverts_tick_stats() {
+    let storage = setup_storage("./test_data/rollback_tick_stats").await;
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+
+    let (alice, bob) = (common::address(1), common::address(2));
+    let mut op_data_list = vec![
+        common::transfer(0, "TEST", &alice, &bob, "100"),
+        common::transfer(1, "TEST", &alice, &bob, "100"),
+    ];
+    for (index, op_data) in op_data_list.iter_mut().enumerate() {
+        op_data.daa_score = 150;
+        op_data.op_score = 150 * 10000 + index as u64;
+    }
+    op_data_list[0].op_accept = 1;
+    op_data_list[1].op_accept = -1;
+    op_data_list[1].op_error = "insufficient balance".to_string();
+    rollback_manager
+        .save_op_state_batch(&op_data_list, &DataStateMapType::new())
+        .await
+        .unwrap();
+    let stats = tickstats::get_tick_stats(&storage.rocksdb, "TEST")
+        .unwrap()
+        .unwrap();
+    assert_eq!((stats.accepted, stats.rejected), (1, 1));
+
+    rollback_manager
+        .rollback_to_block("block_hash_100", 100)
+        .await
+        .unwrap();
+    let stats = tickstats::get_tick_stats(&storage.rocksdb, "TEST")
+        .unwrap()
+        .unwrap();
+    assert_eq!((stats.accepted, stats.rejected), (0, 0));
+    assert!(stats.rejected_by_reason.is_empty());
+}
+
+#[tokio::test]
+async fn test_rollback_to_block_restores_balances_and_holders() {
+    let storage = setup_storage("./test_data/rollback_holders").await;
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    let (alice, bob) = (common::address(1), common::address(2));
+
+    let mut state_map = DataStateMapType::new();
+    state_map
+        .state_balance_map
+        .extend([common::balance_state(&alice, "TEST", "100")]);
+    storage
+        .state
+        .save_state_batch_rocks_begin(&state_map)
+        .unwrap();
+    assert_eq!(
+        holders::get_holder_count(&storage.rocksdb, "TEST").unwrap(),
+        1
+    );
+
+    // Above the rollback target alice transfers to bob, who becomes a holder
+    let mut op_data = common::transfer(0, "TEST", &alice, &bob, "60");
+    op_data.daa_score = 150;
+    op_data.op_score = 150 * 10000;
+    op_data.op_accept = 1;
+    let mut state_map = DataStateMapType::new();
+    state_map.state_balance_map.extend([
+        common::balance_state(&alice, "TEST", "40"),
+        common::balance_state(&bob, "TEST", "60"),
+    ]);
+    rollback_manager
+        .save_op_state_batch(&[op_data], &state_map)
+        .await
+        .unwrap();
+    assert_eq!(
+        holders::get_holder_count(&storage.rocksdb, "TEST").unwrap(),
+        2
+    );
+
+    rollback_manager
+        .rollback_to_block("block_hash_100", 100)
+        .await
+        .unwrap();
+    assert_eq!(
+        common::balance(&storage, &alice, "TEST").as_deref(),
+        Some("100")
+    );
+    assert_eq!(
+        common::balance(&storage, &bob, "TEST").as_deref(),
+        Some("0")
+    );
+    assert_eq!(
+        holders::get_holder_count(&storage.rocksdb, "TEST").unwrap(),
+        1
+    );
+}
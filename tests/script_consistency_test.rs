@@ -105,6 +105,7 @@ mod script_consistency_tests {
             price: None,
             mod_type: "issue".to_string(),
             ca: None,
+            desc: None,
         };
 
         let rust_validation =
@@ -139,6 +140,7 @@ mod script_consistency_tests {
             price: None,
             mod_type: "send".to_string(),
             ca: None,
+            desc: None,
         };
 
         let rust_transfer_validation =
@@ -0,0 +1,436 @@
+use kaspa_addresses::{Address, Prefix, Version};
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::operations::validate_field_sizes;
+use kaspa_indexer_rust::protobuf::convert::rpc_transaction_to_data;
+use kaspa_indexer_rust::protobuf::protowire::{
+    RpcOutpoint, RpcScriptPublicKey, RpcTransaction, RpcTransactionInput, RpcTransactionOutput,
+    RpcTransactionOutputVerboseData, RpcTransactionVerboseData,
+};
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::utils::envelope::{ENVELOPE_VARIANTS, EnvelopeVariant};
+use kaspa_indexer_rust::utils::script_parser::ScriptParser;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const TEST_ADDRESS: &str = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+/// Encode a data push, corresponding to the length prefixes handled by parse_script_input
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else if len <= 255 {
+        format!("4c{:02x}{}", len, data_hex)
+    } else {
+        format!("4d{:02x}{:02x}{}", len & 0xff, len >> 8, data_hex)
+    }
+}
+
+/// Build a P2SH commit-reveal signature script carrying a KRC-20 JSON payload
+fn build_signature_script(payload: &serde_json::Value) -> String {
+    build_enveloped_script(payload, "006307", "kasplex")
+}
+
+/// Signature script whose envelope opens with `prefix` followed by `header`
+fn build_enveloped_script(payload: &serde_json::Value, prefix: &str, header: &str) -> String {
+    let redeem_script = format!(
+        "20{}ac{}{}00{}68",
+        "ab".repeat(32),
+        prefix,
+        hex::encode(header),
+        push_data(&hex::encode(payload.to_string()))
+    );
+    format!(
+        "{}{}",
+        push_data(&"00".repeat(65)),
+        push_data(&redeem_script)
+    )
+}
+
+fn mint_payload(tick: &str) -> serde_json::Value {
+    serde_json::json!({
+        "p": "KRC-20",
+        "op": "mint",
+        "tick": tick,
+        "to": TEST_ADDRESS,
+        "mod_type": "",
+    })
+}
+
+fn build_transaction(inputs: Vec<serde_json::Value>) -> DataTransactionType {
+    DataTransactionType {
+        tx_id: "tx_multi_input".to_string(),
+        daa_score: 110165001,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({
+            "inputs": inputs,
+            "outputs": [],
+        })),
+    }
+}
+
+fn token_state(tick: &str) -> StateTokenType {
+    StateTokenType {
+        tick: tick.to_string(),
+        max: "1000000".to_string(),
+        lim: "1000".to_string(),
+        pre: "0".to_string(),
+        dec: 8,
+        mod_type: "".to_string(),
+        from: TEST_ADDRESS.to_string(),
+        to: TEST_ADDRESS.to_string(),
+        minted: "0".to_string(),
+        burned: "0".to_string(),
+        name: "".to_string(),
+        desc: String::new(),
+        frozen: false,
+        tx_id: "deploy_tx".to_string(),
+        op_add: 0,
+        op_mod: 0,
+        mts_add: 0,
+        mts_mod: 0,
+    }
+}
+
+/// Input revealing `payload` as the node encodes it, spending output `index` of the commit
+fn input(index: u32, payload: &serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "previousOutpoint": { "transactionId": "commit_tx", "index": index },
+        "signatureScript": build_signature_script(payload),
+        "sequence": 0,
+        "sigOpCount": 1,
+    })
+}
+
+#[test]
+fn test_multi_input_ops_ordered_by_input_position() {
+    // Operations follow the position of their input, not the index of the spent outpoint
+    let tx_data = build_transaction(vec![
+        input(2, &mint_payload("CCCC")),
+        input(0, &mint_payload("AAAA")),
+        input(1, &mint_payload("BBBB")),
+    ]);
+
+    let op_data_list = ScriptParser::parse_op_data_list(&[tx_data], true).unwrap();
+    assert_eq!(op_data_list.len(), 1);
+    let mut op_data = op_data_list[0].clone();
+    assert_eq!(op_data.op_score, 110165001 * 10000);
+
+    let ticks: Vec<String> = op_data
+        .op_script
+        .iter()
+        .map(|s| s.tick.clone().unwrap_or_default())
+        .collect();
+    assert_eq!(ticks, vec!["CCCC", "AAAA", "BBBB"]);
+
+    // Apply and check the scripts were executed in input order
+    let mut state_map = DataStateMapType {
+        state_token_map: HashMap::new(),
+        state_balance_map: HashMap::new(),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+    for tick in ["AAAA", "BBBB", "CCCC"] {
+        state_map
+            .state_token_map
+            .insert(tick.to_string(), Some(token_state(tick)));
+    }
+    op_data.fee = 100000000;
+
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    let operation_manager = OperationManager::new(&storage);
+    operation_manager
+        .execute_op_script_list(&mut op_data, &mut state_map, true)
+        .unwrap();
+
+    assert_eq!(op_data.op_accept, 1);
+    assert_eq!(
+        op_data.ss_info.unwrap().tick_affc,
+        vec!["CCCC:1", "AAAA:1", "BBBB:1"]
+    );
+}
+
+#[test]
+fn test_orphan_recyclable_op_is_rejected() {
+    // Input 0 carries an invalid primary op, the recyclable mint in input 1 has no context
+    let invalid_payload = serde_json::json!({
+        "p": "KRC-20",
+        "op": "mint",
+        "tick": "A1",
+        "to": TEST_ADDRESS,
+        "mod_type": "",
+    });
+    let tx_data = build_transaction(vec![
+        serde_json::json!({
+            "index": 0,
+            "signatureScript": build_signature_script(&invalid_payload),
+        }),
+        serde_json::json!({
+            "index": 1,
+            "signatureScript": build_signature_script(&mint_payload("BBBB")),
+        }),
+    ]);
+
+    assert!(ScriptParser::parse_op_data(&tx_data, true).unwrap().is_none());
+    let op_data_list = ScriptParser::parse_op_data_list(&[tx_data], true).unwrap();
+    assert!(op_data_list.is_empty());
+}
+
+#[test]
+fn test_parse_op_from_rpc_transaction() {
+    let tx = RpcTransaction {
+        version: 0,
+        inputs: vec![RpcTransactionInput {
+            previous_outpoint: Some(RpcOutpoint {
+                transaction_id: "commit_tx".to_string(),
+                index: 0,
+            }),
+            signature_script: build_signature_script(&mint_payload("GRPC")),
+            sequence: 0,
+            sig_op_count: 1,
+            verbose_data: None,
+        }],
+        outputs: vec![RpcTransactionOutput {
+            amount: 100000,
+            script_public_key: Some(RpcScriptPublicKey {
+                version: 0,
+                script_public_key: "20abac".to_string(),
+            }),
+            verbose_data: Some(RpcTransactionOutputVerboseData {
+                script_public_key_type: "pubkey".to_string(),
+                script_public_key_address: TEST_ADDRESS.to_string(),
+            }),
+        }],
+        lock_time: 0,
+        subnetwork_id: "0000000000000000000000000000000000000000".to_string(),
+        gas: 0,
+        payload: String::new(),
+        verbose_data: Some(RpcTransactionVerboseData {
+            transaction_id: "reveal_tx".to_string(),
+            hash: "reveal_hash".to_string(),
+            compute_mass: 0,
+            block_hash: "block_hash".to_string(),
+            block_time: 1700000000000,
+        }),
+        mass: 0,
+    };
+
+    let tx_data = rpc_transaction_to_data(&tx, 110165001, "block_hash");
+    assert_eq!(tx_data.tx_id, "reveal_tx");
+
+    let op_data = ScriptParser::parse_op_data(&tx_data, true).unwrap().unwrap();
+    assert_eq!(op_data.tx_id, "reveal_tx");
+    assert_eq!(op_data.mts_add, 1700000000000);
+    assert_eq!(op_data.op_script.len(), 1);
+    assert_eq!(op_data.op_script[0].op, "mint");
+    assert_eq!(op_data.op_script[0].tick.as_deref(), Some("GRPC"));
+}
+
+#[test]
+fn test_script_field_size_limits() {
+    let script = |name: &str| DataScriptType {
+        p: "KRC-20".to_string(),
+        op: "deploy".to_string(),
+        from: Some(TEST_ADDRESS.to_string()),
+        to: Some(TEST_ADDRESS.to_string()),
+        tick: None,
+        max: Some("21000000".to_string()),
+        lim: None,
+        pre: None,
+        dec: Some("8".to_string()),
+        amt: None,
+        utxo: None,
+        price: None,
+        mod_type: "issue".to_string(),
+        name: Some(name.to_string()),
+        ca: None,
+        desc: None,
+    };
+    assert!(validate_field_sizes(&script(&"N".repeat(32))).is_ok());
+    let err = validate_field_sizes(&script(&"N".repeat(33))).unwrap_err();
+    assert_eq!(err.to_string(), "script field name exceeds 32 bytes");
+
+    // Oversized fields drop the script during parsing, boundary lengths are kept
+    let mint_with_utxo = |len: usize| {
+        let mut payload = mint_payload("SIZE");
+        payload["utxo"] = serde_json::json!("u".repeat(len));
+        build_transaction(vec![serde_json::json!({
+            "index": 0,
+            "signatureScript": build_signature_script(&payload),
+        })])
+    };
+    assert!(
+        ScriptParser::parse_op_data(&mint_with_utxo(256), true)
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        ScriptParser::parse_op_data(&mint_with_utxo(257), true)
+            .unwrap()
+            .is_none()
+    );
+
+    let mut payload = mint_payload("SIZE");
+    payload["to"] = serde_json::json!(format!("kaspatest:{}", "q".repeat(119)));
+    let tx_data = build_transaction(vec![serde_json::json!({
+        "index": 0,
+        "signatureScript": build_signature_script(&payload),
+    })]);
+    assert!(ScriptParser::parse_op_data(&tx_data, true).unwrap().is_none());
+
+    // Descriptions are bounded by their own limit
+    let mint_with_desc = |len: usize| {
+        let mut payload = mint_payload("SIZE");
+        payload["desc"] = serde_json::json!("d".repeat(len));
+        build_transaction(vec![serde_json::json!({
+            "index": 0,
+            "signatureScript": build_signature_script(&payload),
+        })])
+    };
+    assert!(
+        ScriptParser::parse_op_data(&mint_with_desc(256), true)
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        ScriptParser::parse_op_data(&mint_with_desc(4096), true)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_envelope_variants_parse_the_same_operation() {
+    let payload = mint_payload("ENVL");
+    let standard =
+        ScriptParser::parse_script_input(&build_signature_script(&payload), true).unwrap();
+    assert!(standard.0);
+
+    for variant in ENVELOPE_VARIANTS {
+        let script = build_enveloped_script(&payload, variant.prefix(), "kasplex");
+        let parsed = ScriptParser::parse_script_input(&script, true).unwrap();
+        assert_eq!(parsed, standard, "{:?}", variant);
+
+        // Header case does not matter, as in the Go version
+        let script = build_enveloped_script(&payload, variant.prefix(), "KASPLEX");
+        let parsed = ScriptParser::parse_script_input(&script, true).unwrap();
+        assert_eq!(parsed, standard, "{:?}", variant);
+    }
+    assert_eq!(
+        EnvelopeVariant::parse(&format!("00634c07{}", hex::encode("kasplex")), 0),
+        Some((EnvelopeVariant::PushData1, 22))
+    );
+}
+
+#[test]
+fn test_unknown_envelopes_are_rejected() {
+    let payload = mint_payload("ENVL");
+    for (prefix, header) in [
+        // OP_RETURN instead of OP_FALSE OP_IF
+        ("6a07", "kasplex"),
+        // OP_TRUE OP_IF
+        ("516307", "kasplex"),
+        // Header pushed with a wrong length
+        ("00634c08", "kasplex"),
+        // Unknown protocol header
+        ("006307", "kasplez"),
+    ] {
+        let script = build_enveloped_script(&payload, prefix, header);
+        let (ok, params) = ScriptParser::parse_script_input(&script, true).unwrap();
+        assert!(!ok, "{} {}", prefix, header);
+        assert!(params.is_empty());
+    }
+
+    // Undecodable headers are rejected rather than failing the whole input
+    let script =
+        build_signature_script(&payload).replacen(&hex::encode("kasplex"), "zzzzzzzzzzzzzz", 1);
+    assert_eq!(
+        ScriptParser::parse_script_input(&script, true).unwrap(),
+        (false, Vec::new())
+    );
+}
+
+/// Signature script spending a redeem script that starts with `signer`, the key or multisig part
+fn signature_script_for_signer(signer: &str) -> String {
+    let redeem_script = format!(
+        "{}006307{}00{}68",
+        signer,
+        hex::encode("kasplex"),
+        push_data(&hex::encode(mint_payload("KASP").to_string()))
+    );
+    format!(
+        "{}{}",
+        push_data(&"00".repeat(65)),
+        push_data(&redeem_script)
+    )
+}
+
+fn sender(signer: &str, testnet: bool) -> String {
+    let (ok, params) =
+        ScriptParser::parse_script_input(&signature_script_for_signer(signer), testnet).unwrap();
+    assert!(ok, "{}", signer);
+    params[0].clone()
+}
+
+#[test]
+fn test_sender_address_round_trips_public_key() {
+    // Schnorr keys of known addresses
+    for (k_pub, address, testnet) in [
+        (
+            "d3e875d567f0f7eb7bc053d51763443c5c8a88fc058a91662dc1e7378aed1b5b",
+            TEST_ADDRESS,
+            true,
+        ),
+        (
+            "402530d2546ccbbf7e8afaa94a6ae570caaffc48270567a13ecc422de360cc4d",
+            "kaspatest:qpqz2vxj23kvh0m73ta2jjn2u4cv4tlufqns2eap8mxyyt0rvrxy6ejkful67",
+            true,
+        ),
+        (
+            "87002b6991609f70998f280f4b9990be37f4d6a4845485156033ed646d230c61",
+            "kaspa:qzrsq2mfj9sf7uye3u5q7juejzlr0axk5jz9fpg4vqe76erdyvxxze84k9nk7",
+            false,
+        ),
+    ] {
+        assert_eq!(sender(&format!("20{}ac", k_pub), testnet), address);
+        let decoded = Address::try_from(address).unwrap();
+        assert_eq!(decoded.version, Version::PubKey);
+        assert_eq!(hex::encode(decoded.payload.as_slice()), k_pub);
+    }
+
+    // ECDSA keys use version 1
+    let k_pub = format!("02{}", "ab".repeat(32));
+    let address = sender(&format!("21{}ab", k_pub), true);
+    let decoded = Address::try_from(address.as_str()).unwrap();
+    assert_eq!(decoded.prefix, Prefix::Testnet);
+    assert_eq!(decoded.version, Version::PubKeyECDSA);
+    assert_eq!(hex::encode(decoded.payload.as_slice()), k_pub);
+
+    // Multisig senders are the P2SH address of the multisig script hash, version 8
+    let multisig = format!("5120{}51ae", "ab".repeat(32));
+    let address = sender(&multisig, false);
+    assert!(address.starts_with("kaspa:p"), "{}", address);
+    let decoded = Address::try_from(address.as_str()).unwrap();
+    assert_eq!(decoded.prefix, Prefix::Mainnet);
+    assert_eq!(decoded.version, Version::ScriptHash);
+    let script_hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .hash(&hex::decode(&multisig).unwrap());
+    assert_eq!(decoded.payload.as_slice(), script_hash.as_bytes());
+}
+
+#[test]
+fn test_testnet_operation_sender_has_testnet_prefix() {
+    let k_pub = "d3e875d567f0f7eb7bc053d51763443c5c8a88fc058a91662dc1e7378aed1b5b";
+    let tx_data = build_transaction(vec![serde_json::json!({
+        "index": 0,
+        "signatureScript": signature_script_for_signer(&format!("20{}ac", k_pub)),
+    })]);
+
+    let op_data = ScriptParser::parse_op_data(&tx_data, true)
+        .unwrap()
+        .unwrap();
+    assert_eq!(op_data.op_script[0].from.as_deref(), Some(TEST_ADDRESS));
+}
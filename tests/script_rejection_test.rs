@@ -0,0 +1,90 @@
+use kaspa_indexer_rust::operations::metrics::{
+    ScriptRejectionCounts, get_script_rejections, reset_script_rejections,
+};
+use kaspa_indexer_rust::storage::types::DataTransactionType;
+use kaspa_indexer_rust::utils::script_parser::ScriptParser;
+
+const TEST_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else {
+        format!("4c{:02x}{}", len, data_hex)
+    }
+}
+
+/// Transaction revealing a mint script with the given protocol, op, tick and receiver
+fn transaction(index: u64, p: &str, op: &str, tick: &str, to: &str) -> DataTransactionType {
+    let payload = serde_json::json!({
+        "p": p,
+        "op": op,
+        "tick": tick,
+        "to": to,
+        "mod_type": "",
+    });
+    let redeem_script = format!(
+        "20{}ac006307{}00{}68",
+        "ab".repeat(32),
+        hex::encode("kasplex"),
+        push_data(&hex::encode(payload.to_string()))
+    );
+    DataTransactionType {
+        tx_id: format!("tx_{}", index),
+        daa_score: 110165001 + index,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({
+            "inputs": [{
+                "index": 0,
+                "signatureScript": format!(
+                    "{}{}",
+                    push_data(&"00".repeat(65)),
+                    push_data(&redeem_script)
+                ),
+            }],
+            "outputs": [],
+        })),
+    }
+}
+
+#[test]
+fn test_rejected_scripts_counted_by_reason() {
+    reset_script_rejections();
+    let tx_data_list = vec![
+        transaction(0, "KRC-20", "mint", "GOOD", TEST_ADDRESS),
+        transaction(1, "BRC-20", "mint", "GOOD", TEST_ADDRESS),
+        transaction(2, "BRC-20", "mint", "GOOD", TEST_ADDRESS),
+        transaction(3, "KRC-20", "inscribe", "GOOD", TEST_ADDRESS),
+        transaction(
+            4,
+            "KRC-20",
+            "mint",
+            "GOOD",
+            "kaspatest:qrf7saw4vlc006\u{e9}",
+        ),
+        transaction(5, "KRC-20", "mint", "G!", TEST_ADDRESS),
+        transaction(6, "KRC-20", "mint", "GOOD", TEST_ADDRESS),
+    ];
+
+    let op_data_list = ScriptParser::parse_op_data_list(&tx_data_list, true).unwrap();
+    assert_eq!(op_data_list.len(), 2);
+    let rejections = get_script_rejections();
+    assert_eq!(
+        rejections,
+        ScriptRejectionCounts {
+            oversized: 0,
+            bad_protocol: 2,
+            bad_op: 1,
+            op_mismatch: 0,
+            non_ascii: 1,
+            invalid_operation: 1,
+        }
+    );
+    assert_eq!(rejections.total(), 5);
+
+    // Counters accumulate over batches
+    ScriptParser::parse_op_data_list(&tx_data_list[1..2], true).unwrap();
+    assert_eq!(get_script_rejections().bad_protocol, 3);
+}
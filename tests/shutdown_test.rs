@@ -0,0 +1,42 @@
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::utils::shutdown::run_within;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_slow_storage_shutdown_gives_up_after_grace_period() {
+    let grace = Duration::from_millis(200);
+    let start = Instant::now();
+    // A flush stuck in a synchronous call, as RocksDB blocks the thread it runs on
+    let completed = run_within(
+        async {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(())
+        },
+        grace,
+    )
+    .await
+    .unwrap();
+    assert!(!completed);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_storage_shutdown_within_grace_period() {
+    let storage = Arc::new(StorageManager::new_in_memory().unwrap());
+    assert!(
+        storage
+            .shutdown_within(Duration::from_secs(5))
+            .await
+            .unwrap()
+    );
+
+    // Errors of a step finishing in time are reported
+    let err = run_within(
+        async { Err(anyhow::anyhow!("flush failed")) },
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.to_string(), "flush failed");
+}
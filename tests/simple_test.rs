@@ -19,6 +19,7 @@ fn test_deploy_operation() {
         mod_type: "deploy".to_string(),
         name: None,
         ca: None,
+        desc: None,
     };
 
     let result = DeployOperation::validate(&mut script, "test_tx_id", 110165001, false);
@@ -42,6 +43,8 @@ fn test_token_state_creation() {
         minted: "0".to_string(),
         burned: "0".to_string(),
         name: "Test Token".to_string(),
+        desc: String::new(),
+        frozen: false,
         tx_id: "test_tx_id".to_string(),
         op_add: 0,
         op_mod: 0,
@@ -0,0 +1,94 @@
+use kaspa_indexer_rust::storage::state_key::{StateKey, StateKeyKind};
+
+const ADDRESSES: &[&str] = &[
+    "kaspa:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz",
+    "kaspa:test_address",
+    "_leading",
+    "trailing_",
+    "a__b",
+    "___",
+    "with:colons:and_underscores",
+    "stbalance_nested_prefix",
+    "ünïcødé_地址",
+    "comma,in,address",
+];
+
+const TICKS: &[&str] = &[
+    "KASP",
+    "k",
+    "TICK99",
+    "ünï",
+    "b4a4b7a1a2f00c0f1d4a1d6bd3f1c9e7c4a7d8e9f0a1b2c3d4e5f60718293a4b",
+];
+
+const TX_IDS: &[&str] = &[
+    "0a1b2c",
+    "b4a4b7a1a2f00c0f1d4a1d6bd3f1c9e7c4a7d8e9f0a1b2c3d4e5f60718293a4b",
+];
+
+fn assert_round_trip(key: StateKey) {
+    let encoded = key.encode();
+    assert_eq!(StateKey::decode(&encoded), Some(key.clone()), "{}", encoded);
+    assert_eq!(
+        StateKey::from_map_key(key.kind(), &key.map_key()),
+        Some(key.clone()),
+        "{}",
+        encoded
+    );
+    assert_eq!(encoded, key.kind().full_key(&key.map_key()));
+}
+
+#[test]
+fn test_state_key_round_trip_adversarial() {
+    for tick in TICKS {
+        assert_round_trip(StateKey::token(tick));
+        assert_round_trip(StateKey::mint_rate(tick));
+        for address in ADDRESSES {
+            assert_round_trip(StateKey::balance(address, tick));
+            assert_round_trip(StateKey::blacklist(tick, address));
+            for tx_id in TX_IDS {
+                assert_round_trip(StateKey::market(tick, address, tx_id));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_state_key_matches_go_layout() {
+    assert_eq!(StateKey::token("KASP").encode(), "sttoken_KASP");
+    assert_eq!(
+        StateKey::balance("kaspa:addr", "KASP").encode(),
+        "stbalance_kaspa:addr_KASP"
+    );
+    assert_eq!(
+        StateKey::market("KASP", "kaspa:addr", "txid").encode(),
+        "stmarket_KASP_kaspa:addr_txid"
+    );
+    assert_eq!(
+        StateKey::blacklist("KASP", "kaspa:addr").encode(),
+        "stblacklist_KASP_kaspa:addr"
+    );
+}
+
+#[test]
+fn test_state_key_rejects_malformed() {
+    for key in [
+        "",
+        "sttoken_",
+        "stbalance_",
+        "stbalance_addr",
+        "stbalance_addr_",
+        "stbalance__KASP",
+        "stmarket_KASP_addr",
+        "stmarket_KASP__txid",
+        "stmarket_KASP_addr_",
+        "stblacklist_KASP",
+        "stblacklist__addr",
+        "opdata:txid",
+        "token:KASP",
+    ] {
+        assert_eq!(StateKey::decode(key), None, "{}", key);
+    }
+
+    assert_eq!(StateKey::from_map_key(StateKeyKind::Market, "KASP"), None);
+}
@@ -0,0 +1,97 @@
+use kaspa_indexer_rust::config::get_op_score_multiplier;
+use kaspa_indexer_rust::storage::backend::{MemoryBackend, StorageBackend};
+use kaspa_indexer_rust::storage::checkpoint;
+use kaspa_indexer_rust::storage::deadletter;
+use kaspa_indexer_rust::storage::prune;
+use kaspa_indexer_rust::storage::rocksdb::RocksDBClient;
+use kaspa_indexer_rust::storage::types::OP_RANGE_BY;
+use rocksdb::WriteBatch;
+
+/// Same writes through both backends
+fn write_sample(backend: &dyn StorageBackend) {
+    backend.put_raw("oplist:1:100001", b"b").unwrap();
+    backend.put_raw("oplist:1:100000", b"a").unwrap();
+    backend.put_raw("oplistx", b"other prefix").unwrap();
+    backend.put_raw("opdata:stale", b"x").unwrap();
+
+    let mut batch = WriteBatch::default();
+    batch.put(b"oplist:2:200000", b"c");
+    batch.delete(b"opdata:stale");
+    backend.write_batch(batch).unwrap();
+}
+
+#[test]
+fn test_memory_backend_matches_rocksdb() {
+    let memory = MemoryBackend::new();
+    let rocksdb = RocksDBClient::new_in_memory().unwrap();
+    write_sample(&memory);
+    write_sample(&rocksdb);
+
+    let scanned = memory.scan_prefix("oplist:").unwrap();
+    let keys: Vec<&str> = scanned.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(
+        keys,
+        vec!["oplist:1:100000", "oplist:1:100001", "oplist:2:200000"]
+    );
+    assert_eq!(
+        scanned,
+        StorageBackend::scan_prefix(&rocksdb, "oplist:").unwrap()
+    );
+    assert!(memory.get_raw("opdata:stale").unwrap().is_none());
+    assert_eq!(memory.len(), 4);
+
+    memory.delete_raw("oplistx").unwrap();
+    assert!(memory.get_raw("oplistx").unwrap().is_none());
+}
+
+#[test]
+fn test_checkpoints_on_memory_backend() {
+    let memory = MemoryBackend::new();
+    memory.put_raw("oplist:1:100000", b"a").unwrap();
+    memory.put_raw("oplist:2:200000", b"b").unwrap();
+
+    // Range 1 is sealed once range 2 receives operations
+    let sealed = checkpoint::seal_completed_ranges(&memory, &[100000, 200000]).unwrap();
+    assert_eq!(sealed.len(), 1);
+    assert_eq!(sealed[0].range, 1);
+    assert_eq!(
+        sealed[0].checkpoint,
+        checkpoint::compute_checkpoint("", 1, &[(100000, b"a".to_vec())])
+    );
+    assert_eq!(
+        checkpoint::get_checkpoint(&memory, 1).unwrap(),
+        Some(sealed[0].clone())
+    );
+
+    checkpoint::unseal_from_op_score(&memory, 100000).unwrap();
+    assert!(checkpoint::get_checkpoint(&memory, 1).unwrap().is_none());
+}
+
+#[test]
+fn test_dead_letters_and_prune_on_memory_backend() {
+    let memory = MemoryBackend::new();
+    let error = anyhow::anyhow!("write failed");
+    deadletter::record_failed_batch(&memory, &[], 10, 20, &error).unwrap();
+    deadletter::record_failed_batch(&memory, &[], 10, 20, &error).unwrap();
+    let entries = deadletter::list_dead_letters(&memory).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].attempts, 2);
+    assert_eq!(entries[0].error, "write failed");
+
+    // oplist keys end with the op_score, the DAA score horizon is scaled by the multiplier
+    let oplist_key = |daa_score: u64| {
+        let op_score = daa_score * get_op_score_multiplier() + 1;
+        format!("oplist:{}:{}", op_score / OP_RANGE_BY, op_score)
+    };
+    let old_key = oplist_key(10);
+    let new_key = oplist_key(50);
+    memory.put_raw(&old_key, b"old").unwrap();
+    memory.put_raw(&new_key, b"new").unwrap();
+    let report = prune::prune_dry_run(&memory, 30).unwrap();
+    assert_eq!(report.oplist_keys, 1);
+    assert!(memory.get_raw(&old_key).unwrap().is_some());
+
+    prune::prune(&memory, 30).unwrap();
+    assert!(memory.get_raw(&old_key).unwrap().is_none());
+    assert!(memory.get_raw(&new_key).unwrap().is_some());
+}
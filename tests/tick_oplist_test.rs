@@ -0,0 +1,127 @@
+mod common;
+
+use common::spawn_server;
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::http::pagination::Pagination;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::*;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// An operation accepted at `daa_score`, affecting the ticks of `tick_affc`
+fn op_data(daa_score: u64, op: &str, tick_affc: &[&str]) -> DataOperationType {
+    let mut op_data = common::committed_op(daa_score);
+    let mut script = common::op_data(0, op, "", "", "").op_script.remove(0);
+    (script.from, script.to, script.tick) = (None, None, None);
+    op_data.op_script.push(script);
+    op_data.ss_info = Some(DataStatsType {
+        tick_affc: tick_affc.iter().map(|affc| affc.to_string()).collect(),
+        address_affc: vec![],
+    });
+    op_data
+}
+
+fn rollback_of(op_data_list: &[DataOperationType]) -> DataRollbackType {
+    let state_map = || DataStateMapType {
+        state_token_map: HashMap::new(),
+        state_balance_map: HashMap::new(),
+        state_market_map: HashMap::new(),
+        state_blacklist_map: HashMap::new(),
+        state_mint_rate_map: HashMap::new(),
+    };
+    DataRollbackType {
+        state_map_before: state_map(),
+        state_map_after: state_map(),
+        op_score_list: op_data_list.iter().map(|op| op.op_score).collect(),
+        tx_id_list: op_data_list.iter().map(|op| op.tx_id.clone()).collect(),
+        daa_score_start: 0,
+        daa_score_end: 0,
+        checkpoint_before: String::new(),
+        checkpoint_after: String::new(),
+        op_score_last: 0,
+    }
+}
+
+/// tx_ids of a tick oplist page and its next cursor
+async fn tick_oplist_page(
+    addr: SocketAddr,
+    tick: &str,
+    query: &str,
+) -> (Vec<String>, Option<String>) {
+    let body: serde_json::Value = reqwest::get(format!(
+        "http://{}/v1/krc20/token/{}/oplist?{}",
+        addr, tick, query
+    ))
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    let data = &body["data"];
+    let tx_ids = data["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["tx_id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(data["hasMore"], data["nextCursor"].is_string());
+    (tx_ids, data["nextCursor"].as_str().map(str::to_string))
+}
+
+#[tokio::test]
+async fn test_tick_oplist_filters_and_pages_newest_first() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+
+    let first = vec![
+        op_data(100, "deploy", &["TICK=1"]),
+        op_data(101, "mint", &["TICK:1"]),
+        op_data(102, "mint", &["OTHER:1"]),
+        op_data(103, "transfer", &["TICK:1"]),
+    ];
+    let second = vec![
+        op_data(104, "mint", &["TICK:0"]),
+        op_data(105, "transfer", &["TICK:-1", "OTHER:0"]),
+        op_data(106, "mint", &["TICK:0"]),
+    ];
+    for batch in [&first, &second] {
+        rollback_manager
+            .save_op_data_batch_rocks(batch)
+            .await
+            .unwrap();
+    }
+    let addr = spawn_server(Arc::clone(&storage)).await;
+
+    let (tx_ids, next) = tick_oplist_page(addr, "tick", "").await;
+    assert_eq!(
+        tx_ids,
+        vec!["tx_106", "tx_105", "tx_104", "tx_103", "tx_101", "tx_100"]
+    );
+    assert_eq!(next, None);
+
+    // Only mints, two per page
+    let (tx_ids, next) = tick_oplist_page(addr, "TICK", "op_type=mint&limit=2").await;
+    assert_eq!(tx_ids, vec!["tx_106", "tx_104"]);
+    assert_eq!(next, Some(Pagination::encode_cursor("1040000")));
+    let query = format!("op_type=mint&limit=2&cursor={}", next.unwrap());
+    let (tx_ids, next) = tick_oplist_page(addr, "TICK", &query).await;
+    assert_eq!(tx_ids, vec!["tx_101"]);
+    assert_eq!(next, None);
+
+    let (tx_ids, _) = tick_oplist_page(addr, "OTHER", "op_type=transfer").await;
+    assert_eq!(tx_ids, vec!["tx_105"]);
+
+    // Rolled back operations leave the index
+    rollback_manager
+        .rollback_op_state_batch(&rollback_of(&second))
+        .await
+        .unwrap();
+    let (tx_ids, _) = tick_oplist_page(addr, "TICK", "op_type=mint").await;
+    assert_eq!(tx_ids, vec!["tx_101"]);
+    let (tx_ids, _) = tick_oplist_page(addr, "OTHER", "").await;
+    assert_eq!(tx_ids, vec!["tx_102"]);
+    assert_eq!(storage.rocksdb.scan_prefix("tickops:").unwrap().len(), 4);
+}
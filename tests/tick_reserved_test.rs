@@ -0,0 +1,85 @@
+use kaspa_indexer_rust::config::{
+    apply_tick_reserved, apply_tick_reserved_expiry, get_reserved_tick_address, is_tick_reserved,
+};
+use kaspa_indexer_rust::operations::{self, DeployOperation};
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::utils::address::encode_address;
+
+const TEST_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const EXPIRY_DAA: u64 = 110165000;
+
+fn deploy_script(tick: &str) -> DataScriptType {
+    DataScriptType {
+        p: "KRC-20".to_string(),
+        op: "deploy".to_string(),
+        from: Some(TEST_ADDRESS.to_string()),
+        to: Some(TEST_ADDRESS.to_string()),
+        tick: Some(tick.to_string()),
+        max: Some("21000000".to_string()),
+        lim: Some("1000".to_string()),
+        pre: Some("0".to_string()),
+        dec: Some("8".to_string()),
+        amt: None,
+        utxo: None,
+        price: None,
+        mod_type: "".to_string(),
+        name: None,
+        ca: None,
+        desc: None,
+    }
+}
+
+/// Deploy `tick` from TEST_ADDRESS at a DAA score, returning its op_accept and op_error
+fn deploy(tick: &str, daa_score: u64) -> (i8, String) {
+    let script = deploy_script(tick);
+    let mut op_data = DataOperationType {
+        tx_id: format!("deploy_{}_{}", tick, daa_score),
+        daa_score,
+        block_accept: "block_hash".to_string(),
+        fee: 100000000000,
+        fee_least: 0,
+        mts_add: 1700000000,
+        op_score: daa_score * 10000,
+        op_accept: 0,
+        op_error: String::new(),
+        op_script: vec![script.clone()],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: None,
+    };
+    let mut state_map = DataStateMapType::new();
+    DeployOperation::do_operation(&script, &mut op_data, &mut state_map, true).unwrap();
+    (op_data.op_accept, op_data.op_error)
+}
+
+#[test]
+fn test_abandoned_reservation_expires() {
+    let owner = encode_address(&[1; 32], true).unwrap();
+    apply_tick_reserved(&[format!("RSRV_{}", owner)]);
+    apply_tick_reserved_expiry(EXPIRY_DAA);
+
+    // Until the expiry only the reserving address may deploy
+    assert_eq!(
+        get_reserved_tick_address("rsrv", EXPIRY_DAA - 1),
+        Some(owner.clone())
+    );
+    assert!(is_tick_reserved("rsrv", EXPIRY_DAA - 1));
+    assert!(operations::is_tick_reserved("RSRV", EXPIRY_DAA - 1));
+    assert_eq!(
+        deploy("RSRV", EXPIRY_DAA - 1),
+        (-1, "tick reserved".to_string())
+    );
+
+    // Past the expiry the still undeployed tick is deployable like any other
+    assert!(get_reserved_tick_address("RSRV", EXPIRY_DAA).is_none());
+    assert!(!is_tick_reserved("RSRV", EXPIRY_DAA));
+    assert_eq!(deploy("RSRV", EXPIRY_DAA + 10), (1, String::new()));
+
+    // Without an expiry reservations hold forever
+    apply_tick_reserved_expiry(0);
+    assert!(is_tick_reserved("RSRV", u64::MAX));
+    assert_eq!(deploy("RSRV", EXPIRY_DAA + 10).1, "tick reserved");
+}
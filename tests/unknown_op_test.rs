@@ -0,0 +1,102 @@
+use kaspa_indexer_rust::config::apply_allow_unknown_ops;
+use kaspa_indexer_rust::operations::OP_ERROR_UNKNOWN_OP;
+use kaspa_indexer_rust::operations::handler::OperationManager;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::utils::script_parser::ScriptParser;
+use std::sync::Arc;
+
+const TEST_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else {
+        format!("4c{:02x}{}", len, data_hex)
+    }
+}
+
+/// Transaction revealing a script of the given operation type
+fn transaction(op: &str) -> DataTransactionType {
+    let payload = serde_json::json!({
+        "p": "KRC-20",
+        "op": op,
+        "tick": "UNKN",
+        "to": TEST_ADDRESS,
+        "mod_type": "",
+    });
+    let redeem_script = format!(
+        "20{}ac006307{}00{}68",
+        "ab".repeat(32),
+        hex::encode("kasplex"),
+        push_data(&hex::encode(payload.to_string())),
+    );
+    DataTransactionType {
+        tx_id: format!("tx_{}", op),
+        daa_score: 110165001,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({
+            "inputs": [{
+                "index": 0,
+                "signatureScript": format!(
+                    "{}{}",
+                    push_data(&"00".repeat(65)),
+                    push_data(&redeem_script)
+                ),
+            }],
+            "outputs": [],
+        })),
+    }
+}
+
+/// Execute a parsed operation alone, returning its op_accept and op_error
+fn execute(operation_manager: &OperationManager, op_data: DataOperationType) -> (i8, String) {
+    let mut op_data_list = vec![op_data];
+    let (mut state_map, _) = operation_manager
+        .prepare_state_batch(&op_data_list)
+        .unwrap();
+    operation_manager
+        .execute_batch(&mut op_data_list, &mut state_map, "", true)
+        .unwrap();
+    let op_data = op_data_list.remove(0);
+    (op_data.op_accept, op_data.op_error)
+}
+
+#[tokio::test]
+async fn test_unknown_op_rejected_unless_lenient() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let operation_manager = OperationManager::new(&storage);
+
+    // Unknown types are rejected by default, also when they reach execution
+    assert!(
+        ScriptParser::parse_op_data(&transaction("swap"), true)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        ScriptParser::parse_op_data(&transaction("mint"), true)
+            .unwrap()
+            .is_some()
+    );
+    apply_allow_unknown_ops(true);
+    let op_data = ScriptParser::parse_op_data(&transaction("swap"), true)
+        .unwrap()
+        .unwrap();
+    apply_allow_unknown_ops(false);
+    assert_eq!(
+        execute(&operation_manager, op_data.clone()),
+        (-1, OP_ERROR_UNKNOWN_OP.to_string())
+    );
+
+    // Lenient indexing stores them unexecuted
+    apply_allow_unknown_ops(true);
+    assert_eq!(
+        execute(&operation_manager, op_data),
+        (0, OP_ERROR_UNKNOWN_OP.to_string())
+    );
+    apply_allow_unknown_ops(false);
+}
@@ -0,0 +1,193 @@
+use kaspa_indexer_rust::config::types::RocksConfig;
+use kaspa_indexer_rust::explorer::RollbackManager;
+use kaspa_indexer_rust::operations::*;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::storage::versioned;
+use std::sync::Arc;
+
+const OWNER_ADDRESS: &str = "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const OTHER_ADDRESS: &str = "kaspatest:qpqz2vxj23kvh0m73ta2jjn2u4cv4tlufqns2eap8mxyyt0rvrxy6ejkful67";
+
+fn update_script(from: &str) -> DataScriptType {
+    DataScriptType {
+        p: "KRC-20".to_string(),
+        op: "update".to_string(),
+        from: Some(from.to_string()),
+        to: None,
+        tick: Some("meta".to_string()),
+        max: Some("1".to_string()),
+        lim: None,
+        pre: None,
+        dec: None,
+        amt: None,
+        utxo: None,
+        price: None,
+        mod_type: "".to_string(),
+        name: Some("Meta Token".to_string()),
+        ca: None,
+        desc: Some("Fixed description".to_string()),
+    }
+}
+
+fn validate(script: &mut DataScriptType) -> bool {
+    UpdateOperation::validate(script, "update_tx", 2000, true)
+}
+
+fn update_op_data(script: DataScriptType) -> DataOperationType {
+    DataOperationType {
+        tx_id: "update_tx".to_string(),
+        daa_score: 2000,
+        block_accept: "block_hash".to_string(),
+        fee: 100000000,
+        fee_least: 0,
+        mts_add: 1700000000,
+        op_score: 2000 * 10000,
+        op_accept: 0,
+        op_error: String::new(),
+        op_script: vec![script],
+        script_sig: String::new(),
+        st_before: vec![],
+        st_after: vec![],
+        checkpoint: String::new(),
+        ss_info: None,
+    }
+}
+
+fn deployed_state_map() -> DataStateMapType {
+    let mut state_map = DataStateMapType::new();
+    let token = StateTokenType {
+        tick: "META".to_string(),
+        max: "21000000".to_string(),
+        lim: "1000".to_string(),
+        from: OWNER_ADDRESS.to_string(),
+        to: OWNER_ADDRESS.to_string(),
+        name: "Old Name".to_string(),
+        desc: "Old description".to_string(),
+        op_add: 1000 * 10000,
+        op_mod: 1000 * 10000,
+        mts_add: 1600000000,
+        mts_mod: 1600000000,
+        ..Default::default()
+    };
+    state_map
+        .state_token_map
+        .insert("META".to_string(), Some(token));
+    state_map
+}
+
+fn token(state_map: &DataStateMapType) -> &StateTokenType {
+    state_map.state_token_map["META"].as_ref().unwrap()
+}
+
+#[test]
+fn test_update_by_owner_accepted() {
+    let mut script = update_script(OWNER_ADDRESS);
+    assert!(validate(&mut script));
+    assert_eq!(script.tick.as_deref(), Some("META"));
+    // Supply parameters never go through an update
+    assert!(script.max.is_none());
+
+    let mut state_map = deployed_state_map();
+    let before = token(&state_map).clone();
+    let mut op_data = update_op_data(script);
+    UpdateOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, 1);
+
+    let after = token(&state_map);
+    assert_eq!(after.name, "Meta Token");
+    assert_eq!(after.desc, "Fixed description");
+    assert_eq!(after.op_mod, op_data.op_score);
+    assert_eq!(after.mts_mod, op_data.mts_add);
+    assert_eq!(after.max, before.max);
+    assert_eq!(after.lim, before.lim);
+    assert_eq!(after.from, before.from);
+}
+
+#[test]
+fn test_update_by_non_owner_rejected() {
+    let mut script = update_script(OTHER_ADDRESS);
+    assert!(validate(&mut script));
+
+    let mut state_map = deployed_state_map();
+    let before = token(&state_map).clone();
+    let mut op_data = update_op_data(script);
+    UpdateOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, -1);
+    assert_eq!(op_data.op_error, "no ownership");
+    assert_eq!(token(&state_map), &before);
+
+    // Unknown ticks are rejected as well
+    let mut script = update_script(OWNER_ADDRESS);
+    script.tick = Some("NONE".to_string());
+    let mut op_data = update_op_data(script);
+    UpdateOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, -1);
+    assert_eq!(op_data.op_error, "tick not found");
+
+    // An update must carry a name or a description
+    let mut script = update_script(OWNER_ADDRESS);
+    script.name = None;
+    script.desc = None;
+    assert!(!validate(&mut script));
+}
+
+#[test]
+fn test_update_rollback_restores_metadata() {
+    let mut script = update_script(OWNER_ADDRESS);
+    // Commas in metadata must survive the state line round trip
+    script.desc = Some("Fixed, with a comma".to_string());
+    assert!(validate(&mut script));
+
+    let mut state_map = deployed_state_map();
+    let before = token(&state_map).clone();
+    let mut op_data = update_op_data(script);
+    UpdateOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, 1);
+    assert_eq!(token(&state_map).desc, "Fixed, with a comma");
+
+    UpdateOperation::rollback(&op_data, &mut state_map).unwrap();
+    assert_eq!(token(&state_map), &before);
+}
+
+#[tokio::test]
+async fn test_rollback_to_block_restores_metadata() {
+    let path = "./test_data/update_rollback";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+
+    let mut script = update_script(OWNER_ADDRESS);
+    assert!(validate(&mut script));
+    let mut state_map = deployed_state_map();
+    let before = token(&state_map).clone();
+    let mut op_data = update_op_data(script);
+    UpdateOperation::do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, 1);
+
+    let token_key = StateKey::token("META").encode();
+    storage
+        .rocksdb
+        .put_raw(&token_key, &versioned::encode(token(&state_map)).unwrap())
+        .unwrap();
+    storage
+        .rocksdb
+        .put_raw("opdata:update_tx", &versioned::encode(&op_data).unwrap())
+        .unwrap();
+
+    let rollback_manager = RollbackManager::new(Arc::clone(&storage)).unwrap();
+    rollback_manager
+        .rollback_data_to_block("block_hash", 1000)
+        .await
+        .unwrap();
+
+    let value = storage.rocksdb.get_raw(&token_key).unwrap().unwrap();
+    assert_eq!(versioned::decode::<StateTokenType>(&value).unwrap(), before);
+    assert!(storage.rocksdb.get_raw("opdata:update_tx").unwrap().is_none());
+}
@@ -0,0 +1,106 @@
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::utils::script_parser::ScriptParser;
+use kaspa_indexer_rust::utils::utxo::UtxoIndex;
+
+const DAA_SCORE: u64 = 110165001;
+const TEST_ADDRESS: &str =
+    "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz";
+const KAS: u64 = 100000000;
+
+fn push_data(data_hex: &str) -> String {
+    let len = data_hex.len() / 2;
+    if len <= 75 {
+        format!("{:02x}{}", len, data_hex)
+    } else {
+        format!("4c{:02x}{}", len, data_hex)
+    }
+}
+
+fn outputs(amounts: &[u64]) -> Vec<serde_json::Value> {
+    amounts
+        .iter()
+        .map(|amount| serde_json::json!({ "amount": amount }))
+        .collect()
+}
+
+/// Plain transaction creating outputs of `amounts`
+fn funding(tx_id: &str, amounts: &[u64]) -> DataTransactionType {
+    DataTransactionType {
+        tx_id: tx_id.to_string(),
+        daa_score: DAA_SCORE,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({ "inputs": [], "outputs": outputs(amounts) })),
+    }
+}
+
+/// Deploy reveal spending output `index` of `spent_tx_id`
+fn deploy(tx_id: &str, spent_tx_id: &str, index: u64, amounts: &[u64]) -> DataTransactionType {
+    let payload = serde_json::json!({
+        "p": "KRC-20",
+        "op": "deploy",
+        "tick": "CHAIN",
+        "max": "2100000000000000",
+        "lim": "100000000000",
+        "to": TEST_ADDRESS,
+        "mod_type": "",
+    });
+    let redeem_script = format!(
+        "20{}ac006307{}00{}68",
+        "ab".repeat(32),
+        hex::encode("kasplex"),
+        push_data(&hex::encode(payload.to_string()))
+    );
+    DataTransactionType {
+        tx_id: tx_id.to_string(),
+        daa_score: DAA_SCORE,
+        block_accept: "block_hash".to_string(),
+        data: Some(serde_json::json!({
+            "inputs": [{
+                "index": 0,
+                "previousOutpoint": { "transactionId": spent_tx_id, "index": index },
+                "signatureScript": format!(
+                    "{}{}",
+                    push_data(&"00".repeat(65)),
+                    push_data(&redeem_script)
+                ),
+            }],
+            "outputs": outputs(amounts),
+        })),
+    }
+}
+
+#[test]
+fn test_chained_transaction_fee_uses_batch_outputs() {
+    // B spends the second output of A, created in the same batch
+    let tx_data_list = vec![
+        funding("tx_a", &[10 * KAS, 1500 * KAS]),
+        deploy("tx_b", "tx_a", 1, &[300 * KAS]),
+    ];
+    let op_data_list = ScriptParser::parse_op_data_list(&tx_data_list, true).unwrap();
+    assert_eq!(op_data_list.len(), 1);
+    assert_eq!(op_data_list[0].tx_id, "tx_b");
+    assert_eq!(op_data_list[0].fee_least, 1000 * KAS);
+    assert_eq!(op_data_list[0].fee, 1200 * KAS);
+
+    let utxo_index = UtxoIndex::from_transactions(&tx_data_list);
+    assert!(ScriptParser::fee_input_tx_ids(&op_data_list, &tx_data_list, &utxo_index).is_empty());
+}
+
+#[test]
+fn test_fee_inputs_outside_batch_resolved_once_fetched() {
+    let tx_data_list = vec![deploy("tx_b", "tx_earlier", 0, &[300 * KAS])];
+    let mut op_data_list = ScriptParser::parse_op_data_list(&tx_data_list, true).unwrap();
+    // The fee of unresolved inputs is unknown rather than assumed paid
+    assert_eq!(op_data_list[0].fee, 0);
+
+    let mut utxo_index = UtxoIndex::from_transactions(&tx_data_list);
+    assert_eq!(
+        ScriptParser::fee_input_tx_ids(&op_data_list, &tx_data_list, &utxo_index),
+        vec!["tx_earlier".to_string()]
+    );
+
+    // An input paying less than the required fee is found out once its transaction is known
+    utxo_index.insert_transactions(&[funding("tx_earlier", &[800 * KAS])]);
+    ScriptParser::calculate_fees(&mut op_data_list, &tx_data_list, &utxo_index);
+    assert_eq!(op_data_list[0].fee, 500 * KAS);
+}
@@ -0,0 +1,149 @@
+use kaspa_indexer_rust::config::types::RocksConfig;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+use kaspa_indexer_rust::storage::versioned::{self, SCHEMA_VERSION_FIELD, Versioned};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Token blob as written before versioning and before tokens had a desc
+fn legacy_token_json() -> Value {
+    serde_json::json!({
+        "tick": "LEGACY",
+        "max": "2100000000000000",
+        "lim": "100000000000",
+        "pre": "0",
+        "dec": 8,
+        "mod_type": "",
+        "from": "kaspatest:qrf7saw4vlc006mmcpfa29mrgs79ez5glszc4ytx9hq7wdu2a5d4kvl7wz5pz",
+        "to": "",
+        "minted": "100000000000",
+        "burned": "0",
+        "name": "",
+        "tx_id": "tx_deploy",
+        "op_add": 1101650000000u64,
+        "op_mod": 1101650010000u64,
+        "mts_add": 1700000000000i64,
+        "mts_mod": 1700000001000i64,
+    })
+}
+
+#[test]
+fn test_legacy_token_blob_migrates_to_current_version() {
+    let legacy = serde_json::to_vec(&legacy_token_json()).unwrap();
+    let token: StateTokenType = versioned::decode(&legacy).unwrap();
+    assert_eq!(token.tick, "LEGACY");
+    assert_eq!(token.minted, "100000000000");
+    assert_eq!(token.desc, "");
+
+    // Written back with the current version, and read again unchanged
+    let encoded = versioned::encode(&token).unwrap();
+    let json: Value = serde_json::from_slice(&encoded).unwrap();
+    assert_eq!(json[SCHEMA_VERSION_FIELD], StateTokenType::SCHEMA_VERSION);
+    let decoded: StateTokenType = versioned::decode(&encoded).unwrap();
+    assert_eq!(decoded, token);
+}
+
+#[test]
+fn test_newer_schema_version_is_rejected() {
+    let mut json = legacy_token_json();
+    json[SCHEMA_VERSION_FIELD] = (StateTokenType::SCHEMA_VERSION + 1).into();
+    let err = versioned::decode::<StateTokenType>(&serde_json::to_vec(&json).unwrap()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("is newer than the supported version")
+    );
+}
+
+/// Struct whose v1 layout named the field `title` and stored the amount as a string
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Renamed {
+    name: String,
+    amount: u64,
+}
+
+impl Versioned for Renamed {
+    const SCHEMA_VERSION: u32 = 3;
+
+    fn migrate(version: u32, object: &mut Map<String, Value>) -> anyhow::Result<()> {
+        match version {
+            1 => {
+                let title = object.remove("title").unwrap_or_default();
+                object.insert("name".to_string(), title);
+            }
+            2 => {
+                let amount = object["amount"].as_str().unwrap_or("0").parse::<u64>()?;
+                object.insert("amount".to_string(), amount.into());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_migrations_run_in_order_from_the_blob_version() {
+    let v1 = serde_json::json!({ "title": "first", "amount": "5" });
+    let renamed: Renamed = versioned::from_value(v1).unwrap();
+    assert_eq!(
+        renamed,
+        Renamed {
+            name: "first".to_string(),
+            amount: 5
+        }
+    );
+
+    // A v2 blob only runs the second step
+    let v2 = serde_json::json!({ "v": 2, "name": "second", "amount": "7" });
+    let renamed: Renamed = versioned::from_value(v2).unwrap();
+    assert_eq!(renamed.name, "second");
+    assert_eq!(renamed.amount, 7);
+}
+
+#[tokio::test]
+async fn test_storage_reads_legacy_blobs() {
+    let path = "./test_data/versioned_legacy";
+    std::fs::remove_dir_all(path).ok();
+    let rocks_config = RocksConfig {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut storage = StorageManager::new(rocks_config, None).await.unwrap();
+    storage.init().await.unwrap();
+
+    // Blobs of a store written before versioning
+    storage
+        .rocksdb
+        .put_raw(
+            &StateKey::token("LEGACY").encode(),
+            &serde_json::to_vec(&legacy_token_json()).unwrap(),
+        )
+        .unwrap();
+    let vspc_list =
+        serde_json::json!([{ "daa_score": 110165000, "hash": "block_0", "tx_id_list": [] }]);
+    storage
+        .rocksdb
+        .set_runtime_data("VSPCLAST", &vspc_list.to_string())
+        .unwrap();
+
+    let token = storage.state.get_state_token("LEGACY").unwrap().unwrap();
+    assert_eq!(token.tx_id, "tx_deploy");
+    assert_eq!(token.desc, "");
+    let vspc_list = storage.runtime.get_runtime_vspc_last().await.unwrap();
+    assert_eq!(vspc_list.len(), 1);
+    assert_eq!(vspc_list[0].hash, "block_0");
+
+    // Saving upgrades the runtime list to the current version
+    storage
+        .runtime
+        .set_runtime_vspc_last(&vspc_list)
+        .await
+        .unwrap();
+    let value_json = storage
+        .rocksdb
+        .get_runtime_data("VSPCLAST")
+        .unwrap()
+        .unwrap();
+    let saved: Vec<Value> = serde_json::from_str(&value_json).unwrap();
+    assert_eq!(saved[0][SCHEMA_VERSION_FIELD], DataVspcType::SCHEMA_VERSION);
+}
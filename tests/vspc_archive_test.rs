@@ -0,0 +1,93 @@
+mod common;
+
+use common::spawn_server;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::*;
+use std::sync::Arc;
+
+fn vspc(daa_score: u64, hash: &str) -> DataVspcType {
+    DataVspcType {
+        daa_score,
+        hash: hash.to_string(),
+        tx_id_list: vec![format!("tx_{}", daa_score)],
+        selected_parent: String::new(),
+    }
+}
+
+fn archived_hash(storage: &StorageManager, daa_score: u64) -> Option<String> {
+    storage
+        .state
+        .get_vspc_by_daa_score(daa_score)
+        .unwrap()
+        .map(|vspc| vspc.hash)
+}
+
+#[tokio::test]
+async fn test_runtime_vspc_list_is_archived() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let runtime = &storage.runtime;
+
+    runtime
+        .set_runtime_vspc_last(&[vspc(100, "a"), vspc(101, "b"), vspc(102, "c")])
+        .await
+        .unwrap();
+    assert_eq!(archived_hash(&storage, 101).as_deref(), Some("b"));
+
+    // 100 aged out of the window, a reorg replaced 102
+    runtime
+        .set_runtime_vspc_last(&[vspc(101, "b"), vspc(102, "c2"), vspc(103, "d")])
+        .await
+        .unwrap();
+    assert_eq!(archived_hash(&storage, 100).as_deref(), Some("a"));
+    assert_eq!(archived_hash(&storage, 102).as_deref(), Some("c2"));
+    assert_eq!(archived_hash(&storage, 103).as_deref(), Some("d"));
+
+    // Rolled back entries leave the archive
+    runtime
+        .set_runtime_vspc_last(&[vspc(101, "b")])
+        .await
+        .unwrap();
+    assert_eq!(archived_hash(&storage, 101).as_deref(), Some("b"));
+    assert_eq!(archived_hash(&storage, 102), None);
+    assert_eq!(archived_hash(&storage, 103), None);
+    assert_eq!(runtime.get_runtime_vspc_last().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_archive_vspc_endpoint() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    storage
+        .runtime
+        .set_runtime_vspc_last(&[vspc(100, "a"), vspc(101, "b")])
+        .await
+        .unwrap();
+    let addr = spawn_server(Arc::new(storage)).await;
+    let get = |path: &str| {
+        let url = format!("http://{}/v1/archive/vspc/{}", addr, path);
+        async move {
+            let response = reqwest::get(url).await.unwrap();
+            let status = response.status().as_u16();
+            let body: serde_json::Value = response.json().await.unwrap();
+            (status, body)
+        }
+    };
+
+    let (status, body) = get("101").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["data"]["hash"], "b");
+    assert_eq!(body["data"]["daa_score"], 101);
+    assert_eq!(body["data"]["tx_id_list"], serde_json::json!(["tx_101"]));
+
+    let (status, body) = get("99").await;
+    assert_eq!(status, 404);
+    assert!(body["error"].is_string());
+
+    // Malformed scores are answered with a JSON body too
+    for path in ["abc", "-1", "18446744073709551616"] {
+        let (status, body) = get(path).await;
+        assert_eq!(status, 400, "{}", path);
+        assert!(body["error"].is_string(), "{}", path);
+    }
+}
@@ -0,0 +1,353 @@
+use futures_util::stream::{self, Stream};
+use kaspa_indexer_rust::explorer::NodeClient;
+use kaspa_indexer_rust::explorer::vspc_client::VSPCClient;
+use kaspa_indexer_rust::protobuf::protowire::rpc_server::{Rpc, RpcServer};
+use kaspa_indexer_rust::protobuf::protowire::*;
+use kaspa_indexer_rust::storage::types::*;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tonic::{Request, Response, Status, Streaming};
+
+fn vspc_block() -> serde_json::Value {
+    serde_json::json!({
+        "hash": "block_hash_100",
+        "daaScore": 100,
+        "acceptedTransactionIds": ["tx_a", "tx_b"],
+    })
+}
+
+/// gRPC node with a selected parent chain of DAA scores 98 to 104 above its pruning point,
+/// recording the start hashes of GetVirtualChainFromBlock. Chain block N merges "merged_N",
+/// holding "tx_N", chain block 100 also merges the red "red_100". Merged blocks answer slower
+/// for lower N, the requests in flight for them are tracked.
+struct ChainNode {
+    start_hashes: Arc<Mutex<Vec<String>>>,
+    in_flight: AtomicUsize,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+const PRUNING_POINT_DAA_SCORE: u64 = 97;
+
+fn chain_hash(daa_score: u64) -> String {
+    format!("block_hash_{}", daa_score)
+}
+
+fn daa_score_of(hash: &str) -> u64 {
+    hash.rsplit('_').next().unwrap().parse().unwrap()
+}
+
+fn transaction(tx_id: &str) -> RpcTransaction {
+    RpcTransaction {
+        verbose_data: Some(RpcTransactionVerboseData {
+            transaction_id: tx_id.to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+impl ChainNode {
+    fn answer(&self, request: kaspad_request::Payload) -> kaspad_response::Payload {
+        match request {
+            kaspad_request::Payload::GetBlockDagInfoRequest(_) => {
+                kaspad_response::Payload::GetBlockDagInfoResponse(GetBlockDagInfoResponseMessage {
+                    pruning_point_hash: chain_hash(PRUNING_POINT_DAA_SCORE),
+                    virtual_daa_score: 105,
+                    ..Default::default()
+                })
+            }
+            kaspad_request::Payload::GetVirtualChainFromBlockRequest(request) => {
+                self.start_hashes
+                    .lock()
+                    .unwrap()
+                    .push(request.start_hash.clone());
+                let start = daa_score_of(&request.start_hash);
+                kaspad_response::Payload::GetVirtualChainFromBlockResponse(
+                    GetVirtualChainFromBlockResponseMessage {
+                        added_chain_block_hashes: (start + 1..=104).map(chain_hash).collect(),
+                        accepted_transaction_ids: vec![RpcAcceptedTransactionIds {
+                            accepting_block_hash: chain_hash(100),
+                            accepted_transaction_ids: vec!["tx_a".to_string(), "tx_b".to_string()],
+                        }],
+                        ..Default::default()
+                    },
+                )
+            }
+            kaspad_request::Payload::GetBlockRequest(request) => {
+                let daa_score = daa_score_of(&request.hash);
+                let block = if request.hash.starts_with("block_hash_") {
+                    RpcBlock {
+                        header: Some(RpcBlockHeader {
+                            daa_score,
+                            ..Default::default()
+                        }),
+                        transactions: vec![],
+                        verbose_data: Some(RpcBlockVerboseData {
+                            hash: request.hash,
+                            selected_parent_hash: chain_hash(daa_score - 1),
+                            merge_set_blues_hashes: vec![format!("merged_{}", daa_score)],
+                            merge_set_reds_hashes: match daa_score {
+                                100 => vec!["red_100".to_string()],
+                                _ => vec![],
+                            },
+                        }),
+                    }
+                } else {
+                    assert!(request.include_transactions);
+                    let tx_id_list = match request.hash.as_str() {
+                        "merged_100" => vec!["tx_a".to_string(), "tx_unaccepted".to_string()],
+                        "red_100" => vec!["tx_b".to_string()],
+                        _ => vec![format!("tx_{}", daa_score)],
+                    };
+                    RpcBlock {
+                        transactions: tx_id_list.iter().map(|tx_id| transaction(tx_id)).collect(),
+                        ..Default::default()
+                    }
+                };
+                kaspad_response::Payload::GetBlockResponse(GetBlockResponseMessage {
+                    block: Some(block),
+                    error: None,
+                })
+            }
+            request => panic!("unexpected request {:?}", request),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Rpc for ChainNode {
+    type MessageStreamStream =
+        Pin<Box<dyn Stream<Item = Result<KaspadResponse, Status>> + Send + 'static>>;
+
+    async fn message_stream(
+        &self,
+        request: Request<Streaming<KaspadRequest>>,
+    ) -> Result<Response<Self::MessageStreamStream>, Status> {
+        let request = request.into_inner().message().await?.unwrap();
+        let payload = request.payload.unwrap();
+        if let kaspad_request::Payload::GetBlockRequest(request) = &payload
+            && request.hash.starts_with("merged_")
+        {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            let delay = 20u64.saturating_sub(daa_score_of(&request.hash)) * 5;
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        }
+        let response = KaspadResponse {
+            id: request.id,
+            payload: Some(self.answer(payload)),
+        };
+        Ok(Response::new(Box::pin(stream::iter([Ok(response)]))))
+    }
+}
+
+async fn spawn_grpc_node(
+    start_hashes: Arc<Mutex<Vec<String>>>,
+    max_in_flight: Arc<AtomicUsize>,
+) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = stream::unfold(listener, |listener| async move {
+        let stream = listener.accept().await.map(|(stream, _)| stream);
+        Some((stream, listener))
+    });
+    let node = ChainNode {
+        start_hashes,
+        in_flight: AtomicUsize::new(0),
+        max_in_flight,
+    };
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(RpcServer::new(node))
+            .serve_with_incoming(incoming),
+    );
+    addr
+}
+
+#[test]
+fn test_parse_vspc_block_accepted_tx_ids() {
+    let vspc = VSPCClient::parse_vspc_block(&vspc_block()).unwrap();
+    assert_eq!(vspc.daa_score, 100);
+    assert_eq!(vspc.hash, "block_hash_100");
+    assert_eq!(vspc.tx_id_list, vec!["tx_a", "tx_b"]);
+
+    let tx_data_list = vspc.transaction_list();
+    assert_eq!(tx_data_list.len(), 2);
+    assert_eq!(tx_data_list[1].tx_id, "tx_b");
+    assert_eq!(tx_data_list[1].daa_score, 100);
+    assert_eq!(tx_data_list[1].block_accept, "block_hash_100");
+
+    // Only accepted transactions of the richer VSPCData are kept
+    let accepted = |transaction_hash: &str, is_accepted: bool| TransactionAcceptanceData {
+        transaction_hash: transaction_hash.to_string(),
+        accepting_block_hash: "block_hash_100".to_string(),
+        block_daa_score: 100,
+        is_accepted,
+    };
+    let vspc_data = VSPCData {
+        block_hash: "block_hash_100".to_string(),
+        daa_score: 100,
+        block_acceptance_data: BlockAcceptanceData {
+            block_acceptance_data: vec![
+                accepted("tx_a", true),
+                accepted("tx_rejected", false),
+                accepted("tx_b", true),
+            ],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(DataVspcType::from(&vspc_data).tx_id_list, vspc.tx_id_list);
+}
+
+#[tokio::test]
+async fn test_vspc_list_read_over_grpc() {
+    let start_hashes = Arc::new(Mutex::new(Vec::new()));
+    let addr = spawn_grpc_node(Arc::clone(&start_hashes), Arc::default()).await;
+    let client = VSPCClient::new(format!("http://{}", addr));
+
+    // The first request reads the chain from the pruning point
+    let vspc_list = NodeClient::get_vspc_list(&client, 100, 10).await.unwrap();
+    let daa_scores: Vec<u64> = vspc_list.iter().map(|vspc| vspc.daa_score).collect();
+    assert_eq!(daa_scores, vec![100, 101, 102, 103, 104]);
+    assert_eq!(vspc_list[0].hash, "block_hash_100");
+    assert_eq!(vspc_list[0].selected_parent, "block_hash_99");
+    assert_eq!(vspc_list[0].tx_id_list, vec!["tx_a", "tx_b"]);
+    assert!(vspc_list[1].tx_id_list.is_empty());
+
+    // Later ones start from the last returned block before the DAA score, up to the limit
+    let vspc_list = NodeClient::get_vspc_list(&client, 102, 2).await.unwrap();
+    let daa_scores: Vec<u64> = vspc_list.iter().map(|vspc| vspc.daa_score).collect();
+    assert_eq!(daa_scores, vec![102, 103]);
+    assert_eq!(
+        *start_hashes.lock().unwrap(),
+        vec!["block_hash_97", "block_hash_101"]
+    );
+}
+
+#[tokio::test]
+async fn test_vspc_list_resumes_from_seeded_chain() {
+    let start_hashes = Arc::new(Mutex::new(Vec::new()));
+    let addr = spawn_grpc_node(Arc::clone(&start_hashes), Arc::default()).await;
+    let client = VSPCClient::new(format!("http://{}", addr));
+
+    // After a restart the persisted runtime VSPC list replaces the pruning point
+    let persisted: Vec<DataVspcType> = [100, 101]
+        .into_iter()
+        .map(|daa_score| DataVspcType {
+            daa_score,
+            hash: chain_hash(daa_score),
+            tx_id_list: vec![],
+            selected_parent: chain_hash(daa_score - 1),
+        })
+        .collect();
+    NodeClient::seed_chain(&client, &persisted);
+
+    let vspc_list = NodeClient::get_vspc_list(&client, 102, 10).await.unwrap();
+    let daa_scores: Vec<u64> = vspc_list.iter().map(|vspc| vspc.daa_score).collect();
+    assert_eq!(daa_scores, vec![102, 103, 104]);
+    assert_eq!(*start_hashes.lock().unwrap(), vec!["block_hash_101"]);
+}
+
+#[tokio::test]
+async fn test_vspc_list_below_start_resumes_from_last_fetched_block() {
+    let start_hashes = Arc::new(Mutex::new(Vec::new()));
+    let addr = spawn_grpc_node(Arc::clone(&start_hashes), Arc::default()).await;
+    let client = VSPCClient::new(format!("http://{}", addr));
+
+    // No chain block reaches the DAA score yet, the next read still goes on from the last one
+    for _ in 0..2 {
+        let vspc_list = NodeClient::get_vspc_list(&client, 110, 10).await.unwrap();
+        assert!(vspc_list.is_empty());
+    }
+    assert_eq!(
+        *start_hashes.lock().unwrap(),
+        vec!["block_hash_97", "block_hash_104"]
+    );
+}
+
+#[tokio::test]
+async fn test_accepted_tx_ids_reach_transaction_data_list() {
+    let addr = spawn_grpc_node(Arc::default(), Arc::default()).await;
+    let client = VSPCClient::new(format!("http://{}", addr));
+    let vspc_list = NodeClient::get_vspc_list(&client, 100, 1).await.unwrap();
+    assert_eq!(vspc_list.len(), 1);
+    assert_eq!(vspc_list[0].tx_id_list, vec!["tx_a", "tx_b"]);
+
+    // Accepted transactions are read from the blues and reds merged by the chain block
+    let tx_data_list: Vec<DataTransactionType> = vspc_list
+        .iter()
+        .flat_map(DataVspcType::transaction_list)
+        .collect();
+    let tx_data_list = NodeClient::get_transaction_data_list(&client, &tx_data_list)
+        .await
+        .unwrap();
+    let tx_ids: Vec<&str> = tx_data_list.iter().map(|tx| tx.tx_id.as_str()).collect();
+    assert_eq!(tx_ids, vec!["tx_a", "tx_b"]);
+    for tx_data in &tx_data_list {
+        assert_eq!(tx_data.block_accept, "block_hash_100");
+        assert_eq!(
+            tx_data.data.as_ref().unwrap()["transactionId"],
+            tx_data.tx_id.as_str()
+        );
+    }
+
+    // Without an accepting block there is no block to read the transaction from
+    let tx_data_list = vec![DataTransactionType {
+        tx_id: "tx_a".to_string(),
+        daa_score: 0,
+        block_accept: String::new(),
+        data: None,
+    }];
+    let tx_data_list = NodeClient::get_transaction_data_list(&client, &tx_data_list)
+        .await
+        .unwrap();
+    assert!(tx_data_list[0].data.is_none());
+}
+
+#[tokio::test]
+async fn test_transaction_fetch_bounded_and_ordered() {
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let addr = spawn_grpc_node(Arc::default(), Arc::clone(&max_in_flight)).await;
+    let client = VSPCClient::new(format!("http://{}", addr)).with_tx_fetch_concurrency(4);
+
+    let tx_data_list: Vec<DataTransactionType> = (1..=20)
+        .map(|i| DataTransactionType {
+            tx_id: format!("tx_{}", i),
+            daa_score: i,
+            block_accept: chain_hash(i),
+            data: None,
+        })
+        .collect();
+    let fetched = NodeClient::get_transaction_data_list(&client, &tx_data_list)
+        .await
+        .unwrap();
+
+    // Later transactions complete first, the list still follows the input order
+    assert_eq!(fetched.len(), tx_data_list.len());
+    for (fetched, tx_data) in fetched.iter().zip(&tx_data_list) {
+        assert_eq!(fetched.tx_id, tx_data.tx_id);
+        assert_eq!(
+            fetched.data.as_ref().unwrap()["transactionId"],
+            tx_data.tx_id.as_str()
+        );
+    }
+
+    let max_in_flight = max_in_flight.load(Ordering::SeqCst);
+    assert!(max_in_flight > 1, "fetches should overlap");
+    assert!(max_in_flight <= 4, "{} fetches in flight", max_in_flight);
+}
+
+#[tokio::test]
+async fn test_virtual_daa_score_over_grpc() {
+    let addr = spawn_grpc_node(Arc::default(), Arc::default()).await;
+    let client = VSPCClient::new(format!("http://{}", addr));
+    assert_eq!(
+        NodeClient::get_virtual_daa_score(&client).await.unwrap(),
+        105
+    );
+}
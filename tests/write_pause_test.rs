@@ -0,0 +1,80 @@
+mod common;
+
+use common::spawn_server;
+use kaspa_indexer_rust::config::types::StartupConfig;
+use kaspa_indexer_rust::explorer::VSPCScanner;
+use kaspa_indexer_rust::explorer::mock_node::MockNodeClient;
+use kaspa_indexer_rust::storage::StorageManager;
+use kaspa_indexer_rust::storage::types::WriteStallStatus;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const MB: u64 = 1024 * 1024;
+
+async fn health_write_stall(addr: SocketAddr) -> serde_json::Value {
+    let body: serde_json::Value = reqwest::get(format!("http://{}/v1/health", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    body["data"]["write_stall"].clone()
+}
+
+fn pending_compaction(pending_compaction_mb: u64, stalled: bool) -> WriteStallStatus {
+    WriteStallStatus {
+        pending_compaction_bytes: pending_compaction_mb * MB,
+        stalled,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_write_stall_pauses_scanner_until_compaction_catches_up() {
+    let mut storage = StorageManager::new_in_memory().unwrap();
+    storage.init().await.unwrap();
+    let storage = Arc::new(storage);
+    let scanner = VSPCScanner::with_node_client(
+        Arc::clone(&storage),
+        StartupConfig::default(),
+        true,
+        MockNodeClient::new(),
+    )
+    .unwrap();
+    let addr = spawn_server(Arc::clone(&storage)).await;
+    let rocksdb = &storage.rocksdb;
+
+    assert_eq!(scanner.check_write_backpressure(), None);
+    assert_eq!(health_write_stall(addr).await["paused"], false);
+
+    // A stall signal pauses ingestion
+    assert!(rocksdb.update_write_pause(&pending_compaction(70 * 1024, true)));
+    let write_stall = health_write_stall(addr).await;
+    assert_eq!(write_stall["paused"], true);
+    assert_eq!(write_stall["pause_count"], 1);
+
+    // Pending compaction below the stall threshold but above the resume one keeps it paused
+    assert!(rocksdb.update_write_pause(&pending_compaction(40 * 1024, false)));
+    let delayed = WriteStallStatus {
+        delayed_write_rate: 16 * MB,
+        ..pending_compaction(0, true)
+    };
+    assert!(rocksdb.update_write_pause(&delayed));
+    assert!(rocksdb.is_write_paused());
+
+    // The idle database reports the signal cleared, the scanner resumes on its next check
+    assert_eq!(scanner.check_write_backpressure(), None);
+    assert!(!rocksdb.is_write_paused());
+    let write_stall = health_write_stall(addr).await;
+    assert_eq!(write_stall["paused"], false);
+    assert_eq!(write_stall["pause_count"], 1);
+
+    let body: serde_json::Value = reqwest::get(format!("http://{}/v1/metrics/write-stall", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["data"]["paused"], false);
+    assert_eq!(body["data"]["pause_count"], 1);
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::address;
+use kaspa_indexer_rust::operations::{
+    BurnOperation, MintOperation, SendOperation, TransferOperation,
+};
+use kaspa_indexer_rust::storage::state_key::StateKey;
+use kaspa_indexer_rust::storage::types::*;
+
+/// State holding a ZERO token with the given mint limit and a balance of `from`
+fn state_map(lim: &str, from: &str) -> DataStateMapType {
+    let mut state_map = DataStateMapType::new();
+    let token = StateTokenType {
+        tick: "ZERO".to_string(),
+        max: "21000000".to_string(),
+        lim: lim.to_string(),
+        minted: "1000".to_string(),
+        burned: "0".to_string(),
+        ..Default::default()
+    };
+    state_map
+        .state_token_map
+        .insert("ZERO".to_string(), Some(token));
+    let balance = StateBalanceType {
+        address: from.to_string(),
+        tick: "ZERO".to_string(),
+        balance: "1000".to_string(),
+        ..Default::default()
+    };
+    state_map
+        .state_balance_map
+        .insert(StateKey::balance(from, "ZERO").map_key(), Some(balance));
+    state_map
+}
+
+fn op_data(op: &str, from: &str, to: &str, amt: Option<&str>) -> DataOperationType {
+    let mut op_data = common::op_data(0, op, "ZERO", from, to);
+    op_data.op_script[0].amt = amt.map(str::to_string);
+    op_data
+}
+
+type DoOperation =
+    fn(usize, &mut DataOperationType, &mut DataStateMapType, bool) -> anyhow::Result<()>;
+
+/// Run an operation, returning its op_error after checking it was rejected without state changes
+fn rejected(do_operation: DoOperation, mut op_data: DataOperationType, lim: &str) -> String {
+    let from = op_data.op_script[0].from.clone().unwrap();
+    let mut state_map = state_map(lim, &from);
+    let before = state_map.clone();
+    do_operation(0, &mut op_data, &mut state_map, true).unwrap();
+    assert_eq!(op_data.op_accept, -1);
+    assert_eq!(state_map.state_token_map, before.state_token_map);
+    assert_eq!(state_map.state_balance_map, before.state_balance_map);
+    op_data.op_error
+}
+
+#[test]
+fn test_zero_amounts_are_rejected_per_operation() {
+    let (alice, bob) = (address(1), address(2));
+
+    assert_eq!(
+        rejected(
+            MintOperation::do_operation,
+            op_data("mint", &alice, &alice, None),
+            "0"
+        ),
+        "mint amount not positive"
+    );
+    assert_eq!(
+        rejected(
+            TransferOperation::do_operation,
+            op_data("transfer", &alice, &bob, Some("0")),
+            "1000"
+        ),
+        "transfer amount not positive"
+    );
+    assert_eq!(
+        rejected(
+            BurnOperation::do_operation,
+            op_data("burn", &alice, &alice, Some("0")),
+            "1000"
+        ),
+        "burn amount not positive"
+    );
+    assert_eq!(
+        rejected(
+            SendOperation::do_operation,
+            op_data("send", &alice, &bob, Some("0")),
+            "1000"
+        ),
+        "send amount not positive"
+    );
+}
+
+#[test]
+fn test_missing_and_negative_amounts_are_rejected() {
+    let (alice, bob) = (address(1), address(2));
+
+    assert_eq!(
+        rejected(
+            TransferOperation::do_operation,
+            op_data("transfer", &alice, &bob, None),
+            "1000"
+        ),
+        "transfer amount missing"
+    );
+    assert_eq!(
+        rejected(
+            SendOperation::do_operation,
+            op_data("send", &alice, &bob, Some("")),
+            "1000"
+        ),
+        "send amount missing"
+    );
+    assert_eq!(
+        rejected(
+            BurnOperation::do_operation,
+            op_data("burn", &alice, &alice, Some("-5")),
+            "1000"
+        ),
+        "burn amount not positive"
+    );
+    assert_eq!(
+        rejected(
+            TransferOperation::do_operation,
+            op_data("transfer", &alice, &bob, Some("1e3")),
+            "1000"
+        ),
+        "transfer amount invalid"
+    );
+}